@@ -1,7 +1,10 @@
+use crate::db::ExerciseType;
 use anyhow::Result;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Utc, Weekday};
+use chrono_tz::Tz;
 use comfy_table::Color;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::fmt;
 use std::fs;
 use std::path::{Path, PathBuf};
@@ -10,6 +13,7 @@ use strum_macros::EnumIter;
 use thiserror::Error;
 
 const CONFIG_FILE_NAME: &str = "config.toml";
+const SYNC_BASE_FILE_NAME: &str = "config.sync-base.toml"; // Server config as of the last sync
 const APP_CONFIG_DIR: &str = "workout-tracker-cli";
 const CONFIG_ENV_VAR: &str = "WORKOUT_CONFIG_DIR"; // Environment variable name
 
@@ -39,6 +43,28 @@ pub enum ConfigError {
     InvalidPbNotificationInput(String),
     #[error("Invalid streak interval: {0}. Must be at least 1.")]
     InvalidStreakInterval(u32),
+    #[error("Unknown timezone: '{0}'. Use an IANA name such as 'Australia/Sydney'.")]
+    InvalidTimezone(String),
+    #[error("Invalid weight increment: {0}. Must be a positive number.")]
+    InvalidWeightIncrement(f64),
+    #[error("Invalid PB tolerance: {0}. Must be zero or a positive number.")]
+    InvalidPbTolerance(f64),
+    #[error("Invalid validation limit: {0}. Must be a positive number.")]
+    InvalidValidationLimit(f64),
+    #[error("Invalid plate weight: {0}. Must be a positive number.")]
+    InvalidPlateWeight(f64),
+    #[error("Unknown theme preset: '{0}'. Available presets: default, dark, light, high-contrast.")]
+    UnknownThemePreset(String),
+    #[error("Invalid decimal separator: '{0}'. Use '.' or ','.")]
+    InvalidDecimalSeparator(char),
+    #[error("Invalid progression scheme: {0}")]
+    InvalidProgressionScheme(String),
+    #[error("Invalid recovery window: {0} hours. Must be at least 1.")]
+    InvalidRecoveryHours(u32),
+    #[error("Invalid max heart rate: {0} bpm. Must be between 100 and 250.")]
+    InvalidMaxHeartRate(u32),
+    #[error("Invalid age: {0}. Must be between 1 and 120.")]
+    InvalidAge(u32),
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
@@ -50,6 +76,14 @@ pub enum Units {
 }
 
 impl Units {
+    /// Standard plate set (weights in these units) used when `available_plates` is empty.
+    pub const fn standard_plates(&self) -> &'static [f64] {
+        match self {
+            Units::Metric => &[25.0, 20.0, 15.0, 10.0, 5.0, 2.5, 1.25],
+            Units::Imperial => &[45.0, 35.0, 25.0, 10.0, 5.0, 2.5],
+        }
+    }
+
     /// Returns the standard abbreviation for weight units.
     pub const fn weight_abbr(&self) -> &'static str {
         match self {
@@ -65,6 +99,14 @@ impl Units {
             Units::Imperial => "miles",
         }
     }
+
+    /// Returns the standard abbreviation for pace units.
+    pub const fn pace_abbr(&self) -> &'static str {
+        match self {
+            Units::Metric => "min/km",
+            Units::Imperial => "min/mile",
+        }
+    }
 }
 
 // Define standard colors using strum for easy iteration/parsing
@@ -135,7 +177,7 @@ pub fn parse_color(color_str: &str) -> Result<StandardColor, ConfigError> {
     Err(ConfigError::InvalidColor(color_str.to_string()))
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
 #[serde(default)] // Ensure defaults are used if fields are missing
 pub struct Theme {
     pub header_color: String,
@@ -149,7 +191,89 @@ impl Default for Theme {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+impl Theme {
+    /// Names accepted by `Theme::preset`.
+    pub const PRESETS: [&'static str; 4] = ["default", "dark", "light", "high-contrast"];
+
+    /// Returns a named preset (case-insensitive), see `Theme::PRESETS`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ConfigError::UnknownThemePreset` if there is no preset with that name.
+    pub fn preset(name: &str) -> Result<Self, ConfigError> {
+        let header_color = match name.trim().to_ascii_lowercase().as_str() {
+            "default" => StandardColor::Green,
+            "dark" => StandardColor::Cyan,
+            "light" => StandardColor::DarkBlue,
+            "high-contrast" => StandardColor::Yellow,
+            _ => return Err(ConfigError::UnknownThemePreset(name.to_string())),
+        };
+        Ok(Self {
+            header_color: header_color.to_string(),
+        })
+    }
+
+    /// Checks that every color in the theme is a known `StandardColor`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ConfigError::InvalidColor` listing each unknown color with its field.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let unknown: Vec<String> = [("header_color", &self.header_color)]
+            .into_iter()
+            .filter(|(_, color)| parse_color(color).is_err())
+            .map(|(field, color)| format!("{field} = '{color}'"))
+            .collect();
+        if unknown.is_empty() {
+            Ok(())
+        } else {
+            Err(ConfigError::InvalidColor(unknown.join(", ")))
+        }
+    }
+}
+
+/// Minimum improvement over the previous best for a result to count as a PB.
+///
+/// `absolute` is in the metric's stored unit (kg, reps, minutes, km, kg volume, min/km);
+/// `percent` is relative to the previous best. The larger of the two applies, and the
+/// improvement must exceed it. Both default to 0, so any improvement counts.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(default)] // Ensure defaults are used if fields are missing
+pub struct PbTolerance {
+    pub absolute: f64,
+    pub percent: f64,
+}
+
+impl PbTolerance {
+    /// Returns true if `improvement` over `previous` is larger than the tolerance.
+    pub fn is_exceeded_by(&self, previous: f64, improvement: f64) -> bool {
+        improvement > self.absolute.max(previous.abs() * self.percent / 100.0)
+    }
+}
+
+/// How `AppService::suggest_progression` picks the next session's target. Weights are in
+/// the configured weight units.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(tag = "scheme", rename_all = "snake_case")]
+pub enum ProgressionScheme {
+    /// Same sets and reps, `increment` more weight after every successful session.
+    Linear { increment: f64 },
+    /// One more rep per session until `max_reps`, then `increment` more weight and back
+    /// down to `min_reps`.
+    DoubleProgression {
+        min_reps: i64,
+        max_reps: i64,
+        increment: f64,
+    },
+}
+
+impl Default for ProgressionScheme {
+    fn default() -> Self {
+        Self::Linear { increment: 2.5 }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(default)] // Ensure defaults are used if fields are missing
 pub struct PbNotificationConfig {
     pub enabled: Option<bool>, // None = prompt first time, Some(true/false) = user setting
@@ -157,6 +281,10 @@ pub struct PbNotificationConfig {
     pub notify_reps: bool,
     pub notify_duration: bool,
     pub notify_distance: bool,
+    pub notify_volume: bool, // Single-entry sets * reps * effective weight
+    pub notify_pace: bool,   // Lower is better
+    pub notify_ties: bool,   // Also report results that match (within tolerance) a PB
+    pub pb_tolerance: PbTolerance, // Default 0 = any improvement is a PB
 }
 
 impl Default for PbNotificationConfig {
@@ -167,22 +295,125 @@ impl Default for PbNotificationConfig {
             notify_reps: true,
             notify_duration: true,
             notify_distance: true,
+            notify_volume: true,
+            notify_pace: true,
+            notify_ties: false,
+            pb_tolerance: PbTolerance::default(),
         }
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+/// Sanity limits applied to workout entries when they are added or edited.
+///
+/// Limits are in the units values are entered in (weight and distance follow `units`).
+/// Everything is unset by default, so no entry is rejected.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+#[serde(default)] // Ensure defaults are used if fields are missing
+pub struct ValidationConfig {
+    pub max_sets: Option<i64>,
+    pub max_reps: Option<i64>,
+    pub max_weight: Option<f64>,   // Added weight, as entered
+    pub max_duration: Option<i64>, // Minutes
+    pub max_distance: Option<f64>,
+    pub warn_on_pb_jump: bool, // Warn when a value is more than 50% above the current PB
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(default)] // Ensure defaults are used if fields are missing
 pub struct Config {
     pub bodyweight: Option<f64>,
     pub units: Units,
+    pub weight_units: Option<Units>,   // Overrides `units` for weights; None = follow `units`
+    pub distance_units: Option<Units>, // Overrides `units` for distance and pace
     pub prompt_for_bodyweight: bool, // Default is true
     pub streak_interval_days: u32,   // Default 1
+    pub week_start: Weekday,         // First day of the week, default Monday
+    pub scheduled_days: Vec<Weekday>, // Training days; empty = every day
+    pub timezone: Option<String>,    // IANA name, None = UTC
     pub target_bodyweight: Option<f64>,
+    pub weight_increment: Option<f64>, // Smallest plate step, e.g. 2.5; None = no rounding
+    pub round_stored_weights: bool,    // Also round weights when logging, default false
+    pub merge_same_day_sets: bool, // Add sets to the day's existing entry instead of a new row
+    pub default_implicit_type: Option<ExerciseType>, // Type for unknown exercises logged without one
+    pub type_keywords: BTreeMap<String, ExerciseType>, // Name keyword -> inferred type, see `infer_exercise_type`
+    pub seed_on_first_run: bool, // Add the built-in exercise list to a brand-new database
+    pub available_plates: Vec<f64>, // Plate weights in configured units; empty = standard set
+    pub distance_decimals: u32, // Decimal places kept for stored and displayed distances, default 3
+    pub e1rm_decimals: u32, // Decimal places shown for E1RM values, default 1
+    pub decimal_separator: char, // '.' or ','; used by `parse_number` for user input
+    pub progression: ProgressionScheme, // Used by `suggest_progression`
+    pub recovery_hours: u32, // Default recovery window per muscle, see `muscle_recovery_status`
+    pub muscle_recovery_hours: BTreeMap<String, u32>, // Per-muscle (lowercase) overrides
+    pub max_hr: Option<u32>, // Max heart rate (bpm) for `hr_zone`; estimated from `age` if unset
+    pub age: Option<u32>,
     pub theme: Theme,
     pub pb_notifications: PbNotificationConfig, // Grouped PB settings
+    pub validation: ValidationConfig,           // Entry limits, unset by default
+    pub auto_backup_before_sync: bool, // Back up the database before applying server changes
+    pub hard_delete: bool, // Remove deleted rows instead of flagging them, see `set_hard_delete`
     pub last_sync_timestamp: Option<DateTime<Utc>>, // New field for sync
     pub sync_server_url: Option<String>,        // New field for sync server
+    pub pb_command: Option<String>, // Shell command run on a PB, see `render_pb_command`
+}
+
+impl Config {
+    /// Returns the configured timezone, falling back to UTC if unset or unknown.
+    pub fn tz(&self) -> Tz {
+        self.timezone
+            .as_deref()
+            .and_then(|name| parse_timezone(name).ok())
+            .unwrap_or(Tz::UTC)
+    }
+
+    /// Units for weights (lifts, bodyweight, plates).
+    pub fn weight_units(&self) -> Units {
+        self.weight_units.unwrap_or(self.units)
+    }
+
+    /// Units for distances and paces.
+    pub fn distance_units(&self) -> Units {
+        self.distance_units.unwrap_or(self.units)
+    }
+}
+
+/// Keywords used to infer the type of implicitly created exercises by default.
+fn default_type_keywords() -> BTreeMap<String, ExerciseType> {
+    const KEYWORDS: &[(&str, ExerciseType)] = &[
+        ("run", ExerciseType::Cardio),
+        ("jog", ExerciseType::Cardio),
+        ("sprint", ExerciseType::Cardio),
+        ("walk", ExerciseType::Cardio),
+        ("hike", ExerciseType::Cardio),
+        ("cycling", ExerciseType::Cardio),
+        ("bike", ExerciseType::Cardio),
+        ("rowing", ExerciseType::Cardio),
+        ("swim", ExerciseType::Cardio),
+        ("elliptical", ExerciseType::Cardio),
+        ("treadmill", ExerciseType::Cardio),
+        ("push-up", ExerciseType::BodyWeight),
+        ("pushup", ExerciseType::BodyWeight),
+        ("pull-up", ExerciseType::BodyWeight),
+        ("pullup", ExerciseType::BodyWeight),
+        ("chin-up", ExerciseType::BodyWeight),
+        ("chinup", ExerciseType::BodyWeight),
+        ("dip", ExerciseType::BodyWeight),
+        ("plank", ExerciseType::BodyWeight),
+        ("burpee", ExerciseType::BodyWeight),
+        ("sit-up", ExerciseType::BodyWeight),
+        ("situp", ExerciseType::BodyWeight),
+        ("crunch", ExerciseType::BodyWeight),
+        ("barbell", ExerciseType::Resistance),
+        ("dumbbell", ExerciseType::Resistance),
+        ("kettlebell", ExerciseType::Resistance),
+        ("deadlift", ExerciseType::Resistance),
+        ("press", ExerciseType::Resistance),
+        ("curl", ExerciseType::Resistance),
+        ("farmer", ExerciseType::Resistance),
+    ];
+    KEYWORDS
+        .iter()
+        .map(|&(keyword, type_)| (keyword.to_string(), type_))
+        .collect()
 }
 
 // Implement Default for Config manually to set defaults correctly
@@ -191,13 +422,37 @@ impl Default for Config {
         Self {
             bodyweight: None,
             units: Units::default(),
+            weight_units: None,
+            distance_units: None,
             prompt_for_bodyweight: true, // Explicitly true by default
             streak_interval_days: 1,     // Default to daily streaks
+            week_start: Weekday::Mon,    // Matches chrono's ISO week
+            scheduled_days: Vec::new(),  // No fixed schedule
+            timezone: None,              // UTC
             target_bodyweight: None,
+            weight_increment: None,
+            round_stored_weights: false,
+            merge_same_day_sets: false,
+            default_implicit_type: None, // Unknown exercises need an explicit type
+            type_keywords: default_type_keywords(),
+            seed_on_first_run: true,
+            available_plates: Vec::new(), // Standard set for the configured units
+            distance_decimals: 3, // Metre precision; keeps miles <-> km round trips exact
+            e1rm_decimals: 1,
+            decimal_separator: '.',
+            progression: ProgressionScheme::default(), // Linear, +2.5 per session
+            recovery_hours: 48,
+            muscle_recovery_hours: BTreeMap::new(),
+            max_hr: None,
+            age: None,
             theme: Theme::default(),
             pb_notifications: PbNotificationConfig::default(), // Use nested default
+            validation: ValidationConfig::default(),           // No limits
+            auto_backup_before_sync: false,
+            hard_delete: false, // Soft-delete, so deletions sync and can be restored
             last_sync_timestamp: None,                         // Default to None
             sync_server_url: Some("http://127.0.0.1:3030".to_string()), // Default to server URL
+            pb_command: None,
         }
     }
 }
@@ -258,12 +513,14 @@ pub fn get_config_path() -> Result<PathBuf, ConfigError> {
 ///
 /// - `ConfigError::Io`: If there's an error reading the config file or writing the default config.
 /// - `ConfigError::TomlParse`: If the existing config file content is invalid TOML.
+/// - `ConfigError::InvalidColor`: If the theme uses an unknown color name.
 /// - `ConfigError::TomlSerialize`: If the default config data cannot be serialized to TOML (should not happen).
 pub fn load(config_path: &Path) -> Result<Config, ConfigError> {
     if config_path.exists() {
         let config_content = fs::read_to_string(config_path)?;
         // Use serde(default) to handle missing fields when parsing
         let config: Config = toml::from_str(&config_content).map_err(ConfigError::TomlParse)?;
+        config.theme.validate()?;
         Ok(config)
     } else {
         // Don't print here, let caller decide how to inform user
@@ -293,3 +550,155 @@ pub fn save(config_path: &Path, config: &Config) -> Result<(), ConfigError> {
     fs::write(config_path, config_content)?;
     Ok(())
 }
+
+/// Parses an IANA timezone name (e.g. `"Australia/Sydney"`).
+///
+/// # Errors
+///
+/// Returns `ConfigError::InvalidTimezone` if the name is not a known timezone.
+pub fn parse_timezone(name: &str) -> Result<Tz, ConfigError> {
+    name.trim()
+        .parse::<Tz>()
+        .map_err(|_| ConfigError::InvalidTimezone(name.to_string()))
+}
+
+/// Returns the path of the sync base snapshot stored next to the config file.
+pub fn sync_base_path(config_path: &Path) -> PathBuf {
+    config_path.with_file_name(SYNC_BASE_FILE_NAME)
+}
+
+/// Loads the server config snapshot saved at the last sync, if any.
+///
+/// # Errors
+///
+/// - `ConfigError::Io`: If the snapshot exists but cannot be read.
+/// - `ConfigError::TomlParse`: If the snapshot content is invalid TOML.
+pub fn load_sync_base(config_path: &Path) -> Result<Option<Config>, ConfigError> {
+    let base_path = sync_base_path(config_path);
+    if !base_path.exists() {
+        return Ok(None);
+    }
+    let content = fs::read_to_string(base_path)?;
+    Ok(Some(toml::from_str(&content)?))
+}
+
+/// Picks the server value if it changed since `base`, otherwise keeps the local value.
+fn merge_field<T: PartialEq + Clone>(local: &T, server: &T, base: &T) -> T {
+    if server == base {
+        local.clone()
+    } else {
+        server.clone()
+    }
+}
+
+/// Merges a server config into the local one field by field.
+///
+/// `base` is the server config as of the last sync. Only fields the server changed since
+/// then are taken from `server`; everything else keeps its local value.
+/// `last_sync_timestamp`, `sync_server_url` and `pb_command` always stay local; a shell
+/// command is never taken from the server. So do `hard_delete` and `auto_backup_before_sync`,
+/// so a synced config can't switch a device to irreversible deletes or turn off its backups.
+///
+/// Exposed at crate root as `merge_config`.
+#[must_use]
+pub fn merge_config(local: &Config, server: &Config, base: &Config) -> Config {
+    Config {
+        bodyweight: merge_field(&local.bodyweight, &server.bodyweight, &base.bodyweight),
+        units: merge_field(&local.units, &server.units, &base.units),
+        weight_units: merge_field(&local.weight_units, &server.weight_units, &base.weight_units),
+        distance_units: merge_field(&local.distance_units, &server.distance_units, &base.distance_units),
+        prompt_for_bodyweight: merge_field(
+            &local.prompt_for_bodyweight,
+            &server.prompt_for_bodyweight,
+            &base.prompt_for_bodyweight,
+        ),
+        streak_interval_days: merge_field(
+            &local.streak_interval_days,
+            &server.streak_interval_days,
+            &base.streak_interval_days,
+        ),
+        week_start: merge_field(&local.week_start, &server.week_start, &base.week_start),
+        scheduled_days: merge_field(
+            &local.scheduled_days,
+            &server.scheduled_days,
+            &base.scheduled_days,
+        ),
+        timezone: merge_field(&local.timezone, &server.timezone, &base.timezone),
+        target_bodyweight: merge_field(
+            &local.target_bodyweight,
+            &server.target_bodyweight,
+            &base.target_bodyweight,
+        ),
+        weight_increment: merge_field(
+            &local.weight_increment,
+            &server.weight_increment,
+            &base.weight_increment,
+        ),
+        round_stored_weights: merge_field(
+            &local.round_stored_weights,
+            &server.round_stored_weights,
+            &base.round_stored_weights,
+        ),
+        merge_same_day_sets: merge_field(
+            &local.merge_same_day_sets,
+            &server.merge_same_day_sets,
+            &base.merge_same_day_sets,
+        ),
+        default_implicit_type: merge_field(
+            &local.default_implicit_type,
+            &server.default_implicit_type,
+            &base.default_implicit_type,
+        ),
+        type_keywords: merge_field(&local.type_keywords, &server.type_keywords, &base.type_keywords),
+        seed_on_first_run: merge_field(
+            &local.seed_on_first_run,
+            &server.seed_on_first_run,
+            &base.seed_on_first_run,
+        ),
+        available_plates: merge_field(
+            &local.available_plates,
+            &server.available_plates,
+            &base.available_plates,
+        ),
+        distance_decimals: merge_field(
+            &local.distance_decimals,
+            &server.distance_decimals,
+            &base.distance_decimals,
+        ),
+        e1rm_decimals: merge_field(&local.e1rm_decimals, &server.e1rm_decimals, &base.e1rm_decimals),
+        decimal_separator: merge_field(
+            &local.decimal_separator,
+            &server.decimal_separator,
+            &base.decimal_separator,
+        ),
+        progression: merge_field(
+            &local.progression,
+            &server.progression,
+            &base.progression,
+        ),
+        recovery_hours: merge_field(
+            &local.recovery_hours,
+            &server.recovery_hours,
+            &base.recovery_hours,
+        ),
+        muscle_recovery_hours: merge_field(
+            &local.muscle_recovery_hours,
+            &server.muscle_recovery_hours,
+            &base.muscle_recovery_hours,
+        ),
+        max_hr: merge_field(&local.max_hr, &server.max_hr, &base.max_hr),
+        age: merge_field(&local.age, &server.age, &base.age),
+        theme: merge_field(&local.theme, &server.theme, &base.theme),
+        pb_notifications: merge_field(
+            &local.pb_notifications,
+            &server.pb_notifications,
+            &base.pb_notifications,
+        ),
+        validation: merge_field(&local.validation, &server.validation, &base.validation),
+        auto_backup_before_sync: local.auto_backup_before_sync,
+        hard_delete: local.hard_delete,
+        last_sync_timestamp: local.last_sync_timestamp,
+        sync_server_url: local.sync_server_url.clone(),
+        pb_command: local.pb_command.clone(),
+    }
+}
@@ -1,14 +1,19 @@
 use anyhow::Result as AnyhowResult; // Use AnyhowResult alias where needed to avoid conflict
-use chrono::{DateTime, NaiveDate, Utc};
+use chrono::{DateTime, NaiveDate, Utc, Weekday};
+use chrono_tz::Tz;
+use rusqlite::functions::FunctionFlags;
 use rusqlite::{named_params, params, Connection, OptionalExtension, Row, ToSql};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 use std::collections::{HashMap, HashSet};
 use std::error::Error as StdError; // Use alias for standard Error trait
 use std::fmt;
+use std::ops::{Deref, DerefMut};
 use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard, PoisonError};
 use thiserror::Error;
 use rusqlite::Transaction;
+use r2d2_sqlite::SqliteConnectionManager;
 
 // Renamed from DbError to avoid repetition
 #[derive(Error, Debug)]
@@ -47,6 +52,26 @@ pub enum Error {
     InvalidParameterCount(usize, usize),
     #[error("Invalid data conversion: {0}")]
     Conversion(String),
+    #[error("Tag '{1}' not found on workout ID {0}")]
+    TagNotFound(i64, String),
+    #[error("Failed to get a pooled database connection: {0}")]
+    Pool(#[from] r2d2::Error),
+}
+
+/// How a multi-muscle filter combines its muscles, see `list_exercises`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum MuscleMatch {
+    Any, // At least one of the muscles
+    #[default]
+    All, // Every muscle
+}
+
+/// Order of `list_exercises` results.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ExerciseSort {
+    #[default]
+    Name, // Favorites first, then by name
+    Custom, // By `sort_order`; exercises without one follow, by name
 }
 
 #[derive(Debug, PartialEq, Serialize, Deserialize, Eq, Clone, Copy)]
@@ -96,10 +121,141 @@ pub struct BodyweightEntryForSync {
     pub _id: Option<String>,
     pub timestamp: DateTime<Utc>, // Actual measurement time
     pub weight: f64,
+    #[serde(default)]
+    pub body_fat_pct: Option<f64>,
+    #[serde(default)]
+    pub muscle_mass: Option<f64>,
+    #[serde(default)]
+    pub notes: Option<String>,
     pub deleted: bool,
     pub last_edited: DateTime<Utc>, // Time of this sync record modification
 }
 
+/// A bodyweight entry with optional body-composition readings.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BodyMeasurement {
+    pub id: i64,
+    pub timestamp: DateTime<Utc>,
+    pub weight: f64,
+    pub body_fat_pct: Option<f64>,
+    pub muscle_mass: Option<f64>, // Same units as weight
+    pub notes: Option<String>,    // Context, e.g. "morning fasted"
+}
+
+/// Body-composition value tracked over time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum BodyMetric {
+    Weight,
+    BodyFatPct,
+    MuscleMass,
+}
+
+impl BodyMetric {
+    const fn column(self) -> &'static str {
+        match self {
+            Self::Weight => "weight",
+            Self::BodyFatPct => "body_fat_pct",
+            Self::MuscleMass => "muscle_mass",
+        }
+    }
+}
+
+/// Metric tracked by personal-best queries.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PbMetric {
+    Weight, // Effective weight (includes bodyweight for BodyWeight exercises)
+    Reps,
+    Duration,
+    Distance,
+}
+
+impl PbMetric {
+    pub const ALL: [Self; 4] = [Self::Weight, Self::Reps, Self::Duration, Self::Distance];
+
+    /// Name used when storing the metric, e.g. in `ExerciseDefinition::pb_metrics`.
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Weight => "weight",
+            Self::Reps => "reps",
+            Self::Duration => "duration",
+            Self::Distance => "distance",
+        }
+    }
+
+    /// SQL expression for the metric's value on a `workouts w JOIN exercises e` row.
+    const fn sql_value(self) -> &'static str {
+        match self {
+            Self::Weight => {
+                "CASE e.type WHEN 'body-weight' THEN COALESCE(w.weight, 0) + COALESCE(w.bodyweight, 0) ELSE w.weight END"
+            }
+            Self::Reps => "w.reps",
+            Self::Duration => "w.duration_minutes",
+            Self::Distance => "w.distance",
+        }
+    }
+}
+
+/// Period used to bucket volume results.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum VolumePeriod {
+    #[default]
+    Daily,
+    /// Weeks, bucketed by the day the week starts on (`VolumeFilters::week_start`, Monday by default).
+    Weekly,
+    /// Calendar months, bucketed by the first day of the month.
+    Monthly,
+}
+
+impl VolumePeriod {
+    /// SQL expression yielding the local period start date (`YYYY-MM-DD`) for `w.timestamp`.
+    fn sql_period_start(self, week_start: Weekday, tz: Tz) -> String {
+        let local_ts = local_timestamp_sql("w.timestamp", tz);
+        match self {
+            Self::Daily => format!("date({local_ts})"),
+            // 'weekday N' advances to the last day of the week (or stays on it), so going back 6 days
+            // lands on the first day. SQLite numbers weekdays from Sunday = 0.
+            Self::Weekly => {
+                let week_end = (week_start.num_days_from_sunday() + 6) % 7;
+                format!("date({local_ts}, 'weekday {week_end}', '-6 days')")
+            }
+            Self::Monthly => format!("date({local_ts}, 'start of month')"),
+        }
+    }
+}
+
+/// SQLite time value of a UTC timestamp `column` in local time in `tz`, using the
+/// `local_datetime` function (see `register_functions`). For UTC this is just the column,
+/// so UTC behaves exactly as before.
+fn local_timestamp_sql(column: &str, tz: Tz) -> String {
+    if tz == Tz::UTC {
+        column.to_string()
+    } else {
+        format!("local_datetime({column}, '{}')", tz.name())
+    }
+}
+
+/// Registers the app's SQL functions on a connection. `init` and the connections of
+/// `open_pool` do this; other connections must call it before running date-filtered queries.
+///
+/// - `local_datetime(timestamp, tz)`: an RFC 3339 `timestamp` as local `YYYY-MM-DD HH:MM:SS`
+///   in the IANA timezone `tz`, with the offset in effect at that instant (so across DST
+///   changes too). NULL if either argument is invalid.
+pub fn register_functions(conn: &Connection) -> rusqlite::Result<()> {
+    conn.create_scalar_function(
+        "local_datetime",
+        2,
+        FunctionFlags::SQLITE_UTF8 | FunctionFlags::SQLITE_DETERMINISTIC,
+        |ctx| {
+            let timestamp = ctx.get::<String>(0)?;
+            let tz = ctx.get::<String>(1)?;
+            Ok(DateTime::parse_from_rfc3339(&timestamp)
+                .ok()
+                .zip(tz.parse::<Tz>().ok())
+                .map(|(ts, tz)| ts.with_timezone(&tz).format("%Y-%m-%d %H:%M:%S").to_string()))
+        },
+    )
+}
+
 #[derive(Default, Debug)]
 pub struct VolumeFilters<'a> {
     pub exercise_name: Option<&'a str>,
@@ -108,11 +264,14 @@ pub struct VolumeFilters<'a> {
     pub exercise_type: Option<ExerciseType>,
     pub muscle: Option<&'a str>,
     pub limit_days: Option<u32>,
+    pub aggregation: VolumePeriod,
+    pub week_start: Option<Weekday>, // First day of weekly buckets, None = Monday
 }
 
 /// Calculates the daily volume (sets * reps * weight) for exercises matching the filters.
 ///
 /// Volume is calculated only for `Resistance` and `BodyWeight` exercises.
+/// Rows are grouped per `filters.aggregation` period; the returned date is the period start.
 /// Results are ordered by date descending, then exercise name ascending.
 /// Only considers non-deleted workouts and exercises.
 ///
@@ -133,10 +292,13 @@ pub struct VolumeFilters<'a> {
 pub fn calculate_daily_volume_filtered(
     conn: &Connection,
     filters: &VolumeFilters,
+    tz: Tz,
 ) -> Result<Vec<(NaiveDate, String, f64)>, Error> {
-    let mut sql = "
+    let local_date = format!("date({})", local_timestamp_sql("w.timestamp", tz));
+    let mut sql = format!(
+        "
         SELECT
-            date(w.timestamp) as workout_date,
+            {} as workout_date,
             w.exercise_name,
             SUM(CASE e.type
                     WHEN 'resistance' THEN COALESCE(w.sets, 1) * COALESCE(w.reps, 0) * COALESCE(w.weight, 0)
@@ -145,8 +307,11 @@ pub fn calculate_daily_volume_filtered(
                 END) as daily_volume
         FROM workouts w
         LEFT JOIN exercises e ON w.exercise_name = e.name
-        WHERE w.deleted = FALSE AND e.deleted = FALSE" // Filter out deleted
-        .to_string();
+        WHERE w.deleted = FALSE AND e.deleted = FALSE AND w.is_warmup = FALSE AND w.is_failure = FALSE", // Filter out deleted, warmups and failed attempts
+        filters
+            .aggregation
+            .sql_period_start(filters.week_start.unwrap_or(Weekday::Mon), tz)
+    );
 
     let mut params_map: HashMap<String, Box<dyn ToSql>> = HashMap::new();
 
@@ -155,14 +320,14 @@ pub fn calculate_daily_volume_filtered(
         params_map.insert(":ex_name".into(), Box::new(name.to_string()));
     }
     if let Some(start) = filters.start_date {
-        sql.push_str(" AND date(w.timestamp) >= date(:start_date)");
+        sql.push_str(&format!(" AND {local_date} >= date(:start_date)"));
         params_map.insert(
             ":start_date".into(),
             Box::new(start.format("%Y-%m-%d").to_string()),
         );
     }
     if let Some(end) = filters.end_date {
-        sql.push_str(" AND date(w.timestamp) <= date(:end_date)");
+        sql.push_str(&format!(" AND {local_date} <= date(:end_date)"));
         params_map.insert(
             ":end_date".into(),
             Box::new(end.format("%Y-%m-%d").to_string()),
@@ -249,10 +414,80 @@ pub struct Workout {
     pub distance: Option<f64>,
     pub notes: Option<String>,
     pub exercise_type: Option<ExerciseType>, // Populated by JOIN
+    #[serde(default)]
+    pub is_warmup: bool, // Warmup sets are excluded from PBs and volume
+    #[serde(default)]
+    pub is_amrap: bool, // As-many-reps-as-possible set, preferred as the top set
+    #[serde(default)]
+    pub is_failure: bool, // Missed attempt: shown, but excluded from PBs and volume
+    #[serde(default)]
+    pub avg_heart_rate: Option<i64>, // bpm, used for cardio intensity zones
+    #[serde(default)]
+    pub max_heart_rate: Option<i64>, // bpm
     pub deleted: bool,               
     pub last_edited: DateTime<Utc>, 
 }
 
+/// Maximum number of entries kept in the undo log.
+pub const UNDO_LOG_DEPTH: u32 = 20;
+
+/// Kind of mutating operation recorded in the undo log.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum UndoAction {
+    AddWorkout,
+    DeleteWorkouts,
+    MergeWorkout, // An add absorbed by an existing entry, see `merge_same_day_sets`
+}
+
+impl UndoAction {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::AddWorkout => "add_workout",
+            Self::DeleteWorkouts => "delete_workouts",
+            Self::MergeWorkout => "merge_workout",
+        }
+    }
+}
+
+impl TryFrom<&str> for UndoAction {
+    type Error = Error;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "add_workout" => Ok(Self::AddWorkout),
+            "delete_workouts" => Ok(Self::DeleteWorkouts),
+            "merge_workout" => Ok(Self::MergeWorkout),
+            _ => Err(Error::Conversion(format!("Invalid undo action '{value}' from DB"))),
+        }
+    }
+}
+
+/// A recorded operation that `undo_last` can reverse.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct UndoEntry {
+    pub id: i64,
+    pub action: UndoAction,
+    pub workout_ids: Vec<i64>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A single interval of a cardio workout.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WorkoutSplit {
+    pub workout_id: i64,
+    pub split_index: i64, // 1-based order within the workout
+    pub distance: f64,    // Always stored as km
+    pub duration_seconds: i64,
+}
+
+impl WorkoutSplit {
+    /// Pace in seconds per km, or `None` for a zero-distance split.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn pace_seconds_per_km(&self) -> Option<f64> {
+        (self.distance > 0.0).then(|| self.duration_seconds as f64 / self.distance)
+    }
+}
+
 impl Workout {
     pub fn calculate_effective_weight(&self) -> Option<f64> {
         match self.exercise_type {
@@ -264,7 +499,7 @@ impl Workout {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct ExerciseDefinition {
     pub id: i64,
     pub _id: Option<String>,
@@ -275,10 +510,80 @@ pub struct ExerciseDefinition {
     pub log_reps: bool,
     pub log_duration: bool,
     pub log_distance: bool,
+    #[serde(default)]
+    pub met_value: Option<f64>, // Metabolic equivalent, used for calorie estimates
+    #[serde(default)]
+    pub description: Option<String>, // Form cues, setup instructions
+    #[serde(default)]
+    pub category: Option<String>, // Programming group, e.g. "Push", "Pull", "Legs"
+    #[serde(default)]
+    pub pb_metrics: Option<Vec<PbMetric>>, // Metrics that count for PBs; None = global config
+    #[serde(default)]
+    pub default_sets: Option<i64>, // Prefilled when logging, e.g. 3x12 accessory work
+    #[serde(default)]
+    pub default_reps: Option<i64>,
+    #[serde(default)]
+    pub default_weight: Option<f64>,
+    #[serde(default)]
+    pub is_favorite: bool, // Pinned: listed first by `list_exercises`
+    #[serde(default)]
+    pub parent_exercise: Option<String>, // Canonical name of the exercise this is a variation of
+    #[serde(default)]
+    pub weight_is_per_side: bool, // Weight is entered per side; the total is stored
+    #[serde(default)]
+    pub carriage_weight: Option<f64>, // Sled/carriage weight added to the per-side total
+    #[serde(default)]
+    pub sort_order: Option<i64>, // Manual position for `ExerciseSort::Custom`, lowest first
     pub deleted: bool,
     pub last_edited: DateTime<Utc>
 }
 
+impl ExerciseDefinition {
+    /// The values to prefill when logging this exercise.
+    pub fn defaults(&self) -> ExerciseDefaults {
+        ExerciseDefaults {
+            sets: self.default_sets,
+            reps: self.default_reps,
+            weight: self.default_weight,
+        }
+    }
+
+    /// Total weight for a weight entered when logging: per-side weights are doubled and
+    /// the carriage weight added, other exercises keep the entered weight.
+    pub fn total_weight(&self, entered: f64) -> f64 {
+        if self.weight_is_per_side {
+            entered * 2.0 + self.carriage_weight.unwrap_or(0.0)
+        } else {
+            entered
+        }
+    }
+
+    /// Inverse of `total_weight`, for showing a stored total as the weight to enter.
+    pub fn entered_weight(&self, total: f64) -> f64 {
+        if self.weight_is_per_side {
+            (total - self.carriage_weight.unwrap_or(0.0)) / 2.0
+        } else {
+            total
+        }
+    }
+}
+
+/// Default sets/reps/weight of an exercise, used as placeholders when logging it.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub struct ExerciseDefaults {
+    pub sets: Option<i64>,
+    pub reps: Option<i64>,
+    pub weight: Option<f64>,
+}
+
+/// Columns selected for every `Workout` query (`workouts w LEFT JOIN exercises e`).
+const WORKOUT_COLUMNS: &str =
+    "w.id, w._id, w.timestamp, w.exercise_name, w.sets, w.reps, w.weight, w.duration_minutes, w.distance, w.bodyweight, w.notes, e.type, w.is_warmup, w.is_amrap, w.is_failure, w.avg_heart_rate, w.max_heart_rate, w.deleted, w.last_edited";
+
+/// Columns selected for every `ExerciseDefinition` query.
+const EXERCISE_COLUMNS: &str =
+    "id, _id, name, type, muscles, log_weight, log_reps, log_duration, log_distance, met_value, description, category, pb_metrics, default_sets, default_reps, default_weight, is_favorite, parent_exercise, weight_is_per_side, carriage_weight, sort_order, deleted, last_edited";
+
 const DB_FILE_NAME: &str = "workouts.sqlite";
 
 pub fn get_db_path() -> Result<PathBuf, Error> {
@@ -301,6 +606,114 @@ pub fn open_db<P: AsRef<Path>>(path: P) -> Result<Connection, Error> {
     Ok(conn)
 }
 
+/// Writes a consistent copy of the database to `dest` with `VACUUM INTO`.
+/// Fails if `dest` already exists.
+pub fn backup_to(conn: &Connection, dest: &Path) -> Result<(), Error> {
+    let dest = dest
+        .to_str()
+        .ok_or_else(|| Error::Conversion(format!("Backup path is not valid UTF-8: {dest:?}")))?;
+    conn.execute("VACUUM INTO ?1", params![dest])
+        .map_err(Error::QueryFailed)?;
+    Ok(())
+}
+
+pub type ConnectionPool = r2d2::Pool<SqliteConnectionManager>;
+
+/// Opens a pool of up to `size` connections to the database file at `path`.
+///
+/// Connections use WAL journaling and a busy timeout so concurrent readers don't
+/// block each other and writers wait for the lock instead of failing immediately.
+pub fn open_pool<P: AsRef<Path>>(path: P, size: u32) -> Result<ConnectionPool, Error> {
+    let manager = SqliteConnectionManager::file(path).with_init(|conn| {
+        conn.busy_timeout(std::time::Duration::from_secs(5))?;
+        register_functions(conn)?;
+        conn.execute_batch("PRAGMA journal_mode = WAL;")
+    });
+    let pool = r2d2::Pool::builder().max_size(size).build(manager)?;
+    Ok(pool)
+}
+
+/// Where `AppService` gets its database connections from.
+pub enum ConnectionSource {
+    /// One connection shared by all calls (CLI/TUI).
+    Single(Mutex<Connection>),
+    /// A connection pool, so calls from multiple threads can run concurrently.
+    Pool(ConnectionPool),
+}
+
+impl From<Connection> for ConnectionSource {
+    fn from(conn: Connection) -> Self {
+        Self::Single(Mutex::new(conn))
+    }
+}
+
+impl From<ConnectionPool> for ConnectionSource {
+    fn from(pool: ConnectionPool) -> Self {
+        Self::Pool(pool)
+    }
+}
+
+impl ConnectionSource {
+    /// Borrows a connection. For `Single` this locks the shared connection until the guard is dropped,
+    /// so callers must not request a second connection while holding one.
+    pub fn get(&self) -> Result<ConnectionGuard<'_>, Error> {
+        match self {
+            Self::Single(conn) => Ok(ConnectionGuard::Single(
+                conn.lock().unwrap_or_else(PoisonError::into_inner),
+            )),
+            Self::Pool(pool) => Ok(ConnectionGuard::Pooled(pool.get()?)),
+        }
+    }
+}
+
+/// A borrowed connection from a `ConnectionSource`.
+pub enum ConnectionGuard<'a> {
+    Single(MutexGuard<'a, Connection>),
+    Pooled(r2d2::PooledConnection<SqliteConnectionManager>),
+}
+
+impl Deref for ConnectionGuard<'_> {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        match self {
+            Self::Single(conn) => conn,
+            Self::Pooled(conn) => conn,
+        }
+    }
+}
+
+impl DerefMut for ConnectionGuard<'_> {
+    fn deref_mut(&mut self) -> &mut Connection {
+        match self {
+            Self::Single(conn) => conn,
+            Self::Pooled(conn) => conn,
+        }
+    }
+}
+
+/// Adds a column with the given definition to the specified table if it doesn't exist.
+fn add_column_if_not_exists(
+    conn: &Connection,
+    table_name: &str,
+    column_name: &str,
+    column_def: &str,
+) -> Result<(), Error> {
+    let mut stmt = conn.prepare(&format!("PRAGMA table_info({table_name})"))?;
+    let column_exists = stmt
+        .query_map([], |row| row.get::<_, String>(1))?
+        .any(|col_res| col_res.is_ok_and(|col| col == column_name));
+
+    if !column_exists {
+        println!("Adding '{column_name}' column to {table_name} table...");
+        conn.execute(
+            &format!("ALTER TABLE {table_name} ADD COLUMN {column_name} {column_def}"),
+            [],
+        )?;
+    }
+    Ok(())
+}
+
 /// Adds a 'deleted' column to the specified table if it doesn't exist.
 fn add_deleted_column_if_not_exists(conn: &Connection, table_name: &str) -> Result<(), Error> {
     let mut stmt = conn.prepare(&format!("PRAGMA table_info({table_name})"))?;
@@ -381,6 +794,7 @@ fn add__id_column_if_not_exists(conn: &Connection, table_name: &str) -> Result<(
 
 
 pub fn init(conn: &Connection) -> Result<(), Error> {
+    register_functions(conn).map_err(Error::Connection)?;
     conn.execute_batch(
         "BEGIN;
         CREATE TABLE IF NOT EXISTS exercises (
@@ -392,6 +806,18 @@ pub fn init(conn: &Connection) -> Result<(), Error> {
             log_reps BOOLEAN NOT NULL DEFAULT TRUE,
             log_duration BOOLEAN NOT NULL DEFAULT TRUE,
             log_distance BOOLEAN NOT NULL DEFAULT TRUE,
+            met_value REAL,
+            description TEXT,
+            category TEXT,
+            pb_metrics TEXT,
+            default_sets INTEGER,
+            default_reps INTEGER,
+            default_weight REAL,
+            is_favorite BOOLEAN NOT NULL DEFAULT FALSE,
+            parent_exercise TEXT COLLATE NOCASE,
+            weight_is_per_side BOOLEAN NOT NULL DEFAULT FALSE,
+            carriage_weight REAL,
+            sort_order INTEGER,
             deleted BOOLEAN NOT NULL DEFAULT FALSE,
             last_edited TEXT NOT NULL 
         );
@@ -406,6 +832,11 @@ pub fn init(conn: &Connection) -> Result<(), Error> {
             distance REAL,
             bodyweight REAL, 
             notes TEXT,
+            is_warmup BOOLEAN NOT NULL DEFAULT FALSE,
+            is_amrap BOOLEAN NOT NULL DEFAULT FALSE,
+            is_failure BOOLEAN NOT NULL DEFAULT FALSE,
+            avg_heart_rate INTEGER,
+            max_heart_rate INTEGER,
             deleted BOOLEAN NOT NULL DEFAULT FALSE,
             last_edited TEXT NOT NULL
         );
@@ -419,13 +850,64 @@ pub fn init(conn: &Connection) -> Result<(), Error> {
             id INTEGER PRIMARY KEY AUTOINCREMENT,
             timestamp TEXT NOT NULL UNIQUE,
             weight REAL NOT NULL,
+            body_fat_pct REAL,
+            muscle_mass REAL,
+            notes TEXT,
             deleted BOOLEAN NOT NULL DEFAULT FALSE,
             last_edited TEXT NOT NULL
         );
+        CREATE TABLE IF NOT EXISTS workout_tags (
+            workout_id INTEGER NOT NULL REFERENCES workouts(id),
+            tag TEXT NOT NULL,
+            PRIMARY KEY (workout_id, tag)
+        );
+        CREATE TABLE IF NOT EXISTS workout_splits (
+            workout_id INTEGER NOT NULL REFERENCES workouts(id),
+            split_index INTEGER NOT NULL,
+            distance REAL NOT NULL,
+            duration_seconds INTEGER NOT NULL,
+            PRIMARY KEY (workout_id, split_index)
+        );
+        CREATE TABLE IF NOT EXISTS workout_metadata (
+            workout_id INTEGER NOT NULL REFERENCES workouts(id),
+            key TEXT NOT NULL,
+            value TEXT NOT NULL,
+            PRIMARY KEY (workout_id, key)
+        );
+        CREATE TABLE IF NOT EXISTS workout_history (
+            history_id INTEGER PRIMARY KEY AUTOINCREMENT,
+            id INTEGER NOT NULL REFERENCES workouts(id), -- Workout ID
+            _id TEXT,
+            timestamp TEXT NOT NULL,
+            exercise_name TEXT NOT NULL COLLATE NOCASE,
+            sets INTEGER,
+            reps INTEGER,
+            weight REAL,
+            duration_minutes INTEGER,
+            distance REAL,
+            bodyweight REAL,
+            notes TEXT,
+            is_warmup BOOLEAN NOT NULL DEFAULT FALSE,
+            is_amrap BOOLEAN NOT NULL DEFAULT FALSE,
+            is_failure BOOLEAN NOT NULL DEFAULT FALSE,
+            avg_heart_rate INTEGER,
+            max_heart_rate INTEGER,
+            deleted BOOLEAN NOT NULL DEFAULT FALSE,
+            last_edited TEXT NOT NULL,
+            edited_at TEXT NOT NULL -- When this version was replaced
+        );
+        CREATE TABLE IF NOT EXISTS undo_log (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            action TEXT NOT NULL,
+            workout_ids TEXT NOT NULL, -- Comma-separated workout IDs
+            created_at TEXT NOT NULL
+        );
         CREATE INDEX IF NOT EXISTS idx_workouts_timestamp ON workouts(timestamp);
         CREATE INDEX IF NOT EXISTS idx_workouts_exercise_name ON workouts(exercise_name);
         CREATE INDEX IF NOT EXISTS idx_aliases_exercise_name ON aliases(exercise_name);
         CREATE INDEX IF NOT EXISTS idx_bodyweights_timestamp ON bodyweights(timestamp);
+        CREATE INDEX IF NOT EXISTS idx_workout_tags_tag ON workout_tags(tag);
+        CREATE INDEX IF NOT EXISTS idx_workout_history_id ON workout_history(id);
         COMMIT;",
     )?;
 
@@ -452,6 +934,30 @@ pub fn init(conn: &Connection) -> Result<(), Error> {
     add__id_column_if_not_exists(conn, "aliases")?;
     add__id_column_if_not_exists(conn, "bodyweights")?;
 
+    add_column_if_not_exists(conn, "exercises", "met_value", "REAL")?;
+    add_column_if_not_exists(conn, "exercises", "description", "TEXT")?;
+    add_column_if_not_exists(conn, "exercises", "category", "TEXT")?;
+    add_column_if_not_exists(conn, "exercises", "pb_metrics", "TEXT")?;
+    add_column_if_not_exists(conn, "exercises", "default_sets", "INTEGER")?;
+    add_column_if_not_exists(conn, "exercises", "default_reps", "INTEGER")?;
+    add_column_if_not_exists(conn, "exercises", "default_weight", "REAL")?;
+    add_column_if_not_exists(conn, "exercises", "is_favorite", "BOOLEAN NOT NULL DEFAULT FALSE")?;
+    add_column_if_not_exists(conn, "exercises", "parent_exercise", "TEXT COLLATE NOCASE")?;
+    add_column_if_not_exists(conn, "exercises", "weight_is_per_side", "BOOLEAN NOT NULL DEFAULT FALSE")?;
+    add_column_if_not_exists(conn, "exercises", "carriage_weight", "REAL")?;
+    add_column_if_not_exists(conn, "exercises", "sort_order", "INTEGER")?;
+    add_column_if_not_exists(conn, "workouts", "is_warmup", "BOOLEAN NOT NULL DEFAULT FALSE")?;
+    add_column_if_not_exists(conn, "workouts", "is_amrap", "BOOLEAN NOT NULL DEFAULT FALSE")?;
+    add_column_if_not_exists(conn, "workouts", "is_failure", "BOOLEAN NOT NULL DEFAULT FALSE")?;
+    add_column_if_not_exists(conn, "workout_history", "is_failure", "BOOLEAN NOT NULL DEFAULT FALSE")?;
+    for table in ["workouts", "workout_history"] {
+        add_column_if_not_exists(conn, table, "avg_heart_rate", "INTEGER")?;
+        add_column_if_not_exists(conn, table, "max_heart_rate", "INTEGER")?;
+    }
+    add_column_if_not_exists(conn, "bodyweights", "body_fat_pct", "REAL")?;
+    add_column_if_not_exists(conn, "bodyweights", "muscle_mass", "REAL")?;
+    add_column_if_not_exists(conn, "bodyweights", "notes", "TEXT")?;
+
     Ok(())
 }
 
@@ -491,6 +997,11 @@ pub struct NewWorkoutData<'a> {
     pub duration: Option<i64>,
     pub distance: Option<f64>,
     pub notes: Option<&'a str>, 
+    pub is_warmup: bool,
+    pub is_amrap: bool,
+    pub is_failure: bool,
+    pub avg_heart_rate: Option<i64>,
+    pub max_heart_rate: Option<i64>,
 }
 
 pub fn add_workout(conn: &Connection, data: &NewWorkoutData) -> Result<i64, Error> {
@@ -499,8 +1010,8 @@ pub fn add_workout(conn: &Connection, data: &NewWorkoutData) -> Result<i64, Erro
     let now_str = Utc::now().to_rfc3339();
     let uuid_str = Uuid::new_v4().to_string();
     conn.execute(
-        "INSERT INTO workouts (_id, timestamp, exercise_name, sets, reps, weight, duration_minutes, distance, bodyweight, notes, last_edited)
-         VALUES (:_id, :ts, :ex_name, :sets, :reps, :weight, :duration, :distance, :bw, :notes, :last_edited)", 
+        "INSERT INTO workouts (_id, timestamp, exercise_name, sets, reps, weight, duration_minutes, distance, bodyweight, notes, is_warmup, is_amrap, is_failure, avg_heart_rate, max_heart_rate, last_edited)
+         VALUES (:_id, :ts, :ex_name, :sets, :reps, :weight, :duration, :distance, :bw, :notes, :warmup, :amrap, :failure, :avg_hr, :max_hr, :last_edited)", 
         named_params! {
             ":_id": uuid_str,
             ":ts": timestamp_str,
@@ -512,17 +1023,39 @@ pub fn add_workout(conn: &Connection, data: &NewWorkoutData) -> Result<i64, Erro
             ":distance": data.distance,
             ":bw": data.bodyweight_to_use,
             ":notes": data.notes,
+            ":warmup": data.is_warmup,
+            ":amrap": data.is_amrap,
+            ":failure": data.is_failure,
+            ":avg_hr": data.avg_heart_rate,
+            ":max_hr": data.max_heart_rate,
             ":last_edited": now_str
         },
     ).map_err(Error::InsertFailed)?;
     Ok(conn.last_insert_rowid())
 }
 
+/// Inserts several workout entries in one transaction, returning their IDs in order.
+pub fn add_workouts(conn: &mut Connection, data: &[NewWorkoutData]) -> Result<Vec<i64>, Error> {
+    let tx = conn.transaction().map_err(Error::Connection)?;
+    let ids = data
+        .iter()
+        .map(|entry| add_workout(&tx, entry))
+        .collect::<Result<Vec<_>, _>>()?;
+    tx.commit().map_err(Error::Connection)?;
+    Ok(ids)
+}
+
+#[allow(clippy::too_many_arguments)]
 pub fn update_workout(
-    conn: &Connection,
+    conn: &mut Connection,
     workout: Workout,
     new_name: Option<String>,
     new_timestamp: Option<DateTime<Utc>>,
+    new_is_warmup: Option<bool>,
+    new_is_amrap: Option<bool>,
+    new_is_failure: Option<bool>,
+    new_avg_heart_rate: Option<Option<i64>>,
+    new_max_heart_rate: Option<Option<i64>>,
 ) -> Result<u64, Error> {
     let Workout {
         id,
@@ -571,10 +1104,30 @@ pub fn update_workout(
         updates.push("notes = :notes");
         params_map.insert(":notes".into(), Box::new(new_notes));
     }
+    if let Some(avg_hr) = new_avg_heart_rate {
+        updates.push("avg_heart_rate = :avg_hr");
+        params_map.insert(":avg_hr".into(), Box::new(avg_hr));
+    }
+    if let Some(max_hr) = new_max_heart_rate {
+        updates.push("max_heart_rate = :max_hr");
+        params_map.insert(":max_hr".into(), Box::new(max_hr));
+    }
     if let Some(ts) = new_timestamp {
         updates.push("timestamp = :ts");
         params_map.insert(":ts".into(), Box::new(ts.to_rfc3339()));
     }
+    if let Some(warmup) = new_is_warmup {
+        updates.push("is_warmup = :is_warmup");
+        params_map.insert(":is_warmup".into(), Box::new(warmup));
+    }
+    if let Some(amrap) = new_is_amrap {
+        updates.push("is_amrap = :is_amrap");
+        params_map.insert(":is_amrap".into(), Box::new(amrap));
+    }
+    if let Some(failure) = new_is_failure {
+        updates.push("is_failure = :is_failure");
+        params_map.insert(":is_failure".into(), Box::new(failure));
+    }
 
     if updates.is_empty() {
          return Ok(0); 
@@ -591,18 +1144,115 @@ pub fn update_workout(
         .map(|(k, v)| (k.as_str(), v.as_ref()))
         .collect();
 
-    let rows_affected = conn
+    let tx = conn.transaction().map_err(Error::Connection)?;
+    let before = get_workout_by_id(&tx, id)?.ok_or(Error::WorkoutNotFound(id))?;
+    let rows_affected = tx
         .execute(&sql, params_for_exec.as_slice())
         .map_err(Error::UpdateFailed)?;
+    let after = get_workout_by_id(&tx, id)?.ok_or(Error::WorkoutNotFound(id))?;
+    if !same_workout_content(&before, &after) {
+        record_workout_version(&tx, &before)?;
+    }
+    tx.commit().map_err(Error::Connection)?;
+    Ok(rows_affected as u64)
+}
+
+/// True if two versions of a workout differ only in `last_edited`.
+fn same_workout_content(a: &Workout, b: &Workout) -> bool {
+    a.timestamp == b.timestamp
+        && a.exercise_name == b.exercise_name
+        && a.sets == b.sets
+        && a.reps == b.reps
+        && a.weight == b.weight
+        && a.duration_minutes == b.duration_minutes
+        && a.distance == b.distance
+        && a.bodyweight == b.bodyweight
+        && a.notes == b.notes
+        && a.is_warmup == b.is_warmup
+        && a.is_amrap == b.is_amrap
+        && a.is_failure == b.is_failure
+        && a.avg_heart_rate == b.avg_heart_rate
+        && a.max_heart_rate == b.max_heart_rate
+}
+
+/// Appends the pre-edit version of a workout to `workout_history`.
+fn record_workout_version(conn: &Connection, before: &Workout) -> Result<(), Error> {
+    conn.execute(
+        "INSERT INTO workout_history (id, _id, timestamp, exercise_name, sets, reps, weight, duration_minutes, distance, bodyweight, notes, is_warmup, is_amrap, is_failure, avg_heart_rate, max_heart_rate, deleted, last_edited, edited_at)
+         VALUES (:id, :_id, :ts, :name, :sets, :reps, :weight, :dur, :dist, :bw, :notes, :warmup, :amrap, :failure, :avg_hr, :max_hr, :del, :le, :edited)",
+        named_params! {
+            ":id": before.id, ":_id": before._id, ":ts": before.timestamp.to_rfc3339(), ":name": before.exercise_name,
+            ":sets": before.sets, ":reps": before.reps, ":weight": before.weight, ":dur": before.duration_minutes,
+            ":dist": before.distance, ":bw": before.bodyweight, ":notes": before.notes, ":warmup": before.is_warmup,
+            ":amrap": before.is_amrap, ":failure": before.is_failure, ":avg_hr": before.avg_heart_rate,
+            ":max_hr": before.max_heart_rate, ":del": before.deleted, ":le": before.last_edited.to_rfc3339(),
+            ":edited": Utc::now().to_rfc3339(),
+        },
+    )
+    .map_err(Error::InsertFailed)?;
+    Ok(())
+}
+
+/// Past versions of a workout, newest first, as `(replaced at, version)` pairs. Each version
+/// is the workout as it was before the edit made at that time.
+pub fn get_workout_history(
+    conn: &Connection,
+    id: i64,
+) -> Result<Vec<(DateTime<Utc>, Workout)>, Error> {
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {WORKOUT_COLUMNS}, w.edited_at
+             FROM workout_history w LEFT JOIN exercises e ON w.exercise_name = e.name
+             WHERE w.id = ?1
+             ORDER BY w.history_id DESC"
+        ))
+        .map_err(Error::QueryFailed)?;
+    let rows = stmt
+        .query_map(params![id], |row| {
+            Ok((parse_datetime_from_string(row.get("edited_at")?)?, map_row_to_workout(row)?))
+        })
+        .map_err(Error::QueryFailed)?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(map_collect_error);
+    rows
+}
 
+/// Puts an active workout back to its most recent version in `workout_history` and drops
+/// that version from the history.
+pub fn revert_workout(conn: &Connection, id: i64) -> Result<u64, Error> {
+    let Some(history_id) = conn
+        .query_row(
+            "SELECT MAX(history_id) FROM workout_history WHERE id = ?1",
+            params![id],
+            |row| row.get::<_, Option<i64>>(0),
+        )
+        .map_err(Error::QueryFailed)?
+    else {
+        return Err(Error::WorkoutNotFound(id));
+    };
+    let rows_affected = conn
+        .execute(
+            "UPDATE workouts SET (timestamp, exercise_name, sets, reps, weight, duration_minutes, distance,
+                                  bodyweight, notes, is_warmup, is_amrap, is_failure, avg_heart_rate, max_heart_rate) =
+                (SELECT timestamp, exercise_name, sets, reps, weight, duration_minutes, distance,
+                        bodyweight, notes, is_warmup, is_amrap, is_failure, avg_heart_rate, max_heart_rate
+                 FROM workout_history WHERE history_id = ?1),
+                last_edited = ?2
+             WHERE id = ?3 AND deleted = FALSE",
+            params![history_id, Utc::now().to_rfc3339(), id],
+        )
+        .map_err(Error::UpdateFailed)?;
     if rows_affected == 0 {
-        Err(Error::WorkoutNotFound(id)) 
-    } else {
-        Ok(rows_affected as u64)
+        return Err(Error::WorkoutNotFound(id));
     }
+    conn.execute("DELETE FROM workout_history WHERE history_id = ?1", params![history_id])
+        .map_err(Error::DeleteFailed)?;
+    Ok(rows_affected as u64)
 }
 
-/// Soft deletes a workout entry from the database by its ID.
+/// Soft deletes a workout entry from the database by its ID. Its tags, splits and metadata
+/// are kept (and hidden while it is deleted) so `restore_workout` brings them back;
+/// `purge_deleted` removes them for good.
 pub fn delete_workout(conn: &Connection, id: i64) -> Result<u64, Error> {
     let now_str = Utc::now().to_rfc3339();
     let rows_affected = conn
@@ -615,75 +1265,628 @@ pub fn delete_workout(conn: &Connection, id: i64) -> Result<u64, Error> {
     }
 }
 
-fn map_row_to_workout(row: &Row) -> Result<Workout, rusqlite::Error> {
-    let timestamp_str: String = row.get("timestamp")?;
-    let timestamp = DateTime::parse_from_rfc3339(&timestamp_str)
-        .map(|dt| dt.with_timezone(&Utc))
-        .map_err(|e| {
-            rusqlite::Error::FromSqlConversionFailure(
-                0,
-                rusqlite::types::Type::Text,
-                Box::new(Error::Conversion(format!(
-                    "Invalid timestamp format '{timestamp_str}': {e}"
-                ))) as Box<dyn StdError + Send + Sync>,
-            )
-        })?;
+/// Rows removed for good by `purge_deleted` or `clear_all_data`, per table.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PurgeCounts {
+    pub exercises: usize,
+    pub workouts: usize, // Their tags, splits, metadata and history go with them
+    pub aliases: usize,
+    pub bodyweights: usize,
+}
 
-    let type_str_opt: Option<String> = row.get("type")?;
-    let exercise_type = type_str_opt
-        .map(|type_str| {
-            ExerciseType::try_from(type_str.as_str()).map_err(|_e| {
-                rusqlite::Error::FromSqlConversionFailure(
-                    0,
-                    rusqlite::types::Type::Text,
-                    Box::new(Error::Conversion(format!(
-                        "Invalid exercise type '{type_str}' from DB"
-                    ))) as Box<dyn StdError + Send + Sync>,
-                )
-            })
-        })
-        .transpose()?; 
+/// Hard-deletes every soft-deleted exercise, workout, alias and bodyweight entry last edited
+/// before `cutoff`, in one transaction.
+pub fn purge_deleted(conn: &mut Connection, cutoff: DateTime<Utc>) -> Result<PurgeCounts, Error> {
+    let tx = conn.transaction().map_err(Error::Connection)?;
+    let cutoff = cutoff.to_rfc3339();
+    let older = "last_edited < ?1";
+    let counts = PurgeCounts {
+        workouts: purge_workout_rows(&tx, older, params![cutoff])?,
+        exercises: purge_rows(&tx, "exercises", older, params![cutoff])?,
+        aliases: purge_rows(&tx, "aliases", older, params![cutoff])?,
+        bodyweights: purge_rows(&tx, "bodyweights", older, params![cutoff])?,
+    };
+    tx.commit().map_err(Error::Connection)?;
+    Ok(counts)
+}
 
-    let _id_val = match row.get::<_, Option<String>>("_id") {
-        Ok(v) => v,
-        Err(_) => None,
+/// Deletes every exercise, workout (with its tags, splits, metadata and history), alias and
+/// bodyweight entry, deleted or not, and the undo log, in one transaction.
+pub fn clear_all_data(conn: &mut Connection) -> Result<PurgeCounts, Error> {
+    let tx = conn.transaction().map_err(Error::Connection)?;
+    let clear = |table: &str| tx.execute(&format!("DELETE FROM {table}"), []).map_err(Error::DeleteFailed);
+    for table in ["workout_tags", "workout_splits", "workout_metadata", "workout_history", "undo_log"] {
+        clear(table)?;
+    }
+    let counts = PurgeCounts {
+        workouts: clear("workouts")?,
+        aliases: clear("aliases")?,
+        exercises: clear("exercises")?,
+        bodyweights: clear("bodyweights")?,
     };
+    tx.commit().map_err(Error::Connection)?;
+    Ok(counts)
+}
 
-    Ok(Workout {
-        id: row.get("id")?,
-        _id: _id_val,
-        timestamp,
-        exercise_name: row.get("exercise_name")?,
-        sets: row.get("sets")?,
-        reps: row.get("reps")?,
-        weight: row.get("weight")?,
-        duration_minutes: row.get("duration_minutes")?,
-        distance: row.get("distance")?,
-        bodyweight: row.get("bodyweight")?,
-        notes: row.get("notes")?,
-        deleted: row.get("deleted")?,
-        last_edited: row.get("last_edited")?,
-        exercise_type,
-    })
+/// Hard-deletes soft-deleted workouts by ID. Returns the number of workouts removed.
+pub fn purge_workouts(conn: &mut Connection, ids: &[i64]) -> Result<usize, Error> {
+    let tx = conn.transaction().map_err(Error::Connection)?;
+    let mut total = 0;
+    for &id in ids {
+        total += purge_workout_rows(&tx, "id = ?1", params![id])?;
+    }
+    tx.commit().map_err(Error::Connection)?;
+    Ok(total)
 }
 
-#[derive(Default, Debug)]
-pub struct WorkoutFilters<'a> {
+/// Hard-deletes a soft-deleted exercise and its soft-deleted aliases.
+pub fn purge_exercise(conn: &Connection, name: &str) -> Result<usize, Error> {
+    purge_rows(conn, "aliases", "exercise_name = ?1 COLLATE NOCASE", params![name])?;
+    purge_rows(conn, "exercises", "name = ?1 COLLATE NOCASE", params![name])
+}
+
+/// Hard-deletes a soft-deleted alias.
+pub fn purge_alias(conn: &Connection, alias_name: &str) -> Result<usize, Error> {
+    purge_rows(conn, "aliases", "alias_name = ?1 COLLATE NOCASE", params![alias_name])
+}
+
+/// Hard-deletes a soft-deleted bodyweight entry.
+pub fn purge_bodyweight(conn: &Connection, id: i64) -> Result<usize, Error> {
+    purge_rows(conn, "bodyweights", "id = ?1", params![id])
+}
+
+/// Hard-deletes the soft-deleted rows of `table` matching `condition`.
+fn purge_rows(conn: &Connection, table: &str, condition: &str, params: &[&dyn ToSql]) -> Result<usize, Error> {
+    conn.execute(&format!("DELETE FROM {table} WHERE deleted = TRUE AND ({condition})"), params)
+        .map_err(Error::DeleteFailed)
+}
+
+/// Hard-deletes the soft-deleted workouts matching `condition`, with their tags, splits,
+/// metadata and history.
+fn purge_workout_rows(conn: &Connection, condition: &str, params: &[&dyn ToSql]) -> Result<usize, Error> {
+    for (table, column) in [
+        ("workout_tags", "workout_id"),
+        ("workout_splits", "workout_id"),
+        ("workout_metadata", "workout_id"),
+        ("workout_history", "id"),
+    ] {
+        conn.execute(
+            &format!(
+                "DELETE FROM {table} WHERE {column} IN (SELECT id FROM workouts WHERE deleted = TRUE AND ({condition}))"
+            ),
+            params,
+        )
+        .map_err(Error::DeleteFailed)?;
+    }
+    purge_rows(conn, "workouts", condition, params)
+}
+
+/// Stores the merged values of several same-day entries on `merged.id` (sets, reps, weight,
+/// bodyweight, duration, distance, heart rate and notes) and soft-deletes `absorbed_ids`, moving their
+/// tags and metadata over (the kept entry's values win). Runs in one transaction; the kept
+/// entry's previous version is recorded in `workout_history`.
+pub fn merge_workouts(conn: &mut Connection, merged: &Workout, absorbed_ids: &[i64]) -> Result<(), Error> {
+    let tx = conn.transaction().map_err(Error::Connection)?;
+    let before = get_workout_by_id(&tx, merged.id)?.ok_or(Error::WorkoutNotFound(merged.id))?;
+    tx.execute(
+        "UPDATE workouts SET sets = :sets, reps = :reps, weight = :weight, bodyweight = :bw,
+                duration_minutes = :dur, distance = :dist, notes = :notes,
+                avg_heart_rate = :avg_hr, max_heart_rate = :max_hr, last_edited = :le
+         WHERE id = :id AND deleted = FALSE",
+        named_params! {
+            ":sets": merged.sets, ":reps": merged.reps, ":weight": merged.weight, ":bw": merged.bodyweight,
+            ":dur": merged.duration_minutes, ":dist": merged.distance, ":notes": merged.notes,
+            ":avg_hr": merged.avg_heart_rate, ":max_hr": merged.max_heart_rate, ":le": Utc::now().to_rfc3339(), ":id": merged.id,
+        },
+    )
+    .map_err(Error::UpdateFailed)?;
+    let after = get_workout_by_id(&tx, merged.id)?.ok_or(Error::WorkoutNotFound(merged.id))?;
+    if !same_workout_content(&before, &after) {
+        record_workout_version(&tx, &before)?;
+    }
+    for &id in absorbed_ids {
+        for table in ["workout_tags", "workout_metadata"] {
+            let columns = if table == "workout_tags" { "tag" } else { "key, value" };
+            tx.execute(
+                &format!(
+                    "INSERT OR IGNORE INTO {table} (workout_id, {columns})
+                     SELECT ?1, {columns} FROM {table} WHERE workout_id = ?2"
+                ),
+                params![merged.id, id],
+            )
+            .map_err(Error::InsertFailed)?;
+        }
+        delete_workout(&tx, id)?;
+    }
+    tx.commit().map_err(Error::Connection)?;
+    Ok(())
+}
+
+/// Sets the notes of several active workouts in one transaction, keeping each previous
+/// version in `workout_history`. Fails without changing anything if any ID is not an
+/// active workout.
+pub fn update_workout_notes(conn: &mut Connection, notes: &[(i64, String)]) -> Result<u64, Error> {
+    let tx = conn.transaction().map_err(Error::Connection)?;
+    let now_str = Utc::now().to_rfc3339();
+    for (id, new_notes) in notes {
+        let before = get_workout_by_id(&tx, *id)?.ok_or(Error::WorkoutNotFound(*id))?;
+        tx.execute(
+            "UPDATE workouts SET notes = ?1, last_edited = ?2 WHERE id = ?3 AND deleted = FALSE",
+            params![new_notes, now_str, id],
+        )
+        .map_err(Error::UpdateFailed)?;
+        record_workout_version(&tx, &before)?;
+    }
+    tx.commit().map_err(Error::Connection)?;
+    Ok(notes.len() as u64)
+}
+
+/// Soft-deletes several workout entries in one transaction. Fails without deleting anything
+/// if any ID is not an active workout.
+pub fn delete_workouts(conn: &mut Connection, ids: &[i64]) -> Result<u64, Error> {
+    let tx = conn.transaction().map_err(Error::Connection)?;
+    let mut total = 0;
+    for &id in ids {
+        total += delete_workout(&tx, id)?;
+    }
+    tx.commit().map_err(Error::Connection)?;
+    Ok(total)
+}
+
+/// Records an operation in the undo log, keeping only the newest `UNDO_LOG_DEPTH` entries.
+pub fn record_undo(conn: &Connection, action: UndoAction, workout_ids: &[i64]) -> Result<(), Error> {
+    let ids = workout_ids
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(",");
+    conn.execute(
+        "INSERT INTO undo_log (action, workout_ids, created_at) VALUES (?1, ?2, ?3)",
+        params![action.as_str(), ids, Utc::now().to_rfc3339()],
+    )
+    .map_err(Error::InsertFailed)?;
+    conn.execute(
+        "DELETE FROM undo_log WHERE id NOT IN (SELECT id FROM undo_log ORDER BY id DESC LIMIT ?1)",
+        params![UNDO_LOG_DEPTH],
+    )
+    .map_err(Error::DeleteFailed)?;
+    Ok(())
+}
+
+/// Removes and returns the most recent undo log entry, if any.
+pub fn pop_undo(conn: &Connection) -> Result<Option<UndoEntry>, Error> {
+    let Some((id, action, ids, created_at)) = conn
+        .query_row(
+            "SELECT id, action, workout_ids, created_at FROM undo_log ORDER BY id DESC LIMIT 1",
+            [],
+            |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, String>(3)?,
+                ))
+            },
+        )
+        .optional()
+        .map_err(Error::QueryFailed)?
+    else {
+        return Ok(None);
+    };
+    conn.execute("DELETE FROM undo_log WHERE id = ?1", params![id])
+        .map_err(Error::DeleteFailed)?;
+
+    let workout_ids = ids
+        .split(',')
+        .filter(|s| !s.is_empty())
+        .map(|s| {
+            s.parse::<i64>()
+                .map_err(|_| Error::Conversion(format!("Invalid workout ID '{s}' in undo log")))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(Some(UndoEntry {
+        id,
+        action: UndoAction::try_from(action.as_str())?,
+        workout_ids,
+        created_at: parse_datetime_from_string(created_at)?,
+    }))
+}
+
+/// Restores a soft-deleted workout entry by its ID, along with its tags and metadata.
+pub fn restore_workout(conn: &Connection, id: i64) -> Result<u64, Error> {
+    let rows_affected = conn
+        .execute(
+            "UPDATE workouts SET deleted = FALSE, last_edited = ?1 WHERE id = ?2 AND deleted = TRUE",
+            params![Utc::now().to_rfc3339(), id],
+        )
+        .map_err(Error::UpdateFailed)?;
+    if rows_affected == 0 {
+        Err(Error::WorkoutNotFound(id))
+    } else {
+        Ok(rows_affected as u64)
+    }
+}
+
+/// Lists soft-deleted workout entries, most recently deleted first.
+pub fn list_deleted_workouts(conn: &Connection, limit: u32) -> Result<Vec<Workout>, Error> {
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {WORKOUT_COLUMNS}
+             FROM workouts w LEFT JOIN exercises e ON w.exercise_name = e.name
+             WHERE w.deleted = TRUE
+             ORDER BY w.last_edited DESC LIMIT ?1"
+        ))
+        .map_err(Error::QueryFailed)?;
+    let workouts = stmt
+        .query_map(params![limit], map_row_to_workout)
+        .map_err(Error::QueryFailed)?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(map_collect_error);
+    workouts
+}
+
+/// Adds tags to a non-deleted workout. Tags are expected to be normalized already;
+/// tags the workout already has are ignored. Returns the number of tags added.
+pub fn add_workout_tags(conn: &Connection, workout_id: i64, tags: &[String]) -> Result<usize, Error> {
+    let workout_exists: bool = conn
+        .query_row(
+            "SELECT EXISTS(SELECT 1 FROM workouts WHERE id = ?1 AND deleted = FALSE)",
+            params![workout_id],
+            |row| row.get(0),
+        )
+        .map_err(Error::QueryFailed)?;
+    if !workout_exists {
+        return Err(Error::WorkoutNotFound(workout_id));
+    }
+    let mut stmt = conn
+        .prepare("INSERT OR IGNORE INTO workout_tags (workout_id, tag) VALUES (?1, ?2)")
+        .map_err(Error::InsertFailed)?;
+    let mut added = 0;
+    for tag in tags {
+        added += stmt.execute(params![workout_id, tag]).map_err(Error::InsertFailed)?;
+    }
+    Ok(added)
+}
+
+/// Removes a single tag from a workout.
+pub fn remove_workout_tag(conn: &Connection, workout_id: i64, tag: &str) -> Result<u64, Error> {
+    let rows_affected = conn
+        .execute(
+            "DELETE FROM workout_tags WHERE workout_id = ?1 AND tag = ?2",
+            params![workout_id, tag],
+        )
+        .map_err(Error::DeleteFailed)?;
+    if rows_affected == 0 {
+        Err(Error::TagNotFound(workout_id, tag.to_string()))
+    } else {
+        Ok(rows_affected as u64)
+    }
+}
+
+/// Lists the tags of a workout, sorted alphabetically. Empty for deleted workouts.
+pub fn get_workout_tags(conn: &Connection, workout_id: i64) -> Result<Vec<String>, Error> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT t.tag FROM workout_tags t JOIN workouts w ON t.workout_id = w.id
+             WHERE t.workout_id = ?1 AND w.deleted = FALSE ORDER BY t.tag ASC",
+        )
+        .map_err(Error::QueryFailed)?;
+    let tag_iter = stmt
+        .query_map(params![workout_id], |row| row.get(0))
+        .map_err(Error::QueryFailed)?;
+    tag_iter.collect::<Result<Vec<_>, _>>().map_err(Error::QueryFailed)
+}
+
+/// Lists every distinct tag used by non-deleted workouts, sorted alphabetically.
+pub fn list_tags(conn: &Connection) -> Result<Vec<String>, Error> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT DISTINCT t.tag FROM workout_tags t JOIN workouts w ON t.workout_id = w.id
+             WHERE w.deleted = FALSE ORDER BY t.tag ASC",
+        )
+        .map_err(Error::QueryFailed)?;
+    let tag_iter = stmt
+        .query_map([], |row| row.get(0))
+        .map_err(Error::QueryFailed)?;
+    tag_iter.collect::<Result<Vec<_>, _>>().map_err(Error::QueryFailed)
+}
+
+/// Sets a metadata value on a non-deleted workout, replacing any existing value for `key`.
+pub fn set_workout_metadata(
+    conn: &Connection,
+    workout_id: i64,
+    key: &str,
+    value: &str,
+) -> Result<(), Error> {
+    let workout_exists: bool = conn
+        .query_row(
+            "SELECT EXISTS(SELECT 1 FROM workouts WHERE id = ?1 AND deleted = FALSE)",
+            params![workout_id],
+            |row| row.get(0),
+        )
+        .map_err(Error::QueryFailed)?;
+    if !workout_exists {
+        return Err(Error::WorkoutNotFound(workout_id));
+    }
+    conn.execute(
+        "INSERT INTO workout_metadata (workout_id, key, value) VALUES (?1, ?2, ?3)
+         ON CONFLICT(workout_id, key) DO UPDATE SET value = excluded.value",
+        params![workout_id, key, value],
+    )
+    .map_err(Error::InsertFailed)?;
+    Ok(())
+}
+
+/// Lists the metadata of a workout as `(key, value)` pairs, sorted by key. Empty for
+/// deleted workouts.
+pub fn get_workout_metadata(
+    conn: &Connection,
+    workout_id: i64,
+) -> Result<Vec<(String, String)>, Error> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT m.key, m.value FROM workout_metadata m JOIN workouts w ON m.workout_id = w.id
+             WHERE m.workout_id = ?1 AND w.deleted = FALSE ORDER BY m.key ASC",
+        )
+        .map_err(Error::QueryFailed)?;
+    let meta_iter = stmt
+        .query_map(params![workout_id], |row| Ok((row.get(0)?, row.get(1)?)))
+        .map_err(Error::QueryFailed)?;
+    meta_iter.collect::<Result<Vec<_>, _>>().map_err(Error::QueryFailed)
+}
+
+/// Appends splits to a non-deleted workout, numbering them after any existing splits.
+/// Distances are expected in km. Returns the number of splits added.
+pub fn add_workout_splits(
+    conn: &mut Connection,
+    workout_id: i64,
+    splits: &[(f64, i64)],
+) -> Result<usize, Error> {
+    let tx = conn.transaction().map_err(Error::Connection)?;
+    let workout_exists: bool = tx
+        .query_row(
+            "SELECT EXISTS(SELECT 1 FROM workouts WHERE id = ?1 AND deleted = FALSE)",
+            params![workout_id],
+            |row| row.get(0),
+        )
+        .map_err(Error::QueryFailed)?;
+    if !workout_exists {
+        return Err(Error::WorkoutNotFound(workout_id));
+    }
+    let next_index: i64 = tx
+        .query_row(
+            "SELECT COALESCE(MAX(split_index), 0) + 1 FROM workout_splits WHERE workout_id = ?1",
+            params![workout_id],
+            |row| row.get(0),
+        )
+        .map_err(Error::QueryFailed)?;
+    {
+        let mut stmt = tx
+            .prepare(
+                "INSERT INTO workout_splits (workout_id, split_index, distance, duration_seconds)
+                 VALUES (?1, ?2, ?3, ?4)",
+            )
+            .map_err(Error::InsertFailed)?;
+        for (index, (distance, duration_seconds)) in (next_index..).zip(splits) {
+            stmt.execute(params![workout_id, index, distance, duration_seconds])
+                .map_err(Error::InsertFailed)?;
+        }
+    }
+    tx.commit().map_err(Error::Connection)?;
+    Ok(splits.len())
+}
+
+fn map_row_to_split(row: &Row) -> Result<WorkoutSplit, rusqlite::Error> {
+    Ok(WorkoutSplit {
+        workout_id: row.get("workout_id")?,
+        split_index: row.get("split_index")?,
+        distance: row.get("distance")?,
+        duration_seconds: row.get("duration_seconds")?,
+    })
+}
+
+/// Retrieves the splits of a workout in order.
+pub fn get_workout_splits(conn: &Connection, workout_id: i64) -> Result<Vec<WorkoutSplit>, Error> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT workout_id, split_index, distance, duration_seconds FROM workout_splits
+             WHERE workout_id = ?1 ORDER BY split_index ASC",
+        )
+        .map_err(Error::QueryFailed)?;
+    let split_iter = stmt
+        .query_map(params![workout_id], map_row_to_split)
+        .map_err(Error::QueryFailed)?;
+    split_iter.collect::<Result<Vec<_>, _>>().map_err(Error::QueryFailed)
+}
+
+/// Finds the split with the best pace (lowest seconds per km) across non-deleted,
+/// non-warmup workouts of an exercise.
+pub fn get_fastest_split_for_exercise(
+    conn: &Connection,
+    canonical_exercise_name: &str,
+) -> Result<Option<WorkoutSplit>, Error> {
+    conn.query_row(
+        "SELECT s.workout_id, s.split_index, s.distance, s.duration_seconds
+         FROM workout_splits s JOIN workouts w ON s.workout_id = w.id
+         WHERE w.exercise_name = ?1 COLLATE NOCASE AND w.deleted = FALSE AND w.is_warmup = FALSE AND w.is_failure = FALSE
+           AND s.distance > 0 AND s.duration_seconds > 0
+         ORDER BY s.duration_seconds / s.distance ASC, w.timestamp ASC LIMIT 1",
+        params![canonical_exercise_name],
+        map_row_to_split,
+    )
+    .optional()
+    .map_err(Error::QueryFailed)
+}
+
+/// Sums split distances (km) per workout for a non-deleted exercise's workouts that have splits.
+pub fn get_split_distance_totals(
+    conn: &Connection,
+    canonical_exercise_name: &str,
+) -> Result<HashMap<i64, f64>, Error> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT s.workout_id, SUM(s.distance) FROM workout_splits s JOIN workouts w ON s.workout_id = w.id
+             WHERE w.exercise_name = ?1 COLLATE NOCASE AND w.deleted = FALSE
+             GROUP BY s.workout_id",
+        )
+        .map_err(Error::QueryFailed)?;
+    let total_iter = stmt
+        .query_map(params![canonical_exercise_name], |row| Ok((row.get(0)?, row.get(1)?)))
+        .map_err(Error::QueryFailed)?;
+    total_iter.collect::<Result<HashMap<_, _>, _>>().map_err(Error::QueryFailed)
+}
+
+fn map_row_to_workout(row: &Row) -> Result<Workout, rusqlite::Error> {
+    let timestamp_str: String = row.get("timestamp")?;
+    let timestamp = DateTime::parse_from_rfc3339(&timestamp_str)
+        .map(|dt| dt.with_timezone(&Utc))
+        .map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(
+                0,
+                rusqlite::types::Type::Text,
+                Box::new(Error::Conversion(format!(
+                    "Invalid timestamp format '{timestamp_str}': {e}"
+                ))) as Box<dyn StdError + Send + Sync>,
+            )
+        })?;
+
+    let type_str_opt: Option<String> = row.get("type")?;
+    let exercise_type = type_str_opt
+        .map(|type_str| {
+            ExerciseType::try_from(type_str.as_str()).map_err(|_e| {
+                rusqlite::Error::FromSqlConversionFailure(
+                    0,
+                    rusqlite::types::Type::Text,
+                    Box::new(Error::Conversion(format!(
+                        "Invalid exercise type '{type_str}' from DB"
+                    ))) as Box<dyn StdError + Send + Sync>,
+                )
+            })
+        })
+        .transpose()?; 
+
+    let _id_val = match row.get::<_, Option<String>>("_id") {
+        Ok(v) => v,
+        Err(_) => None,
+    };
+
+    Ok(Workout {
+        id: row.get("id")?,
+        _id: _id_val,
+        timestamp,
+        exercise_name: row.get("exercise_name")?,
+        sets: row.get("sets")?,
+        reps: row.get("reps")?,
+        weight: row.get("weight")?,
+        duration_minutes: row.get("duration_minutes")?,
+        distance: row.get("distance")?,
+        bodyweight: row.get("bodyweight")?,
+        notes: row.get("notes")?,
+        is_warmup: row.get("is_warmup")?,
+        is_amrap: row.get("is_amrap")?,
+        is_failure: row.get("is_failure")?,
+        avg_heart_rate: row.get("avg_heart_rate")?,
+        max_heart_rate: row.get("max_heart_rate")?,
+        deleted: row.get("deleted")?,
+        last_edited: row.get("last_edited")?,
+        exercise_type,
+    })
+}
+
+/// Non-deleted workouts whose notes contain `query` (case-insensitive for ASCII), newest
+/// first, at most `limit`.
+pub fn search_workout_notes(conn: &Connection, query: &str, limit: u32) -> Result<Vec<Workout>, Error> {
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {WORKOUT_COLUMNS}
+             FROM workouts w LEFT JOIN exercises e ON w.exercise_name = e.name COLLATE NOCASE
+             WHERE w.deleted = FALSE AND instr(lower(w.notes), lower(?1)) > 0
+             ORDER BY w.timestamp DESC, w.id DESC LIMIT ?2"
+        ))
+        .map_err(Error::QueryFailed)?;
+    let workouts = stmt
+        .query_map(params![query, limit], map_row_to_workout)
+        .map_err(Error::QueryFailed)?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(map_collect_error);
+    workouts
+}
+
+/// Retrieves a single non-deleted workout by its ID.
+pub fn get_workout_by_id(conn: &Connection, id: i64) -> Result<Option<Workout>, Error> {
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {WORKOUT_COLUMNS}
+             FROM workouts w LEFT JOIN exercises e ON w.exercise_name = e.name
+             WHERE w.id = ?1 AND w.deleted = FALSE"
+        ))
+        .map_err(Error::QueryFailed)?;
+    stmt.query_row(params![id], map_row_to_workout)
+        .optional()
+        .map_err(map_collect_error)
+}
+
+#[derive(Debug)]
+pub struct WorkoutFilters<'a> {
     pub exercise_name: Option<&'a str>,
     pub date: Option<NaiveDate>,
+    pub start_date: Option<NaiveDate>, // Inclusive
+    pub end_date: Option<NaiveDate>,   // Inclusive
     pub exercise_type: Option<ExerciseType>,
     pub muscle: Option<&'a str>,
+    pub tag: Option<&'a str>,
     pub limit: Option<u32>,
+    pub offset: Option<u32>, // Rows to skip; like `limit`, ignored when `date` is set
+    pub include_warmups: bool, // Default true
+    pub include_failures: bool, // Default true
+}
+
+impl Default for WorkoutFilters<'_> {
+    fn default() -> Self {
+        Self {
+            exercise_name: None,
+            date: None,
+            start_date: None,
+            end_date: None,
+            exercise_type: None,
+            muscle: None,
+            tag: None,
+            limit: None,
+            offset: None,
+            include_warmups: true,
+            include_failures: true,
+        }
+    }
+}
+
+impl WorkoutFilters<'_> {
+    /// Whether any filter narrows the selection (`limit`, `include_warmups` and `include_failures`
+    /// don't count).
+    #[must_use]
+    pub const fn has_criteria(&self) -> bool {
+        self.exercise_name.is_some()
+            || self.date.is_some()
+            || self.start_date.is_some()
+            || self.end_date.is_some()
+            || self.exercise_type.is_some()
+            || self.muscle.is_some()
+            || self.tag.is_some()
+    }
 }
 
 /// Lists non-deleted workout entries from the database based on various filters.
+/// Entries with the same timestamp (e.g. logged for the same date) keep their insertion
+/// order (by ID), so editing one does not move it.
 pub fn list_workouts_filtered(
     conn: &Connection,
     filters: &WorkoutFilters,
+    tz: Tz,
 ) -> Result<Vec<Workout>, Error> {
-    let mut sql = "SELECT w.id, w.timestamp, w.exercise_name, w.sets, w.reps, w.weight, w.duration_minutes, w.distance, w.bodyweight, w.notes, e.type, w.deleted, w.last_edited 
-                   FROM workouts w LEFT JOIN exercises e ON w.exercise_name = e.name 
-                   WHERE w.deleted = FALSE AND (e.id IS NULL OR e.deleted = FALSE)".to_string(); 
+    let mut sql = format!(
+        "SELECT {WORKOUT_COLUMNS}
+         FROM workouts w LEFT JOIN exercises e ON w.exercise_name = e.name
+         WHERE w.deleted = FALSE AND (e.id IS NULL OR e.deleted = FALSE)"
+    );
     let mut params_map: HashMap<String, Box<dyn ToSql>> = HashMap::new();
 
     if let Some(name) = filters.exercise_name {
@@ -691,12 +1894,29 @@ pub fn list_workouts_filtered(
         params_map.insert(":ex_name".into(), Box::new(name.to_string()));
     }
     if let Some(date) = filters.date {
-        sql.push_str(" AND date(w.timestamp) = date(:date)");
+        sql.push_str(&format!(
+            " AND date({}) = date(:date)",
+            local_timestamp_sql("w.timestamp", tz)
+        ));
         params_map.insert(
             ":date".into(),
             Box::new(date.format("%Y-%m-%d").to_string()),
         );
     }
+    if let Some(start) = filters.start_date {
+        sql.push_str(&format!(
+            " AND date({}) >= date(:start_date)",
+            local_timestamp_sql("w.timestamp", tz)
+        ));
+        params_map.insert(":start_date".into(), Box::new(start.format("%Y-%m-%d").to_string()));
+    }
+    if let Some(end) = filters.end_date {
+        sql.push_str(&format!(
+            " AND date({}) <= date(:end_date)",
+            local_timestamp_sql("w.timestamp", tz)
+        ));
+        params_map.insert(":end_date".into(), Box::new(end.format("%Y-%m-%d").to_string()));
+    }
     if let Some(ex_type) = filters.exercise_type {
         sql.push_str(" AND e.type = :ex_type");
         params_map.insert(":ex_type".into(), Box::new(ex_type.to_string()));
@@ -705,11 +1925,21 @@ pub fn list_workouts_filtered(
         sql.push_str(" AND e.muscles LIKE :muscle");
         params_map.insert(":muscle".into(), Box::new(format!("%{m}%")));
     }
+    if let Some(tag) = filters.tag {
+        sql.push_str(" AND EXISTS (SELECT 1 FROM workout_tags t WHERE t.workout_id = w.id AND t.tag = :tag)");
+        params_map.insert(":tag".into(), Box::new(tag.trim().to_lowercase()));
+    }
+    if !filters.include_warmups {
+        sql.push_str(" AND w.is_warmup = FALSE");
+    }
+    if !filters.include_failures {
+        sql.push_str(" AND w.is_failure = FALSE");
+    }
 
     if filters.date.is_some() {
-        sql.push_str(" ORDER BY w.timestamp ASC, w.last_edited ASC");
+        sql.push_str(" ORDER BY w.timestamp ASC, w.id ASC");
     } else {
-        sql.push_str(" ORDER BY w.timestamp DESC, w.last_edited DESC");
+        sql.push_str(" ORDER BY w.timestamp DESC, w.id DESC");
     }
 
     if filters.date.is_none() {
@@ -717,6 +1947,13 @@ pub fn list_workouts_filtered(
             sql.push_str(" LIMIT :limit");
             params_map.insert(":limit".into(), Box::new(limit));
         }
+        if let Some(offset) = filters.offset {
+            if filters.limit.is_none() {
+                sql.push_str(" LIMIT -1"); // SQLite requires LIMIT before OFFSET
+            }
+            sql.push_str(" OFFSET :offset");
+            params_map.insert(":offset".into(), Box::new(offset));
+        }
     }
 
     let params_for_query: Vec<(&str, &dyn ToSql)> = params_map
@@ -739,25 +1976,28 @@ pub fn list_workouts_for_exercise_on_nth_last_day(
     conn: &Connection,
     exercise_name: &str,
     n: u32,
+    tz: Tz,
 ) -> Result<Vec<Workout>, Error> {
     if n == 0 {
         return Err(Error::InvalidParameterCount(0, 0)); 
     }
     let offset = n - 1;
-    let sql = "WITH RankedDays AS (
-                    SELECT DISTINCT date(timestamp) as workout_date
+    let local_ts = local_timestamp_sql("timestamp", tz);
+    let local_w_ts = local_timestamp_sql("w.timestamp", tz);
+    let sql = format!("WITH RankedDays AS (
+                    SELECT DISTINCT date({local_ts}) as workout_date
                     FROM workouts
                     WHERE exercise_name = :ex_name COLLATE NOCASE AND deleted = FALSE
                     ORDER BY workout_date DESC LIMIT 1 OFFSET :offset
                 )
-                SELECT w.id, w.timestamp, w.exercise_name, w.sets, w.reps, w.weight, w.duration_minutes, w.distance, w.bodyweight, w.notes, e.type,  w.deleted, w.last_edited
+                SELECT {WORKOUT_COLUMNS}
                 FROM workouts w
                 LEFT JOIN exercises e ON w.exercise_name = e.name
-                JOIN RankedDays rd ON date(w.timestamp) = rd.workout_date
+                JOIN RankedDays rd ON date({local_w_ts}) = rd.workout_date
                 WHERE w.exercise_name = :ex_name COLLATE NOCASE AND w.deleted = FALSE AND (e.id IS NULL OR e.deleted = FALSE)
-                ORDER BY w.timestamp ASC, w.last_edited ASC;";
+                ORDER BY w.timestamp ASC, w.id ASC;");
 
-    let mut stmt = conn.prepare(sql).map_err(Error::QueryFailed)?;
+    let mut stmt = conn.prepare(&sql).map_err(Error::QueryFailed)?;
     let workout_iter = stmt
         .query_map(
             named_params! { ":ex_name": exercise_name, ":offset": offset },
@@ -770,6 +2010,7 @@ pub fn list_workouts_for_exercise_on_nth_last_day(
         .map_err(map_collect_error)
 }
 
+#[allow(clippy::too_many_arguments)]
 pub fn create_exercise(
     conn: &Connection,
     name: &str,
@@ -779,6 +2020,9 @@ pub fn create_exercise(
     log_reps: Option<bool>,
     log_duration: Option<bool>,
     log_distance: Option<bool>,
+    description: Option<&str>,
+    category: Option<&str>,
+    defaults: &ExerciseDefaults,
 ) -> Result<i64, Error> {
     let type_str = ex_type.to_string();
     let (default_log_w, default_log_r, default_log_dur, default_log_dist) = match ex_type {
@@ -793,8 +2037,8 @@ pub fn create_exercise(
     let now_str = Utc::now().to_rfc3339();
     let uuid_str = Uuid::new_v4().to_string();
     match conn.execute(
-        "INSERT INTO exercises (_id, name, type, muscles, log_weight, log_reps, log_duration, log_distance, last_edited)
-         VALUES (:_id, :name, :type, :muscles, :log_w, :log_r, :log_dur, :log_dist, :last_edited)",
+        "INSERT INTO exercises (_id, name, type, muscles, log_weight, log_reps, log_duration, log_distance, description, category, default_sets, default_reps, default_weight, last_edited)
+         VALUES (:_id, :name, :type, :muscles, :log_w, :log_r, :log_dur, :log_dist, :description, :category, :default_sets, :default_reps, :default_weight, :last_edited)",
         named_params! {
             ":_id": uuid_str,
             ":name": name,
@@ -804,6 +2048,11 @@ pub fn create_exercise(
             ":log_r": final_log_r,
             ":log_dur": final_log_dur,
             ":log_dist": final_log_dist,
+            ":description": description,
+            ":category": category,
+            ":default_sets": defaults.sets,
+            ":default_reps": defaults.reps,
+            ":default_weight": defaults.weight,
             ":last_edited": now_str,
         },
     ) {
@@ -838,6 +2087,9 @@ pub fn update_exercise(
     new_log_reps: Option<bool>,
     new_log_duration: Option<bool>,
     new_log_distance: Option<bool>,
+    new_description: Option<Option<&str>>,
+    new_category: Option<Option<&str>>,
+    new_defaults: Option<&ExerciseDefaults>,
 ) -> Result<u64, Error> {
     let exercise = get_exercise_by_name(conn, canonical_name_to_update)?
         .ok_or_else(|| Error::ExerciseNotFound(canonical_name_to_update.to_string()))?;
@@ -878,6 +2130,20 @@ pub fn update_exercise(
         updates.push("log_distance = :log_dist");
         params_map.insert(":log_dist".into(), Box::new(b));
     }
+    if let Some(d_opt) = new_description {
+        updates.push("description = :description");
+        params_map.insert(":description".into(), Box::new(d_opt.map(str::to_string)));
+    }
+    if let Some(c_opt) = new_category {
+        updates.push("category = :category");
+        params_map.insert(":category".into(), Box::new(c_opt.map(str::to_string)));
+    }
+    if let Some(defaults) = new_defaults {
+        updates.push("default_sets = :default_sets, default_reps = :default_reps, default_weight = :default_weight");
+        params_map.insert(":default_sets".into(), Box::new(defaults.sets));
+        params_map.insert(":default_reps".into(), Box::new(defaults.reps));
+        params_map.insert(":default_weight".into(), Box::new(defaults.weight));
+    }
 
     if updates.is_empty() {
         return Ok(0);
@@ -916,12 +2182,7 @@ pub fn update_exercise(
     };
 
     if name_being_changed && rows_affected > 0 {
-        tx.execute("UPDATE workouts SET exercise_name = :new_name, last_edited = :now WHERE exercise_name = :old_name COLLATE NOCASE AND deleted = FALSE",
-                   named_params! { ":new_name": target_new_name, ":old_name": original_name, ":now": now_str })
-          .map_err(Error::UpdateFailed)?;
-        tx.execute("UPDATE aliases SET exercise_name = :new_name, last_edited = :now WHERE exercise_name = :old_name COLLATE NOCASE AND deleted = FALSE",
-                   named_params! { ":new_name": target_new_name, ":old_name": original_name, ":now": now_str })
-          .map_err(Error::UpdateFailed)?;
+        reassign_exercise_references(&tx, &original_name, target_new_name, &now_str)?;
     }
 
     tx.commit().map_err(Error::Connection)?;
@@ -933,7 +2194,236 @@ pub fn update_exercise(
     }
 }
 
-/// Soft deletes an exercise definition and its associated non-deleted aliases.
+/// Points active workouts, aliases and variations of `old_name` at `new_name`.
+/// Returns the number of workouts reassigned.
+fn reassign_exercise_references(
+    tx: &Transaction,
+    old_name: &str,
+    new_name: &str,
+    now_str: &str,
+) -> Result<u64, Error> {
+    let workouts = tx.execute("UPDATE workouts SET exercise_name = :new_name, last_edited = :now WHERE exercise_name = :old_name COLLATE NOCASE AND deleted = FALSE",
+               named_params! { ":new_name": new_name, ":old_name": old_name, ":now": now_str })
+      .map_err(Error::UpdateFailed)?;
+    tx.execute("UPDATE aliases SET exercise_name = :new_name, last_edited = :now WHERE exercise_name = :old_name COLLATE NOCASE AND deleted = FALSE",
+               named_params! { ":new_name": new_name, ":old_name": old_name, ":now": now_str })
+      .map_err(Error::UpdateFailed)?;
+    // A variation merged into its own parent stops being a variation instead of its own child
+    tx.execute("UPDATE exercises SET parent_exercise = CASE WHEN name = :new_name COLLATE NOCASE THEN NULL ELSE :new_name END, last_edited = :now
+                WHERE parent_exercise = :old_name COLLATE NOCASE AND deleted = FALSE",
+               named_params! { ":new_name": new_name, ":old_name": old_name, ":now": now_str })
+      .map_err(Error::UpdateFailed)?;
+    Ok(workouts as u64)
+}
+
+/// Moves all workouts and aliases from `source_name` to `target_name`, then soft-deletes the
+/// source exercise, in a single transaction. Returns the number of workouts reassigned.
+pub fn merge_exercises(
+    conn: &mut Connection,
+    source_name: &str,
+    target_name: &str,
+) -> Result<u64, Error> {
+    let source = get_exercise_by_name(conn, source_name)?
+        .ok_or_else(|| Error::ExerciseNotFound(source_name.to_string()))?;
+    let target = get_exercise_by_name(conn, target_name)?
+        .ok_or_else(|| Error::ExerciseNotFound(target_name.to_string()))?;
+    let now_str = Utc::now().to_rfc3339();
+
+    let tx = conn.transaction().map_err(Error::Connection)?;
+    let reassigned = reassign_exercise_references(&tx, &source.name, &target.name, &now_str)?;
+    tx.execute(
+        "UPDATE exercises SET deleted = TRUE, last_edited = :now WHERE id = :id AND deleted = FALSE",
+        named_params! { ":id": source.id, ":now": now_str },
+    )
+    .map_err(Error::DeleteFailed)?;
+    tx.commit().map_err(Error::Connection)?;
+    Ok(reassigned)
+}
+
+/// Sets (or clears) the MET value of a non-deleted exercise.
+pub fn set_exercise_met_value(
+    conn: &Connection,
+    canonical_name: &str,
+    met_value: Option<f64>,
+) -> Result<u64, Error> {
+    let rows_affected = conn
+        .execute(
+            "UPDATE exercises SET met_value = ?1, last_edited = ?2 WHERE name = ?3 COLLATE NOCASE AND deleted = FALSE",
+            params![met_value, Utc::now().to_rfc3339(), canonical_name],
+        )
+        .map_err(Error::UpdateFailed)?;
+    if rows_affected == 0 {
+        Err(Error::ExerciseNotFound(canonical_name.to_string()))
+    } else {
+        Ok(rows_affected as u64)
+    }
+}
+
+/// Sets (or clears) the parent of a non-deleted exercise, making it a variation.
+pub fn set_exercise_parent(
+    conn: &Connection,
+    canonical_name: &str,
+    parent: Option<&str>,
+) -> Result<u64, Error> {
+    let rows_affected = conn
+        .execute(
+            "UPDATE exercises SET parent_exercise = ?1, last_edited = ?2 WHERE name = ?3 COLLATE NOCASE AND deleted = FALSE",
+            params![parent, Utc::now().to_rfc3339(), canonical_name],
+        )
+        .map_err(Error::UpdateFailed)?;
+    if rows_affected == 0 {
+        Err(Error::ExerciseNotFound(canonical_name.to_string()))
+    } else {
+        Ok(rows_affected as u64)
+    }
+}
+
+/// Sets whether weights of a non-deleted exercise are entered per side, and its carriage weight.
+pub fn set_exercise_weight_per_side(
+    conn: &Connection,
+    canonical_name: &str,
+    per_side: bool,
+    carriage_weight: Option<f64>,
+) -> Result<u64, Error> {
+    let rows_affected = conn
+        .execute(
+            "UPDATE exercises SET weight_is_per_side = ?1, carriage_weight = ?2, last_edited = ?3 WHERE name = ?4 COLLATE NOCASE AND deleted = FALSE",
+            params![per_side, carriage_weight, Utc::now().to_rfc3339(), canonical_name],
+        )
+        .map_err(Error::UpdateFailed)?;
+    if rows_affected == 0 {
+        Err(Error::ExerciseNotFound(canonical_name.to_string()))
+    } else {
+        Ok(rows_affected as u64)
+    }
+}
+
+/// Sets (or clears) the manual list position of a non-deleted exercise.
+pub fn set_exercise_sort_order(
+    conn: &Connection,
+    canonical_name: &str,
+    sort_order: Option<i64>,
+) -> Result<u64, Error> {
+    let rows_affected = conn
+        .execute(
+            "UPDATE exercises SET sort_order = ?1, last_edited = ?2 WHERE name = ?3 COLLATE NOCASE AND deleted = FALSE",
+            params![sort_order, Utc::now().to_rfc3339(), canonical_name],
+        )
+        .map_err(Error::UpdateFailed)?;
+    if rows_affected == 0 {
+        Err(Error::ExerciseNotFound(canonical_name.to_string()))
+    } else {
+        Ok(rows_affected as u64)
+    }
+}
+
+/// Gives the non-deleted exercises in `canonical_names` the positions 0, 1, ... in that order
+/// and clears the position of every other exercise, in one transaction.
+pub fn reorder_exercises(conn: &mut Connection, canonical_names: &[String]) -> Result<(), Error> {
+    let tx = conn.transaction().map_err(Error::Connection)?;
+    let now = Utc::now().to_rfc3339();
+    tx.execute(
+        "UPDATE exercises SET sort_order = NULL, last_edited = ?1 WHERE sort_order IS NOT NULL AND deleted = FALSE",
+        params![now],
+    )
+    .map_err(Error::UpdateFailed)?;
+    for (position, name) in canonical_names.iter().enumerate() {
+        let rows_affected = tx
+            .execute(
+                "UPDATE exercises SET sort_order = ?1, last_edited = ?2 WHERE name = ?3 COLLATE NOCASE AND deleted = FALSE",
+                params![position as i64, now, name],
+            )
+            .map_err(Error::UpdateFailed)?;
+        if rows_affected == 0 {
+            return Err(Error::ExerciseNotFound(name.clone()));
+        }
+    }
+    tx.commit().map_err(Error::Connection)
+}
+
+/// Names of the non-deleted exercises whose parent is `parent_name`, ordered by name.
+pub fn list_exercise_variations(conn: &Connection, parent_name: &str) -> Result<Vec<String>, Error> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT name FROM exercises WHERE parent_exercise = ?1 COLLATE NOCASE AND deleted = FALSE ORDER BY name",
+        )
+        .map_err(Error::QueryFailed)?;
+    let names = stmt
+        .query_map(params![parent_name], |row| row.get(0))
+        .map_err(Error::QueryFailed)?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(map_collect_error);
+    names
+}
+
+/// Sets (or clears) the PB metrics counted for a non-deleted exercise.
+pub fn set_exercise_pb_metrics(
+    conn: &Connection,
+    canonical_name: &str,
+    pb_metrics: Option<&[PbMetric]>,
+) -> Result<u64, Error> {
+    let rows_affected = conn
+        .execute(
+            "UPDATE exercises SET pb_metrics = ?1, last_edited = ?2 WHERE name = ?3 COLLATE NOCASE AND deleted = FALSE",
+            params![pb_metrics.map(encode_pb_metrics), Utc::now().to_rfc3339(), canonical_name],
+        )
+        .map_err(Error::UpdateFailed)?;
+    if rows_affected == 0 {
+        Err(Error::ExerciseNotFound(canonical_name.to_string()))
+    } else {
+        Ok(rows_affected as u64)
+    }
+}
+
+/// Flips the favorite flag of a non-deleted exercise, bumping `last_edited` so it syncs.
+/// Returns the new value.
+pub fn toggle_exercise_favorite(conn: &Connection, canonical_name: &str) -> Result<bool, Error> {
+    let rows = conn
+        .execute(
+            "UPDATE exercises SET is_favorite = NOT is_favorite, last_edited = ?1 WHERE name = ?2 COLLATE NOCASE AND deleted = FALSE",
+            params![Utc::now().to_rfc3339(), canonical_name],
+        )
+        .map_err(Error::UpdateFailed)?;
+    if rows == 0 {
+        return Err(Error::ExerciseNotFound(canonical_name.to_string()));
+    }
+    conn.query_row(
+        "SELECT is_favorite FROM exercises WHERE name = ?1 COLLATE NOCASE AND deleted = FALSE",
+        params![canonical_name],
+        |row| row.get(0),
+    )
+    .map_err(Error::QueryFailed)
+}
+
+/// Stores PB metrics as a comma-separated list, e.g. `"weight,reps"`.
+fn encode_pb_metrics(metrics: &[PbMetric]) -> String {
+    metrics
+        .iter()
+        .map(|m| m.as_str())
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+fn parse_pb_metrics(stored: &str) -> Result<Vec<PbMetric>, rusqlite::Error> {
+    stored
+        .split(',')
+        .filter(|name| !name.is_empty())
+        .map(|name| {
+            PbMetric::ALL
+                .into_iter()
+                .find(|m| m.as_str() == name)
+                .ok_or_else(|| {
+                    rusqlite::Error::FromSqlConversionFailure(
+                        0,
+                        rusqlite::types::Type::Text,
+                        Box::new(Error::Conversion(format!("Invalid PB metric '{name}' from DB"))),
+                    )
+                })
+        })
+        .collect()
+}
+
+/// Soft deletes an exercise definition and its associated non-deleted aliases.
 pub fn delete_exercise(conn: &mut Connection, canonical_name: &str) -> Result<u64, Error> {
     let exercise = get_exercise_by_name(conn, canonical_name)? 
         .ok_or_else(|| Error::ExerciseNotFound(canonical_name.to_string()))?;
@@ -947,6 +2437,12 @@ pub fn delete_exercise(conn: &mut Connection, canonical_name: &str) -> Result<u6
         named_params! { ":name": name_to_delete, ":now": now_str },
     )
     .map_err(Error::DeleteFailed)?;
+    // Variations stay, as standalone exercises
+    tx.execute(
+        "UPDATE exercises SET parent_exercise = NULL, last_edited = :now WHERE parent_exercise = :name COLLATE NOCASE AND deleted = FALSE",
+        named_params! { ":name": name_to_delete, ":now": now_str },
+    )
+    .map_err(Error::DeleteFailed)?;
     
     let rows_affected = tx
         .execute("UPDATE exercises SET deleted = TRUE, last_edited = :now WHERE id = :id AND deleted = FALSE", 
@@ -962,6 +2458,54 @@ pub fn delete_exercise(conn: &mut Connection, canonical_name: &str) -> Result<u6
     }
 }
 
+/// Restores a soft-deleted exercise (matched by ID or name) and the aliases deleted along with it.
+/// Returns the restored exercise's name.
+pub fn restore_exercise(conn: &mut Connection, identifier: &str) -> Result<String, Error> {
+    let id_param = identifier.parse::<i64>().ok();
+    let exercise = conn
+        .query_row(
+            &format!(
+                "SELECT {EXERCISE_COLUMNS} FROM exercises
+                 WHERE (id = ?1 OR name = ?2 COLLATE NOCASE) AND deleted = TRUE
+                 ORDER BY last_edited DESC LIMIT 1"
+            ),
+            params![id_param, identifier],
+            map_row_to_exercise_definition,
+        )
+        .optional()
+        .map_err(Error::QueryFailed)?
+        .ok_or_else(|| Error::ExerciseNotFound(identifier.to_string()))?;
+
+    let name_taken: bool = conn
+        .query_row(
+            "SELECT EXISTS(SELECT 1 FROM exercises WHERE name = ?1 COLLATE NOCASE AND id != ?2 AND deleted = FALSE)",
+            params![exercise.name, exercise.id],
+            |row| row.get(0),
+        )
+        .map_err(Error::QueryFailed)?;
+    if name_taken {
+        return Err(Error::ExerciseNameNotUnique(exercise.name));
+    }
+
+    let now_str = Utc::now().to_rfc3339();
+    let tx = conn.transaction().map_err(Error::Connection)?;
+    // Aliases soft-deleted together with the exercise share its deletion timestamp.
+    tx.execute(
+        "UPDATE aliases SET deleted = FALSE, last_edited = :now
+         WHERE exercise_name = :name COLLATE NOCASE AND deleted = TRUE AND last_edited = :deleted_at",
+        named_params! { ":name": exercise.name, ":now": now_str, ":deleted_at": exercise.last_edited.to_rfc3339() },
+    )
+    .map_err(Error::UpdateFailed)?;
+    tx.execute(
+        "UPDATE exercises SET deleted = FALSE, last_edited = :now WHERE id = :id",
+        named_params! { ":id": exercise.id, ":now": now_str },
+    )
+    .map_err(Error::UpdateFailed)?;
+    tx.commit().map_err(Error::Connection)?;
+
+    Ok(exercise.name)
+}
+
 fn map_row_to_exercise_definition(row: &Row) -> Result<ExerciseDefinition, rusqlite::Error> {
     let type_str: String = row.get("type")?;
     let ex_type = ExerciseType::try_from(type_str.as_str()).map_err(|_e| {
@@ -985,6 +2529,21 @@ fn map_row_to_exercise_definition(row: &Row) -> Result<ExerciseDefinition, rusql
         log_reps: row.get("log_reps")?,
         log_duration: row.get("log_duration")?,
         log_distance: row.get("log_distance")?,
+        met_value: row.get("met_value")?,
+        description: row.get("description")?,
+        category: row.get("category")?,
+        pb_metrics: row
+            .get::<_, Option<String>>("pb_metrics")?
+            .map(|stored| parse_pb_metrics(&stored))
+            .transpose()?,
+        default_sets: row.get("default_sets")?,
+        default_reps: row.get("default_reps")?,
+        default_weight: row.get("default_weight")?,
+        is_favorite: row.get("is_favorite")?,
+        parent_exercise: row.get("parent_exercise")?,
+        weight_is_per_side: row.get("weight_is_per_side")?,
+        carriage_weight: row.get("carriage_weight")?,
+        sort_order: row.get("sort_order")?,
         deleted: row.get("deleted")?,
         last_edited: parse_datetime_from_string(row.get("last_edited")?)?, // Updated
     })
@@ -1015,6 +2574,9 @@ fn map_row_to_bodyweight_entry_for_sync(row: &Row) -> Result<BodyweightEntryForS
         _id: _id_val,
         timestamp: parse_datetime_from_string(row.get("timestamp")?)?, // This is measurement time
         weight: row.get("weight")?,
+        body_fat_pct: row.get("body_fat_pct")?,
+        muscle_mass: row.get("muscle_mass")?,
+        notes: row.get("notes")?,
         deleted: row.get("deleted")?,
         last_edited: parse_datetime_from_string(row.get("last_edited")?)?,
     })
@@ -1026,10 +2588,9 @@ pub fn get_exercise_by_name(
     name: &str,
 ) -> Result<Option<ExerciseDefinition>, Error> {
     let mut stmt = conn
-        .prepare(
-            "SELECT id, _id, name, type, muscles, log_weight, log_reps, log_duration, log_distance, deleted, last_edited
-             FROM exercises WHERE name = ?1 COLLATE NOCASE AND deleted = FALSE", 
-        )
+        .prepare(&format!(
+            "SELECT {EXERCISE_COLUMNS} FROM exercises WHERE name = ?1 COLLATE NOCASE AND deleted = FALSE"
+        ))
         .map_err(Error::QueryFailed)?;
     stmt.query_row(params![name], map_row_to_exercise_definition)
         .optional()
@@ -1039,10 +2600,9 @@ pub fn get_exercise_by_name(
 /// Retrieves a non-deleted exercise definition by its ID.
 pub fn get_exercise_by_id(conn: &Connection, id: i64) -> Result<Option<ExerciseDefinition>, Error> {
     let mut stmt = conn
-        .prepare(
-            "SELECT id, _id, name, type, muscles, log_weight, log_reps, log_duration, log_distance, deleted, last_edited
-             FROM exercises WHERE id = ?1 AND deleted = FALSE", 
-        )
+        .prepare(&format!(
+            "SELECT {EXERCISE_COLUMNS} FROM exercises WHERE id = ?1 AND deleted = FALSE"
+        ))
         .map_err(Error::QueryFailed)?;
     stmt.query_row(params![id], map_row_to_exercise_definition)
         .optional()
@@ -1100,122 +2660,491 @@ pub fn delete_alias(conn: &Connection, alias_name: &str) -> Result<u64, Error> {
     }
 }
 
-/// Retrieves the canonical exercise name associated with a non-deleted alias (case-insensitive).
-pub fn get_canonical_name_for_alias(
-    conn: &Connection,
-    alias_name: &str,
-) -> Result<Option<String>, Error> {
+/// Retrieves the canonical exercise name associated with a non-deleted alias (case-insensitive).
+pub fn get_canonical_name_for_alias(
+    conn: &Connection,
+    alias_name: &str,
+) -> Result<Option<String>, Error> {
+    let mut stmt = conn
+        .prepare("SELECT exercise_name FROM aliases WHERE alias_name = ?1 COLLATE NOCASE AND deleted = FALSE") 
+        .map_err(Error::QueryFailed)?;
+    stmt.query_row(params![alias_name], |row| row.get(0))
+        .optional()
+        .map_err(Error::QueryFailed)
+}
+
+/// Lists all non-deleted defined aliases and their corresponding canonical exercise names.
+pub fn list_aliases(conn: &Connection) -> Result<HashMap<String, String>, Error> {
+    let mut stmt = conn
+        .prepare("SELECT alias_name, exercise_name FROM aliases WHERE deleted = FALSE ORDER BY alias_name ASC") 
+        .map_err(Error::QueryFailed)?;
+    let alias_iter = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+        .map_err(Error::QueryFailed)?;
+    alias_iter
+        .collect::<Result<HashMap<_, _>, _>>()
+        .map_err(Error::QueryFailed)
+}
+
+/// Lists the non-deleted aliases pointing at an exercise, sorted alphabetically.
+pub fn list_aliases_for_exercise(conn: &Connection, exercise_name: &str) -> Result<Vec<String>, Error> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT alias_name FROM aliases
+             WHERE exercise_name = ?1 COLLATE NOCASE AND deleted = FALSE ORDER BY alias_name ASC",
+        )
+        .map_err(Error::QueryFailed)?;
+    let alias_iter = stmt
+        .query_map(params![exercise_name], |row| row.get(0))
+        .map_err(Error::QueryFailed)?;
+    alias_iter.collect::<Result<Vec<_>, _>>().map_err(Error::QueryFailed)
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ResolvedByType {
+    Id,
+    Alias,
+    Name,
+}
+
+/// Retrieves a non-deleted exercise definition by trying ID, then alias, then name.
+pub fn get_exercise_by_identifier(
+    conn: &Connection,
+    identifier: &str,
+) -> Result<Option<(ExerciseDefinition, ResolvedByType)>, Error> {
+    if let Ok(id) = identifier.parse::<i64>() {
+        if let Some(exercise) = get_exercise_by_id(conn, id)? { 
+            return Ok(Some((exercise, ResolvedByType::Id)));
+        }
+        return Ok(None); 
+    }
+    if let Some(canonical_name) = get_canonical_name_for_alias(conn, identifier)? { 
+        if let Some(exercise) = get_exercise_by_name(conn, &canonical_name)? { 
+            return Ok(Some((exercise, ResolvedByType::Alias)));
+        }
+        eprintln!(
+            "Warning: Alias '{identifier}' points to non-existent or soft-deleted exercise '{canonical_name}'."
+        );
+        return Ok(None);
+    }
+    match get_exercise_by_name(conn, identifier)? { 
+        Some(exercise) => Ok(Some((exercise, ResolvedByType::Name))),
+        None => Ok(None),
+    }
+}
+
+/// Filters and order for `list_exercises`. The default lists every non-deleted exercise,
+/// favorites first, then by name.
+#[derive(Debug, Default, Clone)]
+pub struct ExerciseFilters<'a> {
+    pub type_: Option<ExerciseType>,
+    /// Each muscle is a case-insensitive substring match against the comma-separated
+    /// `muscles` field, so "bic" matches "biceps" and "ceps" matches both "biceps" and
+    /// "triceps". Blank entries are ignored.
+    pub muscle: Option<Vec<&'a str>>,
+    pub muscle_match: MuscleMatch, // Whether an exercise needs every muscle or any one
+    pub category: Option<&'a str>,  // Case-insensitive
+    pub favorites_only: bool,
+    pub sort: ExerciseSort,
+}
+
+/// Lists non-deleted defined exercises matching `filters`.
+pub fn list_exercises(conn: &Connection, filters: &ExerciseFilters) -> Result<Vec<ExerciseDefinition>, Error> {
+    let mut sql = format!("SELECT {EXERCISE_COLUMNS} FROM exercises WHERE deleted = FALSE");
+    if filters.favorites_only {
+        sql.push_str(" AND is_favorite = TRUE");
+    }
+    let mut params_map: HashMap<String, Box<dyn ToSql>> = HashMap::new();
+
+    if let Some(t) = filters.type_ {
+        sql.push_str(" AND type = :type");
+        params_map.insert(":type".into(), Box::new(t.to_string()));
+    }
+    if let Some(c) = filters.category {
+        sql.push_str(" AND category = :category COLLATE NOCASE");
+        params_map.insert(":category".into(), Box::new(c.trim().to_string()));
+    }
+
+    if let Some(muscles) = &filters.muscle {
+        let mut clauses = Vec::new();
+        for (i, muscle) in muscles.iter().map(|m| m.trim()).filter(|m| !m.is_empty()).enumerate() {
+            let param_name = format!(":muscle{}", i);
+            clauses.push(format!("muscles LIKE {}", param_name));
+            params_map.insert(param_name, Box::new(format!("%{}%", muscle)));
+        }
+        if !clauses.is_empty() {
+            let joiner = match filters.muscle_match {
+                MuscleMatch::Any => " OR ",
+                MuscleMatch::All => " AND ",
+            };
+            sql.push_str(&format!(" AND ({})", clauses.join(joiner)));
+        }
+    }
+
+    sql.push_str(match filters.sort {
+        ExerciseSort::Name => " ORDER BY is_favorite DESC, name ASC",
+        ExerciseSort::Custom => " ORDER BY sort_order IS NULL, sort_order ASC, name ASC",
+    });
+
+    let params_for_query: Vec<(&str, &dyn ToSql)> = params_map
+        .iter()
+        .map(|(k, v)| (k.as_str(), v.as_ref()))
+        .collect();
+
+    let mut stmt = conn.prepare(&sql).map_err(Error::QueryFailed)?;
+    let exercise_iter = stmt
+        .query_map(params_for_query.as_slice(), map_row_to_exercise_definition)
+        .map_err(Error::QueryFailed)?;
+
+    exercise_iter
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(map_collect_error)
+}
+
+/// Number of non-deleted workout entries (of non-deleted exercises) per local week starting
+/// on `week_start`, for weeks from `since` on, as `(week start, count)` oldest first.
+/// Weeks without workouts are left out.
+pub fn count_workouts_by_week(
+    conn: &Connection,
+    since: NaiveDate,
+    week_start: Weekday,
+    tz: Tz,
+) -> Result<Vec<(NaiveDate, i64)>, Error> {
+    let week = VolumePeriod::Weekly.sql_period_start(week_start, tz);
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT {week} AS week, COUNT(*)
+             FROM workouts w
+             JOIN exercises e ON w.exercise_name = e.name COLLATE NOCASE AND e.deleted = FALSE
+             WHERE w.deleted = FALSE AND {week} >= date(?1)
+             GROUP BY week
+             ORDER BY week ASC"
+        ))
+        .map_err(Error::QueryFailed)?;
+    let rows = stmt
+        .query_map(params![since.format("%Y-%m-%d").to_string()], |row| {
+            let date_str: String = row.get(0)?;
+            let date = NaiveDate::parse_from_str(&date_str, "%Y-%m-%d").map_err(|e| {
+                rusqlite::Error::FromSqlConversionFailure(
+                    0,
+                    rusqlite::types::Type::Text,
+                    Box::new(Error::Conversion(format!(
+                        "Invalid date format '{date_str}': {e}"
+                    ))) as Box<dyn StdError + Send + Sync>,
+                )
+            })?;
+            Ok((date, row.get(1)?))
+        })
+        .map_err(Error::QueryFailed)?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(map_collect_error);
+    rows
+}
+
+/// Timestamp of the most recent non-deleted workout of any exercise, `None` if there are none.
+pub fn get_last_workout_time(conn: &Connection) -> Result<Option<DateTime<Utc>>, Error> {
+    let last: Option<String> = conn
+        .query_row(
+            "SELECT MAX(timestamp) FROM workouts WHERE deleted = FALSE",
+            [],
+            |row| row.get(0),
+        )
+        .map_err(Error::QueryFailed)?;
+    last.map(parse_datetime_from_string)
+        .transpose()
+        .map_err(map_collect_error)
+}
+
+/// Lists every non-deleted exercise with the timestamp of its most recent non-deleted
+/// workout (`None` if it has never been performed), ordered by name.
+#[allow(clippy::type_complexity)]
+pub fn list_exercise_last_workout_times(
+    conn: &Connection,
+) -> Result<Vec<(String, Option<DateTime<Utc>>)>, Error> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT e.name, MAX(w.timestamp)
+             FROM exercises e
+             LEFT JOIN workouts w ON w.exercise_name = e.name COLLATE NOCASE AND w.deleted = FALSE
+             WHERE e.deleted = FALSE
+             GROUP BY e.id
+             ORDER BY e.name ASC",
+        )
+        .map_err(Error::QueryFailed)?;
+    let rows = stmt
+        .query_map([], |row| {
+            let last: Option<String> = row.get(1)?;
+            Ok((row.get(0)?, last.map(parse_datetime_from_string).transpose()?))
+        })
+        .map_err(Error::QueryFailed)?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(map_collect_error);
+    rows
+}
+
+/// Usage totals for every non-deleted exercise with at least one non-deleted workout, as
+/// `(name, workout count, total volume, last workout timestamp)`, most used first (ties by
+/// name). Volume is sets * reps * effective weight, excluding warmups; the count includes them.
+#[allow(clippy::type_complexity)]
+pub fn list_exercise_usage(
+    conn: &Connection,
+) -> Result<Vec<(String, i64, f64, DateTime<Utc>)>, Error> {
+    let weight = PbMetric::Weight.sql_value();
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT e.name, COUNT(w.id) AS workout_count,
+                    COALESCE(SUM(CASE WHEN w.is_warmup = FALSE AND w.is_failure = FALSE AND e.type IN ('resistance', 'body-weight')
+                        THEN COALESCE(w.sets, 1) * COALESCE(w.reps, 0) * COALESCE({weight}, 0) END), 0.0),
+                    MAX(w.timestamp)
+             FROM exercises e
+             JOIN workouts w ON w.exercise_name = e.name COLLATE NOCASE AND w.deleted = FALSE
+             WHERE e.deleted = FALSE
+             GROUP BY e.id
+             ORDER BY workout_count DESC, e.name ASC"
+        ))
+        .map_err(Error::QueryFailed)?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get(0)?,
+                row.get(1)?,
+                row.get(2)?,
+                parse_datetime_from_string(row.get(3)?)?,
+            ))
+        })
+        .map_err(Error::QueryFailed)?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(map_collect_error);
+    rows
+}
+
+/// Sums over every non-deleted workout of a non-deleted exercise, as
+/// `(workout count, total volume, total reps, total duration minutes, total distance km)`.
+/// Reps are sets * reps; volume is sets * reps * effective weight and, like other volume
+/// queries, excludes warmups.
+pub fn get_lifetime_totals(conn: &Connection) -> Result<(i64, f64, i64, i64, f64), Error> {
+    let weight = PbMetric::Weight.sql_value();
+    conn.query_row(
+        &format!(
+            "SELECT COUNT(w.id),
+                    COALESCE(SUM(CASE WHEN w.is_warmup = FALSE AND w.is_failure = FALSE AND e.type IN ('resistance', 'body-weight')
+                        THEN COALESCE(w.sets, 1) * COALESCE(w.reps, 0) * COALESCE({weight}, 0) END), 0.0),
+                    COALESCE(SUM(COALESCE(w.sets, 1) * w.reps), 0),
+                    COALESCE(SUM(w.duration_minutes), 0),
+                    COALESCE(SUM(w.distance), 0.0)
+             FROM workouts w JOIN exercises e ON w.exercise_name = e.name COLLATE NOCASE
+             WHERE w.deleted = FALSE AND e.deleted = FALSE"
+        ),
+        [],
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?, row.get(4)?)),
+    )
+    .map_err(Error::QueryFailed)
+}
+
+/// Non-deleted workouts whose exercise is missing or soft-deleted, as `(workout id, exercise name)`.
+pub fn find_orphaned_workouts(conn: &Connection) -> Result<Vec<(i64, String)>, Error> {
     let mut stmt = conn
-        .prepare("SELECT exercise_name FROM aliases WHERE alias_name = ?1 COLLATE NOCASE AND deleted = FALSE") 
+        .prepare(
+            "SELECT w.id, w.exercise_name FROM workouts w
+             WHERE w.deleted = FALSE
+               AND NOT EXISTS (SELECT 1 FROM exercises e
+                               WHERE e.name = w.exercise_name COLLATE NOCASE AND e.deleted = FALSE)
+             ORDER BY w.id",
+        )
         .map_err(Error::QueryFailed)?;
-    stmt.query_row(params![alias_name], |row| row.get(0))
-        .optional()
-        .map_err(Error::QueryFailed)
+    let rows = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+        .map_err(Error::QueryFailed)?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(Error::QueryFailed);
+    rows
 }
 
-/// Lists all non-deleted defined aliases and their corresponding canonical exercise names.
-pub fn list_aliases(conn: &Connection) -> Result<HashMap<String, String>, Error> {
+/// Non-deleted aliases pointing at a missing or soft-deleted exercise, as `(alias, exercise name)`.
+pub fn find_dangling_aliases(conn: &Connection) -> Result<Vec<(String, String)>, Error> {
     let mut stmt = conn
-        .prepare("SELECT alias_name, exercise_name FROM aliases WHERE deleted = FALSE ORDER BY alias_name ASC") 
+        .prepare(
+            "SELECT a.alias_name, a.exercise_name FROM aliases a
+             WHERE a.deleted = FALSE
+               AND NOT EXISTS (SELECT 1 FROM exercises e
+                               WHERE e.name = a.exercise_name COLLATE NOCASE AND e.deleted = FALSE)
+             ORDER BY a.alias_name",
+        )
         .map_err(Error::QueryFailed)?;
-    let alias_iter = stmt
+    let rows = stmt
         .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
-        .map_err(Error::QueryFailed)?;
-    alias_iter
-        .collect::<Result<HashMap<_, _>, _>>()
-        .map_err(Error::QueryFailed)
+        .map_err(Error::QueryFailed)?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(Error::QueryFailed);
+    rows
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq, Eq)]
-pub enum ResolvedByType {
-    Id,
-    Alias,
-    Name,
+/// Soft-deletes every alias found by `find_dangling_aliases`. Returns the number of aliases removed.
+pub fn delete_dangling_aliases(conn: &Connection) -> Result<usize, Error> {
+    conn.execute(
+        "UPDATE aliases SET deleted = TRUE, last_edited = ?1
+         WHERE deleted = FALSE
+           AND NOT EXISTS (SELECT 1 FROM exercises e
+                           WHERE e.name = aliases.exercise_name COLLATE NOCASE AND e.deleted = FALSE)",
+        params![Utc::now().to_rfc3339()],
+    )
+    .map_err(Error::UpdateFailed)
 }
 
-/// Retrieves a non-deleted exercise definition by trying ID, then alias, then name.
-pub fn get_exercise_by_identifier(
-    conn: &Connection,
-    identifier: &str,
-) -> Result<Option<(ExerciseDefinition, ResolvedByType)>, Error> {
-    if let Ok(id) = identifier.parse::<i64>() {
-        if let Some(exercise) = get_exercise_by_id(conn, id)? { 
-            return Ok(Some((exercise, ResolvedByType::Id)));
-        }
-        return Ok(None); 
-    }
-    if let Some(canonical_name) = get_canonical_name_for_alias(conn, identifier)? { 
-        if let Some(exercise) = get_exercise_by_name(conn, &canonical_name)? { 
-            return Ok(Some((exercise, ResolvedByType::Alias)));
+/// Non-deleted workouts holding a value that can't be right, as `(workout id, column)`:
+/// negative sets, reps, duration, distance or bodyweight, and negative weight on anything
+/// but a body-weight exercise (where it means assistance). Ordered by id, then column.
+pub fn find_invalid_workout_values(conn: &Connection) -> Result<Vec<(i64, String)>, Error> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT w.id, w.sets, w.reps, w.weight, w.duration_minutes, w.distance, w.bodyweight,
+                    COALESCE(e.type, '')
+             FROM workouts w
+             LEFT JOIN exercises e ON e.name = w.exercise_name COLLATE NOCASE AND e.deleted = FALSE
+             WHERE w.deleted = FALSE
+               AND (w.sets < 0 OR w.reps < 0 OR w.duration_minutes < 0 OR w.distance < 0
+                    OR w.bodyweight < 0 OR (w.weight < 0 AND COALESCE(e.type, '') != 'body-weight'))
+             ORDER BY w.id",
+        )
+        .map_err(Error::QueryFailed)?;
+    let rows = stmt
+        .query_map([], |row| {
+            let id: i64 = row.get(0)?;
+            let is_body_weight = row.get::<_, String>(7)? == "body-weight";
+            let mut invalid = Vec::new();
+            for (index, column) in [(1, "sets"), (2, "reps"), (3, "weight"), (4, "duration_minutes"), (5, "distance"), (6, "bodyweight")] {
+                if column == "weight" && is_body_weight {
+                    continue;
+                }
+                if row.get::<_, Option<f64>>(index)?.is_some_and(|v| v < 0.0) {
+                    invalid.push((id, column.to_string()));
+                }
+            }
+            Ok(invalid)
+        })
+        .map_err(Error::QueryFailed)?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(Error::QueryFailed)?;
+    Ok(rows.into_iter().flatten().collect())
+}
+
+/// Non-deleted exercise names that only differ by case or surrounding whitespace, grouped,
+/// each group sorted by name.
+pub fn find_duplicate_exercise_names(conn: &Connection) -> Result<Vec<Vec<String>>, Error> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT lower(trim(name)) AS key, name FROM exercises
+             WHERE deleted = FALSE
+               AND lower(trim(name)) IN (SELECT lower(trim(name)) FROM exercises WHERE deleted = FALSE
+                                         GROUP BY lower(trim(name)) HAVING COUNT(*) > 1)
+             ORDER BY key, name",
+        )
+        .map_err(Error::QueryFailed)?;
+    let rows = stmt
+        .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?)))
+        .map_err(Error::QueryFailed)?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(Error::QueryFailed)?;
+    let mut groups: Vec<(String, Vec<String>)> = Vec::new();
+    for (key, name) in rows {
+        match groups.last_mut() {
+            Some((last_key, names)) if *last_key == key => names.push(name),
+            _ => groups.push((key, vec![name])),
         }
-        eprintln!(
-            "Warning: Alias '{identifier}' points to non-existent or soft-deleted exercise '{canonical_name}'."
-        );
-        return Ok(None);
-    }
-    match get_exercise_by_name(conn, identifier)? { 
-        Some(exercise) => Ok(Some((exercise, ResolvedByType::Name))),
-        None => Ok(None),
     }
+    Ok(groups.into_iter().map(|(_, names)| names).collect())
 }
 
-/// Lists non-deleted defined exercises, optionally filtering by type and/or muscle.
-pub fn list_exercises(
+/// Gets the maximum *effective* weight lifted for a specific non-deleted exercise from non-deleted workouts.
+pub fn get_max_effective_weight_for_exercise(
     conn: &Connection,
-    type_filter: Option<ExerciseType>,
-    muscle_filter: Option<Vec<&str>>,
-) -> Result<Vec<ExerciseDefinition>, Error> {
-    let mut sql = "SELECT id, name, type, muscles, log_weight, log_reps, log_duration, log_distance, deleted, last_edited
-                   FROM exercises WHERE deleted = FALSE" 
-        .to_string();
-    let mut params_map: HashMap<String, Box<dyn ToSql>> = HashMap::new();
-
-    if let Some(t) = type_filter {
-        sql.push_str(" AND type = :type");
-        params_map.insert(":type".into(), Box::new(t.to_string()));
-    }
+    canonical_exercise_name: &str,
+) -> Result<Option<f64>, Error> {
+    conn.query_row(
+        "SELECT MAX(
+             CASE e.type
+                 WHEN 'body-weight' THEN COALESCE(w.weight, 0) + COALESCE(w.bodyweight, 0)
+                 ELSE w.weight
+             END
+         )
+         FROM workouts w JOIN exercises e ON w.exercise_name = e.name COLLATE NOCASE
+         WHERE w.exercise_name = ?1 COLLATE NOCASE AND w.deleted = FALSE AND e.deleted = FALSE AND w.is_warmup = FALSE AND w.is_failure = FALSE", 
+        params![canonical_exercise_name],
+        |row| row.get(0),
+    )
+    .optional()
+    .map_err(Error::QueryFailed)
+    .map(Option::flatten)
+}
 
-    if let Some(muscles) = muscle_filter {
-        if !muscles.is_empty() {
-            for (i, muscle) in muscles.iter().enumerate() {
-                let param_name = format!(":muscle{}", i);
-                sql.push_str(&format!(" AND muscles LIKE {}", param_name));
-                params_map.insert(param_name, Box::new(format!("%{}%", muscle)));
-            }
-        }
+/// Gets the `n`th highest value (1-based) of `metric` for a non-deleted exercise
+/// from non-deleted, non-warmup workouts.
+pub fn get_nth_best_for_exercise(
+    conn: &Connection,
+    canonical_exercise_name: &str,
+    metric: PbMetric,
+    n: u32,
+) -> Result<Option<f64>, Error> {
+    if n == 0 {
+        return Err(Error::InvalidParameterCount(0, 0));
     }
+    let value = metric.sql_value();
+    conn.query_row(
+        &format!(
+            "SELECT {value} AS metric_value
+             FROM workouts w JOIN exercises e ON w.exercise_name = e.name COLLATE NOCASE
+             WHERE w.exercise_name = ?1 COLLATE NOCASE AND w.deleted = FALSE AND e.deleted = FALSE AND w.is_warmup = FALSE AND w.is_failure = FALSE
+               AND metric_value IS NOT NULL AND metric_value > 0
+             ORDER BY metric_value DESC LIMIT 1 OFFSET ?2"
+        ),
+        params![canonical_exercise_name, n - 1],
+        |row| row.get(0),
+    )
+    .optional()
+    .map_err(Error::QueryFailed)
+}
 
-    sql.push_str(" ORDER BY name ASC");
-
-    let params_for_query: Vec<(&str, &dyn ToSql)> = params_map
-        .iter()
-        .map(|(k, v)| (k.as_str(), v.as_ref()))
-        .collect();
-
-    let mut stmt = conn.prepare(&sql).map_err(Error::QueryFailed)?;
-    let exercise_iter = stmt
-        .query_map(params_for_query.as_slice(), map_row_to_exercise_definition)
+/// Heaviest effective weight lifted at each rep count for a specific non-deleted exercise,
+/// as `(reps, max_weight)` pairs ordered by reps.
+pub fn get_max_weight_by_reps(
+    conn: &Connection,
+    canonical_exercise_name: &str,
+) -> Result<Vec<(i64, f64)>, Error> {
+    let weight = PbMetric::Weight.sql_value();
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT w.reps, MAX({weight}) AS max_weight
+             FROM workouts w JOIN exercises e ON w.exercise_name = e.name COLLATE NOCASE
+             WHERE w.exercise_name = ?1 COLLATE NOCASE AND w.deleted = FALSE AND e.deleted = FALSE AND w.is_warmup = FALSE AND w.is_failure = FALSE
+               AND w.reps > 0
+             GROUP BY w.reps
+             HAVING max_weight > 0
+             ORDER BY w.reps ASC"
+        ))
         .map_err(Error::QueryFailed)?;
-
-    exercise_iter
+    let rows = stmt
+        .query_map(params![canonical_exercise_name], |row| Ok((row.get(0)?, row.get(1)?)))
+        .map_err(Error::QueryFailed)?
         .collect::<Result<Vec<_>, _>>()
-        .map_err(map_collect_error)
+        .map_err(map_collect_error);
+    rows
 }
 
-/// Gets the maximum *effective* weight lifted for a specific non-deleted exercise from non-deleted workouts.
-pub fn get_max_effective_weight_for_exercise(
+/// Gets the maximum single-entry volume (sets * reps * effective weight) for a specific
+/// non-deleted exercise from non-deleted workouts.
+pub fn get_max_volume_for_exercise(
     conn: &Connection,
     canonical_exercise_name: &str,
 ) -> Result<Option<f64>, Error> {
     conn.query_row(
         "SELECT MAX(
-             CASE e.type
+             COALESCE(w.sets, 1) * w.reps * CASE e.type
                  WHEN 'body-weight' THEN COALESCE(w.weight, 0) + COALESCE(w.bodyweight, 0)
                  ELSE w.weight
              END
          )
          FROM workouts w JOIN exercises e ON w.exercise_name = e.name COLLATE NOCASE
-         WHERE w.exercise_name = ?1 COLLATE NOCASE AND w.deleted = FALSE AND e.deleted = FALSE", 
+         WHERE w.exercise_name = ?1 COLLATE NOCASE AND w.reps IS NOT NULL AND w.deleted = FALSE AND e.deleted = FALSE AND w.is_warmup = FALSE AND w.is_failure = FALSE",
         params![canonical_exercise_name],
         |row| row.get(0),
     )
@@ -1224,6 +3153,104 @@ pub fn get_max_effective_weight_for_exercise(
     .map(Option::flatten)
 }
 
+/// Gets the fastest pace (lowest minutes per km) for a specific non-deleted exercise from
+/// non-deleted workouts. Entries without a positive duration and distance are ignored.
+pub fn get_min_pace_for_exercise(
+    conn: &Connection,
+    canonical_exercise_name: &str,
+) -> Result<Option<f64>, Error> {
+    conn.query_row(
+        "SELECT MIN(CAST(w.duration_minutes AS REAL) / w.distance) FROM workouts w JOIN exercises e ON w.exercise_name = e.name COLLATE NOCASE
+         WHERE w.exercise_name = ?1 COLLATE NOCASE AND w.duration_minutes > 0 AND w.distance > 0 AND w.deleted = FALSE AND e.deleted = FALSE AND w.is_warmup = FALSE AND w.is_failure = FALSE",
+        params![canonical_exercise_name], |row| row.get(0),
+    ).optional().map_err(Error::QueryFailed).map(Option::flatten)
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+pub struct PersonalBests {
+    pub max_weight: Option<f64>,
+    pub max_reps: Option<i64>,
+    pub max_duration_minutes: Option<i64>,
+    pub max_distance_km: Option<f64>, // Always store in km
+    pub best_pace_min_per_km: Option<f64>, // Lowest minutes per km
+}
+
+/// When each personal best of an exercise was first achieved, companion to the
+/// `get_max_*_for_exercise` queries. `None` where the exercise has no value for the metric.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PbTimestamps {
+    pub weight: Option<DateTime<Utc>>,
+    pub reps: Option<DateTime<Utc>>,
+    pub duration: Option<DateTime<Utc>>,
+    pub distance: Option<DateTime<Utc>>,
+    pub volume: Option<DateTime<Utc>>,
+    pub pace: Option<DateTime<Utc>>,
+}
+
+/// Best value of `value_sql` (highest, or lowest if `lower_is_better`) among the non-deleted,
+/// non-warmup workouts of an exercise, with the timestamp of the earliest workout achieving
+/// it. Rows where the value is NULL or not positive are ignored.
+fn get_best_value_with_timestamp(
+    conn: &Connection,
+    canonical_exercise_name: &str,
+    value_sql: &str,
+    lower_is_better: bool,
+) -> Result<Option<(f64, DateTime<Utc>)>, Error> {
+    let direction = if lower_is_better { "ASC" } else { "DESC" };
+    let best: Option<(f64, String)> = conn
+        .query_row(
+            &format!(
+                "SELECT CAST(({value_sql}) AS REAL), w.timestamp FROM workouts w JOIN exercises e ON w.exercise_name = e.name COLLATE NOCASE
+                 WHERE w.exercise_name = ?1 COLLATE NOCASE AND w.deleted = FALSE AND e.deleted = FALSE AND w.is_warmup = FALSE AND w.is_failure = FALSE
+                   AND ({value_sql}) > 0
+                 ORDER BY ({value_sql}) {direction}, w.timestamp ASC LIMIT 1"
+            ),
+            params![canonical_exercise_name],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()
+        .map_err(Error::QueryFailed)?;
+    best.map(|(value, ts)| {
+        parse_datetime_from_string(ts)
+            .map(|ts| (value, ts))
+            .map_err(Error::QueryFailed)
+    })
+    .transpose()
+}
+
+/// Gets the all-time maximum of `metric` for a specific non-deleted exercise together with
+/// when it was first achieved, companion to the `get_max_*_for_exercise` queries.
+pub fn get_max_with_timestamp_for_exercise(
+    conn: &Connection,
+    canonical_exercise_name: &str,
+    metric: PbMetric,
+) -> Result<Option<(f64, DateTime<Utc>)>, Error> {
+    get_best_value_with_timestamp(conn, canonical_exercise_name, metric.sql_value(), false)
+}
+
+/// Gets when each personal best of a specific non-deleted exercise was first achieved.
+pub fn get_pb_timestamps_for_exercise(
+    conn: &Connection,
+    canonical_exercise_name: &str,
+) -> Result<PbTimestamps, Error> {
+    let weight = PbMetric::Weight.sql_value();
+    let best = |value_sql: &str, lower_is_better| {
+        get_best_value_with_timestamp(conn, canonical_exercise_name, value_sql, lower_is_better)
+            .map(|best| best.map(|(_, ts)| ts))
+    };
+    Ok(PbTimestamps {
+        weight: best(weight, false)?,
+        reps: best(PbMetric::Reps.sql_value(), false)?,
+        duration: best(PbMetric::Duration.sql_value(), false)?,
+        distance: best(PbMetric::Distance.sql_value(), false)?,
+        volume: best(&format!("COALESCE(w.sets, 1) * w.reps * ({weight})"), false)?,
+        pace: best(
+            "CASE WHEN w.duration_minutes > 0 AND w.distance > 0 THEN CAST(w.duration_minutes AS REAL) / w.distance END",
+            true,
+        )?,
+    })
+}
+
 /// Gets the maximum reps performed for a specific non-deleted exercise from non-deleted workouts.
 pub fn get_max_reps_for_exercise(
     conn: &Connection,
@@ -1231,7 +3258,7 @@ pub fn get_max_reps_for_exercise(
 ) -> Result<Option<i64>, Error> {
     conn.query_row(
         "SELECT MAX(reps) FROM workouts w JOIN exercises e ON w.exercise_name = e.name COLLATE NOCASE
-         WHERE w.exercise_name = ?1 COLLATE NOCASE AND reps IS NOT NULL AND w.deleted = FALSE AND e.deleted = FALSE", 
+         WHERE w.exercise_name = ?1 COLLATE NOCASE AND reps IS NOT NULL AND w.deleted = FALSE AND e.deleted = FALSE AND w.is_warmup = FALSE AND w.is_failure = FALSE", 
         params![canonical_exercise_name], |row| row.get(0),
     ).optional().map_err(Error::QueryFailed).map(Option::flatten)
 }
@@ -1243,7 +3270,7 @@ pub fn get_max_duration_for_exercise(
 ) -> Result<Option<i64>, Error> {
     conn.query_row(
         "SELECT MAX(duration_minutes) FROM workouts w JOIN exercises e ON w.exercise_name = e.name COLLATE NOCASE
-         WHERE w.exercise_name = ?1 COLLATE NOCASE AND duration_minutes IS NOT NULL AND w.deleted = FALSE AND e.deleted = FALSE", 
+         WHERE w.exercise_name = ?1 COLLATE NOCASE AND duration_minutes IS NOT NULL AND w.deleted = FALSE AND e.deleted = FALSE AND w.is_warmup = FALSE AND w.is_failure = FALSE", 
         params![canonical_exercise_name], |row| row.get(0),
     ).optional().map_err(Error::QueryFailed).map(Option::flatten)
 }
@@ -1255,11 +3282,150 @@ pub fn get_max_distance_for_exercise(
 ) -> Result<Option<f64>, Error> {
     conn.query_row(
         "SELECT MAX(distance) FROM workouts w JOIN exercises e ON w.exercise_name = e.name COLLATE NOCASE
-         WHERE w.exercise_name = ?1 COLLATE NOCASE AND distance IS NOT NULL AND w.deleted = FALSE AND e.deleted = FALSE", 
+         WHERE w.exercise_name = ?1 COLLATE NOCASE AND distance IS NOT NULL AND w.deleted = FALSE AND e.deleted = FALSE AND w.is_warmup = FALSE AND w.is_failure = FALSE", 
         params![canonical_exercise_name], |row| row.get(0),
     ).optional().map_err(Error::QueryFailed).map(Option::flatten)
 }
 
+/// Personal bests of several non-deleted exercises in one query, keyed by canonical name,
+/// matching the `get_max_*_for_exercise` and `get_min_pace_for_exercise` queries. Exercises
+/// without any counted workout are left out.
+pub fn get_personal_bests_for_exercises(
+    conn: &Connection,
+    canonical_exercise_names: &[String],
+) -> Result<HashMap<String, PersonalBests>, Error> {
+    if canonical_exercise_names.is_empty() {
+        return Ok(HashMap::new());
+    }
+    let weight = PbMetric::Weight.sql_value();
+    let placeholders = vec!["?"; canonical_exercise_names.len()].join(", ");
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT e.name, MAX({weight}), MAX(w.reps), MAX(w.duration_minutes), MAX(w.distance),
+                    MIN(CASE WHEN w.duration_minutes > 0 AND w.distance > 0 THEN CAST(w.duration_minutes AS REAL) / w.distance END)
+             FROM workouts w JOIN exercises e ON w.exercise_name = e.name COLLATE NOCASE
+             WHERE e.name COLLATE NOCASE IN ({placeholders}) AND w.deleted = FALSE AND e.deleted = FALSE AND w.is_warmup = FALSE AND w.is_failure = FALSE
+             GROUP BY e.name"
+        ))
+        .map_err(Error::QueryFailed)?;
+    let rows = stmt
+        .query_map(rusqlite::params_from_iter(canonical_exercise_names), |row| {
+            Ok((
+                row.get(0)?,
+                PersonalBests {
+                    max_weight: row.get(1)?,
+                    max_reps: row.get(2)?,
+                    max_duration_minutes: row.get(3)?,
+                    max_distance_km: row.get(4)?,
+                    best_pace_min_per_km: row.get(5)?,
+                },
+            ))
+        })
+        .map_err(Error::QueryFailed)?
+        .collect::<Result<HashMap<_, _>, _>>()
+        .map_err(map_collect_error);
+    rows
+}
+
+/// Non-deleted workout timestamps of several non-deleted exercises in one query, keyed by
+/// canonical name, each ordered chronologically like `get_workout_timestamps_for_exercise`.
+/// Exercises without workouts are left out.
+pub fn get_workout_timestamps_for_exercises(
+    conn: &Connection,
+    canonical_exercise_names: &[String],
+) -> Result<HashMap<String, Vec<DateTime<Utc>>>, Error> {
+    let mut by_exercise: HashMap<String, Vec<DateTime<Utc>>> = HashMap::new();
+    if canonical_exercise_names.is_empty() {
+        return Ok(by_exercise);
+    }
+    let placeholders = vec!["?"; canonical_exercise_names.len()].join(", ");
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT e.name, w.timestamp FROM workouts w JOIN exercises e ON w.exercise_name = e.name COLLATE NOCASE
+             WHERE e.name COLLATE NOCASE IN ({placeholders}) AND w.deleted = FALSE AND e.deleted = FALSE
+             ORDER BY w.timestamp ASC, w.id ASC"
+        ))
+        .map_err(Error::QueryFailed)?;
+    let rows = stmt
+        .query_map(rusqlite::params_from_iter(canonical_exercise_names), |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+        })
+        .map_err(Error::QueryFailed)?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(map_collect_error)?;
+    for (name, timestamp) in rows {
+        let timestamp = parse_datetime_from_string(timestamp).map_err(Error::QueryFailed)?;
+        by_exercise.entry(name).or_default().push(timestamp);
+    }
+    Ok(by_exercise)
+}
+
+/// One row of the exercise comparison report, see `get_exercise_report_rows`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExerciseReportRow {
+    pub exercise_name: String,
+    pub exercise_type: ExerciseType,
+    pub max_weight: Option<f64>,  // Effective weight, as in `PersonalBests::max_weight`
+    pub e1rm: Option<f64>,        // Best Epley estimate over entered weight and reps
+    pub best_volume: Option<f64>, // Best single-entry sets * reps * effective weight
+    pub last_performed: Option<DateTime<Utc>>,
+}
+
+/// Report rows of several non-deleted exercises in one query, keyed by canonical name. The
+/// metrics skip warmup and failure sets like `get_personal_bests_for_exercises`;
+/// `last_performed` counts every non-deleted workout. Exercises without workouts are left out.
+pub fn get_exercise_report_rows(
+    conn: &Connection,
+    canonical_exercise_names: &[String],
+) -> Result<HashMap<String, ExerciseReportRow>, Error> {
+    if canonical_exercise_names.is_empty() {
+        return Ok(HashMap::new());
+    }
+    let weight = PbMetric::Weight.sql_value();
+    let counted = "w.is_warmup = FALSE AND w.is_failure = FALSE";
+    let placeholders = vec!["?"; canonical_exercise_names.len()].join(", ");
+    let mut stmt = conn
+        .prepare(&format!(
+            "SELECT e.name, e.type,
+                    MAX(CASE WHEN {counted} THEN {weight} END),
+                    MAX(CASE WHEN {counted} AND w.reps > 0 AND w.weight > 0 THEN w.weight * (1 + w.reps / 30.0) END),
+                    MAX(CASE WHEN {counted} AND w.reps IS NOT NULL THEN COALESCE(w.sets, 1) * w.reps * {weight} END),
+                    MAX(w.timestamp)
+             FROM workouts w JOIN exercises e ON w.exercise_name = e.name COLLATE NOCASE
+             WHERE e.name COLLATE NOCASE IN ({placeholders}) AND w.deleted = FALSE AND e.deleted = FALSE
+             GROUP BY e.name"
+        ))
+        .map_err(Error::QueryFailed)?;
+    let rows = stmt
+        .query_map(rusqlite::params_from_iter(canonical_exercise_names), |row| {
+            let type_str: String = row.get(1)?;
+            let last_performed = row
+                .get::<_, Option<String>>(5)?
+                .map(parse_datetime_from_string)
+                .transpose()?;
+            Ok(ExerciseReportRow {
+                exercise_name: row.get(0)?,
+                exercise_type: ExerciseType::try_from(type_str.as_str()).map_err(|_e| {
+                    rusqlite::Error::FromSqlConversionFailure(
+                        1, rusqlite::types::Type::Text,
+                        Box::new(Error::Conversion(format!("Invalid exercise type '{type_str}' from DB")))
+                            as Box<dyn StdError + Send + Sync>)
+                })?,
+                max_weight: row.get(2)?,
+                e1rm: row.get(3)?,
+                best_volume: row.get(4)?,
+                last_performed,
+            })
+        })
+        .map_err(Error::QueryFailed)?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(map_collect_error)?;
+    Ok(rows
+        .into_iter()
+        .map(|row| (row.exercise_name.clone(), row))
+        .collect())
+}
+
 /// Retrieves all non-deleted workout timestamps for a specific non-deleted exercise, ordered chronologically.
 pub fn get_workout_timestamps_for_exercise(
     conn: &Connection,
@@ -1267,7 +3433,7 @@ pub fn get_workout_timestamps_for_exercise(
 ) -> Result<Vec<DateTime<Utc>>, Error> {
     let mut stmt = conn.prepare(
         "SELECT w.timestamp FROM workouts w JOIN exercises e ON w.exercise_name = e.name COLLATE NOCASE
-         WHERE w.exercise_name = ?1 COLLATE NOCASE AND w.deleted = FALSE AND e.deleted = FALSE ORDER BY w.timestamp ASC, w.last_edited ASC", 
+         WHERE w.exercise_name = ?1 COLLATE NOCASE AND w.deleted = FALSE AND e.deleted = FALSE ORDER BY w.timestamp ASC, w.id ASC", 
     )?;
     let timestamp_iter = stmt.query_map(params![canonical_exercise_name], |row| {
         let timestamp_str: String = row.get(0)?;
@@ -1292,13 +3458,25 @@ pub fn add_bodyweight(
     conn: &Connection,
     timestamp: DateTime<Utc>,
     weight: f64,
+) -> Result<i64, Error> {
+    add_body_measurement(conn, timestamp, weight, None, None, None)
+}
+
+/// Adds a bodyweight entry with optional body-fat percentage, muscle mass and notes.
+pub fn add_body_measurement(
+    conn: &Connection,
+    timestamp: DateTime<Utc>,
+    weight: f64,
+    body_fat_pct: Option<f64>,
+    muscle_mass: Option<f64>,
+    notes: Option<&str>,
 ) -> Result<i64, Error> {
     let timestamp_str = timestamp.to_rfc3339();
     let now_str = Utc::now().to_rfc3339();
     let uuid_str = Uuid::new_v4().to_string();
     conn.execute(
-        "INSERT INTO bodyweights (_id, timestamp, weight, last_edited) VALUES (?1, ?2, ?3, ?4)",
-        params![uuid_str, timestamp_str, weight, now_str], 
+        "INSERT INTO bodyweights (_id, timestamp, weight, body_fat_pct, muscle_mass, notes, last_edited) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+        params![uuid_str, timestamp_str, weight, body_fat_pct, muscle_mass, notes, now_str], 
     )
     .map_err(|e| {
         if let rusqlite::Error::SqliteFailure(
@@ -1318,6 +3496,104 @@ pub fn add_bodyweight(
     Ok(conn.last_insert_rowid())
 }
 
+/// Outcome of one row passed to `import_body_measurements`.
+#[derive(Debug)]
+pub enum ImportRowOutcome {
+    Imported(i64),
+    Duplicate, // Same timestamp and weight already recorded
+    Failed(Error),
+}
+
+/// Inserts `(timestamp, weight, body_fat_pct, muscle_mass, notes)` rows in one transaction.
+/// A failing row (e.g. an existing timestamp) is reported in its outcome without aborting
+/// the others. With `skip_duplicates`, rows matching an active entry's timestamp and weight
+/// are skipped.
+#[allow(clippy::type_complexity)]
+pub fn import_body_measurements(
+    conn: &mut Connection,
+    rows: &[(DateTime<Utc>, f64, Option<f64>, Option<f64>, Option<String>)],
+    skip_duplicates: bool,
+) -> Result<Vec<ImportRowOutcome>, Error> {
+    let tx = conn.transaction().map_err(Error::Connection)?;
+    let mut outcomes = Vec::with_capacity(rows.len());
+    for (timestamp, weight, body_fat_pct, muscle_mass, notes) in rows {
+        let (timestamp, weight) = (*timestamp, *weight);
+        if skip_duplicates {
+            let exists: bool = tx
+                .query_row(
+                    "SELECT EXISTS(SELECT 1 FROM bodyweights WHERE timestamp = ?1 AND weight = ?2 AND deleted = FALSE)",
+                    params![timestamp.to_rfc3339(), weight],
+                    |row| row.get(0),
+                )
+                .map_err(Error::QueryFailed)?;
+            if exists {
+                outcomes.push(ImportRowOutcome::Duplicate);
+                continue;
+            }
+        }
+        outcomes.push(
+            match add_body_measurement(
+                &tx,
+                timestamp,
+                weight,
+                *body_fat_pct,
+                *muscle_mass,
+                notes.as_deref(),
+            ) {
+                Ok(id) => ImportRowOutcome::Imported(id),
+                Err(e) => ImportRowOutcome::Failed(e),
+            },
+        );
+    }
+    tx.commit().map_err(Error::Connection)?;
+    Ok(outcomes)
+}
+
+/// True if the exercises table has never held a row (soft-deleted rows count as rows).
+pub fn exercises_table_is_empty(conn: &Connection) -> Result<bool, Error> {
+    conn.query_row("SELECT NOT EXISTS(SELECT 1 FROM exercises)", [], |row| row.get(0))
+        .map_err(Error::QueryFailed)
+}
+
+/// Creates each `(name, type, muscles)` exercise in one transaction, skipping names already
+/// used by an exercise (including soft-deleted ones) or an alias. Returns the number created.
+pub fn seed_exercises(
+    conn: &mut Connection,
+    exercises: &[(&str, ExerciseType, &str)],
+) -> Result<usize, Error> {
+    let tx = conn.transaction().map_err(Error::Connection)?;
+    let mut created = 0;
+    for (name, ex_type, muscles) in exercises {
+        let taken: bool = tx
+            .query_row(
+                "SELECT EXISTS(SELECT 1 FROM exercises WHERE name = ?1 COLLATE NOCASE)
+                     OR EXISTS(SELECT 1 FROM aliases WHERE alias_name = ?1 COLLATE NOCASE AND deleted = FALSE)",
+                params![name],
+                |row| row.get(0),
+            )
+            .map_err(Error::QueryFailed)?;
+        if taken {
+            continue;
+        }
+        create_exercise(
+            &tx,
+            name,
+            ex_type,
+            Some(muscles),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            &ExerciseDefaults::default(),
+        )?;
+        created += 1;
+    }
+    tx.commit().map_err(Error::Connection)?;
+    Ok(created)
+}
+
 /// Retrieves the most recent non-deleted bodyweight entry.
 pub fn get_latest_bodyweight(conn: &Connection) -> Result<Option<f64>, Error> {
     conn.query_row(
@@ -1329,6 +3605,66 @@ pub fn get_latest_bodyweight(conn: &Connection) -> Result<Option<f64>, Error> {
     .map_err(Error::QueryFailed)
 }
 
+/// Retrieves non-deleted bodyweight entries with body-composition readings,
+/// ordered by timestamp descending, up to a limit.
+pub fn list_body_measurements(conn: &Connection, limit: u32) -> Result<Vec<BodyMeasurement>, Error> {
+    let mut stmt = conn.prepare(
+        "SELECT id, timestamp, weight, body_fat_pct, muscle_mass, notes FROM bodyweights
+         WHERE deleted = FALSE ORDER BY timestamp DESC, last_edited DESC LIMIT ?1",
+    )?;
+    let iter = stmt.query_map(params![limit], |row| {
+        Ok(BodyMeasurement {
+            id: row.get("id")?,
+            timestamp: parse_datetime_from_string(row.get("timestamp")?)?,
+            weight: row.get("weight")?,
+            body_fat_pct: row.get("body_fat_pct")?,
+            muscle_mass: row.get("muscle_mass")?,
+            notes: row.get("notes")?,
+        })
+    })?;
+    iter.collect::<Result<Vec<_>, _>>().map_err(map_collect_error)
+}
+
+/// Retrieves `(timestamp, value)` pairs for a body metric in chronological order,
+/// skipping entries where the metric wasn't recorded.
+pub fn get_body_metric_series(
+    conn: &Connection,
+    metric: BodyMetric,
+    start_date: Option<NaiveDate>,
+    end_date: Option<NaiveDate>,
+    tz: Tz,
+) -> Result<Vec<(DateTime<Utc>, f64)>, Error> {
+    let column = metric.column();
+    let local_date = format!("date({})", local_timestamp_sql("timestamp", tz));
+    let mut sql = format!(
+        "SELECT timestamp, {column} FROM bodyweights WHERE deleted = FALSE AND {column} IS NOT NULL"
+    );
+    let mut params_map: HashMap<String, Box<dyn ToSql>> = HashMap::new();
+    if let Some(start) = start_date {
+        sql.push_str(&format!(" AND {local_date} >= date(:start_date)"));
+        params_map.insert(":start_date".into(), Box::new(start.format("%Y-%m-%d").to_string()));
+    }
+    if let Some(end) = end_date {
+        sql.push_str(&format!(" AND {local_date} <= date(:end_date)"));
+        params_map.insert(":end_date".into(), Box::new(end.format("%Y-%m-%d").to_string()));
+    }
+    sql.push_str(" ORDER BY timestamp ASC, last_edited ASC");
+
+    let params_for_query: Vec<(&str, &dyn ToSql)> = params_map
+        .iter()
+        .map(|(k, v)| (k.as_str(), v.as_ref()))
+        .collect();
+    let mut stmt = conn.prepare(&sql).map_err(Error::QueryFailed)?;
+    let series = stmt
+        .query_map(params_for_query.as_slice(), |row| {
+            Ok((parse_datetime_from_string(row.get(0)?)?, row.get(1)?))
+        })
+        .map_err(Error::QueryFailed)?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(map_collect_error);
+    series
+}
+
 /// Retrieves non-deleted bodyweight entries, ordered by timestamp descending, up to a limit.
 pub fn list_bodyweights(
     conn: &Connection,
@@ -1448,20 +3784,22 @@ pub fn get_workout_dates_for_month_db(
     conn: &Connection,
     year: i32,
     month: u32,
+    tz: Tz,
 ) -> Result<Vec<String>, Error> {
     if !(1..=12).contains(&month) {
         return Err(Error::InvalidParameterCount(month as usize, 12)); 
     }
 
+    let local_ts = local_timestamp_sql("timestamp", tz);
     let mut stmt = conn
-        .prepare(
-            "SELECT DISTINCT strftime('%Y-%m-%d', timestamp) AS workout_day \
+        .prepare(&format!(
+            "SELECT DISTINCT strftime('%Y-%m-%d', {local_ts}) AS workout_day \
          FROM workouts \
-         WHERE CAST(strftime('%Y', timestamp) AS INTEGER) = ?1 \
-           AND CAST(strftime('%m', timestamp) AS INTEGER) = ?2 \
+         WHERE CAST(strftime('%Y', {local_ts}) AS INTEGER) = ?1 \
+           AND CAST(strftime('%m', {local_ts}) AS INTEGER) = ?2 \
            AND deleted = FALSE \
          ORDER BY workout_day;", 
-        )
+        ))
         .map_err(Error::QueryFailed)?;
 
     let date_iter = stmt
@@ -1496,7 +3834,7 @@ fn parse_datetime_from_string(s: String) -> Result<DateTime<Utc>, rusqlite::Erro
 
 
 pub fn get_exercises_modified_since(conn: &Connection, since: Option<DateTime<Utc>>) -> Result<Vec<ExerciseDefinition>, Error> {
-    let mut query = "SELECT id, _id, name, type, muscles, log_weight, log_reps, log_duration, log_distance, deleted, last_edited FROM exercises".to_string();
+    let mut query = format!("SELECT {EXERCISE_COLUMNS} FROM exercises");
     let mut params_vec: Vec<Box<dyn ToSql>> = Vec::new();
     if let Some(since_ts) = since {
         query.push_str(" WHERE last_edited > ?1");
@@ -1513,8 +3851,7 @@ pub fn get_exercises_modified_since(conn: &Connection, since: Option<DateTime<Ut
 }
 
 pub fn get_workouts_modified_since(conn: &Connection, since: Option<DateTime<Utc>>) -> Result<Vec<Workout>, Error> {
-    let mut query = "SELECT w.id, w._id, w.timestamp, w.exercise_name, w.sets, w.reps, w.weight, w.duration_minutes, w.distance, w.bodyweight, w.notes, e.type, w.deleted, w.last_edited 
-                     FROM workouts w LEFT JOIN exercises e ON w.exercise_name = e.name COLLATE NOCASE".to_string(); // Added COLLATE NOCASE
+    let mut query = format!("SELECT {WORKOUT_COLUMNS} FROM workouts w LEFT JOIN exercises e ON w.exercise_name = e.name COLLATE NOCASE");
     let mut params_vec: Vec<Box<dyn ToSql>> = Vec::new();
     if let Some(since_ts) = since {
         query.push_str(" WHERE w.last_edited > ?1");
@@ -1548,7 +3885,7 @@ pub fn get_aliases_modified_since(conn: &Connection, since: Option<DateTime<Utc>
 }
 
 pub fn get_bodyweights_modified_since(conn: &Connection, since: Option<DateTime<Utc>>) -> Result<Vec<BodyweightEntryForSync>, Error> {
-    let mut query = "SELECT id, _id, timestamp, weight, deleted, last_edited FROM bodyweights".to_string();
+    let mut query = "SELECT id, _id, timestamp, weight, body_fat_pct, muscle_mass, notes, deleted, last_edited FROM bodyweights".to_string();
     let mut params_vec: Vec<Box<dyn ToSql>> = Vec::new();
     if let Some(since_ts) = since {
         query.push_str(" WHERE last_edited > ?1");
@@ -1566,17 +3903,20 @@ pub fn get_bodyweights_modified_since(conn: &Connection, since: Option<DateTime<
 
 pub fn upsert_exercise(tx: &Transaction, ex: &ExerciseDefinition) -> Result<(), Error> {
     tx.execute(
-        "INSERT INTO exercises (id, name, type, muscles, log_weight, log_reps, log_duration, log_distance, deleted, last_edited)
-         VALUES (:id, :name, :type, :muscles, :lw, :lr, :ldu, :ldi, :del, :le)
+        "INSERT INTO exercises (id, name, type, muscles, log_weight, log_reps, log_duration, log_distance, met_value, description, category, pb_metrics, default_sets, default_reps, default_weight, is_favorite, parent_exercise, weight_is_per_side, carriage_weight, sort_order, deleted, last_edited)
+         VALUES (:id, :name, :type, :muscles, :lw, :lr, :ldu, :ldi, :met, :desc, :cat, :pbm, :dset, :drep, :dwt, :fav, :parent, :per_side, :carriage, :sort, :del, :le)
          ON CONFLICT(id) DO UPDATE SET
            name = excluded.name, type = excluded.type, muscles = excluded.muscles,
            log_weight = excluded.log_weight, log_reps = excluded.log_reps, log_duration = excluded.log_duration,
-           log_distance = excluded.log_distance, deleted = excluded.deleted, last_edited = excluded.last_edited
+           log_distance = excluded.log_distance, met_value = excluded.met_value, description = excluded.description, category = excluded.category, pb_metrics = excluded.pb_metrics, default_sets = excluded.default_sets, default_reps = excluded.default_reps, default_weight = excluded.default_weight, is_favorite = excluded.is_favorite, parent_exercise = excluded.parent_exercise, weight_is_per_side = excluded.weight_is_per_side, carriage_weight = excluded.carriage_weight, sort_order = excluded.sort_order, deleted = excluded.deleted, last_edited = excluded.last_edited
          WHERE excluded.last_edited >= exercises.last_edited",
         named_params! {
             ":id": ex.id, ":name": ex.name, ":type": ex.type_.to_string(), ":muscles": ex.muscles,
             ":lw": ex.log_weight, ":lr": ex.log_reps, ":ldu": ex.log_duration, ":ldi": ex.log_distance,
-            ":del": ex.deleted, ":le": ex.last_edited.to_rfc3339(),
+            ":met": ex.met_value, ":desc": ex.description, ":cat": ex.category,
+            ":pbm": ex.pb_metrics.as_deref().map(encode_pb_metrics), ":dset": ex.default_sets, ":drep": ex.default_reps,
+            ":dwt": ex.default_weight, ":fav": ex.is_favorite, ":parent": ex.parent_exercise,
+            ":per_side": ex.weight_is_per_side, ":carriage": ex.carriage_weight, ":sort": ex.sort_order, ":del": ex.deleted, ":le": ex.last_edited.to_rfc3339(),
         }
     ).map_err(|e| {
         if let rusqlite::Error::SqliteFailure(ref err_info, Some(ref msg)) = e {
@@ -1591,18 +3931,20 @@ pub fn upsert_exercise(tx: &Transaction, ex: &ExerciseDefinition) -> Result<(),
 
 pub fn upsert_workout(tx: &Transaction, w: &Workout) -> Result<(), Error> {
     tx.execute(
-        "INSERT INTO workouts (id, timestamp, exercise_name, sets, reps, weight, duration_minutes, distance, bodyweight, notes, deleted, last_edited)
-         VALUES (:id, :ts, :ex_name, :sets, :reps, :w, :dur, :dist, :bw, :notes, :del, :le)
+        "INSERT INTO workouts (id, timestamp, exercise_name, sets, reps, weight, duration_minutes, distance, bodyweight, notes, is_warmup, is_amrap, is_failure, avg_heart_rate, max_heart_rate, deleted, last_edited)
+         VALUES (:id, :ts, :ex_name, :sets, :reps, :w, :dur, :dist, :bw, :notes, :warmup, :amrap, :failure, :avg_hr, :max_hr, :del, :le)
          ON CONFLICT(id) DO UPDATE SET
            timestamp = excluded.timestamp, exercise_name = excluded.exercise_name, sets = excluded.sets,
            reps = excluded.reps, weight = excluded.weight, duration_minutes = excluded.duration_minutes,
            distance = excluded.distance, bodyweight = excluded.bodyweight, notes = excluded.notes,
-           deleted = excluded.deleted, last_edited = excluded.last_edited
+           is_warmup = excluded.is_warmup, is_amrap = excluded.is_amrap, is_failure = excluded.is_failure,
+           avg_heart_rate = excluded.avg_heart_rate, max_heart_rate = excluded.max_heart_rate, deleted = excluded.deleted, last_edited = excluded.last_edited
          WHERE excluded.last_edited >= workouts.last_edited",
         named_params! {
             ":id": w.id, ":ts": w.timestamp.to_rfc3339(), ":ex_name": w.exercise_name, ":sets": w.sets,
             ":reps": w.reps, ":w": w.weight, ":dur": w.duration_minutes, ":dist": w.distance,
-            ":bw": w.bodyweight, ":notes": w.notes, ":del": w.deleted, ":le": w.last_edited.to_rfc3339(),
+            ":bw": w.bodyweight, ":notes": w.notes, ":warmup": w.is_warmup, ":amrap": w.is_amrap, ":failure": w.is_failure,
+            ":avg_hr": w.avg_heart_rate, ":max_hr": w.max_heart_rate, ":del": w.deleted, ":le": w.last_edited.to_rfc3339(),
         }
     ).map_err(Error::UpdateFailed)?;
     Ok(())
@@ -1634,14 +3976,16 @@ pub fn upsert_alias(tx: &Transaction, alias: &AliasEntryForSync) -> Result<(), E
 
 pub fn upsert_bodyweight_entry(tx: &Transaction, bw: &BodyweightEntryForSync) -> Result<(), Error> {
     tx.execute(
-        "INSERT INTO bodyweights (id, timestamp, weight, deleted, last_edited)
-         VALUES (:id, :ts, :w, :del, :le)
+        "INSERT INTO bodyweights (id, timestamp, weight, body_fat_pct, muscle_mass, notes, deleted, last_edited)
+         VALUES (:id, :ts, :w, :bf, :mm, :notes, :del, :le)
          ON CONFLICT(id) DO UPDATE SET
            timestamp = excluded.timestamp, weight = excluded.weight,
-           deleted = excluded.deleted, last_edited = excluded.last_edited
+           body_fat_pct = excluded.body_fat_pct, muscle_mass = excluded.muscle_mass,
+           notes = excluded.notes, deleted = excluded.deleted, last_edited = excluded.last_edited
          WHERE excluded.last_edited >= bodyweights.last_edited",
         named_params! {
             ":id": bw.id, ":ts": bw.timestamp.to_rfc3339(), ":w": bw.weight,
+            ":bf": bw.body_fat_pct, ":mm": bw.muscle_mass, ":notes": bw.notes,
             ":del": bw.deleted, ":le": bw.last_edited.to_rfc3339(),
         }
     ).map_err(|e| {
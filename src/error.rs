@@ -0,0 +1,184 @@
+use crate::config::ConfigError;
+use crate::db::Error as DbError;
+use std::error::Error as StdError;
+use std::fmt;
+
+/// Error returned by public `AppService` methods.
+///
+/// Each variant wraps the full `anyhow::Error` (message and context chain), so callers can
+/// match on the variant to decide how to report it (e.g. an HTTP status code) and still
+/// downcast to the underlying `DbError`/`ConfigError` with `downcast_ref`.
+#[derive(Debug)]
+pub enum ServiceError {
+    /// The exercise, workout, alias, tag or entry does not exist.
+    NotFound(anyhow::Error),
+    /// The input was rejected (bad value, unsupported combination, ...).
+    Validation(anyhow::Error),
+    /// The change clashes with existing data (duplicate name, alias or entry).
+    Conflict(anyhow::Error),
+    /// The database failed.
+    Db(anyhow::Error),
+    /// Reading or writing the configuration failed.
+    Config(anyhow::Error),
+    /// Any other failure, e.g. I/O or network errors.
+    Internal(anyhow::Error),
+}
+
+pub type ServiceResult<T> = Result<T, ServiceError>;
+
+/// Variant of a `ServiceError`, used while classifying an error chain.
+#[derive(Debug, Clone, Copy)]
+enum Kind {
+    NotFound,
+    Validation,
+    Conflict,
+    Db,
+    Config,
+    Internal,
+}
+
+impl Kind {
+    fn wrap(self, err: anyhow::Error) -> ServiceError {
+        match self {
+            Self::NotFound => ServiceError::NotFound(err),
+            Self::Validation => ServiceError::Validation(err),
+            Self::Conflict => ServiceError::Conflict(err),
+            Self::Db => ServiceError::Db(err),
+            Self::Config => ServiceError::Config(err),
+            Self::Internal => ServiceError::Internal(err),
+        }
+    }
+
+    /// Kind of the first typed cause found in the chain, if any.
+    fn classify(err: &anyhow::Error) -> Option<Self> {
+        err.chain().find_map(|cause| {
+            if let Some(service_err) = cause.downcast_ref::<ServiceError>() {
+                return Some(service_err.kind());
+            }
+            if let Some(db_err) = cause.downcast_ref::<DbError>() {
+                return Some(match db_err {
+                    DbError::ExerciseNotFound(_)
+                    | DbError::WorkoutNotFound(_)
+                    | DbError::AliasNotFound(_)
+                    | DbError::NoWorkoutDataFound(_)
+                    | DbError::BodyWeightEntryNotFound(_)
+                    | DbError::TagNotFound(..) => Self::NotFound,
+                    DbError::BodyweightEntryExists(_)
+                    | DbError::AliasAlreadyExists(_)
+                    | DbError::ExerciseNameNotUnique(_) => Self::Conflict,
+                    _ => Self::Db,
+                });
+            }
+            if let Some(config_err) = cause.downcast_ref::<ConfigError>() {
+                return Some(match config_err {
+                    ConfigError::InvalidColor(_)
+                    | ConfigError::InvalidBodyweightInput(_)
+                    | ConfigError::InvalidPbNotificationInput(_)
+                    | ConfigError::InvalidStreakInterval(_)
+                    | ConfigError::InvalidTimezone(_)
+                    | ConfigError::InvalidWeightIncrement(_)
+                    | ConfigError::InvalidPbTolerance(_)
+                    | ConfigError::InvalidValidationLimit(_)
+                    | ConfigError::InvalidPlateWeight(_)
+                    | ConfigError::UnknownThemePreset(_)
+                    | ConfigError::InvalidDecimalSeparator(_)
+                    | ConfigError::InvalidProgressionScheme(_)
+                    | ConfigError::InvalidRecoveryHours(_)
+                    | ConfigError::InvalidMaxHeartRate(_)
+                    | ConfigError::InvalidAge(_) => Self::Validation,
+                    _ => Self::Config,
+                });
+            }
+            (cause.is::<rusqlite::Error>() || cause.is::<r2d2::Error>()).then_some(Self::Db)
+        })
+    }
+}
+
+impl ServiceError {
+    /// Builds a `ServiceError` for an error raised directly by the service (`bail!`).
+    /// Typed causes are classified as in `From<anyhow::Error>`; plain messages are treated
+    /// as validation failures.
+    pub(crate) fn raised(err: anyhow::Error) -> Self {
+        Kind::classify(&err).unwrap_or(Kind::Validation).wrap(err)
+    }
+
+    fn kind(&self) -> Kind {
+        match self {
+            Self::NotFound(_) => Kind::NotFound,
+            Self::Validation(_) => Kind::Validation,
+            Self::Conflict(_) => Kind::Conflict,
+            Self::Db(_) => Kind::Db,
+            Self::Config(_) => Kind::Config,
+            Self::Internal(_) => Kind::Internal,
+        }
+    }
+
+    /// The wrapped error, including any context added by the service.
+    pub fn inner(&self) -> &anyhow::Error {
+        match self {
+            Self::NotFound(e)
+            | Self::Validation(e)
+            | Self::Conflict(e)
+            | Self::Db(e)
+            | Self::Config(e)
+            | Self::Internal(e) => e,
+        }
+    }
+
+    /// Consumes the error, returning the wrapped `anyhow::Error`.
+    pub fn into_inner(self) -> anyhow::Error {
+        match self {
+            Self::NotFound(e)
+            | Self::Validation(e)
+            | Self::Conflict(e)
+            | Self::Db(e)
+            | Self::Config(e)
+            | Self::Internal(e) => e,
+        }
+    }
+
+    /// Returns the underlying error of type `E` (e.g. `DbError`), if there is one anywhere
+    /// in the chain.
+    pub fn downcast_ref<E>(&self) -> Option<&E>
+    where
+        E: fmt::Display + fmt::Debug + Send + Sync + 'static,
+    {
+        let inner = self.inner();
+        inner.downcast_ref::<E>().or_else(|| {
+            inner
+                .chain()
+                .find_map(|cause| cause.downcast_ref::<Self>())
+                .and_then(Self::downcast_ref::<E>)
+        })
+    }
+}
+
+impl fmt::Display for ServiceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self.inner(), f)
+    }
+}
+
+impl StdError for ServiceError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        self.inner().source()
+    }
+}
+
+impl From<anyhow::Error> for ServiceError {
+    fn from(err: anyhow::Error) -> Self {
+        Kind::classify(&err).unwrap_or(Kind::Internal).wrap(err)
+    }
+}
+
+impl From<DbError> for ServiceError {
+    fn from(err: DbError) -> Self {
+        Self::from(anyhow::Error::new(err))
+    }
+}
+
+impl From<ConfigError> for ServiceError {
+    fn from(err: ConfigError) -> Self {
+        Self::from(anyhow::Error::new(err))
+    }
+}
@@ -1,49 +1,110 @@
-use anyhow::{bail, Context, Result};
-// Use anyhow::Result as standard Result for service layer
-use chrono::{DateTime, Datelike, Duration, NaiveDate, TimeZone, Utc};
+use anyhow::Context;
+use chrono::{DateTime, Datelike, Duration, NaiveDate, TimeZone, Timelike, Utc, Weekday};
+use chrono_tz::Tz;
 use crate::sync_client::{ChangesPayload, ConfigChange};
 use db::NewWorkoutData; // Import specific struct needed
-use rusqlite::Connection;
 use serde::{Deserialize, Serialize};
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::collections::HashMap;
+use std::io::{BufRead, Write};
 use std::path::{Path, PathBuf};
 use toml;
 
 // --- Declare modules ---
 mod config;
 pub mod db;
+mod error;
 pub mod sync_client;
 
 // --- Expose public types ---
 pub use config::{
     get_config_path as get_config_path_util,
     load as load_config_util,
+    merge_config,
     parse_color,
+    parse_timezone,
     save as save_config_util,
     Config,
     ConfigError, // Renamed from Error
     PbNotificationConfig,
+    PbTolerance,
+    ProgressionScheme,
     StandardColor,
     Theme,
     Units,
+    ValidationConfig,
 };
 
 
+pub use error::{ServiceError, ServiceResult};
 pub use db::{
     get_db_path as get_db_path_util,
+    BodyMeasurement,
+    BodyMetric,
+    ConnectionSource,
     Error as DbError, // Renamed from DbError
     // list_aliases as list_aliases_util, // Example if needed
+    ExerciseDefaults,
     ExerciseDefinition,
+    ExerciseFilters,
+    ExerciseReportRow,
+    ExerciseSort,
     ExerciseType,
+    MuscleMatch,
+    PbMetric,
+    PersonalBests,
+    PurgeCounts,
     ResolvedByType,
     VolumeFilters,
+    UndoAction,
+    UndoEntry,
+    VolumePeriod,
     Workout,
     WorkoutFilters,
+    WorkoutSplit,
+    UNDO_LOG_DEPTH,
 };
 
+// Service methods return `ServiceError`; `anyhow` errors from `?`/`.context()` are
+// classified by its `From` impl.
+type Result<T, E = ServiceError> = std::result::Result<T, E>;
+
+/// Like `anyhow::bail!`, but returns a `ServiceError` (plain messages become `Validation`).
+macro_rules! bail {
+    ($($arg:tt)*) => {
+        return Err(ServiceError::raised(anyhow::anyhow!($($arg)*)))
+    };
+}
+
 pub const KM_TO_MILE: f64 = 0.621_371;
 pub const MILE_TO_KM: f64 = 1.60934;
+pub const LBS_TO_KG: f64 = 0.453_592;
+/// Number of preceding weeks averaged when looking for deload weeks.
+pub const DELOAD_TRAILING_WEEKS: usize = 4;
+/// Bucket used by muscle-based reports for exercises without any muscles listed.
+pub const UNSPECIFIED_MUSCLE: &str = "unspecified";
+/// Number of workouts fetched per query by `stream_workouts`.
+pub const STREAM_BATCH_SIZE: u32 = 500;
+/// Bucket used by category reports for exercises without a category.
+pub const UNCATEGORIZED: &str = "uncategorized";
+/// Highest rep count reported by the rep-max table (1RM through 10RM).
+pub const REP_MAX_TABLE_SIZE: i64 = 10;
+/// Days without training after which `suggest_next_workout` considers an exercise.
+pub const SUGGESTION_STALE_DAYS: u32 = 3;
+/// Days of recent muscle volume `suggest_next_workout` balances against.
+pub const SUGGESTION_LOOKBACK_DAYS: u32 = 7;
+/// Maximum number of exercises returned by `suggest_next_workout`.
+pub const SUGGESTION_LIMIT: usize = 5;
+/// Fewest days of history `predict_metric` fits a trend to.
+pub const PREDICTION_MIN_POINTS: usize = 3;
+/// Directory, next to the database file, that `backup_database` writes to by default.
+pub const BACKUP_DIR_NAME: &str = "backups";
+/// Ratio to the current PB above which a new value is flagged as a likely typo.
+pub const PB_JUMP_WARNING_RATIO: f64 = 1.5;
+/// Heaviest load per side of the bar, in the configured units, `calculate_plates` will solve for.
+pub const MAX_PLATE_LOAD_PER_SIDE: f64 = 1000.0;
+/// Text `clear_all_data` must be given, exactly, before it wipes anything.
+pub const CLEAR_ALL_DATA_CONFIRMATION: &str = "DELETE ALL MY DATA";
 
 // Helper struct to hold previous bests internally
 #[derive(Debug)]
@@ -52,6 +113,9 @@ struct PreviousBests {
     reps: Option<i64>,
     duration: Option<i64>,
     distance_km: Option<f64>,
+    volume: Option<f64>,
+    pace_min_per_km: Option<f64>,
+    achieved_at: db::PbTimestamps,
 }
 
 impl PreviousBests {
@@ -60,6 +124,8 @@ impl PreviousBests {
             && self.reps.is_none()
             && self.duration.is_none()
             && self.distance_km.is_none()
+            && self.volume.is_none()
+            && self.pace_min_per_km.is_none()
     }
 }
 
@@ -75,6 +141,61 @@ pub struct EditWorkoutParams {
     pub new_distance_arg: Option<f64>,
     pub new_notes: Option<String>,
     pub new_date: Option<NaiveDate>,
+    pub new_is_warmup: Option<bool>,
+    pub new_is_amrap: Option<bool>,
+    pub new_is_failure: Option<bool>,
+    pub new_avg_heart_rate: Option<Option<i64>>, // `Some(None)` clears it
+    pub new_max_heart_rate: Option<Option<i64>>, // `Some(None)` clears it
+}
+
+/// A new exercise for `AppService::add_exercise`. Log flags left `None` follow the type
+/// (weight and reps for resistance/body-weight, duration and distance for cardio).
+#[derive(Debug, Clone)]
+pub struct NewExerciseParams<'a> {
+    pub name: &'a str,
+    pub type_: ExerciseType,
+    pub muscles: Option<&'a str>,
+    pub log_weight: Option<bool>,
+    pub log_reps: Option<bool>,
+    pub log_duration: Option<bool>,
+    pub log_distance: Option<bool>,
+    pub description: Option<&'a str>, // Form cues, setup
+    pub category: Option<&'a str>,    // e.g. "Push", "Pull"
+    pub defaults: Option<ExerciseDefaults>, // Prefilled sets/reps/weight when logging it
+}
+
+impl<'a> NewExerciseParams<'a> {
+    /// Params for an exercise with only a name and type set.
+    #[must_use]
+    pub const fn new(name: &'a str, type_: ExerciseType) -> Self {
+        Self {
+            name,
+            type_,
+            muscles: None,
+            log_weight: None,
+            log_reps: None,
+            log_duration: None,
+            log_distance: None,
+            description: None,
+            category: None,
+            defaults: None,
+        }
+    }
+}
+
+/// Changes for `AppService::edit_exercise`; `None` leaves a field unchanged.
+#[derive(Debug, Clone, Default)]
+pub struct EditExerciseParams<'a> {
+    pub new_name: Option<&'a str>,
+    pub new_type: Option<ExerciseType>,
+    pub new_muscles: Option<Option<&'a str>>,
+    pub new_log_weight: Option<bool>,
+    pub new_log_reps: Option<bool>,
+    pub new_log_duration: Option<bool>,
+    pub new_log_distance: Option<bool>,
+    pub new_description: Option<Option<&'a str>>, // `Some(None)` or `Some(Some(""))` clears it
+    pub new_category: Option<Option<&'a str>>,    // `Some(None)` or `Some(Some(""))` clears it
+    pub new_defaults: Option<ExerciseDefaults>, // Replaces all three; `Some(ExerciseDefaults::default())` clears them
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
@@ -86,6 +207,190 @@ pub enum GraphType {
     WorkoutReps,
     WorkoutDuration,
     WorkoutDistance,
+    CaloriesBurned,
+    RelativeIntensity, // Top-set weight as % of the best E1RM so far
+    WorkoutDensity,    // Volume per minute
+}
+
+/// Post-processing of graph values, see `AppService::get_data_for_graph`.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GraphTransform {
+    Log10,          // Log scale: equal steps are equal percentage gains
+    PercentOfFirst, // Each value as a percentage of the first point, which becomes 100
+}
+
+impl GraphTransform {
+    /// Applies the transform to graph points. Values must be positive, as the points of
+    /// `get_data_for_graph` are.
+    #[must_use]
+    pub fn apply(self, points: Vec<(NaiveDate, f64)>) -> Vec<(NaiveDate, f64)> {
+        match self {
+            Self::Log10 => points.into_iter().map(|(date, value)| (date, value.log10())).collect(),
+            Self::PercentOfFirst => {
+                let Some(&(_, first)) = points.first() else {
+                    return points;
+                };
+                points
+                    .into_iter()
+                    .map(|(date, value)| (date, value / first * 100.0))
+                    .collect()
+            }
+        }
+    }
+}
+
+/// A graph value with its display-rounded counterpart, see `AppService::get_graph_points`.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct GraphPoint {
+    pub date: NaiveDate,
+    pub value: f64,   // Exact value, as returned by `get_data_for_graph`
+    pub rounded: f64, // Value for labels; E1RM graphs use `AppService::round_e1rm`
+}
+
+/// Graph series of several exercises, see `AppService::get_multi_graph_data`.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq)]
+pub struct MultiGraphData {
+    pub series: HashMap<String, Vec<(NaiveDate, f64)>>, // Keyed by canonical name
+    pub skipped: Vec<String>, // Identifiers that didn't resolve, in the order given
+}
+
+/// When a metric is projected to reach a target, see `AppService::predict_metric`.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct Prediction {
+    pub target_date: NaiveDate, // Day the trend line reaches the target
+    pub weekly_rate: f64,       // Trend slope, in the metric's units per week
+    pub r_squared: f64,         // How well the line fits the history: 1.0 is a perfect fit
+}
+
+/// How a search query matched an exercise, best first, see `AppService::search_exercises`.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MatchKind {
+    Prefix,
+    Contains,
+    Fuzzy,
+}
+
+/// An exercise found by `AppService::search_exercises`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct ExerciseMatch {
+    pub canonical_name: String,
+    pub matched_alias: Option<String>, // None when the name itself matched
+    pub kind: MatchKind,
+}
+
+/// A hit of `AppService::global_search`, with what is needed to open it.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub enum SearchResult {
+    Exercise {
+        canonical_name: String,
+        kind: MatchKind,
+    },
+    Alias {
+        alias: String,
+        canonical_name: String,
+        kind: MatchKind,
+    },
+    WorkoutNote {
+        workout_id: i64,
+        exercise_name: String,
+        date: NaiveDate, // Local date of the workout
+        notes: String,
+    },
+}
+
+/// A record changed since a point in time, see `AppService::recent_changes`. Deleted records
+/// are included (with `deleted` set), as the sync client sees them.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub enum RecentChange {
+    Exercise(ExerciseDefinition),
+    Workout(Workout),
+    Alias(db::AliasEntryForSync),
+    Bodyweight(db::BodyweightEntryForSync),
+}
+
+impl RecentChange {
+    /// When the record was last modified.
+    #[must_use]
+    pub const fn last_edited(&self) -> DateTime<Utc> {
+        match self {
+            Self::Exercise(e) => e.last_edited,
+            Self::Workout(w) => w.last_edited,
+            Self::Alias(a) => a.last_edited,
+            Self::Bodyweight(b) => b.last_edited,
+        }
+    }
+
+    /// Whether the change was a (soft) deletion.
+    #[must_use]
+    pub const fn is_deleted(&self) -> bool {
+        match self {
+            Self::Exercise(e) => e.deleted,
+            Self::Workout(w) => w.deleted,
+            Self::Alias(a) => a.deleted,
+            Self::Bodyweight(b) => b.deleted,
+        }
+    }
+}
+
+/// Part of the day a workout was logged in, by local hour.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum TimeOfDay {
+    Morning,   // 04:00-11:59
+    Afternoon, // 12:00-16:59
+    Evening,   // 17:00-03:59
+}
+
+impl TimeOfDay {
+    pub const fn from_hour(hour: u32) -> Self {
+        match hour {
+            4..=11 => Self::Morning,
+            12..=16 => Self::Afternoon,
+            _ => Self::Evening,
+        }
+    }
+}
+
+/// Cardio intensity zone by average heart rate as a share of max heart rate.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum HrZone {
+    Zone1, // 50-59%: very light
+    Zone2, // 60-69%: light, endurance
+    Zone3, // 70-79%: moderate, aerobic
+    Zone4, // 80-89%: hard, threshold
+    Zone5, // 90%+: maximum
+}
+
+impl HrZone {
+    /// Zone of `heart_rate` given `max_hr` (both in bpm); `None` below 50% of max.
+    #[must_use]
+    pub fn from_heart_rate(heart_rate: i64, max_hr: u32) -> Option<Self> {
+        let percent = heart_rate * 100 / i64::from(max_hr.max(1));
+        match percent {
+            ..=49 => None,
+            50..=59 => Some(Self::Zone1),
+            60..=69 => Some(Self::Zone2),
+            70..=79 => Some(Self::Zone3),
+            80..=89 => Some(Self::Zone4),
+            _ => Some(Self::Zone5),
+        }
+    }
+}
+
+/// Average estimated 1RM of the entries logged in one part of the day.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct TimeOfDayPerformance {
+    pub time_of_day: TimeOfDay,
+    pub average_e1rm: f64,
+    pub entries: usize,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct BodyMeasurementParams {
+    pub timestamp: DateTime<Utc>,
+    pub weight: f64,
+    pub body_fat_pct: Option<f64>, // 0-100
+    pub muscle_mass: Option<f64>,  // Same units as weight
+    pub notes: Option<String>,     // Context, e.g. "post-workout"; blank is stored as None
 }
 
 #[derive(Default, Clone)]
@@ -98,16 +403,26 @@ pub struct AddWorkoutParams<'a> {
     pub duration: Option<i64>,
     pub distance: Option<f64>,
     pub notes: Option<String>,
+    pub start_time: Option<DateTime<Utc>>, // With `end_time`, fills in `duration` if absent
+    pub end_time: Option<DateTime<Utc>>,
     pub implicit_type: Option<ExerciseType>,
     pub implicit_muscles: Option<String>,
     pub bodyweight_to_use: Option<f64>,
+    pub is_warmup: bool, // Warmups are stored but skipped for PBs and volume
+    pub is_amrap: bool,  // Marks the set used as the top set, see `get_top_set`
+    pub is_failure: bool, // Missed attempt: stored for display and `get_failure_rate`, skipped for PBs and volume
+    pub avg_heart_rate: Option<i64>, // bpm, see `hr_zone`
+    pub max_heart_rate: Option<i64>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, Default)]
 pub struct PbMetricInfo<T: PartialEq + Default + Copy> {
     pub achieved: bool,
+    #[serde(default)]
+    pub tied: bool, // Matched the previous best, see `PbNotificationConfig::notify_ties`
     pub new_value: Option<T>,
     pub previous_value: Option<T>,
+    pub previous_date: Option<NaiveDate>, // Local date the previous best was first achieved
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
@@ -116,6 +431,11 @@ pub struct PBInfo {
     pub reps: PbMetricInfo<i64>,
     pub duration: PbMetricInfo<i64>,
     pub distance: PbMetricInfo<f64>, // Always stored/compared as km
+    pub volume: PbMetricInfo<f64>,   // Single entry: sets * reps * effective weight
+    pub pace: PbMetricInfo<f64>,     // Minutes per km; lower is better
+    pub session_volume: PbMetricInfo<f64>, // Whole day, all exercises; see `best_session_volume`
+    #[serde(default)]
+    pub warnings: Vec<String>, // Likely typos, see `AppService::pb_jump_warnings`
 }
 
 impl PBInfo {
@@ -126,15 +446,44 @@ impl PBInfo {
             || self.reps.achieved
             || self.duration.achieved
             || self.distance.achieved
+            || self.volume.achieved
+            || self.pace.achieved
+            || self.session_volume.achieved
+    }
+
+    /// Helper to check if any PB was matched without being beaten.
+    #[must_use]
+    pub const fn any_tie(&self) -> bool {
+        self.weight.tied
+            || self.reps.tied
+            || self.duration.tied
+            || self.distance.tied
+            || self.volume.tied
+            || self.pace.tied
+    }
+}
+
+impl<T: PartialEq + Default + Copy> PbMetricInfo<T> {
+    /// Heading for a notification about this metric: "New PB", "Matched PB", or `None` if
+    /// there is nothing to report.
+    #[must_use]
+    pub const fn headline(&self) -> Option<&'static str> {
+        if self.achieved {
+            Some("New PB")
+        } else if self.tied {
+            Some("Matched PB")
+        } else {
+            None
+        }
     }
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
-pub struct PersonalBests {
-    pub max_weight: Option<f64>,
-    pub max_reps: Option<i64>,
-    pub max_duration_minutes: Option<i64>,
-    pub max_distance_km: Option<f64>, // Always store in km
+/// One personal best from a `PBInfo`, see `AppService::pb_events`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct PbEvent {
+    pub exercise: String,
+    pub metric: String, // "weight", "reps", "duration", "distance", "volume", "pace", "session_volume"
+    pub value: f64,     // Distance and pace in configured units
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
@@ -145,26 +494,224 @@ pub struct ExerciseStats {
     pub last_workout_date: Option<NaiveDate>,
     pub avg_workouts_per_week: Option<f64>,
     pub longest_gap_days: Option<u64>,
+    pub consistency_score: Option<f64>, // 0-100, see `calculate_consistency_score`
     pub personal_bests: PersonalBests,
     pub current_streak: u32,
     pub longest_streak: u32,
     pub streak_interval_days: u32, // From config
 }
 
+/// Stats of several exercises, see `AppService::get_exercise_stats_batch`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ExerciseStatsBatch {
+    pub stats: HashMap<String, ExerciseStats>, // Keyed by canonical name
+    pub skipped: Vec<String>, // Identifiers that didn't resolve, then exercises without workouts
+}
+
+/// Exercise comparison report, see `AppService::build_exercise_report`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ExerciseReport {
+    pub rows: Vec<ExerciseReportRow>,
+    pub skipped: Vec<String>, // Identifiers that didn't resolve
+}
+
+/// Outcome of `AppService::merge_exercises`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct MergeResult {
+    pub reassigned: u64, // Workouts moved to the target
+    pub type_mismatch: Option<(ExerciseType, ExerciseType)>, // (source, target) when they differ
+}
+
+/// An exercise that hasn't been performed recently.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct StaleExercise {
+    pub name: String,
+    pub last_date: Option<NaiveDate>, // None = never performed
+}
+
+/// How recovered a muscle probably is, see `AppService::muscle_recovery_status`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MuscleRecovery {
+    pub muscle: String,
+    pub last_trained: Option<DateTime<Utc>>, // None = never trained
+    pub recovery_hours: u32,                 // Recovery window used for this muscle
+    pub recovered_pct: f64,                  // 0-100, grows linearly over the window
+    pub fully_recovered: bool,
+}
+
+/// How much an exercise has been used, see `AppService::get_exercise_leaderboard`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ExerciseUsage {
+    pub name: String,
+    pub workout_count: usize,
+    pub total_volume: f64, // Sets * reps * effective weight, warmups excluded
+    pub last_performed: NaiveDate,
+}
+
+/// Totals across the whole training history, see `AppService::get_lifetime_totals`.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub struct LifetimeTotals {
+    pub workout_count: usize,
+    pub total_volume: f64, // Sets * reps * effective weight, warmups excluded
+    pub total_reps: i64,   // Sets * reps
+    pub total_duration_minutes: i64,
+    pub total_distance: f64, // In configured units
+}
+
+/// Problems found by `AppService::check_integrity`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct IntegrityReport {
+    pub orphaned_workouts: Vec<(i64, String)>,  // (workout id, missing exercise name)
+    pub dangling_aliases: Vec<(String, String)>, // (alias, missing exercise name)
+    pub invalid_values: Vec<(i64, String)>,     // (workout id, column holding an impossible value)
+    pub duplicate_names: Vec<Vec<String>>,      // Exercise names differing only by case/whitespace
+    pub repaired_aliases: usize,                // Dangling aliases soft-deleted by a repair run
+}
+
+impl IntegrityReport {
+    /// True when no problem was found (repaired ones included).
+    pub fn is_clean(&self) -> bool {
+        self.orphaned_workouts.is_empty()
+            && self.dangling_aliases.is_empty()
+            && self.invalid_values.is_empty()
+            && self.duplicate_names.is_empty()
+    }
+}
+
+/// One exercise's totals in two periods, see `AppService::compare_periods`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ExerciseChange {
+    pub name: String,
+    pub volume_a: f64,
+    pub volume_b: f64,
+    pub volume_change_pct: Option<f64>, // From A to B; None when A is zero
+    pub workouts_a: usize,
+    pub workouts_b: usize,
+}
+
+/// Totals of two periods side by side, see `AppService::compare_periods`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct PeriodComparison {
+    pub volume_a: f64,
+    pub volume_b: f64,
+    pub volume_change_pct: Option<f64>, // From A to B; None when A is zero
+    pub workouts_a: usize,
+    pub workouts_b: usize,
+    pub workouts_change_pct: Option<f64>,
+    pub exercises: Vec<ExerciseChange>, // By name; exercises missing from a period have zeros there
+}
+
+/// Total volume of one day's training across all exercises.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SessionVolume {
+    pub date: NaiveDate,
+    pub total_volume: f64,
+    pub by_exercise: Vec<(String, f64)>, // Highest volume first
+}
+
+/// Volume rows with their totals, see `AppService::calculate_volume_with_totals`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct VolumeTotals {
+    pub rows: Vec<(NaiveDate, String, f64)>, // As returned by `calculate_daily_volume`
+    pub by_exercise: BTreeMap<String, f64>,  // Subtotal per exercise over all rows
+    pub total: f64,
+}
+
+/// The heaviest set of a session by estimated 1RM, see `AppService::get_top_set`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct TopSet {
+    pub workout_id: i64,
+    pub reps: i64,
+    pub weight: f64,
+    pub e1rm: f64,
+    pub is_amrap: bool,
+}
+
+/// How often attempts at an exercise were missed, see `AppService::get_failure_rate`.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub struct FailureRate {
+    pub attempts: i64, // Working sets, failed ones included
+    pub failures: i64,
+    pub percentage: f64, // 0.0 when there are no attempts
+}
+
+/// An exercise suggested by `AppService::suggest_next_workout`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ExerciseSuggestion {
+    pub name: String,
+    pub reason: String,                    // Human-readable explanation for the pick
+    pub last_performed: Option<NaiveDate>, // None = never performed
+}
+
+/// Target for the next session of an exercise, see `AppService::suggest_progression`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct ProgressionSuggestion {
+    pub sets: i64,
+    pub reps: i64,
+    pub weight: f64,         // As entered when logging (per side for per-side exercises)
+    pub based_on: NaiveDate, // Session the suggestion progresses from
+}
+
 pub struct AppService {
     pub config: Config,
-    pub conn: Connection,
+    pub conn: ConnectionSource,
     pub db_path: PathBuf,
     pub config_path: PathBuf,
 }
 
+/// A row that could not be imported.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ImportRowError {
+    pub line: usize, // 1-based line number in the input
+    pub message: String,
+}
+
+/// Result of a bulk import. Rows with errors are skipped; the rest are imported.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ImportSummary {
+    pub imported: usize,
+    pub skipped_duplicates: usize,
+    pub errors: Vec<ImportRowError>,
+}
+
+/// CSV dialect for the import/export functions. Defaults to comma-delimited, `"`-quoted,
+/// LF-terminated rows with RFC 3339 (UTC) timestamps.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CsvOptions {
+    pub delimiter: char,
+    pub quote: char,
+    pub line_ending: String, // e.g. "\r\n" for spreadsheets on Windows
+    pub date_format: Option<String>, // chrono format in the configured timezone, e.g. "%d.%m.%Y %H:%M"
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: ',',
+            quote: '"',
+            line_ending: "\n".to_string(),
+            date_format: None,
+        }
+    }
+}
+
 #[derive(Default, Debug, Deserialize, Serialize)]
 pub struct SyncSummary {
+    pub backup: Option<PathBuf>, // Set when `auto_backup_before_sync` made a backup
     pub config: bool,
     pub exercises: usize,
     pub workouts: usize,
     pub aliases: usize,
     pub bodyweights: usize,
+    #[serde(default)]
+    pub failures: Vec<SyncFailure>, // Records skipped by a best-effort (non-strict) sync
+}
+
+/// A server record that could not be applied, see `AppService::apply_server_changes`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SyncFailure {
+    pub record: String, // e.g. "workout ID 12" or "alias 'bp'"
+    pub error: String,
 }
 
 
@@ -173,7 +720,7 @@ pub struct SyncSummary {
 impl AppService {
     /// Initializes the application service.
     /// # Errors
-    /// Returns `anyhow::Error` if config/db path determination, loading, or initialization fails.
+    /// Returns `ServiceError` if config/db path determination, loading, or initialization fails.
     pub fn initialize() -> Result<Self> {
         let config_path =
             config::get_config_path().context("Failed to determine configuration file path")?;
@@ -186,12 +733,78 @@ impl AppService {
 
         db::init(&conn).context("Failed to initialize database schema")?;
 
-        Ok(Self {
+        let service = Self {
             config,
-            conn,
+            conn: conn.into(),
             db_path,
             config_path,
-        })
+        };
+        service.seed_if_first_run()?;
+        Ok(service)
+    }
+
+    /// Initializes the application service backed by a pool of up to `size` connections.
+    ///
+    /// Unlike `initialize`, the resulting service is `Sync`, so read methods (`&self`) can be
+    /// called concurrently from multiple threads, e.g. when embedding the library in a server.
+    /// # Errors
+    /// Returns `ServiceError` if `size` is 0, or config/db path determination, loading,
+    /// pool creation, or initialization fails.
+    pub fn initialize_with_pool(size: u32) -> Result<Self> {
+        if size == 0 {
+            bail!("Connection pool size must be at least 1.");
+        }
+        let config_path =
+            config::get_config_path().context("Failed to determine configuration file path")?;
+        let config = config::load(&config_path)
+            .context(format!("Failed to load config from {config_path:?}"))?;
+
+        let db_path = db::get_db_path().context("Failed to determine database path")?;
+        let pool = db::open_pool(&db_path, size)
+            .with_context(|| format!("Failed to open database pool at {db_path:?}"))?;
+
+        db::init(&*pool.get().map_err(DbError::Pool)?)
+            .context("Failed to initialize database schema")?;
+
+        let service = Self {
+            config,
+            conn: pool.into(),
+            db_path,
+            config_path,
+        };
+        service.seed_if_first_run()?;
+        Ok(service)
+    }
+
+    /// Seeds the built-in exercises when `seed_on_first_run` is set and the exercises table
+    /// has never held a row, so deleting every exercise doesn't bring them back.
+    fn seed_if_first_run(&self) -> Result<()> {
+        if !self.config.seed_on_first_run {
+            return Ok(());
+        }
+        let is_empty = db::exercises_table_is_empty(&*self.connection()?)
+            .context("Failed to check for existing exercises")?;
+        if is_empty {
+            self.seed_default_exercises()?;
+        }
+        Ok(())
+    }
+
+    /// Adds the built-in list of common exercises (see `DEFAULT_EXERCISES`), skipping names
+    /// already used by an exercise or alias. Returns the number of exercises created.
+    /// # Errors
+    /// - `ServiceError` wrapping `DbError` variants.
+    pub fn seed_default_exercises(&self) -> Result<usize> {
+        db::seed_exercises(&mut *self.connection()?, DEFAULT_EXERCISES)
+            .context("Failed to seed default exercises")
+            .map_err(Into::into)
+    }
+
+    /// Borrows a database connection for the duration of a single operation.
+    /// Don't call other `AppService` methods while holding it: in single-connection
+    /// mode that would wait on the connection already held.
+    fn connection(&self) -> Result<db::ConnectionGuard<'_>, DbError> {
+        self.conn.get()
     }
 
     pub fn get_config_path(&self) -> &Path {
@@ -248,6 +861,39 @@ impl AppService {
         self.save_config()
     }
 
+    /// Sets (or clears with `None`) the IANA timezone used to assign workouts to local dates.
+    /// # Errors
+    /// - `ConfigError::InvalidTimezone` if the name is unknown.
+    /// - `ConfigError` variants if saving fails.
+    pub fn set_timezone(&mut self, timezone: Option<&str>) -> Result<(), ConfigError> {
+        self.config.timezone = match timezone.map(str::trim).filter(|tz| !tz.is_empty()) {
+            Some(name) => Some(parse_timezone(name)?.name().to_string()),
+            None => None,
+        };
+        self.save_config()
+    }
+
+    /// Calendar date of a timestamp in the configured timezone.
+    fn local_date(&self, timestamp: DateTime<Utc>) -> NaiveDate {
+        timestamp.with_timezone(&self.config.tz()).date_naive()
+    }
+
+    /// Sets the first day of the week used for weekly aggregation.
+    /// # Errors
+    /// Returns `ConfigError` variants if saving fails.
+    pub fn set_week_start(&mut self, day: Weekday) -> Result<(), ConfigError> {
+        self.config.week_start = day;
+        self.save_config()
+    }
+
+    /// Sets the weekdays used by `suggest_next_workout`; an empty list means every day.
+    /// # Errors
+    /// Returns `ConfigError` variants if saving fails.
+    pub fn set_scheduled_days(&mut self, days: Vec<Weekday>) -> Result<(), ConfigError> {
+        self.config.scheduled_days = days;
+        self.save_config()
+    }
+
     /// Sets the global PB notification preference.
     /// # Errors
     /// Returns `ConfigError` variants if saving fails.
@@ -295,6 +941,73 @@ impl AppService {
         self.save_config()
     }
 
+    /// Sets the volume PB notification flag.
+    /// # Errors
+    /// Returns `ConfigError` variants if saving fails.
+    pub fn set_pb_notify_volume(&mut self, enabled: bool) -> Result<(), ConfigError> {
+        self.config.pb_notifications.notify_volume = enabled;
+        self.save_config()
+    }
+
+    /// Sets the pace PB notification flag.
+    /// # Errors
+    /// Returns `ConfigError` variants if saving fails.
+    pub fn set_pb_notify_pace(&mut self, enabled: bool) -> Result<(), ConfigError> {
+        self.config.pb_notifications.notify_pace = enabled;
+        self.save_config()
+    }
+
+    /// Sets whether results that match a PB (within the PB tolerance) are reported as ties.
+    /// # Errors
+    /// Returns `ConfigError` variants if saving fails.
+    pub fn set_pb_notify_ties(&mut self, enabled: bool) -> Result<(), ConfigError> {
+        self.config.pb_notifications.notify_ties = enabled;
+        self.save_config()
+    }
+
+    /// Sets the minimum improvement required before a result is reported as a PB.
+    /// # Errors
+    /// - `ConfigError::InvalidPbTolerance` if either value is negative or not finite.
+    /// - `ConfigError` variants if saving fails.
+    pub fn set_pb_tolerance(&mut self, absolute: f64, percent: f64) -> Result<(), ConfigError> {
+        for value in [absolute, percent] {
+            if !value.is_finite() || value < 0.0 {
+                return Err(ConfigError::InvalidPbTolerance(value));
+            }
+        }
+        self.config.pb_notifications.pb_tolerance = PbTolerance { absolute, percent };
+        self.save_config()
+    }
+
+    /// Sets the limits checked when workouts are added or edited.
+    /// # Errors
+    /// - `ConfigError::InvalidValidationLimit` if a limit is not positive.
+    /// - `ConfigError` variants if saving fails.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn set_validation_config(&mut self, validation: ValidationConfig) -> Result<(), ConfigError> {
+        let limits = [
+            validation.max_sets.map(|v| v as f64),
+            validation.max_reps.map(|v| v as f64),
+            validation.max_weight,
+            validation.max_duration.map(|v| v as f64),
+            validation.max_distance,
+        ];
+        if let Some(invalid) = limits.into_iter().flatten().find(|v| !v.is_finite() || *v <= 0.0) {
+            return Err(ConfigError::InvalidValidationLimit(invalid));
+        }
+        self.config.validation = validation;
+        self.save_config()
+    }
+
+    /// Replaces the theme with a named preset, see `Theme::PRESETS`.
+    /// # Errors
+    /// - `ConfigError::UnknownThemePreset` if there is no preset with that name.
+    /// - `ConfigError` variants if saving fails.
+    pub fn apply_theme_preset(&mut self, name: &str) -> Result<(), ConfigError> {
+        self.config.theme = Theme::preset(name)?;
+        self.save_config()
+    }
+
     /// Sets the target bodyweight.
     /// # Errors
     /// - `ConfigError::InvalidBodyweightInput` if weight is not positive.
@@ -315,62 +1028,771 @@ impl AppService {
         self.config.target_bodyweight
     }
 
-    /// Sets the measurement units.
+    /// Sets (or clears with `None`) the weight increment used to round weights.
     /// # Errors
-    /// Returns `ConfigError` variants if saving fails.
-    pub fn set_units(&mut self, units: Units) -> Result<(), ConfigError> {
-        self.config.units = units;
+    /// - `ConfigError::InvalidWeightIncrement` if the increment is not positive.
+    /// - `ConfigError` variants if saving fails.
+    pub fn set_weight_increment(&mut self, increment: Option<f64>) -> Result<(), ConfigError> {
+        if let Some(inc) = increment {
+            if !inc.is_finite() || inc <= 0.0 {
+                return Err(ConfigError::InvalidWeightIncrement(inc));
+            }
+        }
+        self.config.weight_increment = increment;
         self.save_config()
     }
 
-    /// Adds a new bodyweight entry.
+    /// Sets the plate weights (in configured units) used by `calculate_plates`; an empty list
+    /// means the standard set for the configured units.
     /// # Errors
-    /// - `ConfigError::InvalidBodyweightInput` if weight not positive.
-    /// - `anyhow::Error` wrapping `DbError` variants.
-    pub fn add_bodyweight_entry(&self, timestamp: DateTime<Utc>, weight: f64) -> Result<i64> {
-        if weight <= 0.0 {
-            bail!(ConfigError::InvalidBodyweightInput(
-                "Bodyweight must be positive.".to_string()
-            ));
+    /// - `ConfigError::InvalidPlateWeight` if a plate weight is not positive.
+    /// - `ConfigError` variants if saving fails.
+    pub fn set_available_plates(&mut self, plates: Vec<f64>) -> Result<(), ConfigError> {
+        if let Some(&invalid) = plates.iter().find(|p| !p.is_finite() || **p <= 0.0) {
+            return Err(ConfigError::InvalidPlateWeight(invalid));
         }
-        db::add_bodyweight(&self.conn, timestamp, weight)
-            .context("Failed to add bodyweight entry")
-            .map_err(Into::into)
+        self.config.available_plates = plates;
+        self.save_config()
     }
 
-    /// Retrieves the most recent bodyweight entry.
+    /// Plates to load on each side of a `bar` to reach `target`, heaviest first, using as few
+    /// plates as possible. Any number of each available plate can be used. Weights are in the
+    /// configured units, and the plates come from `available_plates` (or the standard set).
     /// # Errors
-    /// - `anyhow::Error` wrapping `DbError` variants.
-    pub fn get_latest_bodyweight(&self) -> Result<Option<f64>> {
-        db::get_latest_bodyweight(&self.conn)
-            .context("Failed to retrieve latest bodyweight")
-            .map_err(Into::into)
+    /// Returns `ServiceError` if `target` is below the bar weight, needs more than
+    /// `MAX_PLATE_LOAD_PER_SIDE` per side, or can't be made with the available plates; the
+    /// message includes the closest lighter load.
+    pub fn calculate_plates(&self, target: f64, bar: f64) -> Result<Vec<f64>> {
+        if !target.is_finite() || !bar.is_finite() || bar < 0.0 || target < bar {
+            bail!("Target weight {target} must be at least the bar weight {bar}.");
+        }
+        if (target - bar) / 2.0 > MAX_PLATE_LOAD_PER_SIDE {
+            bail!(
+                "Target weight {target} needs more than {MAX_PLATE_LOAD_PER_SIDE} {} per side.",
+                self.config.weight_units().weight_abbr()
+            );
+        }
+        let plates = if self.config.available_plates.is_empty() {
+            self.config.weight_units().standard_plates()
+        } else {
+            &self.config.available_plates
+        };
+        load_plates((target - bar) / 2.0, plates).map_err(|closest| {
+            let unit = self.config.weight_units().weight_abbr();
+            ServiceError::raised(anyhow::anyhow!(
+                "Cannot load {target} {unit} on a {bar} {unit} bar with plates {plates:?}. Closest lighter load is {} {unit}.",
+                bar + 2.0 * closest
+            ))
+        })
     }
 
-    /// Lists logged bodyweight entries.
+    /// Sets (or clears with `None`) the shell command to run for each PB, see `pb_commands`.
+    /// The command can use the `{exercise}`, `{metric}` and `{value}` placeholders.
     /// # Errors
-    /// - `anyhow::Error` wrapping `DbError` variants.
-    pub fn list_bodyweights(&self, limit: u32) -> Result<Vec<(i64, DateTime<Utc>, f64)>> {
-        db::list_bodyweights(&self.conn, limit)
-            .context("Failed to list bodyweights")
-            .map_err(Into::into)
+    /// Returns `ConfigError` variants if saving fails.
+    pub fn set_pb_command(&mut self, command: Option<String>) -> Result<(), ConfigError> {
+        self.config.pb_command = command.filter(|c| !c.trim().is_empty());
+        self.save_config()
+    }
+
+    /// Lists the personal bests achieved in `pb` (as returned by `add_workout`) as events.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)]
+    pub fn pb_events(&self, exercise: &str, pb: &PBInfo) -> Vec<PbEvent> {
+        let achieved = |info: &PbMetricInfo<f64>| info.achieved.then_some(info.new_value).flatten();
+        let achieved_int = |info: &PbMetricInfo<i64>| {
+            info.achieved.then_some(info.new_value).flatten().map(|v| v as f64)
+        };
+        [
+            ("weight", achieved(&pb.weight)),
+            ("reps", achieved_int(&pb.reps)),
+            ("duration", achieved_int(&pb.duration)),
+            ("distance", achieved(&pb.distance).map(|d| self.display_distance(d))),
+            ("volume", achieved(&pb.volume)),
+            ("pace", achieved(&pb.pace).map(|p| self.display_pace(p))),
+            ("session_volume", achieved(&pb.session_volume)),
+        ]
+        .into_iter()
+        .filter_map(|(metric, value)| {
+            Some(PbEvent {
+                exercise: exercise.to_string(),
+                metric: metric.to_string(),
+                value: value?,
+            })
+        })
+        .collect()
+    }
+
+    /// The configured `pb_command` rendered for each event, ready for the caller to run
+    /// (e.g. with `sh -c`); empty if no command is set. The library never runs them itself.
+    #[must_use]
+    pub fn pb_commands(&self, events: &[PbEvent]) -> Vec<String> {
+        self.config.pb_command.as_deref().map_or_else(Vec::new, |template| {
+            events.iter().map(|event| render_pb_command(template, event)).collect()
+        })
     }
 
-    /// Deletes a bodyweight entry by ID.
+    /// Sets whether logged weights are rounded to the weight increment before being stored.
     /// # Errors
-    /// Returns `DbError` variants if deletion fails.
-    pub fn delete_bodyweight(&mut self, id: i64) -> Result<usize, DbError> {
-        db::delete_bodyweight(&self.conn, id)
+    /// Returns `ConfigError` variants if saving fails.
+    pub fn set_round_stored_weights(&mut self, enabled: bool) -> Result<(), ConfigError> {
+        self.config.round_stored_weights = enabled;
+        self.save_config()
     }
 
-    pub fn get_db_path(&self) -> &Path {
-        &self.db_path
+    /// Sets whether `add_workout` adds sets to an existing entry of the same exercise and day
+    /// instead of inserting a new row.
+    /// # Errors
+    /// Returns `ConfigError` variants if saving fails.
+    pub fn set_merge_same_day_sets(&mut self, enabled: bool) -> Result<(), ConfigError> {
+        self.config.merge_same_day_sets = enabled;
+        self.save_config()
     }
 
-    /// Resolves an identifier (ID, Alias, Name) to an `ExerciseDefinition`.
+    /// Sets the type given to unknown exercises that are logged without `implicit_type`,
+    /// or `None` to require one.
     /// # Errors
-    /// Returns `anyhow::Error` if identifier is empty or resolution fails.
-    pub fn resolve_exercise_identifier(
+    /// Returns `ConfigError` variants if saving fails.
+    pub fn set_default_implicit_type(
+        &mut self,
+        type_: Option<ExerciseType>,
+    ) -> Result<(), ConfigError> {
+        self.config.default_implicit_type = type_;
+        self.save_config()
+    }
+
+    /// Converts a stored distance in km to the configured units, rounded to `distance_decimals`.
+    #[must_use]
+    pub fn display_distance(&self, distance_km: f64) -> f64 {
+        let converted = match self.config.distance_units() {
+            Units::Metric => distance_km,
+            Units::Imperial => distance_km * KM_TO_MILE,
+        };
+        round_to_decimals(converted, self.config.distance_decimals)
+    }
+
+    /// Converts a pace in minutes per km to the configured units (min/km or min/mile).
+    #[must_use]
+    pub fn display_pace(&self, pace_min_per_km: f64) -> f64 {
+        match self.config.distance_units() {
+            Units::Metric => pace_min_per_km,
+            Units::Imperial => pace_min_per_km * MILE_TO_KM,
+        }
+    }
+
+    /// Rounds a weight for display using the configured increment (unchanged if unset).
+    #[must_use]
+    pub fn display_weight(&self, weight: f64) -> f64 {
+        self.config
+            .weight_increment
+            .map_or(weight, |inc| round_to_increment(weight, inc))
+    }
+
+    /// Rounds an E1RM for display: to the configured `weight_increment` (if set), then to
+    /// `e1rm_decimals` decimal places.
+    #[must_use]
+    pub fn round_e1rm(&self, e1rm: f64) -> f64 {
+        round_to_decimals(self.display_weight(e1rm), self.config.e1rm_decimals)
+    }
+
+    /// Formats an E1RM for display, e.g. `116.666` as `"116.7"` with the default settings.
+    #[must_use]
+    pub fn format_e1rm(&self, e1rm: f64) -> String {
+        let decimals = usize::try_from(self.config.e1rm_decimals).unwrap_or(usize::MAX);
+        format!("{:.*}", decimals, self.round_e1rm(e1rm))
+    }
+
+    /// Sets the number of decimal places shown for E1RM values.
+    /// # Errors
+    /// Returns `ConfigError` variants if saving fails.
+    pub fn set_e1rm_decimals(&mut self, decimals: u32) -> Result<(), ConfigError> {
+        self.config.e1rm_decimals = decimals;
+        self.save_config()
+    }
+
+    /// Sets the decimal separator accepted by `parse_number_input`.
+    /// # Errors
+    /// - `ConfigError::InvalidDecimalSeparator` unless it is '.' or ','.
+    /// - `ConfigError` variants if saving fails.
+    pub fn set_decimal_separator(&mut self, separator: char) -> Result<(), ConfigError> {
+        if !matches!(separator, '.' | ',') {
+            return Err(ConfigError::InvalidDecimalSeparator(separator));
+        }
+        self.config.decimal_separator = separator;
+        self.save_config()
+    }
+
+    /// Sets the scheme used by `suggest_progression`.
+    /// # Errors
+    /// - `ConfigError::InvalidProgressionScheme` if the increment is not positive or the rep
+    ///   range is empty or starts below 1.
+    /// - `ConfigError` variants if saving fails.
+    pub fn set_progression_scheme(&mut self, scheme: ProgressionScheme) -> Result<(), ConfigError> {
+        let (increment, rep_range) = match scheme {
+            ProgressionScheme::Linear { increment } => (increment, None),
+            ProgressionScheme::DoubleProgression { min_reps, max_reps, increment } => {
+                (increment, Some((min_reps, max_reps)))
+            }
+        };
+        if !increment.is_finite() || increment <= 0.0 {
+            return Err(ConfigError::InvalidProgressionScheme(format!(
+                "increment must be a positive number, got {increment}"
+            )));
+        }
+        if let Some((min_reps, max_reps)) = rep_range {
+            if min_reps < 1 || max_reps <= min_reps {
+                return Err(ConfigError::InvalidProgressionScheme(format!(
+                    "rep range {min_reps}-{max_reps} must start at 1 or more and end above its start"
+                )));
+            }
+        }
+        self.config.progression = scheme;
+        self.save_config()
+    }
+
+    /// Sets the recovery window `muscle_recovery_status` uses for muscles without their own.
+    /// # Errors
+    /// - `ConfigError::InvalidRecoveryHours` if `hours` is 0.
+    /// - `ConfigError` variants if saving fails.
+    pub fn set_recovery_hours(&mut self, hours: u32) -> Result<(), ConfigError> {
+        if hours == 0 {
+            return Err(ConfigError::InvalidRecoveryHours(hours));
+        }
+        self.config.recovery_hours = hours;
+        self.save_config()
+    }
+
+    /// Sets (or with `None` removes) the recovery window of one muscle, e.g. longer for legs.
+    /// # Errors
+    /// - `ConfigError::InvalidRecoveryHours` if `hours` is 0.
+    /// - `ConfigError` variants if saving fails.
+    pub fn set_muscle_recovery_hours(&mut self, muscle: &str, hours: Option<u32>) -> Result<(), ConfigError> {
+        let muscle = muscle.trim().to_lowercase();
+        match hours {
+            Some(0) => return Err(ConfigError::InvalidRecoveryHours(0)),
+            Some(hours) => {
+                self.config.muscle_recovery_hours.insert(muscle, hours);
+            }
+            None => {
+                self.config.muscle_recovery_hours.remove(&muscle);
+            }
+        }
+        self.save_config()
+    }
+
+    /// Sets (or with `None` clears) the max heart rate `hr_zone` measures against.
+    /// # Errors
+    /// - `ConfigError::InvalidMaxHeartRate` if `max_hr` is outside 100-250 bpm.
+    /// - `ConfigError` variants if saving fails.
+    pub fn set_max_hr(&mut self, max_hr: Option<u32>) -> Result<(), ConfigError> {
+        if let Some(bpm) = max_hr.filter(|bpm| !(100..=250).contains(bpm)) {
+            return Err(ConfigError::InvalidMaxHeartRate(bpm));
+        }
+        self.config.max_hr = max_hr;
+        self.save_config()
+    }
+
+    /// Sets (or with `None` clears) the age used to estimate max heart rate (220 - age) when
+    /// `max_hr` is not set.
+    /// # Errors
+    /// - `ConfigError::InvalidAge` if `age` is outside 1-120.
+    /// - `ConfigError` variants if saving fails.
+    pub fn set_age(&mut self, age: Option<u32>) -> Result<(), ConfigError> {
+        if let Some(age) = age.filter(|age| !(1..=120).contains(age)) {
+            return Err(ConfigError::InvalidAge(age));
+        }
+        self.config.age = age;
+        self.save_config()
+    }
+
+    /// Max heart rate in bpm: the configured `max_hr`, else estimated as 220 - `age`.
+    pub fn effective_max_hr(&self) -> Option<u32> {
+        self.config
+            .max_hr
+            .or_else(|| self.config.age.map(|age| 220u32.saturating_sub(age)))
+    }
+
+    /// Parses a number typed by the user with the configured `decimal_separator`, see
+    /// `parse_number`.
+    /// # Errors
+    /// Returns `ServiceError::Validation` if the input is not a number.
+    pub fn parse_number_input(&self, input: &str) -> Result<f64> {
+        parse_number(input, self.config.decimal_separator)
+    }
+
+    /// Sets the measurement units for both weight and distance, clearing any per-metric units.
+    /// # Errors
+    /// Returns `ConfigError` variants if saving fails.
+    pub fn set_units(&mut self, units: Units) -> Result<(), ConfigError> {
+        self.config.units = units;
+        self.config.weight_units = None;
+        self.config.distance_units = None;
+        self.save_config()
+    }
+
+    /// Sets the units for weights only (e.g. kg while running in miles).
+    /// # Errors
+    /// Returns `ConfigError` variants if saving fails.
+    pub fn set_weight_units(&mut self, units: Units) -> Result<(), ConfigError> {
+        self.config.weight_units = Some(units);
+        self.save_config()
+    }
+
+    /// Sets the units for distances and paces only.
+    /// # Errors
+    /// Returns `ConfigError` variants if saving fails.
+    pub fn set_distance_units(&mut self, units: Units) -> Result<(), ConfigError> {
+        self.config.distance_units = Some(units);
+        self.save_config()
+    }
+
+    /// Adds a new bodyweight entry.
+    /// # Errors
+    /// - `ConfigError::InvalidBodyweightInput` if weight not positive.
+    /// - `ServiceError` wrapping `DbError` variants.
+    pub fn add_bodyweight_entry(&self, timestamp: DateTime<Utc>, weight: f64) -> Result<i64> {
+        self.add_body_measurement(&BodyMeasurementParams {
+            timestamp,
+            weight,
+            ..Default::default()
+        })
+    }
+
+    /// Adds a bodyweight entry with optional body-fat percentage, muscle mass and notes.
+    /// # Errors
+    /// - `ConfigError::InvalidBodyweightInput` if weight or muscle mass is not positive,
+    ///   or body fat is outside 0-100%.
+    /// - `ServiceError` wrapping `DbError` variants.
+    pub fn add_body_measurement(&self, params: &BodyMeasurementParams) -> Result<i64> {
+        validate_body_measurement(params)?;
+        db::add_body_measurement(
+            &*self.connection()?,
+            params.timestamp,
+            params.weight,
+            params.body_fat_pct,
+            params.muscle_mass,
+            params.notes.as_deref().map(str::trim).filter(|n| !n.is_empty()),
+        )
+        .context("Failed to add bodyweight entry")
+        .map_err(Into::into)
+    }
+
+    /// Imports bodyweight entries from CSV rows of
+    /// `timestamp,weight[,body_fat_pct[,muscle_mass[,notes]]]`.
+    ///
+    /// Timestamps are RFC 3339, `YYYY-MM-DD HH:MM[:SS]` (local time) or `YYYY-MM-DD` (local
+    /// noon). Weights and muscle mass are read in `source_units` (the configured weight units
+    /// if `None`) and converted to the configured units before storing. A header line and blank
+    /// lines are ignored. Valid rows are inserted in a single transaction; invalid rows are
+    /// reported in `ImportSummary::errors`. With `skip_duplicates`, rows whose timestamp and
+    /// weight are already recorded are counted in `skipped_duplicates` instead.
+    /// `options` sets the delimiter and quote character; with a `date_format`, timestamps in
+    /// that format are accepted as well.
+    /// # Errors
+    /// Returns `ServiceError` if the options are invalid, or reading the input or the DB
+    /// transaction fails.
+    pub fn import_bodyweights_from_csv<R: BufRead>(
+        &self,
+        reader: R,
+        skip_duplicates: bool,
+        options: &CsvOptions,
+        source_units: Option<Units>,
+    ) -> Result<ImportSummary> {
+        validate_csv_options(options)?;
+        let to_stored = |value: f64| {
+            convert_weight(value, source_units.unwrap_or(self.config.weight_units()), self.config.weight_units())
+        };
+        let mut summary = ImportSummary::default();
+        let mut rows = Vec::new();
+        let mut row_lines = Vec::new();
+        for (index, line) in reader.lines().enumerate() {
+            let line = line.context("Failed to read bodyweight CSV")?;
+            let line_number = index + 1;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let fields = split_csv_line(&line, options);
+            if line_number == 1 && is_bodyweight_csv_header(&fields) {
+                continue;
+            }
+            match self.parse_bodyweight_csv_row(&fields, options) {
+                Ok(params) => {
+                    rows.push((
+                        params.timestamp,
+                        to_stored(params.weight),
+                        params.body_fat_pct,
+                        params.muscle_mass.map(to_stored),
+                        params.notes,
+                    ));
+                    row_lines.push(line_number);
+                }
+                Err(e) => summary.errors.push(ImportRowError {
+                    line: line_number,
+                    message: e.to_string(),
+                }),
+            }
+        }
+
+        let outcomes = db::import_body_measurements(&mut *self.connection()?, &rows, skip_duplicates)
+            .context("Failed to import bodyweight entries")?;
+        for (outcome, line) in outcomes.into_iter().zip(row_lines) {
+            match outcome {
+                db::ImportRowOutcome::Imported(_) => summary.imported += 1,
+                db::ImportRowOutcome::Duplicate => summary.skipped_duplicates += 1,
+                db::ImportRowOutcome::Failed(e) => summary.errors.push(ImportRowError {
+                    line,
+                    message: e.to_string(),
+                }),
+            }
+        }
+        summary.errors.sort_by_key(|e| e.line);
+        Ok(summary)
+    }
+
+    /// Parses and validates one `timestamp,weight[,body_fat_pct[,muscle_mass[,notes]]]` CSV row.
+    fn parse_bodyweight_csv_row(
+        &self,
+        fields: &[String],
+        options: &CsvOptions,
+    ) -> Result<BodyMeasurementParams> {
+        if !(2..=5).contains(&fields.len()) {
+            bail!("Expected 2 to 5 columns, found {}.", fields.len());
+        }
+        let optional_number = |index: usize, label: &str| -> Result<Option<f64>> {
+            match fields.get(index).filter(|f| !f.is_empty()) {
+                Some(field) => match field.parse::<f64>() {
+                    Ok(value) => Ok(Some(value)),
+                    Err(_) => bail!("Invalid {label} '{field}'."),
+                },
+                None => Ok(None),
+            }
+        };
+        let params = BodyMeasurementParams {
+            timestamp: self.parse_csv_timestamp(&fields[0], options)?,
+            weight: optional_number(1, "weight")?
+                .ok_or_else(|| ServiceError::raised(anyhow::anyhow!("Weight is required.")))?,
+            body_fat_pct: optional_number(2, "body fat")?,
+            muscle_mass: optional_number(3, "muscle mass")?,
+            notes: fields.get(4).filter(|n| !n.is_empty()).cloned(),
+        };
+        validate_body_measurement(&params)?;
+        Ok(params)
+    }
+
+    /// Parses a CSV timestamp in `options.date_format` (local time unless the format has an
+    /// offset), falling back to the formats accepted by `parse_import_timestamp`.
+    fn parse_csv_timestamp(&self, input: &str, options: &CsvOptions) -> Result<DateTime<Utc>> {
+        if let Some(format) = options.date_format.as_deref() {
+            if let Ok(ts) = DateTime::parse_from_str(input, format) {
+                return Ok(ts.with_timezone(&Utc));
+            }
+            if let Ok(naive) = chrono::NaiveDateTime::parse_from_str(input, format) {
+                let tz = self.config.tz();
+                return tz
+                    .from_local_datetime(&naive)
+                    .earliest()
+                    .map(|dt| dt.with_timezone(&Utc))
+                    .ok_or_else(|| {
+                        ServiceError::raised(anyhow::anyhow!("'{input}' does not exist in {tz}."))
+                    });
+            }
+        }
+        self.parse_import_timestamp(input)
+    }
+
+    /// Formats a timestamp for CSV export, see `CsvOptions::date_format`.
+    fn format_csv_timestamp(&self, timestamp: DateTime<Utc>, options: &CsvOptions) -> String {
+        match options.date_format.as_deref() {
+            Some(format) => timestamp.with_timezone(&self.config.tz()).format(format).to_string(),
+            None => timestamp.to_rfc3339(),
+        }
+    }
+
+    /// Writes every bodyweight entry to `writer` as CSV rows of
+    /// `timestamp,weight,body_fat_pct,muscle_mass,notes` (oldest first, with a header), in the
+    /// dialect given by `options`. The output can be read back with
+    /// `import_bodyweights_from_csv` using the same options.
+    /// Returns the number of entries written.
+    /// # Errors
+    /// - `ServiceError` if the options are invalid or writing fails.
+    /// - `ServiceError` wrapping `DbError` variants.
+    pub fn export_bodyweights_to_csv<W: Write>(
+        &self,
+        mut writer: W,
+        options: &CsvOptions,
+    ) -> Result<usize> {
+        validate_csv_options(options)?;
+        let mut entries = self.list_body_measurements(u32::MAX)?;
+        entries.reverse();
+        write_csv_row(
+            &mut writer,
+            &["timestamp", "weight", "body_fat_pct", "muscle_mass", "notes"],
+            options,
+        )?;
+        for entry in &entries {
+            let row = [
+                self.format_csv_timestamp(entry.timestamp, options),
+                entry.weight.to_string(),
+                entry.body_fat_pct.map(|v| v.to_string()).unwrap_or_default(),
+                entry.muscle_mass.map(|v| v.to_string()).unwrap_or_default(),
+                entry.notes.clone().unwrap_or_default(),
+            ];
+            write_csv_row(&mut writer, &row, options)?;
+        }
+        writer.flush().context("Failed to flush bodyweight CSV")?;
+        Ok(entries.len())
+    }
+
+    /// Parses an RFC 3339 timestamp, or a local date/time in the configured timezone.
+    fn parse_import_timestamp(&self, input: &str) -> Result<DateTime<Utc>> {
+        if let Ok(ts) = DateTime::parse_from_rfc3339(input) {
+            return Ok(ts.with_timezone(&Utc));
+        }
+        let tz = self.config.tz();
+        for format in ["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S", "%Y-%m-%d %H:%M"] {
+            if let Ok(naive) = chrono::NaiveDateTime::parse_from_str(input, format) {
+                return tz
+                    .from_local_datetime(&naive)
+                    .earliest()
+                    .map(|dt| dt.with_timezone(&Utc))
+                    .ok_or_else(|| {
+                        ServiceError::raised(anyhow::anyhow!("'{input}' does not exist in {tz}."))
+                    });
+            }
+        }
+        match NaiveDate::parse_from_str(input, "%Y-%m-%d") {
+            Ok(date) => create_timestamp_from_date(date, tz),
+            Err(_) => bail!("Invalid timestamp '{input}'."),
+        }
+    }
+
+    /// Retrieves the most recent bodyweight entry.
+    /// # Errors
+    /// - `ServiceError` wrapping `DbError` variants.
+    pub fn get_latest_bodyweight(&self) -> Result<Option<f64>> {
+        db::get_latest_bodyweight(&*self.connection()?)
+            .context("Failed to retrieve latest bodyweight")
+            .map_err(Into::into)
+    }
+
+    /// Returns the bodyweight to use for calorie estimates, in kilograms.
+    /// Prefers the latest logged entry and falls back to the configured bodyweight.
+    fn current_bodyweight_kg(&self) -> Result<Option<f64>> {
+        let bodyweight = self.get_latest_bodyweight()?.or(self.config.bodyweight);
+        Ok(bodyweight.map(|bw| match self.config.weight_units() {
+            Units::Metric => bw,
+            Units::Imperial => bw * LBS_TO_KG,
+        }))
+    }
+
+    /// Estimates calories burned for a single workout as MET x bodyweight (kg) x hours.
+    ///
+    /// Returns `None` if the exercise has no MET value, the workout has no duration,
+    /// no bodyweight is known, or the exercise is a resistance exercise.
+    /// # Errors
+    /// - `DbError::WorkoutNotFound` if the workout does not exist.
+    /// - `ServiceError` wrapping other `DbError` variants.
+    pub fn estimate_calories(&self, workout_id: i64) -> Result<Option<f64>> {
+        let workout = db::get_workout_by_id(&*self.connection()?, workout_id)
+            .context("Failed to fetch workout")?
+            .ok_or(DbError::WorkoutNotFound(workout_id))?;
+        let Some(exercise_def) = db::get_exercise_by_name(&*self.connection()?, &workout.exercise_name)? else {
+            return Ok(None);
+        };
+        let bodyweight_kg = self.current_bodyweight_kg()?;
+        Ok(estimate_calories_burned(&exercise_def, workout.duration_minutes, bodyweight_kg))
+    }
+
+    /// Training density of a workout: its volume (sets * reps * effective weight) per minute.
+    ///
+    /// Returns `None` if the workout has no duration or no volume.
+    /// # Errors
+    /// - `DbError::WorkoutNotFound` if the workout does not exist.
+    /// - `ServiceError` wrapping other `DbError` variants.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn get_density(&self, workout_id: i64) -> Result<Option<f64>> {
+        let workout = db::get_workout_by_id(&*self.connection()?, workout_id)
+            .context("Failed to fetch workout")?
+            .ok_or(DbError::WorkoutNotFound(workout_id))?;
+        let Some(exercise_def) = db::get_exercise_by_name(&*self.connection()?, &workout.exercise_name)? else {
+            return Ok(None);
+        };
+        let volume = workout_volume(&exercise_def, &workout);
+        Ok(workout
+            .duration_minutes
+            .filter(|&d| d > 0 && volume > 0.0)
+            .map(|d| volume / d as f64))
+    }
+
+    /// Lists logged bodyweight entries. See `list_body_measurements` for body composition
+    /// and notes.
+    /// # Errors
+    /// - `ServiceError` wrapping `DbError` variants.
+    pub fn list_bodyweights(&self, limit: u32) -> Result<Vec<(i64, DateTime<Utc>, f64)>> {
+        db::list_bodyweights(&*self.connection()?, limit)
+            .context("Failed to list bodyweights")
+            .map_err(Into::into)
+    }
+
+    /// Lists logged bodyweight entries including body-composition readings and notes.
+    /// # Errors
+    /// - `ServiceError` wrapping `DbError` variants.
+    pub fn list_body_measurements(&self, limit: u32) -> Result<Vec<BodyMeasurement>> {
+        db::list_body_measurements(&*self.connection()?, limit)
+            .context("Failed to list body measurements")
+            .map_err(Into::into)
+    }
+
+    /// Returns a daily series for a body metric, using the last reading of each day.
+    /// # Errors
+    /// - `ServiceError` wrapping `DbError` variants.
+    pub fn get_body_metric_series(
+        &self,
+        metric: BodyMetric,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+    ) -> Result<Vec<(NaiveDate, f64)>> {
+        let readings = db::get_body_metric_series(&*self.connection()?, metric, start_date, end_date, self.config.tz())
+            .context("Failed to retrieve body metric series")?;
+        let daily: BTreeMap<NaiveDate, f64> = readings
+            .into_iter()
+            .map(|(ts, value)| (self.local_date(ts), value))
+            .collect();
+        Ok(daily.into_iter().collect())
+    }
+
+    /// Change in a body metric over the last `days` days (latest minus earliest reading
+    /// in the window). Returns `None` with fewer than two readings.
+    /// # Errors
+    /// - `ServiceError` wrapping `DbError` variants.
+    pub fn get_body_metric_trend(&self, metric: BodyMetric, days: u32) -> Result<Option<f64>> {
+        let start = Utc::now().date_naive() - Duration::days(i64::from(days));
+        let series = self.get_body_metric_series(metric, Some(start), None)?;
+        Ok(match (series.first(), series.last()) {
+            (Some(first), Some(last)) if series.len() > 1 => Some(last.1 - first.1),
+            _ => None,
+        })
+    }
+
+    /// Deletes a bodyweight entry by ID (for good with `hard_delete`).
+    /// # Errors
+    /// Returns `DbError` variants if deletion fails.
+    pub fn delete_bodyweight(&mut self, id: i64) -> Result<usize, DbError> {
+        let conn = self.connection()?;
+        let deleted = db::delete_bodyweight(&conn, id)?;
+        if self.config.hard_delete {
+            db::purge_bodyweight(&conn, id)?;
+        }
+        Ok(deleted)
+    }
+
+    pub fn get_db_path(&self) -> &Path {
+        &self.db_path
+    }
+
+    /// Sets whether `apply_server_changes` backs up the database first.
+    /// # Errors
+    /// Returns `ConfigError` variants if saving fails.
+    pub fn set_auto_backup_before_sync(&mut self, enabled: bool) -> Result<(), ConfigError> {
+        self.config.auto_backup_before_sync = enabled;
+        self.save_config()
+    }
+
+    /// Sets whether `delete_workouts`, `delete_workouts_by_filter`, `delete_exercise`,
+    /// `delete_alias` and `delete_bodyweight` remove rows for good instead of flagging them
+    /// as deleted. Hard-deleted rows can't be restored or undone, and their deletion is not
+    /// sent to the sync server, so other devices keep them.
+    /// # Errors
+    /// Returns `ConfigError` variants if saving fails.
+    pub fn set_hard_delete(&mut self, enabled: bool) -> Result<(), ConfigError> {
+        self.config.hard_delete = enabled;
+        self.save_config()
+    }
+
+    /// Hard-deletes soft-deleted exercises, workouts (with their tags, splits, metadata and
+    /// history), aliases and bodyweight entries last changed more than `older_than` ago, in
+    /// one transaction, to keep the database and sync payloads small.
+    ///
+    /// Soft-deleted rows are the tombstones sync uses to pass deletions on: a purged row's
+    /// deletion never reaches devices that haven't synced since it was deleted, so they
+    /// keep it (and may upload it again). Pick a cutoff longer than any device goes
+    /// without syncing.
+    /// # Errors
+    /// - `ServiceError` if `older_than` is negative.
+    /// - `ServiceError` wrapping `DbError` variants.
+    pub fn purge_deleted(&self, older_than: Duration) -> Result<PurgeCounts> {
+        if older_than < Duration::zero() {
+            bail!("Purge age cannot be negative.");
+        }
+        db::purge_deleted(&mut *self.connection()?, Utc::now() - older_than)
+            .context("Failed to purge deleted rows")
+            .map_err(Into::into)
+    }
+
+    /// Deletes all exercises, workouts, aliases and bodyweight entries (including soft-deleted
+    /// ones) and the undo log in one transaction, keeping the database file and the config.
+    /// `confirmation` must be exactly `CLEAR_ALL_DATA_CONFIRMATION`. Returns the number of
+    /// rows removed per table.
+    ///
+    /// No tombstones are left for sync, so the next sync downloads the server's data again.
+    /// # Errors
+    /// - `ServiceError` if the confirmation does not match; nothing is deleted then.
+    /// - `ServiceError` wrapping `DbError` variants.
+    pub fn clear_all_data(&self, confirmation: &str) -> Result<PurgeCounts> {
+        if confirmation != CLEAR_ALL_DATA_CONFIRMATION {
+            bail!("Confirmation must be exactly '{CLEAR_ALL_DATA_CONFIRMATION}'; no data was deleted.");
+        }
+        db::clear_all_data(&mut *self.connection()?)
+            .context("Failed to clear data")
+            .map_err(Into::into)
+    }
+
+    /// Writes a consistent copy of the database (`VACUUM INTO`) and returns its path.
+    ///
+    /// `dest` may be a file path or an existing directory. If it is a directory or `None`
+    /// (the `BACKUP_DIR_NAME` directory next to the database), the copy gets a timestamped
+    /// name such as `workouts-20240131-184500-123.sqlite`.
+    /// # Errors
+    /// Returns `ServiceError` if the directory can't be created, the destination file already
+    /// exists, or the copy fails.
+    pub fn backup_database(&self, dest: Option<PathBuf>) -> Result<PathBuf> {
+        let dest = match dest {
+            Some(path) if !path.is_dir() => path,
+            dir => {
+                let dir = dir.unwrap_or_else(|| {
+                    self.db_path
+                        .parent()
+                        .unwrap_or_else(|| Path::new(""))
+                        .join(BACKUP_DIR_NAME)
+                });
+                let stem = self
+                    .db_path
+                    .file_stem()
+                    .and_then(|s| s.to_str())
+                    .filter(|s| !s.starts_with(':')) // In-memory databases
+                    .unwrap_or("workouts");
+                let timestamp = Utc::now().format("%Y%m%d-%H%M%S-%3f");
+                dir.join(format!("{stem}-{timestamp}.sqlite"))
+            }
+        };
+        if dest.exists() {
+            return Err(ServiceError::Conflict(anyhow::anyhow!(
+                "Backup destination {dest:?} already exists."
+            )));
+        }
+        if let Some(parent) = dest.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create backup directory {parent:?}"))?;
+        }
+        db::backup_to(&*self.connection()?, &dest)
+            .with_context(|| format!("Failed to back up database to {dest:?}"))?;
+        Ok(dest)
+    }
+
+    /// Resolves an identifier (ID, Alias, Name) to an `ExerciseDefinition`.
+    /// # Errors
+    /// Returns `ServiceError` if identifier is empty or resolution fails.
+    pub fn resolve_exercise_identifier(
         &self,
         identifier: &str,
     ) -> Result<Option<ExerciseDefinition>> {
@@ -378,23 +1800,160 @@ impl AppService {
         if trimmed.is_empty() {
             bail!("Exercise identifier cannot be empty.");
         }
-        db::get_exercise_by_identifier(&self.conn, trimmed)
+        db::get_exercise_by_identifier(&*self.connection()?, trimmed)
             .map(|opt_res| opt_res.map(|(def, _)| def))
             .with_context(|| format!("Failed to resolve exercise identifier '{trimmed}'"))
             .map_err(Into::into)
     }
 
+    /// Active exercises whose name or an alias is close to `identifier` (Levenshtein
+    /// distance, case-insensitive), closest first, for "did you mean ...?" hints. At most
+    /// `max` canonical names are returned; candidates further than a third of the
+    /// identifier's length (minimum 2 edits) are left out. Use this after
+    /// `resolve_exercise_identifier` finds nothing; it never changes how identifiers resolve.
+    /// # Errors
+    /// Returns `ServiceError` wrapping DB errors.
+    pub fn suggest_similar_exercises(&self, identifier: &str, max: usize) -> Result<Vec<String>> {
+        let query = identifier.trim().to_lowercase();
+        if query.is_empty() || max == 0 {
+            return Ok(Vec::new());
+        }
+        let threshold = (query.chars().count() / 3).max(2);
+        let mut best: HashMap<String, usize> = HashMap::new();
+        for (candidate, canonical) in self.exercise_identifiers()? {
+            let distance = levenshtein(&query, &candidate.to_lowercase());
+            if distance <= threshold {
+                let entry = best.entry(canonical).or_insert(distance);
+                *entry = (*entry).min(distance);
+            }
+        }
+        let mut ranked: Vec<(String, usize)> = best.into_iter().collect();
+        ranked.sort_by(|a, b| a.1.cmp(&b.1).then_with(|| a.0.cmp(&b.0)));
+        Ok(ranked.into_iter().take(max).map(|(name, _)| name).collect())
+    }
+
+    /// Exercises matching `query` by name or alias (case-insensitive), best matches first, for
+    /// pickers that update as the user types. Prefix matches rank above matches elsewhere in
+    /// the text, which rank above fuzzy matches (queries of 4+ characters within a few edits of
+    /// the start of the name or alias). Within a kind, earlier and closer matches come first,
+    /// then shorter names. Each exercise appears once, by its best match; at most `limit`
+    /// results are returned.
+    /// # Errors
+    /// Returns `ServiceError` wrapping DB errors.
+    pub fn search_exercises(&self, query: &str, limit: usize) -> Result<Vec<ExerciseMatch>> {
+        let query = query.trim().to_lowercase();
+        let query_len = query.chars().count();
+        let threshold = (query_len / 3).max(2);
+        // (kind, position or edit distance, is alias, identifier length); lower is better
+        type Rank = (MatchKind, usize, bool, usize);
+        let mut best: HashMap<String, (Rank, Option<String>)> = HashMap::new();
+        for (candidate, canonical) in self.exercise_identifiers()? {
+            let lowered = candidate.to_lowercase();
+            let (kind, score) = if lowered.starts_with(&query) {
+                (MatchKind::Prefix, 0)
+            } else if let Some(position) = lowered.find(&query) {
+                (MatchKind::Contains, position)
+            } else {
+                let prefix: String = lowered.chars().take(query_len).collect();
+                let distance = levenshtein(&query, &prefix);
+                if query_len < 4 || distance > threshold {
+                    continue;
+                }
+                (MatchKind::Fuzzy, distance)
+            };
+            let is_alias = candidate != canonical;
+            let rank = (kind, score, is_alias, lowered.chars().count());
+            let alias = is_alias.then_some(candidate);
+            match best.get(&canonical) {
+                Some((current, _)) if *current <= rank => {}
+                _ => {
+                    best.insert(canonical, (rank, alias));
+                }
+            }
+        }
+        let mut ranked: Vec<_> = best.into_iter().collect();
+        ranked.sort_by(|a, b| a.1 .0.cmp(&b.1 .0).then_with(|| a.0.cmp(&b.0)));
+        Ok(ranked
+            .into_iter()
+            .take(limit)
+            .map(|(canonical_name, ((kind, ..), matched_alias))| ExerciseMatch {
+                canonical_name,
+                matched_alias,
+                kind,
+            })
+            .collect())
+    }
+
+    /// Searches exercise names, aliases and workout notes at once, e.g. for a command
+    /// palette. Exercises and aliases are matched like `search_exercises` (one result per
+    /// exercise, by its best match); notes match on a case-insensitive substring.
+    /// Results are ranked prefix name/alias matches first, then other name/alias matches
+    /// containing the query, then notes (newest workouts first), then fuzzy name/alias
+    /// matches. At most `limit` results are returned; an empty query returns none.
+    /// # Errors
+    /// Returns `ServiceError` wrapping DB errors.
+    pub fn global_search(&self, query: &str, limit: usize) -> Result<Vec<SearchResult>> {
+        let query = query.trim();
+        if query.is_empty() || limit == 0 {
+            return Ok(Vec::new());
+        }
+        let exercises = self.search_exercises(query, limit)?;
+        let note_limit = u32::try_from(limit).unwrap_or(u32::MAX);
+        let notes = db::search_workout_notes(&*self.connection()?, query, note_limit)
+            .context("Failed to search workout notes")?;
+
+        let (fuzzy, direct): (Vec<_>, Vec<_>) = exercises
+            .into_iter()
+            .partition(|m| m.kind == MatchKind::Fuzzy);
+        let to_result = |m: ExerciseMatch| match m.matched_alias {
+            Some(alias) => SearchResult::Alias {
+                alias,
+                canonical_name: m.canonical_name,
+                kind: m.kind,
+            },
+            None => SearchResult::Exercise {
+                canonical_name: m.canonical_name,
+                kind: m.kind,
+            },
+        };
+        let note_results = notes.into_iter().map(|w| SearchResult::WorkoutNote {
+            workout_id: w.id,
+            date: self.local_date(w.timestamp),
+            exercise_name: w.exercise_name,
+            notes: w.notes.unwrap_or_default(),
+        });
+        Ok(direct
+            .into_iter()
+            .map(to_result)
+            .chain(note_results)
+            .chain(fuzzy.into_iter().map(to_result))
+            .take(limit)
+            .collect())
+    }
+
+    /// Every active exercise name and alias, as `(identifier, canonical name)` pairs.
+    fn exercise_identifiers(&self) -> Result<Vec<(String, String)>> {
+        let conn = self.connection()?;
+        let mut identifiers: Vec<(String, String)> = db::list_exercises(&conn, &ExerciseFilters::default())?
+            .into_iter()
+            .map(|def| (def.name.clone(), def.name))
+            .collect();
+        identifiers.extend(db::list_aliases(&conn)?);
+        Ok(identifiers)
+    }
+
     /// Resolves an identifier (ID, Alias, Name) to its canonical name.
     /// # Errors
-    /// Returns `anyhow::Error` if identifier is empty or resolution fails.
+    /// Returns `ServiceError` if identifier is empty or resolution fails.
     fn resolve_identifier_to_canonical_name(&self, identifier: &str) -> Result<Option<String>> {
         self.resolve_exercise_identifier(identifier)
             .map(|opt_def| opt_def.map(|def| def.name))
     }
 
-    /// Creates a new exercise definition.
+    /// Creates a new exercise definition. See `add_exercise` for description, category and
+    /// defaults.
     /// # Errors
-    /// Returns `anyhow::Error` if name is empty or DB insertion fails.
+    /// Returns `ServiceError` if name is empty or DB insertion fails.
     pub fn create_exercise(
         &self,
         name: &str,
@@ -402,84 +1961,309 @@ impl AppService {
         log_flags: Option<(Option<bool>, Option<bool>, Option<bool>, Option<bool>)>, // (w, r, dur, dist)
         muscles: Option<&str>,
     ) -> Result<i64> {
-        let trimmed_name = name.trim();
+        let (log_weight, log_reps, log_duration, log_distance) = log_flags.unwrap_or_default();
+        self.add_exercise(NewExerciseParams {
+            muscles,
+            log_weight,
+            log_reps,
+            log_duration,
+            log_distance,
+            ..NewExerciseParams::new(name, type_)
+        })
+    }
+
+    /// Creates a new exercise definition from `params`. Empty description and category are
+    /// stored as `None`.
+    /// # Errors
+    /// Returns `ServiceError` if name is empty, a default is not positive, or DB insertion fails.
+    pub fn add_exercise(&self, params: NewExerciseParams) -> Result<i64> {
+        let trimmed_name = params.name.trim();
         if trimmed_name.is_empty() {
             bail!("Exercise name cannot be empty.");
         }
-        let (log_w, log_r, log_dur, log_dist) = match log_flags {
-            Some((w, r, dur, dist)) => (w, r, dur, dist),
-            None => (None, None, None, None), // Use DB defaults based on type
-        };
+        let defaults = params.defaults.unwrap_or_default();
+        Self::validate_exercise_defaults(&defaults)?;
         db::create_exercise(
-            &self.conn,
+            &*self.connection()?,
             trimmed_name,
-            &type_,
-            muscles,
-            log_w,
-            log_r,
-            log_dur,
-            log_dist,
+            &params.type_,
+            params.muscles,
+            params.log_weight,
+            params.log_reps,
+            params.log_duration,
+            params.log_distance,
+            params.description.map(str::trim).filter(|d| !d.is_empty()),
+            params.category.map(str::trim).filter(|c| !c.is_empty()),
+            &defaults,
         )
         .map_err(|db_err| match db_err {
             DbError::ExerciseNameNotUnique(_) => anyhow::anyhow!(db_err),
             _ => anyhow::Error::new(db_err)
                 .context(format!("Failed to create exercise '{trimmed_name}'")),
         })
+        .map_err(Into::into)
     }
 
-    /// Edits an existing exercise definition.
-    /// # Arguments
-    /// * `log_flags`: Optional tuple of new flags (w, r, dur, dist). Use `None` for flags you don't want to change.
+    /// Edits an existing exercise definition; fields left `None` in `params` are unchanged.
     /// # Errors
-    /// Returns `anyhow::Error` if identifier invalid, new name invalid, or DB update fails.
-    pub fn edit_exercise(
-        &mut self,
-        identifier: &str,
-        new_name: Option<&str>,
-        new_type: Option<ExerciseType>,
-        log_flags: Option<(Option<bool>, Option<bool>, Option<bool>, Option<bool>)>, // (w, r, dur, dist)
-        new_muscles: Option<Option<&str>>,
-    ) -> Result<u64> {
+    /// Returns `ServiceError` if identifier invalid, new name or default invalid, or DB update fails.
+    pub fn edit_exercise(&mut self, identifier: &str, params: EditExerciseParams) -> Result<u64> {
         let current_def = self
             .resolve_exercise_identifier(identifier)?
             .ok_or_else(|| DbError::ExerciseNotFound(identifier.to_string()))?;
         let canonical_name_to_update = current_def.name;
 
-        let trimmed_new_name = new_name.map(str::trim).filter(|n| !n.is_empty());
-        if new_name.is_some() && trimmed_new_name.is_none() {
+        let trimmed_new_name = params.new_name.map(str::trim).filter(|n| !n.is_empty());
+        if params.new_name.is_some() && trimmed_new_name.is_none() {
             bail!("New exercise name cannot be empty if provided.");
         }
-        let (log_w, log_r, log_dur, log_dist) = match log_flags {
-            Some((w, r, dur, dist)) => (w, r, dur, dist),
-            None => (None, None, None, None), // No changes to flags
-        };
+        if let Some(defaults) = &params.new_defaults {
+            Self::validate_exercise_defaults(defaults)?;
+        }
 
         db::update_exercise(
-            &mut self.conn,
+            &mut *self.connection()?,
             &canonical_name_to_update,
             trimmed_new_name,
-            new_type.as_ref(),
-            new_muscles,
-            log_w,
-            log_r,
-            log_dur,
-            log_dist,
+            params.new_type.as_ref(),
+            params.new_muscles,
+            params.new_log_weight,
+            params.new_log_reps,
+            params.new_log_duration,
+            params.new_log_distance,
+            params.new_description.map(|d| d.map(str::trim).filter(|d| !d.is_empty())),
+            params.new_category.map(|c| c.map(str::trim).filter(|c| !c.is_empty())),
+            params.new_defaults.as_ref(),
         )
-        .map_err(|db_err| match db_err {
-            DbError::ExerciseNameNotUnique(name) => {
-                anyhow::anyhow!("Name '{name}' is already taken.")
+        .map_err(|db_err| {
+            let message = match &db_err {
+                DbError::ExerciseNameNotUnique(name) => format!("Name '{name}' is already taken."),
+                DbError::ExerciseNotFound(_) => format!("Exercise '{identifier}' not found to edit."),
+                _ => format!("Failed to update exercise '{identifier}'"),
+            };
+            anyhow::Error::new(db_err).context(message) // Keeps the `DbError` for `ServiceError`
+        })
+        .map_err(Into::into)
+    }
+
+    /// Default sets, reps and weight of an exercise, for prefilling a new entry.
+    /// # Errors
+    /// Returns `ServiceError` if the exercise is not found.
+    pub fn get_exercise_defaults(&self, identifier: &str) -> Result<ExerciseDefaults> {
+        self.resolve_exercise_identifier(identifier)?
+            .map(|def| def.defaults())
+            .ok_or_else(|| DbError::ExerciseNotFound(identifier.to_string()).into())
+    }
+
+    fn validate_exercise_defaults(defaults: &ExerciseDefaults) -> Result<()> {
+        if defaults.sets.is_some_and(|sets| sets <= 0) || defaults.reps.is_some_and(|reps| reps <= 0) {
+            bail!("Default sets and reps must be positive.");
+        }
+        Ok(())
+    }
+
+    /// Sets (or clears with `None`) the MET value used to estimate calories for an exercise.
+    /// # Errors
+    /// Returns `ServiceError` if the exercise is not found, the value is not positive, or DB update fails.
+    pub fn set_exercise_met_value(&self, identifier: &str, met_value: Option<f64>) -> Result<u64> {
+        if let Some(met) = met_value {
+            if !met.is_finite() || met <= 0.0 {
+                bail!("MET value must be a positive number.");
             }
-            DbError::ExerciseNotFound(_) => {
-                anyhow::anyhow!("Exercise '{identifier}' not found to edit.")
+        }
+        let canonical_name = self
+            .resolve_identifier_to_canonical_name(identifier)?
+            .ok_or_else(|| DbError::ExerciseNotFound(identifier.to_string()))?;
+        db::set_exercise_met_value(&*self.connection()?, &canonical_name, met_value)
+            .with_context(|| format!("Failed to set MET value for '{canonical_name}'"))
+            .map_err(Into::into)
+    }
+
+    /// Sets which metrics count as PBs for an exercise, overriding the global
+    /// `notify_weight`/`notify_reps`/`notify_duration`/`notify_distance` flags. `None` goes
+    /// back to the global config. Volume and pace PBs always follow the global flags.
+    /// # Errors
+    /// Returns `ServiceError` if the exercise is not found or DB update fails.
+    pub fn set_exercise_pb_metrics(&self, identifier: &str, metrics: Option<&[PbMetric]>) -> Result<u64> {
+        let metrics = metrics.map(|metrics| {
+            PbMetric::ALL
+                .into_iter()
+                .filter(|m| metrics.contains(m))
+                .collect::<Vec<_>>()
+        });
+        let canonical_name = self
+            .resolve_identifier_to_canonical_name(identifier)?
+            .ok_or_else(|| DbError::ExerciseNotFound(identifier.to_string()))?;
+        db::set_exercise_pb_metrics(&*self.connection()?, &canonical_name, metrics.as_deref())
+            .with_context(|| format!("Failed to set PB metrics for '{canonical_name}'"))
+            .map_err(Into::into)
+    }
+
+    /// Makes an exercise's weights entered per side (plate-loaded machines, sleds): logged
+    /// weights are doubled and `carriage_weight` is added before storing, so stored weights,
+    /// graphs and PBs all use the total. Existing entries are left as they are. The carriage
+    /// weight is cleared when `per_side` is false.
+    /// # Errors
+    /// Returns `ServiceError` if the exercise is not found, the carriage weight is negative,
+    /// or DB update fails.
+    pub fn set_exercise_weight_per_side(
+        &self,
+        identifier: &str,
+        per_side: bool,
+        carriage_weight: Option<f64>,
+    ) -> Result<u64> {
+        if carriage_weight.is_some_and(|w| !w.is_finite() || w < 0.0) {
+            bail!("Carriage weight cannot be negative.");
+        }
+        let canonical_name = self
+            .resolve_identifier_to_canonical_name(identifier)?
+            .ok_or_else(|| DbError::ExerciseNotFound(identifier.to_string()))?;
+        let carriage_weight = carriage_weight.filter(|_| per_side);
+        db::set_exercise_weight_per_side(&*self.connection()?, &canonical_name, per_side, carriage_weight)
+            .with_context(|| format!("Failed to set per-side weight for '{canonical_name}'"))
+            .map_err(Into::into)
+    }
+
+    /// Sets an exercise's position in the manual order used by `ExerciseSort::Custom`
+    /// (lowest first), or clears it with `None` so it is listed after the ordered ones.
+    /// # Errors
+    /// Returns `ServiceError` if the exercise is not found, the position is negative, or
+    /// DB update fails.
+    pub fn set_exercise_order(&self, identifier: &str, position: Option<i64>) -> Result<u64> {
+        if position.is_some_and(|p| p < 0) {
+            bail!("Exercise position cannot be negative.");
+        }
+        let canonical_name = self
+            .resolve_identifier_to_canonical_name(identifier)?
+            .ok_or_else(|| DbError::ExerciseNotFound(identifier.to_string()))?;
+        db::set_exercise_sort_order(&*self.connection()?, &canonical_name, position)
+            .with_context(|| format!("Failed to set position of '{canonical_name}'"))
+            .map_err(Into::into)
+    }
+
+    /// Replaces the manual exercise order with `identifiers`, in that order. Exercises not
+    /// listed lose their position and follow the ordered ones by name.
+    /// # Errors
+    /// Returns `ServiceError` if an identifier is not found or listed twice, or the DB
+    /// transaction fails (nothing is changed then).
+    pub fn reorder_exercises(&self, identifiers: &[&str]) -> Result<()> {
+        let mut names: Vec<String> = Vec::with_capacity(identifiers.len());
+        for &identifier in identifiers {
+            let canonical_name = self
+                .resolve_identifier_to_canonical_name(identifier)?
+                .ok_or_else(|| DbError::ExerciseNotFound(identifier.to_string()))?;
+            if names.contains(&canonical_name) {
+                bail!("Exercise '{canonical_name}' is listed more than once.");
             }
-            _ => anyhow::Error::new(db_err)
-                .context(format!("Failed to update exercise '{identifier}'")),
+            names.push(canonical_name);
+        }
+        db::reorder_exercises(&mut *self.connection()?, &names)
+            .context("Failed to reorder exercises")
+            .map_err(Into::into)
+    }
+
+    /// Makes an exercise a variation of `parent_identifier` (e.g. "Paused Bench" of "Bench
+    /// Press"), or a standalone exercise again with `None`. Renaming or merging the parent
+    /// updates its variations; deleting it leaves them standalone.
+    /// # Errors
+    /// - `ServiceError` if either exercise is not found, or the parent is the exercise itself
+    ///   or one of its own variations.
+    /// - `ServiceError` wrapping other `DbError` variants.
+    pub fn set_exercise_parent(&self, identifier: &str, parent_identifier: Option<&str>) -> Result<u64> {
+        let canonical_name = self
+            .resolve_identifier_to_canonical_name(identifier)?
+            .ok_or_else(|| DbError::ExerciseNotFound(identifier.to_string()))?;
+        let parent_name = parent_identifier
+            .map(|parent| -> Result<String> {
+                self.resolve_identifier_to_canonical_name(parent)?
+                    .ok_or_else(|| DbError::ExerciseNotFound(parent.to_string()).into())
+            })
+            .transpose()?;
+        if let Some(parent_name) = &parent_name {
+            // Walk up from the new parent; reaching this exercise would close a cycle
+            let conn = self.connection()?;
+            let mut ancestor = Some(parent_name.clone());
+            let mut seen = BTreeSet::new();
+            while let Some(name) = ancestor {
+                if name.eq_ignore_ascii_case(&canonical_name) {
+                    bail!("'{parent_name}' can't be the parent of '{canonical_name}': that would create a cycle.");
+                }
+                if !seen.insert(name.to_lowercase()) {
+                    break;
+                }
+                ancestor = db::get_exercise_by_name(&conn, &name)?.and_then(|ex| ex.parent_exercise);
+            }
+        }
+        db::set_exercise_parent(&*self.connection()?, &canonical_name, parent_name.as_deref())
+            .with_context(|| format!("Failed to set the parent of '{canonical_name}'"))
+            .map_err(Into::into)
+    }
+
+    /// Canonical names of all variations of an exercise: its children, their children and so
+    /// on, ordered by name. The exercise itself is not included.
+    /// # Errors
+    /// - `ServiceError` if the exercise is not found.
+    /// - `ServiceError` wrapping `DbError` variants.
+    pub fn get_exercise_variations(&self, identifier: &str) -> Result<Vec<String>> {
+        let canonical_name = self
+            .resolve_identifier_to_canonical_name(identifier)?
+            .ok_or_else(|| DbError::ExerciseNotFound(identifier.to_string()))?;
+        let conn = self.connection()?;
+        let mut seen = BTreeSet::from([canonical_name.to_lowercase()]);
+        let mut variations = Vec::new();
+        let mut pending = vec![canonical_name];
+        while let Some(parent) = pending.pop() {
+            for child in db::list_exercise_variations(&conn, &parent)? {
+                // Guards against cycles created outside `set_exercise_parent`, e.g. by a sync
+                if seen.insert(child.to_lowercase()) {
+                    variations.push(child.clone());
+                    pending.push(child);
+                }
+            }
+        }
+        variations.sort();
+        Ok(variations)
+    }
+
+    /// Marks an exercise as a favorite, or unmarks it if it already is one. Favorites are
+    /// listed first by `list_exercises`. Returns the new state.
+    /// # Errors
+    /// Returns `ServiceError` if the exercise is not found or DB update fails.
+    pub fn toggle_favorite(&self, identifier: &str) -> Result<bool> {
+        let canonical_name = self
+            .resolve_identifier_to_canonical_name(identifier)?
+            .ok_or_else(|| DbError::ExerciseNotFound(identifier.to_string()))?;
+        db::toggle_exercise_favorite(&*self.connection()?, &canonical_name)
+            .with_context(|| format!("Failed to toggle favorite for '{canonical_name}'"))
+            .map_err(Into::into)
+    }
+
+    /// Restores a soft-deleted exercise (by ID or name) along with the aliases deleted with it.
+    /// Returns the restored exercise's name.
+    /// # Errors
+    /// - `ServiceError` if no deleted exercise matches, or its name is now used by an active exercise.
+    /// - `ServiceError` wrapping other `DbError` variants.
+    pub fn restore_exercise(&self, identifier: &str) -> Result<String> {
+        let trimmed = identifier.trim();
+        if trimmed.is_empty() {
+            bail!("Exercise identifier cannot be empty.");
+        }
+        db::restore_exercise(&mut *self.connection()?, trimmed).map_err(|db_err| {
+            let message = match &db_err {
+                DbError::ExerciseNotFound(_) => format!("No deleted exercise '{trimmed}' found to restore."),
+                DbError::ExerciseNameNotUnique(name) => {
+                    format!("Cannot restore '{name}': an active exercise already uses that name.")
+                }
+                _ => format!("Failed to restore exercise '{trimmed}'"),
+            };
+            anyhow::Error::new(db_err).context(message) // Keeps the `DbError` for `ServiceError`
         })
+        .map_err(Into::into)
     }
 
-    /// Deletes exercise definitions.
+    /// Deletes exercise definitions and their aliases (for good with `hard_delete`).
     /// # Errors
-    /// Returns `anyhow::Error` if an identifier invalid or DB deletion fails.
+    /// Returns `ServiceError` if an identifier invalid or DB deletion fails.
     pub fn delete_exercise(&mut self, identifiers: &[String]) -> Result<u64> {
         let mut total_deleted: u64 = 0;
         for identifier in identifiers {
@@ -488,8 +2272,8 @@ impl AppService {
                 .ok_or_else(|| DbError::ExerciseNotFound(identifier.clone()))?;
             let canonical_name = exercise_def.name;
 
-            let workout_count: i64 = self
-                .conn
+            let mut conn = self.connection()?;
+            let workout_count: i64 = conn
                 .query_row(
                     "SELECT COUNT(*) FROM workouts WHERE exercise_name = ?1 COLLATE NOCASE AND deleted = FALSE", // Only count active workouts
                     [&canonical_name],
@@ -503,22 +2287,53 @@ impl AppService {
             }
 
             let deleted_count =
-                db::delete_exercise(&mut self.conn, &canonical_name).map_err(|e| match e {
-                    DbError::ExerciseNotFound(_) => {
+                db::delete_exercise(&mut conn, &canonical_name).map_err(|e| {
+                    let message = match &e {
                         // This means it was already deleted or never existed in an active state.
-                        anyhow::anyhow!("Exercise '{identifier}' not found or already deleted.")
-                    }
-                    _ => anyhow::Error::new(e)
-                        .context(format!("Failed to soft-delete exercise '{canonical_name}'")),
+                        DbError::ExerciseNotFound(_) => {
+                            format!("Exercise '{identifier}' not found or already deleted.")
+                        }
+                        _ => format!("Failed to soft-delete exercise '{canonical_name}'"),
+                    };
+                    anyhow::Error::new(e).context(message)
                 })?;
+            if self.config.hard_delete {
+                db::purge_exercise(&conn, &canonical_name)
+                    .with_context(|| format!("Failed to hard-delete exercise '{canonical_name}'"))?;
+            }
             total_deleted += deleted_count;
         }
         Ok(total_deleted)
     }
 
+    /// Merges one exercise into another: reassigns the source's workouts and aliases to the
+    /// target's canonical name, then soft-deletes the source. Exercises of different types
+    /// are still merged (the workouts take the target's type); `MergeResult::type_mismatch`
+    /// reports it.
+    /// # Errors
+    /// - `ServiceError` if either identifier is not found or both resolve to the same exercise.
+    /// - `ServiceError` wrapping other `DbError` variants.
+    pub fn merge_exercises(&self, source_identifier: &str, target_identifier: &str) -> Result<MergeResult> {
+        let source = self
+            .resolve_exercise_identifier(source_identifier)?
+            .ok_or_else(|| DbError::ExerciseNotFound(source_identifier.to_string()))?;
+        let target = self
+            .resolve_exercise_identifier(target_identifier)?
+            .ok_or_else(|| DbError::ExerciseNotFound(target_identifier.to_string()))?;
+        if source.id == target.id {
+            bail!("Cannot merge exercise '{}' into itself.", source.name);
+        }
+        let reassigned = db::merge_exercises(&mut *self.connection()?, &source.name, &target.name)
+            .with_context(|| format!("Failed to merge exercise '{}' into '{}'", source.name, target.name))?;
+        Ok(MergeResult {
+            reassigned,
+            type_mismatch: (source.type_ != target.type_).then_some((source.type_, target.type_)),
+        })
+    }
+
     /// Retrieves an exercise definition by identifier.
     /// # Errors
-    /// Returns `anyhow::Error` if identifier empty or resolution fails.
+    /// Returns `ServiceError` if identifier empty or resolution fails.
     pub fn get_exercise_by_identifier_service(
         &self,
         identifier: &str,
@@ -526,29 +2341,35 @@ impl AppService {
         self.resolve_exercise_identifier(identifier)
     }
 
-    /// Lists exercise definitions based on filters.
+    /// Retrieves a non-deleted exercise definition directly by its primary key, without
+    /// going through the name/alias resolver.
     /// # Errors
-    /// Returns `anyhow::Error` wrapping DB errors.
-    pub fn list_exercises(
-        &self,
-        type_filter: Option<ExerciseType>,
-        muscle_filter: Option<Vec<&str>>,
-    ) -> Result<Vec<ExerciseDefinition>> {
-        db::list_exercises(&self.conn, type_filter, muscle_filter)
+    /// - `ServiceError` wrapping `DbError` variants.
+    pub fn get_exercise_by_id(&self, id: i64) -> Result<Option<ExerciseDefinition>> {
+        db::get_exercise_by_id(&*self.connection()?, id)
+            .with_context(|| format!("Failed to get exercise ID {id}"))
+            .map_err(Into::into)
+    }
+
+    /// Lists exercise definitions matching `filters`, see `ExerciseFilters`.
+    /// # Errors
+    /// Returns `ServiceError` wrapping DB errors.
+    pub fn list_exercises(&self, filters: &ExerciseFilters) -> Result<Vec<ExerciseDefinition>> {
+        db::list_exercises(&*self.connection()?, filters)
             .context("Failed to list exercise definitions")
             .map_err(Into::into)
     }
 
     /// Creates a new alias for an exercise.
     /// # Errors
-    /// Returns `anyhow::Error` if alias/identifier invalid or DB creation fails.
+    /// Returns `ServiceError` if alias/identifier invalid or DB creation fails.
     pub fn create_alias(&self, alias_name: &str, exercise_identifier: &str) -> Result<()> {
         let trimmed_alias = alias_name.trim();
         if trimmed_alias.is_empty() {
             bail!("Alias name cannot be empty.");
         }
         // Check against active exercises/aliases
-        if let Some((_, res_type)) = db::get_exercise_by_identifier(&self.conn, trimmed_alias)? {
+        if let Some((_, res_type)) = db::get_exercise_by_identifier(&*self.connection()?, trimmed_alias)? {
             match res_type {
                 ResolvedByType::Id => {
                     bail!("Alias '{trimmed_alias}' conflicts with an existing exercise ID.")
@@ -564,50 +2385,77 @@ impl AppService {
             .ok_or_else(|| DbError::ExerciseNotFound(exercise_identifier.to_string()))?;
 
         println!("deleted2");
-        db::create_alias(&self.conn, trimmed_alias, &canonical_name).map_err(
+        db::create_alias(&*self.connection()?, trimmed_alias, &canonical_name).map_err(
             |db_err| match db_err {
                 DbError::AliasAlreadyExists(_) => anyhow::anyhow!(db_err), // Will trigger if alias name (PK) is taken, even if soft-deleted
                 _ => anyhow::Error::new(db_err)
                     .context(format!("Failed to create alias '{trimmed_alias}'")),
             },
         )
+        .map_err(Into::into)
     }
 
-    /// Deletes an exercise alias.
+    /// Deletes an exercise alias (for good with `hard_delete`).
     /// # Errors
-    /// Returns `anyhow::Error` if alias name empty or DB deletion fails.
+    /// Returns `ServiceError` if alias name empty or DB deletion fails.
     pub fn delete_alias(&self, alias_name: &str) -> Result<u64> {
         let trimmed_alias = alias_name.trim();
         if trimmed_alias.is_empty() {
             bail!("Alias name cannot be empty.");
         }
-        db::delete_alias(&self.conn, trimmed_alias).map_err(|db_err| match db_err {
+        let conn = self.connection()?;
+        let deleted = db::delete_alias(&conn, trimmed_alias).map_err(|db_err| match db_err {
             DbError::AliasNotFound(_) => anyhow::anyhow!(db_err), // Not found or already deleted
             _ => anyhow::Error::new(db_err)
                 .context(format!("Failed to soft-delete alias '{trimmed_alias}'")),
-        })
+        })?;
+        if self.config.hard_delete {
+            db::purge_alias(&conn, trimmed_alias)
+                .with_context(|| format!("Failed to hard-delete alias '{trimmed_alias}'"))?;
+        }
+        Ok(deleted)
     }
 
     /// Lists all defined aliases.
     /// # Errors
-    /// Returns `anyhow::Error` wrapping DB errors.
+    /// Returns `ServiceError` wrapping DB errors.
     pub fn list_aliases(&self) -> Result<HashMap<String, String>> {
-        db::list_aliases(&self.conn)
+        db::list_aliases(&*self.connection()?)
             .context("Failed to list aliases")
             .map_err(Into::into)
     }
 
-    /// Adds a workout entry.
+    /// Lists the aliases of a single exercise, sorted alphabetically. Empty if it has none.
+    /// # Errors
+    /// - `DbError::ExerciseNotFound` if the identifier does not resolve.
+    /// - `ServiceError` wrapping other `DbError` variants.
+    pub fn list_aliases_for(&self, identifier: &str) -> Result<Vec<String>> {
+        let canonical_name = self
+            .resolve_identifier_to_canonical_name(identifier)?
+            .ok_or_else(|| DbError::ExerciseNotFound(identifier.to_string()))?;
+        db::list_aliases_for_exercise(&*self.connection()?, &canonical_name)
+            .with_context(|| format!("Failed to list aliases for '{canonical_name}'"))
+            .map_err(Into::into)
+    }
+
+    /// Adds a workout entry. With `merge_same_day_sets`, an entry of the same exercise and
+    /// kind (warmup/AMRAP/failure) already logged that day absorbs it instead, see
+    /// `consolidate_day`; `undo_last` then restores that entry's pre-merge version.
+    /// For per-side exercises `params.weight` is the weight per side, see
+    /// `set_exercise_weight_per_side`.
     /// # Returns
-    /// A `Result` containing `(workout_id, Option<PBInfo>)`.
+    /// A `Result` containing `(workout_id, Option<PBInfo>)`; the ID is the merged entry's if merged.
+    /// With `validation.warn_on_pb_jump` set, `PBInfo::warnings` holds any `pb_jump_warnings`.
     /// # Errors
-    /// Returns `anyhow::Error` if exercise invalid, bodyweight needed but missing,
-    /// logging restricted metrics, or DB add fails.
+    /// Returns `ServiceError` if exercise invalid, bodyweight needed but missing,
+    /// logging restricted metrics, a value above the configured validation limits,
+    /// end time not after start time, or DB add fails.
     pub fn add_workout(&mut self, params: AddWorkoutParams) -> Result<(i64, Option<PBInfo>)> {
+        let duration = entry_duration(&params)?;
         let exercise_def = self.resolve_or_create_exercise(
             params.exercise_identifier,
             params.implicit_type,
-            params.implicit_muscles,
+            params.implicit_muscles.clone(),
         )?;
         let canonical_exercise_name = &exercise_def.name;
         println!("here");
@@ -620,7 +2468,7 @@ impl AppService {
         if !exercise_def.log_reps && params.reps.is_some() {
             violations.push("reps");
         }
-        if !exercise_def.log_duration && params.duration.is_some() {
+        if !exercise_def.log_duration && duration.is_some() {
             violations.push("duration");
         }
         if !exercise_def.log_distance && params.distance.is_some() {
@@ -644,11 +2492,19 @@ impl AppService {
              );
         }
         
-        let additional_weight_for_db = params.weight;
+        self.check_entry_limits(params.sets, params.reps, params.weight, duration, params.distance)?;
+        check_heart_rates(params.avg_heart_rate, params.max_heart_rate)?;
+
+        let additional_weight_for_db = self.weight_input_for_db(&exercise_def, params.weight);
         let bodyweight_for_db = params.bodyweight_to_use; 
 
         let timestamp = params.date;
         let previous_bests = self.get_previous_bests(canonical_exercise_name)?;
+        let warnings = if self.config.validation.warn_on_pb_jump && !params.is_warmup && !params.is_failure {
+            self.pb_jumps(&previous_bests, &exercise_def, &params, duration)
+        } else {
+            Vec::new()
+        };
 
         let workout_data = NewWorkoutData {
             exercise_name: canonical_exercise_name,
@@ -657,28 +2513,267 @@ impl AppService {
             weight: additional_weight_for_db, 
             bodyweight_to_use: bodyweight_for_db, 
             reps: params.reps,
-            duration: params.duration,
+            duration,
             distance: self.convert_distance_input_to_km(params.distance), 
             notes: params.notes.as_deref(),
+            is_warmup: params.is_warmup,
+            is_amrap: params.is_amrap,
+            is_failure: params.is_failure,
+            avg_heart_rate: params.avg_heart_rate,
+            max_heart_rate: params.max_heart_rate,
+        };
+
+        let merge_target = if self.config.merge_same_day_sets {
+            self.same_day_entry(&workout_data)?
+        } else {
+            None
+        };
+        let inserted_id = if let Some(mut existing) = merge_target {
+            merge_workout_into(&mut existing, &Workout {
+                id: 0,
+                _id: None,
+                timestamp,
+                exercise_name: canonical_exercise_name.clone(),
+                sets: workout_data.sets,
+                reps: workout_data.reps,
+                weight: workout_data.weight,
+                duration_minutes: workout_data.duration,
+                bodyweight: workout_data.bodyweight_to_use,
+                distance: workout_data.distance,
+                notes: params.notes.clone(),
+                exercise_type: None,
+                is_warmup: params.is_warmup,
+                is_amrap: params.is_amrap,
+                is_failure: params.is_failure,
+                avg_heart_rate: params.avg_heart_rate,
+                max_heart_rate: params.max_heart_rate,
+                deleted: false,
+                last_edited: Utc::now(),
+            });
+            let mut conn = self.connection()?;
+            db::merge_workouts(&mut conn, &existing, &[])
+                .with_context(|| format!("Failed to merge into workout ID {}", existing.id))?;
+            db::record_undo(&conn, UndoAction::MergeWorkout, &[existing.id])
+                .context("Failed to record undo entry")?;
+            existing.id
+        } else {
+            let inserted_id = self.insert_workout_record(&workout_data)?;
+            db::record_undo(&*self.connection()?, UndoAction::AddWorkout, &[inserted_id])
+                .context("Failed to record undo entry")?;
+            inserted_id
         };
+        let mut pb_info = self.check_entry_pbs(&exercise_def, &previous_bests, &workout_data)?;
+        if !warnings.is_empty() {
+            pb_info.get_or_insert_with(PBInfo::default).warnings = warnings;
+        }
+        Ok((inserted_id, pb_info))
+    }
 
+    /// Copies a workout entry (all metrics, flags and notes) to `new_date`, keeping its local
+    /// time of day. The copy is always a new entry, even with `merge_same_day_sets`, and can
+    /// be undone like any added workout.
+    /// # Returns
+    /// `(new_workout_id, Option<PBInfo>)`, the PBs being those the copy sets on its new date.
+    /// # Errors
+    /// - `DbError::WorkoutNotFound` if no active workout has this ID.
+    /// - `ServiceError` wrapping other `DbError` variants.
+    pub fn duplicate_workout(&self, id: i64, new_date: NaiveDate) -> Result<(i64, Option<PBInfo>)> {
+        let original = db::get_workout_by_id(&*self.connection()?, id)?
+            .ok_or(DbError::WorkoutNotFound(id))?;
+        let exercise_def = self
+            .resolve_exercise_identifier(&original.exercise_name)?
+            .ok_or_else(|| DbError::ExerciseNotFound(original.exercise_name.clone()))?;
+        let previous_bests = self.get_previous_bests(&exercise_def.name)?;
+
+        let workout_data = self.copy_of_workout(&original, new_date)?;
         let inserted_id = self.insert_workout_record(&workout_data)?;
-        
-        let effective_weight_for_pb_check = calculate_effective_weight(
-            &exercise_def,
-            additional_weight_for_db, 
-            bodyweight_for_db,        
-        );
+        db::record_undo(&*self.connection()?, UndoAction::AddWorkout, &[inserted_id])
+            .context("Failed to record undo entry")?;
 
-        let pb_info = self.check_for_new_pbs(
-            &previous_bests,
-            effective_weight_for_pb_check,
-            params.reps,
-            params.duration,
-            self.convert_distance_input_to_km(params.distance), 
+        let pb_info = self.check_entry_pbs(&exercise_def, &previous_bests, &workout_data)?;
+        Ok((inserted_id, pb_info))
+    }
+
+    /// Copies every workout entry logged on `from` to `to`, keeping their order and local
+    /// times of day. All copies are inserted in one transaction and undone together.
+    /// PBs are not reported.
+    /// # Returns
+    /// The IDs of the new entries; empty if nothing was logged on `from`.
+    /// # Errors
+    /// Returns `ServiceError` if the DB query or insert fails (nothing is copied then).
+    pub fn duplicate_day(&self, from: NaiveDate, to: NaiveDate) -> Result<Vec<i64>> {
+        let originals = self.list_workouts(&WorkoutFilters {
+            date: Some(from),
+            ..Default::default()
+        })?;
+        if originals.is_empty() {
+            return Ok(Vec::new());
+        }
+        let copies = originals
+            .iter()
+            .map(|workout| self.copy_of_workout(workout, to))
+            .collect::<Result<Vec<_>>>()?;
+
+        let conn = &mut *self.connection()?;
+        let inserted_ids = db::add_workouts(conn, &copies)
+            .with_context(|| format!("Failed to copy workouts from {from} to {to}"))?;
+        db::record_undo(conn, UndoAction::AddWorkout, &inserted_ids)
+            .context("Failed to record undo entry")?;
+        Ok(inserted_ids)
+    }
+
+    /// `NewWorkoutData` copying `workout` to `date` at the same local time of day (noon if
+    /// that time doesn't exist on `date`).
+    fn copy_of_workout<'a>(&self, workout: &'a Workout, date: NaiveDate) -> Result<NewWorkoutData<'a>> {
+        let tz = self.config.tz();
+        let time_of_day = workout.timestamp.with_timezone(&tz).time();
+        let timestamp = match tz.from_local_datetime(&date.and_time(time_of_day)).earliest() {
+            Some(local) => local.with_timezone(&Utc),
+            None => create_timestamp_from_date(date, tz)?,
+        };
+        Ok(NewWorkoutData {
+            exercise_name: &workout.exercise_name,
+            timestamp,
+            sets: workout.sets,
+            reps: workout.reps,
+            weight: workout.weight,
+            bodyweight_to_use: workout.bodyweight,
+            duration: workout.duration_minutes,
+            distance: workout.distance,
+            notes: workout.notes.as_deref(),
+            is_warmup: workout.is_warmup,
+            is_amrap: workout.is_amrap,
+            is_failure: workout.is_failure,
+            avg_heart_rate: workout.avg_heart_rate,
+            max_heart_rate: workout.max_heart_rate,
+        })
+    }
+
+    /// Values of a prospective `add_workout` entry that are more than 50% above the current
+    /// PB of their metric (`PB_JUMP_WARNING_RATIO`), which usually means a typo. One message
+    /// per metric; empty if the exercise doesn't exist yet or has no records. `add_workout`
+    /// returns these in `PBInfo::warnings` when `validation.warn_on_pb_jump` is set.
+    /// # Errors
+    /// Returns `ServiceError` if end time is not after start time or the DB query fails.
+    pub fn pb_jump_warnings(&self, params: &AddWorkoutParams) -> Result<Vec<String>> {
+        let duration = entry_duration(params)?;
+        let Some(exercise_def) = self.resolve_exercise_identifier(params.exercise_identifier)? else {
+            return Ok(Vec::new());
+        };
+        let previous_bests = self.get_previous_bests(&exercise_def.name)?;
+        Ok(self.pb_jumps(&previous_bests, &exercise_def, params, duration))
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    fn pb_jumps(
+        &self,
+        prev: &PreviousBests,
+        exercise_def: &ExerciseDefinition,
+        params: &AddWorkoutParams,
+        duration: Option<i64>,
+    ) -> Vec<String> {
+        let weight = calculate_effective_weight(
+            exercise_def,
+            self.weight_input_for_db(exercise_def, params.weight),
+            params.bodyweight_to_use,
         );
+        let distance = self
+            .convert_distance_input_to_km(params.distance)
+            .map(|km| self.display_distance(km));
+        let candidates = [
+            ("Weight", weight, prev.weight),
+            ("Reps", params.reps.map(|r| r as f64), prev.reps.map(|r| r as f64)),
+            ("Duration", duration.map(|d| d as f64), prev.duration.map(|d| d as f64)),
+            ("Distance", distance, prev.distance_km.map(|km| self.display_distance(km))),
+        ];
+        candidates
+            .into_iter()
+            .filter_map(|(metric, value, best)| {
+                let (value, best) = (value?, best.filter(|&b| b > 0.0)?);
+                (value > best * PB_JUMP_WARNING_RATIO).then(|| {
+                    format!("{metric} {value} is more than 50% above the current PB of {best} for '{}'. Is this a typo?", exercise_def.name)
+                })
+            })
+            .collect()
+    }
 
-        Ok((inserted_id, pb_info))
+    /// Rejects values above the limits in the `validation` config. Weight and distance are
+    /// compared as entered.
+    fn check_entry_limits(
+        &self,
+        sets: Option<i64>,
+        reps: Option<i64>,
+        weight: Option<f64>,
+        duration: Option<i64>,
+        distance: Option<f64>,
+    ) -> Result<()> {
+        let limits = &self.config.validation;
+        let mut exceeded = Vec::new();
+        if let (Some(v), Some(max)) = (sets, limits.max_sets) {
+            if v > max {
+                exceeded.push(format!("sets {v} (max {max})"));
+            }
+        }
+        if let (Some(v), Some(max)) = (reps, limits.max_reps) {
+            if v > max {
+                exceeded.push(format!("reps {v} (max {max})"));
+            }
+        }
+        if let (Some(v), Some(max)) = (weight, limits.max_weight) {
+            if v > max {
+                exceeded.push(format!("weight {v} (max {max})"));
+            }
+        }
+        if let (Some(v), Some(max)) = (duration, limits.max_duration) {
+            if v > max {
+                exceeded.push(format!("duration {v} min (max {max})"));
+            }
+        }
+        if let (Some(v), Some(max)) = (distance, limits.max_distance) {
+            if v > max {
+                exceeded.push(format!("distance {v} (max {max})"));
+            }
+        }
+        if !exceeded.is_empty() {
+            bail!(
+                "Entry exceeds the configured validation limits: {}. Correct the values or raise the limits in the [validation] section of the config.",
+                exceeded.join(", ")
+            );
+        }
+        Ok(())
+    }
+
+    /// Checks whether adding `added_volume` (already stored) pushed the total volume of `day`
+    /// past the best total of every other day. The first training day only sets a baseline.
+    fn check_for_session_volume_record(
+        &self,
+        day: NaiveDate,
+        added_volume: f64,
+    ) -> Result<PbMetricInfo<f64>> {
+        let totals: BTreeMap<NaiveDate, f64> = self
+            .session_volumes()?
+            .into_iter()
+            .map(|(date, by_exercise)| (date, by_exercise.iter().map(|(_, v)| v).sum()))
+            .collect();
+        let day_total = totals.get(&day).copied().unwrap_or(0.0);
+        // Earliest day wins ties, matching `best_session_volume`
+        let Some((other_best_date, other_best)) = totals
+            .iter()
+            .filter(|&(&date, _)| date != day)
+            .map(|(&date, &total)| (date, total))
+            .reduce(|best, cur| if cur.1 > best.1 { cur } else { best })
+        else {
+            return Ok(PbMetricInfo::default());
+        };
+        let tol = self.config.pb_notifications.pb_tolerance;
+        let was_record = tol.is_exceeded_by(other_best, day_total - added_volume - other_best);
+        Ok(PbMetricInfo {
+            achieved: !was_record && tol.is_exceeded_by(other_best, day_total - other_best),
+            new_value: Some(day_total),
+            previous_value: Some(other_best),
+            previous_date: Some(other_best_date),
+            ..Default::default()
+        })
     }
 
     fn resolve_or_create_exercise(
@@ -687,9 +2782,18 @@ impl AppService {
         imp_type: Option<ExerciseType>,
         imp_muscles: Option<String>,
     ) -> Result<ExerciseDefinition> {
+        // Without an explicit type, the type inferred from the name or `default_implicit_type`
+        // applies and muscles are optional
+        let implicit = match (imp_type, imp_muscles) {
+            (Some(ex_type), Some(muscles)) => Some((ex_type, muscles)),
+            (None, muscles) => infer_exercise_type(identifier, &self.config)
+                .or(self.config.default_implicit_type)
+                .map(|ex_type| (ex_type, muscles.unwrap_or_default())),
+            (Some(_), None) => None,
+        };
         if let Some(def) = self.resolve_exercise_identifier(identifier)? { // resolve_exercise_identifier only gets active
             Ok(def)
-        } else if let (Some(ex_type), Some(muscles)) = (imp_type, imp_muscles) {
+        } else if let Some((ex_type, muscles)) = implicit {
             // At this point, exercise is not active. It might be soft-deleted or not exist.
             // db::create_exercise will fail if the name is taken by a soft-deleted exercise due to UNIQUE constraint.
             println!("Exercise '{identifier}' not found, defining implicitly...");
@@ -705,38 +2809,174 @@ impl AppService {
                         .ok_or_else(|| {
                             anyhow::anyhow!("Failed to re-fetch implicitly created '{identifier}'")
                         })
+                        .map_err(Into::into)
                 }
                 Err(e) => { // Could be DbError::ExerciseNameNotUnique if name taken by soft-deleted
                     Err(e).context(format!("Implicit definition failed for '{identifier}' (name might be taken by a soft-deleted exercise)"))
+                        .map_err(Into::into)
                 }
             }
         } else {
             bail!(
-                "Exercise '{identifier}' not found. Define it first or provide --type/--muscles."
+                "Exercise '{identifier}' not found. Define it first, provide --type/--muscles or set a default type."
             );
         }
     }
 
+    /// Rounds a weight being logged when `round_stored_weights` is enabled.
+    fn round_weight_input(&self, weight_arg: Option<f64>) -> Option<f64> {
+        match self.config.weight_increment {
+            Some(inc) if self.config.round_stored_weights => {
+                weight_arg.map(|w| round_to_increment(w, inc))
+            }
+            _ => weight_arg,
+        }
+    }
+
+    /// Weight to store for a weight entered for `exercise_def`: rounded like
+    /// `round_weight_input`, then turned into the total for per-side exercises.
+    fn weight_input_for_db(&self, exercise_def: &ExerciseDefinition, weight_arg: Option<f64>) -> Option<f64> {
+        self.round_weight_input(weight_arg)
+            .map(|w| exercise_def.total_weight(w))
+    }
+
+    /// Converts a distance entered in the configured units to km, rounded to
+    /// `distance_decimals` so converting it back for display yields the value entered.
     fn convert_distance_input_to_km(&self, dist_arg: Option<f64>) -> Option<f64> {
-        dist_arg.map(|d| match self.config.units {
-            Units::Metric => d,
-            Units::Imperial => d * MILE_TO_KM,
+        dist_arg.map(|d| {
+            let km = match self.config.distance_units() {
+                Units::Metric => d,
+                Units::Imperial => d * MILE_TO_KM,
+            };
+            round_to_decimals(km, self.config.distance_decimals)
         })
     }
 
     fn get_previous_bests(&self, name: &str) -> Result<PreviousBests> {
+        let conn = self.connection()?;
         Ok(PreviousBests {
-            weight: db::get_max_effective_weight_for_exercise(&self.conn, name)?,
-            reps: db::get_max_reps_for_exercise(&self.conn, name)?,
-            duration: db::get_max_duration_for_exercise(&self.conn, name)?,
-            distance_km: db::get_max_distance_for_exercise(&self.conn, name)?,
+            weight: db::get_max_effective_weight_for_exercise(&conn, name)?,
+            reps: db::get_max_reps_for_exercise(&conn, name)?,
+            duration: db::get_max_duration_for_exercise(&conn, name)?,
+            distance_km: db::get_max_distance_for_exercise(&conn, name)?,
+            volume: db::get_max_volume_for_exercise(&conn, name)?,
+            pace_min_per_km: db::get_min_pace_for_exercise(&conn, name)?,
+            achieved_at: db::get_pb_timestamps_for_exercise(&conn, name)?,
         })
     }
 
+    /// PBs set by an entry that was just inserted, compared against `previous_bests` taken
+    /// before the insert. Warmup and failure sets never count.
+    fn check_entry_pbs(
+        &self,
+        exercise_def: &ExerciseDefinition,
+        previous_bests: &PreviousBests,
+        data: &NewWorkoutData,
+    ) -> Result<Option<PBInfo>> {
+        if data.is_warmup || data.is_failure {
+            return Ok(None);
+        }
+
+        let effective_weight_for_pb_check = calculate_effective_weight(
+            exercise_def,
+            data.weight,
+            data.bodyweight_to_use,
+        );
+
+        let volume_for_pb_check = match (effective_weight_for_pb_check, data.reps) {
+            (Some(w), Some(r)) => Some(data.sets.unwrap_or(1) as f64 * r as f64 * w),
+            _ => None,
+        };
+
+        let mut pb_info = self.check_for_new_pbs(
+            previous_bests,
+            effective_weight_for_pb_check,
+            data.reps,
+            data.duration,
+            data.distance,
+            volume_for_pb_check,
+            data.duration.and_then(|dur| calculate_pace(dur, data.distance?)),
+            exercise_def.pb_metrics.as_deref(),
+        );
+
+        // Cardio volume isn't counted in session totals
+        if self.config.pb_notifications.notify_volume && exercise_def.type_ != ExerciseType::Cardio {
+            if let Some(volume) = volume_for_pb_check.filter(|&v| v > 0.0) {
+                let session = self.check_for_session_volume_record(self.local_date(data.timestamp), volume)?;
+                match pb_info.as_mut() {
+                    Some(pb) => pb.session_volume = session,
+                    None if session.achieved => {
+                        pb_info = Some(PBInfo {
+                            session_volume: session,
+                            ..Default::default()
+                        });
+                    }
+                    None => {}
+                }
+            }
+        }
+
+        Ok(pb_info)
+    }
+
     fn insert_workout_record(&self, data: &NewWorkoutData) -> Result<i64> {
-        db::add_workout(&self.conn, data).map_err(Into::into)
+        db::add_workout(&*self.connection()?, data)
+            .map_err(Into::into)
+    }
+
+    /// The latest entry of the same exercise, local day and kind as `data`, if any.
+    fn same_day_entry(&self, data: &NewWorkoutData) -> Result<Option<Workout>> {
+        let same_day = self.list_workouts(&WorkoutFilters {
+            exercise_name: Some(data.exercise_name),
+            date: Some(self.local_date(data.timestamp)),
+            ..Default::default()
+        })?;
+        Ok(same_day.into_iter().rev().find(|w| {
+            (w.is_warmup, w.is_amrap, w.is_failure) == (data.is_warmup, data.is_amrap, data.is_failure)
+        }))
     }
 
+    /// Merges the entries of an exercise logged on `date` into one per kind (warmup, AMRAP,
+    /// failure, regular): sets are added up, the heaviest set's weight and reps are kept,
+    /// duration and distance are summed and notes joined. The earliest entry of each kind is
+    /// kept; the others are soft-deleted. Returns the number of entries merged away.
+    /// # Errors
+    /// Returns `ServiceError` if the identifier cannot be resolved or the DB update fails.
+    pub fn consolidate_day(&mut self, date: NaiveDate, identifier: &str) -> Result<usize> {
+        let canonical_name = self
+            .resolve_identifier_to_canonical_name(identifier)?
+            .ok_or_else(|| DbError::ExerciseNotFound(identifier.to_string()))?;
+        let entries = self.list_workouts(&WorkoutFilters {
+            exercise_name: Some(&canonical_name),
+            date: Some(date),
+            ..Default::default()
+        })?;
+
+        let mut groups: Vec<(Workout, Vec<i64>)> = Vec::new();
+        for w in entries {
+            let kind = (w.is_warmup, w.is_amrap, w.is_failure);
+            match groups
+                .iter_mut()
+                .find(|(kept, _)| (kept.is_warmup, kept.is_amrap, kept.is_failure) == kind)
+            {
+                Some((kept, absorbed)) => {
+                    merge_workout_into(kept, &w);
+                    absorbed.push(w.id);
+                }
+                None => groups.push((w, Vec::new())),
+            }
+        }
+
+        let mut merged = 0;
+        for (kept, absorbed) in groups.iter().filter(|(_, absorbed)| !absorbed.is_empty()) {
+            db::merge_workouts(&mut *self.connection()?, kept, absorbed)
+                .with_context(|| format!("Failed to consolidate '{canonical_name}' on {date}"))?;
+            merged += absorbed.len();
+        }
+        Ok(merged)
+    }
+
+    #[allow(clippy::too_many_arguments)]
     fn check_for_new_pbs(
         &self,
         prev: &PreviousBests,
@@ -744,6 +2984,9 @@ impl AppService {
         cur_r: Option<i64>,
         cur_d: Option<i64>,
         cur_dist: Option<f64>,
+        cur_vol: Option<f64>,
+        cur_pace: Option<f64>,
+        exercise_pb_metrics: Option<&[PbMetric]>, // Per-exercise override of the notify flags
     ) -> Option<PBInfo> {
         if prev.no_records() {
             return None;
@@ -752,42 +2995,104 @@ impl AppService {
             weight: PbMetricInfo {
                 previous_value: prev.weight,
                 new_value: cur_w,
+                previous_date: prev.achieved_at.weight.map(|ts| self.local_date(ts)),
                 ..Default::default()
             },
             reps: PbMetricInfo {
                 previous_value: prev.reps,
                 new_value: cur_r,
+                previous_date: prev.achieved_at.reps.map(|ts| self.local_date(ts)),
                 ..Default::default()
             },
             duration: PbMetricInfo {
                 previous_value: prev.duration,
                 new_value: cur_d,
+                previous_date: prev.achieved_at.duration.map(|ts| self.local_date(ts)),
                 ..Default::default()
             },
             distance: PbMetricInfo {
                 previous_value: prev.distance_km,
                 new_value: cur_dist,
+                previous_date: prev.achieved_at.distance.map(|ts| self.local_date(ts)),
+                ..Default::default()
+            },
+            volume: PbMetricInfo {
+                previous_value: prev.volume,
+                new_value: cur_vol,
+                previous_date: prev.achieved_at.volume.map(|ts| self.local_date(ts)),
                 ..Default::default()
             },
+            pace: PbMetricInfo {
+                previous_value: prev.pace_min_per_km,
+                new_value: cur_pace,
+                previous_date: prev.achieved_at.pace.map(|ts| self.local_date(ts)),
+                ..Default::default()
+            },
+            session_volume: PbMetricInfo::default(), // Filled in by `add_workout`
+            warnings: Vec::new(), // Filled in by `add_workout`
         };
         let cfg = &self.config.pb_notifications;
+        let notify = |metric: PbMetric, global: bool| {
+            exercise_pb_metrics.map_or(global, |metrics| metrics.contains(&metric))
+        };
+        // Higher is better; `previous` of 0 when there is no prior record for the metric
+        let improved = |previous: f64, current: f64| {
+            current > 0.0 && cfg.pb_tolerance.is_exceeded_by(previous, current - previous)
+        };
+        // Within tolerance of an existing best, in either direction
+        let matched = |previous: Option<f64>, current: Option<f64>| {
+            cfg.notify_ties
+                && matches!((previous, current), (Some(p), Some(c))
+                    if p > 0.0 && !cfg.pb_tolerance.is_exceeded_by(p, (c - p).abs()))
+        };
 
-        if cfg.notify_weight && cur_w.map_or(false, |w| w > 0.0 && w > prev.weight.unwrap_or(0.0)) {
+        if notify(PbMetric::Weight, cfg.notify_weight) && cur_w.is_some_and(|w| improved(prev.weight.unwrap_or(0.0), w)) {
             pb.weight.achieved = true;
         }
-        if cfg.notify_reps && cur_r.map_or(false, |r| r > 0 && r > prev.reps.unwrap_or(0)) {
+        if notify(PbMetric::Reps, cfg.notify_reps)
+            && cur_r.is_some_and(|r| improved(prev.reps.unwrap_or(0) as f64, r as f64))
+        {
             pb.reps.achieved = true;
         }
-        if cfg.notify_duration && cur_d.map_or(false, |d| d > 0 && d > prev.duration.unwrap_or(0)) {
+        if notify(PbMetric::Duration, cfg.notify_duration)
+            && cur_d.is_some_and(|d| improved(prev.duration.unwrap_or(0) as f64, d as f64))
+        {
             pb.duration.achieved = true;
         }
-        if cfg.notify_distance
-            && cur_dist.map_or(false, |d| d > 0.0 && d > prev.distance_km.unwrap_or(0.0))
+        if notify(PbMetric::Distance, cfg.notify_distance)
+            && cur_dist.is_some_and(|d| improved(prev.distance_km.unwrap_or(0.0), d))
+        {
+            pb.distance.achieved = true;
+        }
+        if cfg.notify_volume && cur_vol.is_some_and(|v| improved(prev.volume.unwrap_or(0.0), v)) {
+            pb.volume.achieved = true;
+        }
+        // Lower pace is better; the first paced entry only sets a baseline
+        if cfg.notify_pace
+            && cur_pace.is_some_and(|p| {
+                prev.pace_min_per_km
+                    .is_some_and(|best| cfg.pb_tolerance.is_exceeded_by(best, best - p))
+            })
         {
-            pb.distance.achieved = true;
+            pb.pace.achieved = true;
         }
-
-        if pb.any_pb() {
+        let as_f64 = |v: Option<i64>| v.map(|v| v as f64);
+        pb.weight.tied = !pb.weight.achieved
+            && notify(PbMetric::Weight, cfg.notify_weight)
+            && matched(prev.weight, cur_w);
+        pb.reps.tied = !pb.reps.achieved
+            && notify(PbMetric::Reps, cfg.notify_reps)
+            && matched(as_f64(prev.reps), as_f64(cur_r));
+        pb.duration.tied = !pb.duration.achieved
+            && notify(PbMetric::Duration, cfg.notify_duration)
+            && matched(as_f64(prev.duration), as_f64(cur_d));
+        pb.distance.tied = !pb.distance.achieved
+            && notify(PbMetric::Distance, cfg.notify_distance)
+            && matched(prev.distance_km, cur_dist);
+        pb.volume.tied = !pb.volume.achieved && cfg.notify_volume && matched(prev.volume, cur_vol);
+        pb.pace.tied = !pb.pace.achieved && cfg.notify_pace && matched(prev.pace_min_per_km, cur_pace);
+
+        if pb.any_pb() || pb.any_tie() {
             Some(pb)
         } else {
             None
@@ -795,8 +3100,10 @@ impl AppService {
     }
 
     /// Edits an existing workout entry.
+    /// For per-side exercises `new_weight` is the weight per side, as in `add_workout`.
     /// # Errors
-    /// Returns `anyhow::Error` if identifier/id invalid or DB update fails.
+    /// Returns `ServiceError` if identifier/id invalid, a new value is above the configured
+    /// validation limits, or DB update fails.
     pub fn edit_workout(&self, params: EditWorkoutParams) -> Result<u64> {
         let new_canonical_name = params
             .new_exercise_identifier
@@ -806,18 +3113,49 @@ impl AppService {
             })
             .transpose()?; 
 
+        self.check_entry_limits(
+            params.new_sets,
+            params.new_reps,
+            params.new_weight,
+            params.new_duration,
+            params.new_distance_arg,
+        )?;
+        if params.new_avg_heart_rate.is_some() || params.new_max_heart_rate.is_some() {
+            let current = db::get_workout_by_id(&*self.connection()?, params.id)?
+                .ok_or(DbError::WorkoutNotFound(params.id))?;
+            check_heart_rates(
+                params.new_avg_heart_rate.unwrap_or(current.avg_heart_rate),
+                params.new_max_heart_rate.unwrap_or(current.max_heart_rate),
+            )?;
+        }
+
         let new_timestamp = params
             .new_date
-            .map(create_timestamp_from_date)
+            .map(|date| create_timestamp_from_date(date, self.config.tz()))
             .transpose()?;
         let new_distance_km = self.convert_distance_input_to_km(params.new_distance_arg);
+        let new_weight = match params.new_weight {
+            Some(_) => {
+                let exercise_name = match &new_canonical_name {
+                    Some(name) => name.clone(),
+                    None => db::get_workout_by_id(&*self.connection()?, params.id)?
+                        .ok_or(DbError::WorkoutNotFound(params.id))?
+                        .exercise_name,
+                };
+                match self.resolve_exercise_identifier(&exercise_name)? {
+                    Some(def) => self.weight_input_for_db(&def, params.new_weight),
+                    None => self.round_weight_input(params.new_weight),
+                }
+            }
+            None => None,
+        };
 
         let workout_updates = Workout {
             _id: None,
             id: params.id,
             sets: params.new_sets,
             reps: params.new_reps,
-            weight: params.new_weight,
+            weight: new_weight,
             duration_minutes: params.new_duration,
             bodyweight: params.new_bodyweight,
             distance: new_distance_km,
@@ -825,121 +3163,1045 @@ impl AppService {
             timestamp: Utc::now(),
             exercise_name: String::new(),
             exercise_type: None, 
+            is_warmup: false, // Applied via `new_is_warmup`
+            is_amrap: false,  // Applied via `new_is_amrap`
+            is_failure: false, // Applied via `new_is_failure`
+            avg_heart_rate: None, // Applied via `new_avg_heart_rate`
+            max_heart_rate: None, // Applied via `new_max_heart_rate`
             deleted: false,
             last_edited: Utc::now()
         };
 
         db::update_workout(
-            &self.conn,
+            &mut *self.connection()?,
             workout_updates,
             new_canonical_name,
             new_timestamp,
+            params.new_is_warmup,
+            params.new_is_amrap,
+            params.new_is_failure,
+            params.new_avg_heart_rate,
+            params.new_max_heart_rate,
         )
         .with_context(|| format!("Failed to update workout ID {}", params.id))
         .map_err(Into::into) 
     }
 
-    /// Deletes workout entries by IDs.
+    /// Intensity zone of a workout from its average heart rate and `effective_max_hr`.
+    /// # Returns
+    /// `None` if the workout has no average heart rate, no max heart rate (or age) is
+    /// configured, or the average is below zone 1.
+    /// # Errors
+    /// - `DbError::WorkoutNotFound` if no active workout has this ID.
+    /// - `ServiceError` wrapping other `DbError` variants.
+    pub fn hr_zone(&self, workout_id: i64) -> Result<Option<HrZone>> {
+        let workout = db::get_workout_by_id(&*self.connection()?, workout_id)?
+            .ok_or(DbError::WorkoutNotFound(workout_id))?;
+        Ok(workout
+            .avg_heart_rate
+            .zip(self.effective_max_hr())
+            .and_then(|(avg, max_hr)| HrZone::from_heart_rate(avg, max_hr)))
+    }
+
+    /// Replaces every occurrence of `find` (case-sensitive) in the notes of active workouts
+    /// matching `filters` (all workouts if `None`), in one transaction. Previous notes are kept
+    /// in the workout history. With `dry_run` nothing is written. Returns the IDs of the
+    /// workouts whose notes change (or would change).
+    /// # Errors
+    /// Returns `ServiceError` if `find` is empty, a filter is invalid, or the DB update fails.
+    pub fn replace_in_notes(
+        &self,
+        find: &str,
+        replace: &str,
+        filters: Option<WorkoutFilters>,
+        dry_run: bool,
+    ) -> Result<Vec<i64>> {
+        if find.is_empty() {
+            bail!("Text to find cannot be empty.");
+        }
+        let workouts = self.list_workouts(&filters.unwrap_or_default())?;
+        let changes: Vec<(i64, String)> = workouts
+            .into_iter()
+            .filter_map(|w| {
+                let notes = w.notes.filter(|n| n.contains(find))?;
+                Some((w.id, notes.replace(find, replace)))
+            })
+            .collect();
+        if !dry_run && !changes.is_empty() {
+            db::update_workout_notes(&mut *self.connection()?, &changes)
+                .context("Failed to replace text in workout notes")?;
+        }
+        Ok(changes.into_iter().map(|(id, _)| id).collect())
+    }
+
+    /// Past versions of a workout, newest first, as `(edited at, version)` pairs: each
+    /// version is the workout as it was before the edit made at that time. Edits that
+    /// changed nothing are not recorded.
+    /// # Errors
+    /// - `DbError::WorkoutNotFound` if no active workout has this ID.
+    /// - `ServiceError` wrapping other `DbError` variants.
+    pub fn get_workout_history(&self, workout_id: i64) -> Result<Vec<(DateTime<Utc>, Workout)>> {
+        let conn = self.connection()?;
+        if db::get_workout_by_id(&conn, workout_id)?.is_none() {
+            bail!(DbError::WorkoutNotFound(workout_id));
+        }
+        db::get_workout_history(&conn, workout_id)
+            .with_context(|| format!("Failed to get history of workout ID {workout_id}"))
+            .map_err(Into::into)
+    }
+
+    /// Deletes workout entries by IDs. With `hard_delete` they are removed for good and the
+    /// deletion can't be undone.
     /// # Errors
-    /// Returns `anyhow::Error` if any ID invalid or DB deletion fails.
+    /// Returns `ServiceError` if any ID invalid or DB deletion fails.
     pub fn delete_workouts(&self, ids: &[i64]) -> Result<Vec<i64>> {
         let mut deleted_ids = Vec::with_capacity(ids.len());
         for &id in ids {
-            db::delete_workout(&self.conn, id).map_err(|db_err| match db_err {
+            db::delete_workout(&*self.connection()?, id).map_err(|db_err| match db_err {
                 DbError::WorkoutNotFound(_) => anyhow::anyhow!(db_err), // Not found or already deleted
                 _ => {
                     anyhow::Error::new(db_err).context(format!("Failed to soft-delete workout ID {id}"))
                 }
-            })?;
-            deleted_ids.push(id);
-        }
-        Ok(deleted_ids)
+            })?;
+            deleted_ids.push(id);
+        }
+        if self.config.hard_delete {
+            db::purge_workouts(&mut *self.connection()?, &deleted_ids)
+                .context("Failed to hard-delete workouts")?;
+        } else if !deleted_ids.is_empty() {
+            db::record_undo(&*self.connection()?, UndoAction::DeleteWorkouts, &deleted_ids)
+                .context("Failed to record undo entry")?;
+        }
+        Ok(deleted_ids)
+    }
+
+    /// Reverses the most recent recorded `add_workout` or `delete_workouts` call: added
+    /// workouts are soft-deleted, deleted ones restored (with their tags) and entries an add
+    /// was merged into put back to their pre-merge version. Workouts already in the target
+    /// state are skipped. Returns the undone entry, or `None` if the
+    /// undo log is empty. At most `UNDO_LOG_DEPTH` operations are kept.
+    /// # Errors
+    /// - `ServiceError` wrapping `DbError` variants.
+    pub fn undo_last(&self) -> Result<Option<UndoEntry>> {
+        let conn = self.connection()?;
+        let Some(entry) = db::pop_undo(&conn).context("Failed to read undo log")? else {
+            return Ok(None);
+        };
+        for &id in &entry.workout_ids {
+            let result = match entry.action {
+                UndoAction::AddWorkout => db::delete_workout(&conn, id),
+                UndoAction::DeleteWorkouts => db::restore_workout(&conn, id),
+                UndoAction::MergeWorkout => db::revert_workout(&conn, id),
+            };
+            match result {
+                Ok(_) | Err(DbError::WorkoutNotFound(_)) => {}
+                Err(e) => {
+                    return Err(anyhow::Error::new(e)
+                        .context(format!("Failed to undo {:?} for workout ID {id}", entry.action))
+                        .into())
+                }
+            }
+        }
+        Ok(Some(entry))
+    }
+
+    /// Soft-deletes every workout matching `filters` in a single transaction and returns the
+    /// deleted IDs. At least one narrowing filter must be set, so an empty filter can't wipe
+    /// the whole log. With `hard_delete` they are removed for good, as in `delete_workouts`.
+    /// # Errors
+    /// - `ServiceError` if no filter is set.
+    /// - `DbError::ExerciseNotFound` if the exercise filter doesn't resolve.
+    /// - `ServiceError` wrapping other `DbError` variants.
+    pub fn delete_workouts_by_filter(&self, filters: &WorkoutFilters) -> Result<Vec<i64>> {
+        if !filters.has_criteria() {
+            bail!("Refusing to delete workouts without a filter. Set at least one filter.");
+        }
+        let ids: Vec<i64> = self.list_workouts(filters)?.iter().map(|w| w.id).collect();
+        if ids.is_empty() {
+            return Ok(ids);
+        }
+        let mut conn = self.connection()?;
+        db::delete_workouts(&mut conn, &ids).context("Failed to delete workouts by filter")?;
+        if self.config.hard_delete {
+            db::purge_workouts(&mut conn, &ids).context("Failed to hard-delete workouts")?;
+        } else {
+            db::record_undo(&conn, UndoAction::DeleteWorkouts, &ids)
+                .context("Failed to record undo entry")?;
+        }
+        Ok(ids)
+    }
+
+    /// Restores a soft-deleted workout by ID, with its tags and metadata.
+    /// # Errors
+    /// - `DbError::WorkoutNotFound` if the ID is not a deleted workout.
+    /// - `ServiceError` wrapping other `DbError` variants.
+    pub fn restore_workout(&self, id: i64) -> Result<u64> {
+        db::restore_workout(&*self.connection()?, id).map_err(|db_err| match db_err {
+            DbError::WorkoutNotFound(_) => anyhow::anyhow!(db_err), // Not found or not deleted
+            _ => anyhow::Error::new(db_err).context(format!("Failed to restore workout ID {id}")),
+        })
+        .map_err(Into::into)
+    }
+
+    /// Lists soft-deleted workouts (most recently deleted first), e.g. for a trash bin view.
+    /// # Errors
+    /// Returns `ServiceError` wrapping DB errors.
+    pub fn list_deleted_workouts(&self, limit: u32) -> Result<Vec<Workout>> {
+        db::list_deleted_workouts(&*self.connection()?, limit)
+            .context("Failed to list deleted workouts")
+            .map_err(Into::into)
+    }
+
+    /// Lists workouts based on filters, newest first (oldest first for a single `date`).
+    /// Entries with the same timestamp are ordered by when they were added.
+    /// # Errors
+    /// Returns `ServiceError` if identifier invalid or DB list fails.
+    pub fn list_workouts(&self, filters: &WorkoutFilters) -> Result<Vec<Workout>> {
+        let canonical_exercise_name = filters
+            .exercise_name
+            .map(|ident| -> Result<String> {
+                self.resolve_identifier_to_canonical_name(ident)? // Resolves active exercise
+                    .ok_or_else(|| {
+                        eprintln!(
+                            "Warning: Exercise identifier '{ident}' not found (or is soft-deleted) for filtering."
+                        );
+                        DbError::ExerciseNotFound(ident.to_string()).into() 
+                    })
+            })
+            .transpose()?; 
+
+        let resolved_filters = WorkoutFilters {
+            exercise_name: canonical_exercise_name.as_deref(),
+            date: filters.date,
+            start_date: filters.start_date,
+            end_date: filters.end_date,
+            exercise_type: filters.exercise_type,
+            muscle: filters.muscle,
+            tag: filters.tag,
+            limit: filters.limit,
+            offset: filters.offset,
+            include_warmups: filters.include_warmups,
+            include_failures: filters.include_failures,
+        };
+
+        db::list_workouts_filtered(&*self.connection()?, &resolved_filters, self.config.tz())
+            .context("Failed to list workouts")
+            .map_err(Into::into) 
+    }
+
+    /// Streams workouts matching `filters` to `writer` as JSON Lines (one serialized
+    /// `Workout` per line), fetching `STREAM_BATCH_SIZE` rows at a time and flushing after
+    /// each batch so large histories are never held in memory at once. `filters.limit`
+    /// caps the total written and `filters.offset` skips leading rows.
+    /// Returns the number of workouts written.
+    /// # Errors
+    /// - `ServiceError` wrapping `DbError` variants.
+    /// - `ServiceError` if serialization or writing fails.
+    pub fn stream_workouts<W: Write>(&self, filters: &WorkoutFilters, mut writer: W) -> Result<usize> {
+        let mut written = 0usize;
+        let mut offset = filters.offset.unwrap_or(0);
+        loop {
+            let remaining = filters
+                .limit
+                .map(|limit| limit.saturating_sub(u32::try_from(written).unwrap_or(u32::MAX)));
+            let batch_size = remaining.map_or(STREAM_BATCH_SIZE, |r| r.min(STREAM_BATCH_SIZE));
+            if batch_size == 0 {
+                break;
+            }
+            let batch = self.list_workouts(&WorkoutFilters {
+                limit: Some(batch_size),
+                offset: Some(offset),
+                ..*filters
+            })?;
+            for workout in &batch {
+                serde_json::to_writer(&mut writer, workout)
+                    .context("Failed to serialize workout")?;
+                writer.write_all(b"\n").context("Failed to write workout")?;
+            }
+            writer.flush().context("Failed to flush workout stream")?;
+            written += batch.len();
+            // A date filter ignores limit/offset, so the first batch already has every row
+            if filters.date.is_some() || batch.len() < batch_size as usize {
+                break;
+            }
+            offset += batch_size;
+        }
+        Ok(written)
+    }
+
+    /// Writes workouts matching `filters` to `writer` as CSV (with a header), in the dialect
+    /// given by `options`. Weights and distances are stored values (kg/km).
+    /// Returns the number of workouts written.
+    /// # Errors
+    /// - `ServiceError` if the options are invalid or writing fails.
+    /// - `ServiceError` wrapping `DbError` variants.
+    pub fn export_workouts_to_csv<W: Write>(
+        &self,
+        filters: &WorkoutFilters,
+        mut writer: W,
+        options: &CsvOptions,
+    ) -> Result<usize> {
+        validate_csv_options(options)?;
+        let workouts = self.list_workouts(filters)?;
+        write_csv_row(&mut writer, &WORKOUT_CSV_HEADER, options)?;
+        let optional = |value: Option<String>| value.unwrap_or_default();
+        for workout in &workouts {
+            let row = [
+                workout.id.to_string(),
+                self.format_csv_timestamp(workout.timestamp, options),
+                workout.exercise_name.clone(),
+                optional(workout.sets.map(|v| v.to_string())),
+                optional(workout.reps.map(|v| v.to_string())),
+                optional(workout.weight.map(|v| v.to_string())),
+                optional(workout.duration_minutes.map(|v| v.to_string())),
+                optional(workout.distance.map(|v| v.to_string())),
+                optional(workout.bodyweight.map(|v| v.to_string())),
+                workout.is_warmup.to_string(),
+                workout.is_amrap.to_string(),
+                workout.is_failure.to_string(),
+                optional(workout.notes.clone()),
+            ];
+            write_csv_row(&mut writer, &row, options)?;
+        }
+        writer.flush().context("Failed to flush workout CSV")?;
+        Ok(workouts.len())
+    }
+
+    /// Writes the training days of the next `weeks` weeks (starting today) to `writer` as an
+    /// iCalendar (RFC 5545) file with one all-day event per `scheduled_days` day, or per day
+    /// if no schedule is set. Event UIDs are derived from the date, so calendars re-importing
+    /// the file update events instead of duplicating them.
+    /// Returns the number of events written.
+    /// # Errors
+    /// Returns `ServiceError` if writing fails.
+    pub fn export_schedule_ical<W: Write>(&self, weeks: u32, mut writer: W) -> Result<usize> {
+        let today = self.local_date(Utc::now());
+        let dtstamp = Utc::now().format("%Y%m%dT%H%M%SZ").to_string();
+        let scheduled = |date: &NaiveDate| {
+            self.config.scheduled_days.is_empty()
+                || self.config.scheduled_days.contains(&date.weekday())
+        };
+        let days: Vec<NaiveDate> = today
+            .iter_days()
+            .take(weeks as usize * 7)
+            .filter(scheduled)
+            .collect();
+
+        let mut lines = vec![
+            "BEGIN:VCALENDAR".to_string(),
+            "VERSION:2.0".to_string(),
+            "PRODID:-//TaskAthlete//Training Schedule//EN".to_string(),
+            "CALSCALE:GREGORIAN".to_string(),
+        ];
+        for date in &days {
+            lines.extend([
+                "BEGIN:VEVENT".to_string(),
+                format!("UID:{}@task-athlete", date.format("%Y%m%d")),
+                format!("DTSTAMP:{dtstamp}"),
+                format!("DTSTART;VALUE=DATE:{}", date.format("%Y%m%d")),
+                format!("DTEND;VALUE=DATE:{}", (*date + Duration::days(1)).format("%Y%m%d")),
+                "SUMMARY:Workout".to_string(),
+                "TRANSP:TRANSPARENT".to_string(),
+                "END:VEVENT".to_string(),
+            ]);
+        }
+        lines.push("END:VCALENDAR".to_string());
+
+        for line in &lines {
+            write!(writer, "{line}\r\n").context("Failed to write calendar")?;
+        }
+        writer.flush().context("Failed to flush calendar")?;
+        Ok(days.len())
+    }
+
+    /// Lists workouts across every exercise that targets `muscle` (substring match on the
+    /// exercise's muscle list), optionally limited to an inclusive `(start, end)` date range.
+    /// # Errors
+    /// - `ServiceError` if the muscle is empty or the range is reversed.
+    /// - `ServiceError` wrapping `DbError` variants.
+    pub fn list_workouts_by_muscle(
+        &self,
+        muscle: &str,
+        date_range: Option<(NaiveDate, NaiveDate)>,
+    ) -> Result<Vec<Workout>> {
+        let muscle = muscle.trim();
+        if muscle.is_empty() {
+            bail!("Muscle cannot be empty.");
+        }
+        if let Some((start, end)) = date_range {
+            if start > end {
+                bail!("Start date {start} is after end date {end}.");
+            }
+        }
+        self.list_workouts(&WorkoutFilters {
+            muscle: Some(muscle),
+            start_date: date_range.map(|(start, _)| start),
+            end_date: date_range.map(|(_, end)| end),
+            ..Default::default()
+        })
+    }
+
+    /// Tags a workout. Tags are trimmed and lowercased; duplicates are ignored.
+    /// # Returns
+    /// The number of tags newly added.
+    /// # Errors
+    /// Returns `ServiceError` if a tag is empty, the workout doesn't exist, or the DB insert fails.
+    pub fn add_workout_tags(&self, workout_id: i64, tags: &[&str]) -> Result<usize> {
+        let normalized = tags
+            .iter()
+            .map(|tag| {
+                let tag = normalize_tag(tag);
+                if tag.is_empty() {
+                    bail!("Tag cannot be empty.");
+                }
+                Ok(tag)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        db::add_workout_tags(&*self.connection()?, workout_id, &normalized).map_err(|db_err| {
+            match db_err {
+                DbError::WorkoutNotFound(_) => anyhow::anyhow!(db_err),
+                _ => anyhow::Error::new(db_err)
+                    .context(format!("Failed to tag workout ID {workout_id}")),
+            }
+        })
+        .map_err(Into::into)
+    }
+
+    /// Removes a tag from a workout.
+    /// # Errors
+    /// Returns `ServiceError` if the workout doesn't have the tag or the DB delete fails.
+    pub fn remove_workout_tag(&self, workout_id: i64, tag: &str) -> Result<u64> {
+        db::remove_workout_tag(&*self.connection()?, workout_id, &normalize_tag(tag)).map_err(
+            |db_err| match db_err {
+                DbError::TagNotFound(..) => anyhow::anyhow!(db_err),
+                _ => anyhow::Error::new(db_err)
+                    .context(format!("Failed to remove tag from workout ID {workout_id}")),
+            },
+        )
+        .map_err(Into::into)
+    }
+
+    /// Lists the tags of a single workout.
+    /// # Errors
+    /// Returns `ServiceError` wrapping DB errors.
+    pub fn get_workout_tags(&self, workout_id: i64) -> Result<Vec<String>> {
+        db::get_workout_tags(&*self.connection()?, workout_id)
+            .with_context(|| format!("Failed to get tags for workout ID {workout_id}"))
+            .map_err(Into::into)
+    }
+
+    /// Lists all distinct tags in use by active workouts.
+    /// # Errors
+    /// Returns `ServiceError` wrapping DB errors.
+    pub fn list_tags(&self) -> Result<Vec<String>> {
+        db::list_tags(&*self.connection()?).context("Failed to list tags")
+            .map_err(Into::into)
+    }
+
+    /// Sets a free-form metadata value (e.g. tempo, band tension) on a workout, replacing any
+    /// previous value for the key. Keys are trimmed and lowercased and may be namespaced with
+    /// dots (e.g. `531.week`); values are stored as text.
+    /// # Errors
+    /// Returns `ServiceError` if the key is invalid, the workout doesn't exist, or the DB
+    /// insert fails.
+    pub fn set_workout_metadata(&self, workout_id: i64, key: &str, value: &str) -> Result<()> {
+        let key = normalize_metadata_key(key)?;
+        db::set_workout_metadata(&*self.connection()?, workout_id, &key, value).map_err(|db_err| {
+            match db_err {
+                DbError::WorkoutNotFound(_) => anyhow::anyhow!(db_err),
+                _ => anyhow::Error::new(db_err)
+                    .context(format!("Failed to set metadata on workout ID {workout_id}")),
+            }
+        })
+        .map_err(Into::into)
+    }
+
+    /// Returns the metadata of a workout keyed by name. Empty if none has been set.
+    /// # Errors
+    /// Returns `ServiceError` wrapping DB errors.
+    pub fn get_workout_metadata(&self, workout_id: i64) -> Result<BTreeMap<String, String>> {
+        let pairs = db::get_workout_metadata(&*self.connection()?, workout_id)
+            .with_context(|| format!("Failed to get metadata for workout ID {workout_id}"))?;
+        Ok(pairs.into_iter().collect())
+    }
+
+    /// Appends interval splits to a cardio workout.
+    /// # Arguments
+    /// * `splits` - `(distance, duration_seconds)` pairs; distance is in the configured units.
+    /// # Errors
+    /// Returns `ServiceError` if the workout doesn't exist or isn't cardio,
+    /// a split has a non-positive distance or duration, or the DB insert fails.
+    pub fn add_splits(&self, workout_id: i64, splits: &[(f64, i64)]) -> Result<usize> {
+        if splits.iter().any(|&(distance, duration)| distance <= 0.0 || duration <= 0) {
+            bail!("Split distance and duration must be positive.");
+        }
+        let workout = db::get_workout_by_id(&*self.connection()?, workout_id)
+            .context("Failed to fetch workout")?
+            .ok_or(DbError::WorkoutNotFound(workout_id))?;
+        if workout.exercise_type != Some(ExerciseType::Cardio) {
+            bail!("Splits can only be recorded for cardio workouts.");
+        }
+        let splits_km: Vec<(f64, i64)> = splits
+            .iter()
+            .filter_map(|&(distance, duration)| {
+                self.convert_distance_input_to_km(Some(distance)).map(|km| (km, duration))
+            })
+            .collect();
+        db::add_workout_splits(&mut *self.connection()?, workout_id, &splits_km).map_err(|db_err| {
+            match db_err {
+                DbError::WorkoutNotFound(_) => anyhow::anyhow!(db_err),
+                _ => anyhow::Error::new(db_err)
+                    .context(format!("Failed to add splits to workout ID {workout_id}")),
+            }
+        })
+        .map_err(Into::into)
+    }
+
+    /// Lists the splits of a workout in order (distances in km).
+    /// # Errors
+    /// Returns `ServiceError` wrapping DB errors.
+    pub fn get_splits(&self, workout_id: i64) -> Result<Vec<WorkoutSplit>> {
+        db::get_workout_splits(&*self.connection()?, workout_id)
+            .with_context(|| format!("Failed to get splits for workout ID {workout_id}"))
+            .map_err(Into::into)
+    }
+
+    /// Finds the split with the best pace across all workouts of an exercise.
+    /// # Errors
+    /// Returns `ServiceError` if the identifier is invalid or the DB query fails.
+    pub fn fastest_split(&self, identifier: &str) -> Result<Option<WorkoutSplit>> {
+        let canonical_name = self
+            .resolve_identifier_to_canonical_name(identifier)?
+            .ok_or_else(|| DbError::ExerciseNotFound(identifier.to_string()))?;
+        db::get_fastest_split_for_exercise(&*self.connection()?, &canonical_name)
+            .with_context(|| format!("Failed to find fastest split for '{canonical_name}'"))
+            .map_err(Into::into)
+    }
+
+    /// Lists workouts for the Nth most recent day an exercise was performed.
+    /// # Arguments
+    /// * `n` - Must be > 0.
+    /// # Errors
+    /// Returns `ServiceError` if identifier invalid, n=0, or DB list fails.
+    pub fn list_workouts_for_exercise_on_nth_last_day(
+        &self,
+        identifier: &str,
+        n: u32,
+    ) -> Result<Vec<Workout>> {
+        let canonical_name = self
+            .resolve_identifier_to_canonical_name(identifier)? // Resolves active exercise
+            .ok_or_else(|| DbError::ExerciseNotFound(identifier.to_string()))?;
+        db::list_workouts_for_exercise_on_nth_last_day(
+            &*self.connection()?,
+            &canonical_name,
+            n,
+            self.config.tz(),
+        )
+            .with_context(|| format!("Failed nth day lookup for '{canonical_name}' (n={n})"))
+            .map_err(Into::into)
+    }
+
+    /// Retrieves a list of unique dates ("YYYY-MM-DD") within a given month and year
+    /// that have at least one workout recorded. Delegates to db module.
+    /// # Errors
+    /// Returns `ServiceError` if the database query fails or month is invalid.
+    pub fn get_workout_dates_for_month(&self, year: i32, month: u32) -> Result<Vec<String>> {
+        if !(1..=12).contains(&month) {
+            bail!("Invalid month: {}. Month must be between 1 and 12.", month);
+        }
+
+        db::get_workout_dates_for_month_db(&*self.connection()?, year, month, self.config.tz())
+            .with_context(|| {
+                format!("Failed to get workout dates for {year}-{month:02} from database")
+            })
+            .map_err(Into::into) 
+    }
+
+    /// Number of local calendar days since the most recent workout of any exercise (0 if
+    /// it was today), or `None` if nothing has been logged.
+    /// # Errors
+    /// - `ServiceError` wrapping `DbError` variants.
+    pub fn days_since_last_workout(&self) -> Result<Option<i64>> {
+        let last = db::get_last_workout_time(&*self.connection()?)
+            .context("Failed to query the last workout")?;
+        let today = self.local_date(Utc::now());
+        Ok(last.map(|ts| (today - self.local_date(ts)).num_days()))
+    }
+
+    /// Number of workout entries in the current week and each of the `weeks_back` weeks
+    /// before it, as `(week start, count)` oldest first. Weeks start on the configured
+    /// `week_start`; weeks without workouts have a count of 0.
+    /// # Errors
+    /// - `ServiceError` wrapping `DbError` variants.
+    pub fn weekly_counts(&self, weeks_back: u32) -> Result<Vec<(NaiveDate, usize)>> {
+        let today = self.local_date(Utc::now());
+        let days_into_week = (7 + today.weekday().num_days_from_monday()
+            - self.config.week_start.num_days_from_monday())
+            % 7;
+        let current_week = today - Duration::days(i64::from(days_into_week));
+        let first_week = current_week - Duration::weeks(i64::from(weeks_back));
+        let counts: HashMap<NaiveDate, i64> = db::count_workouts_by_week(
+            &*self.connection()?,
+            first_week,
+            self.config.week_start,
+            self.config.tz(),
+        )
+        .context("Failed to count workouts per week")?
+        .into_iter()
+        .collect();
+        Ok((0..=weeks_back)
+            .map(|i| {
+                let week = first_week + Duration::weeks(i64::from(i));
+                let count = counts.get(&week).copied().unwrap_or(0);
+                (week, usize::try_from(count).unwrap_or(0))
+            })
+            .collect())
+    }
+
+    /// True if any workout was logged within the last `threshold_days` days. Never having
+    /// logged anything counts as inactive.
+    /// # Errors
+    /// - `ServiceError` wrapping `DbError` variants.
+    pub fn is_currently_active(&self, threshold_days: u32) -> Result<bool> {
+        Ok(self
+            .days_since_last_workout()?
+            .is_some_and(|days| days <= i64::from(threshold_days)))
+    }
+
+    /// Exercises whose most recent workout is more than `days` days ago, plus exercises that
+    /// have never been performed. Never-performed exercises come first, then oldest first.
+    /// # Errors
+    /// - `ServiceError` wrapping `DbError` variants.
+    pub fn get_stale_exercises(&self, days: u32) -> Result<Vec<StaleExercise>> {
+        let cutoff = self.local_date(Utc::now()) - Duration::days(i64::from(days));
+        let last_times = db::list_exercise_last_workout_times(&*self.connection()?)
+            .context("Failed to query last workout dates")?;
+        let mut stale: Vec<StaleExercise> = last_times
+            .into_iter()
+            .map(|(name, last)| StaleExercise {
+                name,
+                last_date: last.map(|ts| self.local_date(ts)),
+            })
+            .filter(|ex| ex.last_date.is_none_or(|date| date < cutoff))
+            .collect();
+        stale.sort_by_key(|ex| ex.last_date);
+        Ok(stale)
+    }
+
+    /// Estimates how recovered each muscle is, as a planning aid. A muscle's last training
+    /// time is the latest workout of any exercise listing it; it is considered recovering
+    /// for its window (`muscle_recovery_hours`, else `recovery_hours`) and recovered after.
+    /// Muscles never trained are fully recovered. Covers every muscle of the active
+    /// exercises, least recovered first, then by name.
+    /// # Errors
+    /// - `ServiceError` wrapping `DbError` variants.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn muscle_recovery_status(&self) -> Result<Vec<MuscleRecovery>> {
+        let last_times: HashMap<String, Option<DateTime<Utc>>> =
+            db::list_exercise_last_workout_times(&*self.connection()?)
+                .context("Failed to query last workout dates")?
+                .into_iter()
+                .collect();
+        let mut last_by_muscle: BTreeMap<String, Option<DateTime<Utc>>> = BTreeMap::new();
+        for def in self.list_exercises(&ExerciseFilters::default())? {
+            let last = last_times.get(&def.name).copied().flatten();
+            for muscle in parse_muscles(def.muscles.as_deref()) {
+                let entry = last_by_muscle.entry(muscle).or_default();
+                *entry = (*entry).max(last);
+            }
+        }
+
+        let now = Utc::now();
+        let mut status: Vec<MuscleRecovery> = last_by_muscle
+            .into_iter()
+            .map(|(muscle, last_trained)| {
+                let recovery_hours = self
+                    .config
+                    .muscle_recovery_hours
+                    .get(&muscle)
+                    .copied()
+                    .unwrap_or(self.config.recovery_hours)
+                    .max(1);
+                let recovered_pct = last_trained.map_or(100.0, |last| {
+                    let hours = (now - last).num_minutes() as f64 / 60.0;
+                    (hours / f64::from(recovery_hours) * 100.0).clamp(0.0, 100.0)
+                });
+                MuscleRecovery {
+                    muscle,
+                    last_trained,
+                    recovery_hours,
+                    recovered_pct,
+                    fully_recovered: recovered_pct >= 100.0,
+                }
+            })
+            .collect();
+        status.sort_by(|a, b| a.recovered_pct.total_cmp(&b.recovered_pct));
+        Ok(status)
+    }
+
+    /// Suggests exercises to train today.
+    ///
+    /// Candidates are the exercises from `get_stale_exercises(SUGGESTION_STALE_DAYS)`. They are
+    /// ranked by the least-trained of their muscles over the last `SUGGESTION_LOOKBACK_DAYS`
+    /// (via `muscle_volume_breakdown`), then by staleness, then by name. A first pass picks at
+    /// most one exercise per muscle group; remaining slots are filled in rank order, up to
+    /// `SUGGESTION_LIMIT`. Returns an empty list if `scheduled_days` is set and today is not
+    /// one of them.
+    /// # Errors
+    /// - `ServiceError` wrapping `DbError` variants.
+    pub fn suggest_next_workout(&self) -> Result<Vec<ExerciseSuggestion>> {
+        let today = self.local_date(Utc::now());
+        if !self.config.scheduled_days.is_empty()
+            && !self.config.scheduled_days.contains(&today.weekday())
+        {
+            return Ok(Vec::new());
+        }
+
+        let stale = self.get_stale_exercises(SUGGESTION_STALE_DAYS)?;
+        if stale.is_empty() {
+            return Ok(Vec::new());
+        }
+        let recent_volume = self.muscle_volume_breakdown(
+            Some(today - Duration::days(i64::from(SUGGESTION_LOOKBACK_DAYS))),
+            Some(today),
+        )?;
+        let muscles_by_exercise: HashMap<String, Vec<String>> = self
+            .list_exercises(&ExerciseFilters::default())?
+            .into_iter()
+            .map(|def| (def.name, parse_muscles(def.muscles.as_deref())))
+            .collect();
+
+        // (name, last_date, least-trained muscle, its recent volume)
+        let mut candidates: Vec<(String, Option<NaiveDate>, Option<String>, f64)> = stale
+            .into_iter()
+            .map(|ex| {
+                let least_trained = muscles_by_exercise
+                    .get(&ex.name)
+                    .into_iter()
+                    .flatten()
+                    .map(|m| (m.clone(), recent_volume.get(m).copied().unwrap_or(0.0)))
+                    .min_by(|a, b| a.1.total_cmp(&b.1).then_with(|| a.0.cmp(&b.0)));
+                let (muscle, volume) = least_trained.map_or((None, 0.0), |(m, v)| (Some(m), v));
+                (ex.name, ex.last_date, muscle, volume)
+            })
+            .collect();
+        candidates.sort_by(|a, b| {
+            a.3.total_cmp(&b.3)
+                .then_with(|| a.1.cmp(&b.1))
+                .then_with(|| a.0.cmp(&b.0))
+        });
+
+        let mut picked: Vec<usize> = Vec::new();
+        let mut covered: Vec<&str> = Vec::new();
+        for (i, (_, _, muscle, _)) in candidates.iter().enumerate() {
+            if picked.len() >= SUGGESTION_LIMIT {
+                break;
+            }
+            match muscle.as_deref() {
+                Some(m) if covered.contains(&m) => {}
+                Some(m) => {
+                    covered.push(m);
+                    picked.push(i);
+                }
+                None => picked.push(i),
+            }
+        }
+        for i in 0..candidates.len() {
+            if picked.len() >= SUGGESTION_LIMIT {
+                break;
+            }
+            if !picked.contains(&i) {
+                picked.push(i);
+            }
+        }
+
+        Ok(picked
+            .into_iter()
+            .map(|i| {
+                let (name, last_date, muscle, volume) = &candidates[i];
+                let mut reason = match last_date {
+                    Some(date) => format!("Last done {} days ago", (today - *date).num_days()),
+                    None => "Never performed".to_string(),
+                };
+                if let Some(muscle) = muscle {
+                    if *volume <= 0.0 {
+                        reason.push_str(&format!(
+                            "; {muscle} not trained in the last {SUGGESTION_LOOKBACK_DAYS} days"
+                        ));
+                    }
+                }
+                ExerciseSuggestion {
+                    name: name.clone(),
+                    reason,
+                    last_performed: *last_date,
+                }
+            })
+            .collect())
     }
 
-    /// Lists workouts based on filters.
+    /// Suggests sets, reps and weight for the next session of an exercise, following the
+    /// configured `progression` scheme from the top working set (heaviest, then most reps)
+    /// of the last session. If that set was marked as a failure the same target is
+    /// suggested again. New weights are rounded to `weight_increment` when set.
+    ///
+    /// Returns `None` if the exercise doesn't log weight and reps, or has no working sets
+    /// with reps yet.
     /// # Errors
-    /// Returns `anyhow::Error` if identifier invalid or DB list fails.
-    pub fn list_workouts(&self, filters: &WorkoutFilters) -> Result<Vec<Workout>> {
-        let canonical_exercise_name = filters
-            .exercise_name
-            .map(|ident| -> Result<String> {
-                self.resolve_identifier_to_canonical_name(ident)? // Resolves active exercise
-                    .ok_or_else(|| {
-                        eprintln!(
-                            "Warning: Exercise identifier '{ident}' not found (or is soft-deleted) for filtering."
-                        );
-                        DbError::ExerciseNotFound(ident.to_string()).into() 
-                    })
+    /// - `ServiceError` if the exercise is not found.
+    /// - `ServiceError` wrapping `DbError` variants.
+    pub fn suggest_progression(&self, identifier: &str) -> Result<Option<ProgressionSuggestion>> {
+        let exercise_def = self
+            .resolve_exercise_identifier(identifier)?
+            .ok_or_else(|| DbError::ExerciseNotFound(identifier.to_string()))?;
+        if !exercise_def.log_weight || !exercise_def.log_reps {
+            return Ok(None);
+        }
+        let last_session = self.list_workouts_for_exercise_on_nth_last_day(&exercise_def.name, 1)?;
+        let Some(top) = last_session
+            .iter()
+            .filter(|w| !w.is_warmup && w.reps.is_some_and(|r| r > 0))
+            .max_by(|a, b| {
+                a.weight
+                    .unwrap_or(0.0)
+                    .total_cmp(&b.weight.unwrap_or(0.0))
+                    .then(a.reps.cmp(&b.reps))
             })
-            .transpose()?; 
-
-        let resolved_filters = WorkoutFilters {
-            exercise_name: canonical_exercise_name.as_deref(),
-            date: filters.date,
-            exercise_type: filters.exercise_type,
-            muscle: filters.muscle,
-            limit: filters.limit,
+        else {
+            return Ok(None);
         };
 
-        db::list_workouts_filtered(&self.conn, &resolved_filters)
-            .context("Failed to list workouts")
-            .map_err(Into::into) 
+        let sets = top.sets.unwrap_or(1);
+        let reps = top.reps.unwrap_or(1);
+        let weight = top.weight.unwrap_or(0.0);
+        let (reps, weight) = if top.is_failure {
+            (reps, weight)
+        } else {
+            match self.config.progression {
+                ProgressionScheme::Linear { increment } => (reps, weight + increment),
+                ProgressionScheme::DoubleProgression { min_reps, max_reps, increment } => {
+                    if reps >= max_reps {
+                        (min_reps, weight + increment)
+                    } else {
+                        ((reps + 1).max(min_reps), weight)
+                    }
+                }
+            }
+        };
+        let weight = match self.config.weight_increment {
+            Some(inc) => round_to_increment(weight, inc),
+            None => weight,
+        };
+        Ok(Some(ProgressionSuggestion {
+            sets,
+            reps,
+            weight: exercise_def.entered_weight(weight),
+            based_on: self.local_date(top.timestamp),
+        }))
     }
 
-    /// Lists workouts for the Nth most recent day an exercise was performed.
-    /// # Arguments
-    /// * `n` - Must be > 0.
+    /// Calculates and returns statistics for an exercise.
     /// # Errors
-    /// Returns `anyhow::Error` if identifier invalid, n=0, or DB list fails.
-    pub fn list_workouts_for_exercise_on_nth_last_day(
-        &self,
-        identifier: &str,
-        n: u32,
-    ) -> Result<Vec<Workout>> {
+    /// Returns `ServiceError` if identifier invalid or DB query fails.
+    /// # Panics
+    /// See `calculate_streaks` potential panic.
+    pub fn get_exercise_stats(&self, identifier: &str) -> Result<ExerciseStats> {
         let canonical_name = self
             .resolve_identifier_to_canonical_name(identifier)? // Resolves active exercise
             .ok_or_else(|| DbError::ExerciseNotFound(identifier.to_string()))?;
-        db::list_workouts_for_exercise_on_nth_last_day(&self.conn, &canonical_name, n)
-            .with_context(|| format!("Failed nth day lookup for '{canonical_name}' (n={n})"))
-            .map_err(Into::into)
+        self.stats_for_exercises(canonical_name.clone(), &[canonical_name])
     }
 
-    /// Retrieves a list of unique dates ("YYYY-MM-DD") within a given month and year
-    /// that have at least one workout recorded. Delegates to db module.
+    /// Like `get_exercise_stats`, but counts the workouts and PBs of every variation of the
+    /// exercise (see `get_exercise_variations`) as its own.
     /// # Errors
-    /// Returns `anyhow::Error` if the database query fails or month is invalid.
-    pub fn get_workout_dates_for_month(&self, year: i32, month: u32) -> Result<Vec<String>> {
-        if !(1..=12).contains(&month) {
-            bail!("Invalid month: {}. Month must be between 1 and 12.", month);
+    /// Same as `get_exercise_stats`; there must be workout data for at least one of them.
+    pub fn get_stats_including_variations(&self, identifier: &str) -> Result<ExerciseStats> {
+        let canonical_name = self
+            .resolve_identifier_to_canonical_name(identifier)?
+            .ok_or_else(|| DbError::ExerciseNotFound(identifier.to_string()))?;
+        let mut names = vec![canonical_name.clone()];
+        names.extend(self.get_exercise_variations(&canonical_name)?);
+        self.stats_for_exercises(canonical_name, &names)
+    }
+
+    /// Same as `get_exercise_stats` for several exercises at once, keyed by canonical name,
+    /// for overviews. Workout dates and PBs of all of them are fetched with one query each.
+    /// Identifiers that don't resolve and exercises without workouts are returned in
+    /// `skipped` instead of failing the call.
+    /// # Errors
+    /// Returns `ServiceError` if a DB query fails.
+    pub fn get_exercise_stats_batch(&self, identifiers: &[&str]) -> Result<ExerciseStatsBatch> {
+        let mut names: Vec<String> = Vec::new();
+        let mut skipped = Vec::new();
+        for &identifier in identifiers {
+            match self.resolve_identifier_to_canonical_name(identifier)? {
+                Some(name) if !names.contains(&name) => names.push(name),
+                Some(_) => {}
+                None => skipped.push(identifier.to_string()),
+            }
         }
 
-        db::get_workout_dates_for_month_db(&self.conn, year, month)
-            .with_context(|| {
-                format!("Failed to get workout dates for {year}-{month:02} from database")
+        let conn = self.connection()?;
+        let mut timestamps = db::get_workout_timestamps_for_exercises(&conn, &names)
+            .context("Failed history retrieval")?;
+        let mut personal_bests = db::get_personal_bests_for_exercises(&conn, &names)
+            .context("Failed to get personal bests")?;
+        drop(conn);
+
+        let mut stats = HashMap::new();
+        for name in names {
+            match timestamps.remove(&name) {
+                Some(ts) => {
+                    let bests = personal_bests.remove(&name).unwrap_or_default();
+                    stats.insert(name.clone(), self.build_exercise_stats(name, &ts, bests));
+                }
+                None => skipped.push(name),
+            }
+        }
+        Ok(ExerciseStatsBatch { stats, skipped })
+    }
+
+    /// Comparison table of exercises, one row each with max weight, E1RM, best volume and
+    /// last performed time, fetched with a single query. `None` reports every exercise (by
+    /// name); otherwise rows follow `identifiers`, with duplicates dropped. Exercises without
+    /// workouts get a row with empty metrics. Unknown identifiers are returned in `skipped`,
+    /// as in `get_exercise_stats_batch`.
+    /// # Errors
+    /// Returns `ServiceError` if a DB query fails.
+    pub fn build_exercise_report(&self, identifiers: Option<&[&str]>) -> Result<ExerciseReport> {
+        let mut skipped = Vec::new();
+        let definitions = match identifiers {
+            None => self.list_exercises(&ExerciseFilters::default())?,
+            Some(identifiers) => {
+                let mut definitions: Vec<ExerciseDefinition> = Vec::new();
+                for &identifier in identifiers {
+                    match self.get_exercise_by_identifier_service(identifier)? {
+                        Some(def) if !definitions.iter().any(|d| d.name == def.name) => {
+                            definitions.push(def);
+                        }
+                        Some(_) => {}
+                        None => skipped.push(identifier.to_string()),
+                    }
+                }
+                definitions
+            }
+        };
+
+        let names: Vec<String> = definitions.iter().map(|d| d.name.clone()).collect();
+        let mut rows = db::get_exercise_report_rows(&*self.connection()?, &names)
+            .context("Failed to build exercise report")?;
+        let rows = definitions
+            .into_iter()
+            .map(|def| {
+                rows.remove(&def.name).unwrap_or(ExerciseReportRow {
+                    exercise_name: def.name,
+                    exercise_type: def.type_,
+                    max_weight: None,
+                    e1rm: None,
+                    best_volume: None,
+                    last_performed: None,
+                })
             })
-            .map_err(Into::into) 
+            .collect();
+        Ok(ExerciseReport { rows, skipped })
     }
 
-    /// Calculates and returns statistics for an exercise.
+    /// Writes report rows from `build_exercise_report` to `writer` as CSV rows of
+    /// `exercise,type,max_weight,e1rm,best_volume,last_performed` (with a header), in the
+    /// dialect given by `options`. Empty metrics are left blank.
+    /// Returns the number of rows written.
     /// # Errors
-    /// Returns `anyhow::Error` if identifier invalid or DB query fails.
-    /// # Panics
-    /// See `calculate_streaks` potential panic.
+    /// Returns `ServiceError` if the options are invalid or writing fails.
+    pub fn export_exercise_report_to_csv<W: Write>(
+        &self,
+        rows: &[ExerciseReportRow],
+        mut writer: W,
+        options: &CsvOptions,
+    ) -> Result<usize> {
+        validate_csv_options(options)?;
+        write_csv_row(
+            &mut writer,
+            &["exercise", "type", "max_weight", "e1rm", "best_volume", "last_performed"],
+            options,
+        )?;
+        for row in rows {
+            let fields = [
+                row.exercise_name.clone(),
+                row.exercise_type.to_string(),
+                row.max_weight.map(|v| v.to_string()).unwrap_or_default(),
+                row.e1rm.map(|v| self.round_e1rm(v).to_string()).unwrap_or_default(),
+                row.best_volume.map(|v| v.to_string()).unwrap_or_default(),
+                row.last_performed
+                    .map(|ts| self.format_csv_timestamp(ts, options))
+                    .unwrap_or_default(),
+            ];
+            write_csv_row(&mut writer, &fields, options)?;
+        }
+        writer.flush().context("Failed to flush exercise report CSV")?;
+        Ok(rows.len())
+    }
+
+    /// Stats over the combined workouts of `names`, reported under `canonical_name`.
+    fn stats_for_exercises(&self, canonical_name: String, names: &[String]) -> Result<ExerciseStats> {
+        let conn = self.connection()?;
+        let mut timestamps = Vec::new();
+        for name in names {
+            timestamps.extend(
+                db::get_workout_timestamps_for_exercise(&conn, name)
+                    .context(format!("Failed history retrieval for '{name}'"))?,
+            );
+        }
+        timestamps.sort();
+        if timestamps.is_empty() {
+            bail!(DbError::NoWorkoutDataFound(canonical_name));
+        }
+
+        let mut personal_bests = PersonalBests::default();
+        for name in names {
+            let best = |current: Option<f64>, value: Option<f64>, better: fn(f64, f64) -> f64| {
+                match (current, value) {
+                    (Some(a), Some(b)) => Some(better(a, b)),
+                    (a, b) => a.or(b),
+                }
+            };
+            personal_bests = PersonalBests {
+                max_weight: best(
+                    personal_bests.max_weight,
+                    db::get_max_effective_weight_for_exercise(&conn, name)?,
+                    f64::max,
+                ),
+                max_reps: personal_bests.max_reps.max(db::get_max_reps_for_exercise(&conn, name)?),
+                max_duration_minutes: personal_bests
+                    .max_duration_minutes
+                    .max(db::get_max_duration_for_exercise(&conn, name)?),
+                max_distance_km: best(
+                    personal_bests.max_distance_km,
+                    db::get_max_distance_for_exercise(&conn, name)?,
+                    f64::max,
+                ),
+                best_pace_min_per_km: best(
+                    personal_bests.best_pace_min_per_km,
+                    db::get_min_pace_for_exercise(&conn, name)?,
+                    f64::min,
+                ),
+            };
+        }
+
+        Ok(self.build_exercise_stats(canonical_name, &timestamps, personal_bests))
+    }
+
+    /// Stats of an exercise from its (sorted, non-empty) workout timestamps and its PBs.
     #[allow(
         clippy::cast_precision_loss,
         clippy::cast_possible_wrap,
         clippy::cast_sign_loss
     )]
-    pub fn get_exercise_stats(&self, identifier: &str) -> Result<ExerciseStats> {
-        let canonical_name = self
-            .resolve_identifier_to_canonical_name(identifier)? // Resolves active exercise
-            .ok_or_else(|| DbError::ExerciseNotFound(identifier.to_string()))?;
-        let timestamps = db::get_workout_timestamps_for_exercise(&self.conn, &canonical_name)
-            .context(format!("Failed history retrieval for '{canonical_name}'"))?;
-        if timestamps.is_empty() {
-            bail!(DbError::NoWorkoutDataFound(canonical_name));
-        }
-
+    fn build_exercise_stats(
+        &self,
+        canonical_name: String,
+        timestamps: &[DateTime<Utc>],
+        personal_bests: PersonalBests,
+    ) -> ExerciseStats {
         let first_ts = timestamps.first().expect("Timestamps non-empty");
         let last_ts = timestamps.last().expect("Timestamps non-empty");
 
@@ -967,32 +4229,32 @@ impl AppService {
         };
 
         let streak_interval = Duration::days(i64::from(self.config.streak_interval_days));
-        let (current_streak, longest_streak) = calculate_streaks(&timestamps, streak_interval);
-
-        let personal_bests = PersonalBests {
-            max_weight: db::get_max_effective_weight_for_exercise(&self.conn, &canonical_name)?,
-            max_reps: db::get_max_reps_for_exercise(&self.conn, &canonical_name)?,
-            max_duration_minutes: db::get_max_duration_for_exercise(&self.conn, &canonical_name)?,
-            max_distance_km: db::get_max_distance_for_exercise(&self.conn, &canonical_name)?,
-        };
+        let (current_streak, longest_streak) = calculate_streaks(timestamps, streak_interval);
 
-        Ok(ExerciseStats {
+        ExerciseStats {
             canonical_name,
             total_workouts: timestamps.len(),
-            first_workout_date: Some(first_ts.date_naive()),
-            last_workout_date: Some(last_ts.date_naive()),
+            first_workout_date: Some(self.local_date(*first_ts)),
+            last_workout_date: Some(self.local_date(*last_ts)),
             avg_workouts_per_week,
             longest_gap_days,
+            consistency_score: calculate_consistency_score(
+                &timestamps.iter().map(|&ts| self.local_date(ts)).collect::<Vec<_>>(),
+            ),
             personal_bests,
             current_streak,
             longest_streak,
             streak_interval_days: self.config.streak_interval_days,
-        })
+        }
     }
 
     /// Calculates workout volume based on filters.
+    ///
+    /// Results are bucketed per `filters.aggregation` (daily by default); for weekly and
+    /// monthly buckets the returned date is the period start. Weeks start on
+    /// `filters.week_start`, falling back to the configured `week_start`.
     /// # Errors
-    /// Returns `anyhow::Error` if identifier invalid or DB query fails.
+    /// Returns `ServiceError` if identifier invalid or DB query fails.
     pub fn calculate_daily_volume(
         &self,
         filters: &VolumeFilters,
@@ -1017,26 +4279,432 @@ impl AppService {
             exercise_type: filters.exercise_type,
             muscle: filters.muscle,
             limit_days: filters.limit_days,
+            aggregation: filters.aggregation,
+            week_start: filters.week_start.or(Some(self.config.week_start)),
         };
 
-        db::calculate_daily_volume_filtered(&self.conn, &resolved_filters)
+        db::calculate_daily_volume_filtered(&*self.connection()?, &resolved_filters, self.config.tz())
             .context("Failed to calculate workout volume")
             .map_err(Into::into) 
     }
 
+    /// Same rows as `calculate_daily_volume`, plus the subtotal of each exercise and the
+    /// grand total over the whole filtered range, for a totals row under a volume table.
+    /// # Errors
+    /// Same as `calculate_daily_volume`.
+    pub fn calculate_volume_with_totals(&self, filters: &VolumeFilters) -> Result<VolumeTotals> {
+        let rows = self.calculate_daily_volume(filters)?;
+        let mut by_exercise: BTreeMap<String, f64> = BTreeMap::new();
+        for (_, exercise_name, volume) in &rows {
+            *by_exercise.entry(exercise_name.clone()).or_insert(0.0) += volume;
+        }
+        let total = by_exercise.values().sum();
+        Ok(VolumeTotals {
+            rows,
+            by_exercise,
+            total,
+        })
+    }
+
+    /// Volume per exercise for every training day, using the `calculate_daily_volume` formula.
+    fn session_volumes(&self) -> Result<BTreeMap<NaiveDate, Vec<(String, f64)>>> {
+        let mut sessions: BTreeMap<NaiveDate, Vec<(String, f64)>> = BTreeMap::new();
+        for (date, exercise_name, volume) in self.calculate_daily_volume(&VolumeFilters::default())? {
+            if volume > 0.0 {
+                sessions.entry(date).or_default().push((exercise_name, volume));
+            }
+        }
+        Ok(sessions)
+    }
+
+    /// The day with the highest total volume across all exercises (earliest day on ties),
+    /// with its per-exercise breakdown. `None` if no volume has been logged.
+    /// # Errors
+    /// Returns `ServiceError` if the DB queries fail.
+    pub fn best_session_volume(&self) -> Result<Option<SessionVolume>> {
+        let mut best: Option<SessionVolume> = None;
+        for (date, mut by_exercise) in self.session_volumes()? {
+            let total_volume: f64 = by_exercise.iter().map(|(_, v)| v).sum();
+            if best.as_ref().is_some_and(|b| b.total_volume >= total_volume) {
+                continue;
+            }
+            by_exercise.sort_by(|a, b| b.1.total_cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+            best = Some(SessionVolume {
+                date,
+                total_volume,
+                by_exercise,
+            });
+        }
+        Ok(best)
+    }
+
+    /// Sums workout volume per individual muscle over an optional date range.
+    ///
+    /// Each exercise's volume (same formula as `calculate_daily_volume`, including
+    /// bodyweight handling) is added to every muscle listed in its comma-separated
+    /// `muscles` field. Exercises without muscles are bucketed under `"unspecified"`.
+    /// # Errors
+    /// Returns `ServiceError` if the DB queries fail.
+    pub fn muscle_volume_breakdown(
+        &self,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+    ) -> Result<HashMap<String, f64>> {
+        let volume_rows = self.calculate_daily_volume(&VolumeFilters {
+            start_date,
+            end_date,
+            ..Default::default()
+        })?;
+
+        let muscles_by_exercise: HashMap<String, Vec<String>> = self
+            .list_exercises(&ExerciseFilters::default())?
+            .into_iter()
+            .map(|def| (def.name.to_lowercase(), parse_muscles(def.muscles.as_deref())))
+            .collect();
+
+        let mut breakdown: HashMap<String, f64> = HashMap::new();
+        for (_, exercise_name, volume) in volume_rows {
+            if volume <= 0.0 {
+                continue;
+            }
+            let muscles = muscles_by_exercise
+                .get(&exercise_name.to_lowercase())
+                .filter(|m| !m.is_empty());
+            match muscles {
+                Some(muscles) => {
+                    for muscle in muscles {
+                        *breakdown.entry(muscle.clone()).or_insert(0.0) += volume;
+                    }
+                }
+                None => {
+                    *breakdown
+                        .entry(UNSPECIFIED_MUSCLE.to_string())
+                        .or_insert(0.0) += volume;
+                }
+            }
+        }
+        Ok(breakdown)
+    }
+
+    /// Sums volume per exercise category (e.g. "Push", "Pull") over an optional date range.
+    ///
+    /// Uses the same volume formula as `calculate_daily_volume`. Exercises without a
+    /// category are bucketed under `UNCATEGORIZED`; categories compare case-insensitively
+    /// and are reported as first seen.
+    /// # Errors
+    /// Returns `ServiceError` if the DB queries fail.
+    pub fn volume_by_category(
+        &self,
+        start_date: Option<NaiveDate>,
+        end_date: Option<NaiveDate>,
+    ) -> Result<HashMap<String, f64>> {
+        let volume_rows = self.calculate_daily_volume(&VolumeFilters {
+            start_date,
+            end_date,
+            ..Default::default()
+        })?;
+
+        let mut display_names: HashMap<String, String> = HashMap::new();
+        let category_by_exercise: HashMap<String, String> = self
+            .list_exercises(&ExerciseFilters::default())?
+            .into_iter()
+            .map(|def| {
+                let category = def
+                    .category
+                    .filter(|c| !c.trim().is_empty())
+                    .unwrap_or_else(|| UNCATEGORIZED.to_string());
+                let key = display_names
+                    .entry(category.to_lowercase())
+                    .or_insert(category)
+                    .clone();
+                (def.name.to_lowercase(), key)
+            })
+            .collect();
+
+        let mut breakdown: HashMap<String, f64> = HashMap::new();
+        for (_, exercise_name, volume) in volume_rows {
+            if volume <= 0.0 {
+                continue;
+            }
+            let category = category_by_exercise
+                .get(&exercise_name.to_lowercase())
+                .map_or(UNCATEGORIZED, String::as_str);
+            *breakdown.entry(category.to_string()).or_insert(0.0) += volume;
+        }
+        Ok(breakdown)
+    }
+
+    /// Volume and workout count per exercise between two dates (inclusive), keyed by name.
+    /// Volume uses the `calculate_daily_volume` formula; the count includes every entry.
+    fn period_summary(&self, (start, end): (NaiveDate, NaiveDate)) -> Result<BTreeMap<String, (f64, usize)>> {
+        if start > end {
+            bail!("Period start {start} is after its end {end}.");
+        }
+        let mut summary: BTreeMap<String, (f64, usize)> = BTreeMap::new();
+        let volume_rows = self.calculate_daily_volume(&VolumeFilters {
+            start_date: Some(start),
+            end_date: Some(end),
+            ..Default::default()
+        })?;
+        for (_, exercise_name, volume) in volume_rows {
+            summary.entry(exercise_name).or_default().0 += volume.max(0.0);
+        }
+        let workouts = self.list_workouts(&WorkoutFilters {
+            start_date: Some(start),
+            end_date: Some(end),
+            ..Default::default()
+        })?;
+        for w in workouts {
+            summary.entry(w.exercise_name).or_default().1 += 1;
+        }
+        Ok(summary)
+    }
+
+    /// Compares volume and workout count between two periods (inclusive date ranges), in
+    /// total and per exercise, with the percent change from `period_a` to `period_b`.
+    /// An exercise trained in only one period is listed with zeros for the other.
+    /// # Errors
+    /// Returns `ServiceError` if a period starts after it ends or the DB queries fail.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn compare_periods(
+        &self,
+        period_a: (NaiveDate, NaiveDate),
+        period_b: (NaiveDate, NaiveDate),
+    ) -> Result<PeriodComparison> {
+        let summary_a = self.period_summary(period_a)?;
+        let summary_b = self.period_summary(period_b)?;
+        let change_pct = |a: f64, b: f64| (a > 0.0).then(|| (b - a) / a * 100.0);
+
+        let names: BTreeSet<&String> = summary_a.keys().chain(summary_b.keys()).collect();
+        let mut comparison = PeriodComparison::default();
+        for name in names {
+            let (volume_a, workouts_a) = summary_a.get(name).copied().unwrap_or_default();
+            let (volume_b, workouts_b) = summary_b.get(name).copied().unwrap_or_default();
+            comparison.volume_a += volume_a;
+            comparison.volume_b += volume_b;
+            comparison.workouts_a += workouts_a;
+            comparison.workouts_b += workouts_b;
+            comparison.exercises.push(ExerciseChange {
+                name: name.clone(),
+                volume_a,
+                volume_b,
+                volume_change_pct: change_pct(volume_a, volume_b),
+                workouts_a,
+                workouts_b,
+            });
+        }
+        comparison.volume_change_pct = change_pct(comparison.volume_a, comparison.volume_b);
+        comparison.workouts_change_pct =
+            change_pct(comparison.workouts_a as f64, comparison.workouts_b as f64);
+        Ok(comparison)
+    }
+
+    /// Finds likely deload weeks: weeks whose total volume fell below `threshold`
+    /// (e.g. `0.6`) of the average of the preceding `DELOAD_TRAILING_WEEKS` weeks.
+    ///
+    /// Volume is bucketed weekly (honoring the configured `week_start`) for one exercise,
+    /// or across all exercises when `identifier` is `None`. Weeks without any workouts
+    /// count as zero volume. Returns `(week_start, ratio)` pairs in chronological order.
+    /// # Errors
+    /// Returns `ServiceError` if `threshold` is not positive, the identifier is invalid,
+    /// or the DB query fails.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn detect_deloads(
+        &self,
+        identifier: Option<&str>,
+        threshold: f64,
+    ) -> Result<Vec<(NaiveDate, f64)>> {
+        if !threshold.is_finite() || threshold <= 0.0 {
+            bail!("Deload threshold must be a positive number.");
+        }
+        let volume_rows = self.calculate_daily_volume(&VolumeFilters {
+            exercise_name: identifier,
+            aggregation: VolumePeriod::Weekly,
+            ..Default::default()
+        })?;
+
+        let mut weekly_totals: BTreeMap<NaiveDate, f64> = BTreeMap::new();
+        for (week_start, _, volume) in volume_rows {
+            *weekly_totals.entry(week_start).or_insert(0.0) += volume;
+        }
+        let (Some(&first_week), Some(&last_week)) =
+            (weekly_totals.keys().next(), weekly_totals.keys().next_back())
+        else {
+            return Ok(vec![]);
+        };
+
+        // Fill in weeks without workouts so skipped weeks count as zero volume.
+        let mut weeks: Vec<(NaiveDate, f64)> = Vec::new();
+        let mut week = first_week;
+        while week <= last_week {
+            weeks.push((week, weekly_totals.get(&week).copied().unwrap_or(0.0)));
+            week += Duration::days(7);
+        }
+
+        let deloads = weeks
+            .windows(DELOAD_TRAILING_WEEKS + 1)
+            .filter_map(|window| {
+                let (week_start, volume) = window[DELOAD_TRAILING_WEEKS];
+                let trailing_avg = window[..DELOAD_TRAILING_WEEKS]
+                    .iter()
+                    .map(|(_, v)| v)
+                    .sum::<f64>()
+                    / DELOAD_TRAILING_WEEKS as f64;
+                if trailing_avg <= 0.0 {
+                    return None;
+                }
+                let ratio = volume / trailing_avg;
+                (ratio < threshold).then_some((week_start, ratio))
+            })
+            .collect();
+        Ok(deloads)
+    }
+
     /// Gets a list of all unique dates with recorded workouts.
     /// # Errors
     /// Returns `DbError` variants if the query fails.
     pub fn get_all_dates_with_exercise(&self) -> Result<Vec<NaiveDate>, DbError> {
-        db::get_all_dates_with_exercise(&self.conn)
+        db::get_all_dates_with_exercise(&*self.connection()?)
+    }
+
+    /// Fetches graph data for several exercises at once, keyed by canonical name, so the
+    /// series can be overlaid on a shared date axis. Identifiers that don't resolve are
+    /// returned in `skipped` instead of failing the call.
+    /// # Errors
+    /// Returns `ServiceError` if fetching data for a resolved exercise fails.
+    pub fn get_multi_graph_data(
+        &self,
+        identifiers: &[&str],
+        graph_type: GraphType,
+        start_date_filter: Option<NaiveDate>,
+        end_date_filter: Option<NaiveDate>,
+    ) -> Result<MultiGraphData> {
+        let mut series = HashMap::new();
+        let mut skipped = Vec::new();
+        for &identifier in identifiers {
+            let Some(canonical_name) = self.resolve_identifier_to_canonical_name(identifier)? else {
+                skipped.push(identifier.to_string());
+                continue;
+            };
+            if series.contains_key(&canonical_name) {
+                continue;
+            }
+            let data = self.get_data_for_graph(
+                &canonical_name,
+                graph_type,
+                start_date_filter,
+                end_date_filter,
+                None,
+            )?;
+            series.insert(canonical_name, data);
+        }
+        Ok(MultiGraphData { series, skipped })
+    }
+
+    /// Same as `get_data_for_graph`, with each value paired with a rounded one for labels.
+    /// `GraphType::Estimated1RM` values are rounded with `round_e1rm`; other graph types are
+    /// left as they are.
+    /// # Errors
+    /// Same as `get_data_for_graph`.
+    pub fn get_graph_points(
+        &self,
+        identifier: &str,
+        graph_type: GraphType,
+        start_date_filter: Option<NaiveDate>,
+        end_date_filter: Option<NaiveDate>,
+    ) -> Result<Vec<GraphPoint>> {
+        let data =
+            self.get_data_for_graph(identifier, graph_type, start_date_filter, end_date_filter, None)?;
+        Ok(data
+            .into_iter()
+            .map(|(date, value)| GraphPoint {
+                date,
+                value,
+                rounded: if graph_type == GraphType::Estimated1RM {
+                    self.round_e1rm(value)
+                } else {
+                    value
+                },
+            })
+            .collect())
+    }
+
+    /// Same as `get_data_for_graph`, with a centered simple moving average of `window` data
+    /// points applied to the values (see `moving_average`). Dates are unchanged.
+    /// # Errors
+    /// Same as `get_data_for_graph`.
+    pub fn get_smoothed_graph_data(
+        &self,
+        identifier: &str,
+        graph_type: GraphType,
+        start_date_filter: Option<NaiveDate>,
+        end_date_filter: Option<NaiveDate>,
+        window: u32,
+    ) -> Result<Vec<(NaiveDate, f64)>> {
+        let data =
+            self.get_data_for_graph(identifier, graph_type, start_date_filter, end_date_filter, None)?;
+        Ok(moving_average(&data, window))
+    }
+
+    /// Projects when an exercise's `metric` will reach `target` at the current rate of
+    /// progress.
+    ///
+    /// The model is a straight line fitted by least squares through the daily values of
+    /// `get_data_for_graph` (whole history). The slope gives the weekly rate, and the
+    /// target date is where the line crosses `target`; it can be in the past if the trend
+    /// has already passed it. R² says how closely the history follows the line, so values
+    /// near 0 mean the projection is little more than a guess.
+    ///
+    /// Returns `None` with fewer than `PREDICTION_MIN_POINTS` days of data, when the trend
+    /// is flat or going down, or when the date would be out of range.
+    /// # Errors
+    /// - `ServiceError::Validation` if `metric` is not `Estimated1RM` or `MaxWeight`.
+    /// - Same as `get_data_for_graph` otherwise.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn predict_metric(
+        &self,
+        identifier: &str,
+        metric: GraphType,
+        target: f64,
+    ) -> Result<Option<Prediction>> {
+        if !matches!(metric, GraphType::Estimated1RM | GraphType::MaxWeight) {
+            bail!("Predictions are only available for E1RM and max weight, not {metric:?}");
+        }
+        let data = self.get_data_for_graph(identifier, metric, None, None, None)?;
+        if data.len() < PREDICTION_MIN_POINTS {
+            return Ok(None);
+        }
+        let first_date = data[0].0;
+        let points: Vec<(f64, f64)> = data
+            .iter()
+            .map(|&(date, value)| ((date - first_date).num_days() as f64, value))
+            .collect();
+        let Some((slope, intercept, r_squared)) = fit_line(&points) else {
+            return Ok(None);
+        };
+        if slope <= 0.0 {
+            return Ok(None);
+        }
+        let days = ((target - intercept) / slope).ceil();
+        if !days.is_finite() || days.abs() > f64::from(i32::MAX) {
+            return Ok(None);
+        }
+        #[allow(clippy::cast_possible_truncation)]
+        let target_date = first_date.checked_add_signed(Duration::days(days as i64));
+        Ok(target_date.map(|target_date| Prediction {
+            target_date,
+            weekly_rate: slope * 7.0,
+            r_squared,
+        }))
     }
 
     /// Fetches and processes workout data for plotting, aggregated daily.
     ///
     /// Data is filtered by exercise identifier and an optional date range.
     /// The specific metric (e.g., max weight, total volume) is determined by `graph_type`.
+    /// Warmup entries are skipped for every graph type.
     /// For `GraphType::WorkoutDistance`, the returned distance values are converted
-    /// to the unit specified in the application configuration (`config.units`).
+    /// to the configured distance units (`Config::distance_units`).
     /// All other metrics are returned as recorded or calculated (e.g., E1RM).
     ///
     /// # Arguments
@@ -1045,6 +4713,8 @@ impl AppService {
     /// * `graph_type` - The type of metric to calculate and return for each day.
     /// * `start_date_filter` - Optional start date (inclusive) for filtering workouts.
     /// * `end_date_filter` - Optional end date (inclusive) for filtering workouts.
+    /// * `transform` - Optional post-processing of the values (log10, or percent of the first
+    ///   point in the range), applied after unit conversion.
     ///
     /// # Returns
     ///
@@ -1053,13 +4723,18 @@ impl AppService {
     /// - The `NaiveDate` is the date of the workout(s).
     /// - The `f64` is the aggregated value for that date based on the `graph_type`.
     ///   - For `Estimated1RM`, `MaxWeight`, `MaxReps`: the maximum value achieved on that day.
-    ///   - For `WorkoutVolume`, `WorkoutReps`, `WorkoutDuration`, `WorkoutDistance`: the sum of values for that day.
+    ///     `Estimated1RM` only considers AMRAP sets on days that have one.
+    ///   - For `WorkoutVolume`, `WorkoutReps`, `WorkoutDuration`, `WorkoutDistance`, `CaloriesBurned`: the sum of values for that day.
+    ///   - For `WorkoutDensity`: the day's volume per minute, over entries with both a volume
+    ///     and a duration.
+    ///   - For `RelativeIntensity`: the day's heaviest weight as a percentage of the best E1RM
+    ///     reached up to and including that day (earlier history counts even when filtered out).
     ///
     /// Returns an empty vector if no workouts match the criteria or if all aggregated values are non-positive.
     ///
     /// # Errors
     ///
-    /// Returns `anyhow::Error` if:
+    /// Returns `ServiceError` if:
     /// - The `identifier` cannot be resolved to a valid exercise.
     /// - There's an issue querying the database.
     /// - The `exercise_definition` for the resolved exercise cannot be found (should be rare if identifier resolves).
@@ -1070,18 +4745,28 @@ impl AppService {
         graph_type: GraphType,
         start_date_filter: Option<NaiveDate>, 
         end_date_filter: Option<NaiveDate>,   
+        transform: Option<GraphTransform>,
     ) -> Result<Vec<(NaiveDate, f64)>> {
         let canonical_name = self
             .resolve_identifier_to_canonical_name(identifier)? // Resolves active exercise
             .ok_or_else(|| DbError::ExerciseNotFound(identifier.to_string()))?;
 
         // Fetches active exercise definition
-        let exercise_definition = db::get_exercise_by_identifier(&self.conn, &canonical_name)?
+        let exercise_definition = db::get_exercise_by_identifier(&*self.connection()?, &canonical_name)?
             .map(|(def, _)| def)
             .ok_or_else(|| DbError::ExerciseNotFound(canonical_name.clone()))?;
 
+        // The date range is pushed into SQL so only the needed rows are loaded. SQL dates use
+        // a single UTC offset, so the bounds are widened by a day to cover a DST shift and the
+        // exact range is applied below. RelativeIntensity needs the earlier E1RMs as a baseline.
         let base_filter = WorkoutFilters {
             exercise_name: Some(&canonical_name),
+            start_date: start_date_filter
+                .filter(|_| graph_type != GraphType::RelativeIntensity)
+                .and_then(|date| date.pred_opt()),
+            end_date: end_date_filter.and_then(|date| date.succ_opt()),
+            include_warmups: false,
+            include_failures: false,
             ..Default::default()
         };
 
@@ -1090,25 +4775,62 @@ impl AppService {
             .list_workouts(&base_filter) 
             .context(format!("Failed graph data fetch for '{canonical_name}'"))?;
 
+        history.sort_by_key(|w| (w.timestamp, w.id)); // Same-day entries in the order they were added
+        let mut best_e1rm = 0.0_f64; // Running maximum for RelativeIntensity
         if let Some(start_date) = start_date_filter {
-            history.retain(|w| w.timestamp.date_naive() >= start_date);
+            if graph_type == GraphType::RelativeIntensity {
+                best_e1rm = history
+                    .iter()
+                    .filter(|w| self.local_date(w.timestamp) < start_date)
+                    .filter_map(|w| calculate_e1rm(w.weight?, w.reps?))
+                    .fold(best_e1rm, f64::max);
+            }
+            history.retain(|w| self.local_date(w.timestamp) >= start_date);
         }
         if let Some(end_date) = end_date_filter {
-            history.retain(|w| w.timestamp.date_naive() <= end_date);
+            history.retain(|w| self.local_date(w.timestamp) <= end_date);
         }
-        history.sort_by_key(|w| w.timestamp);
 
         if history.is_empty() {
             return Ok(vec![]);
         }
 
+        // Workouts with splits count their summed split distance instead of the single value.
+        let split_distances = if graph_type == GraphType::WorkoutDistance {
+            db::get_split_distance_totals(&*self.connection()?, &canonical_name)?
+        } else {
+            HashMap::new()
+        };
+
+        let bodyweight_kg = if graph_type == GraphType::CaloriesBurned {
+            self.current_bodyweight_kg()?
+        } else {
+            None
+        };
+
+        // Days with an AMRAP set chart that set's E1RM rather than the day's best
+        let amrap_days: Vec<NaiveDate> = if graph_type == GraphType::Estimated1RM {
+            history
+                .iter()
+                .filter(|w| w.is_amrap)
+                .map(|w| self.local_date(w.timestamp))
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        let mut daily_top_weights: HashMap<NaiveDate, f64> = HashMap::new();
+        let mut daily_density: HashMap<NaiveDate, (f64, i64)> = HashMap::new();
         let mut daily_aggregated_data: BTreeMap<NaiveDate, f64> = BTreeMap::new();
         for w in history { // w is a non-deleted workout for a non-deleted exercise
-            let date = w.timestamp.date_naive();
+            let date = self.local_date(w.timestamp);
             let entry = daily_aggregated_data.entry(date).or_insert(0.0);
 
             match graph_type {
                 GraphType::Estimated1RM => {
+                    if !w.is_amrap && amrap_days.contains(&date) {
+                        continue;
+                    }
                     if let (Some(wt), Some(r)) = (w.weight, w.reps) {
                         if let Some(e1rm) = calculate_e1rm(wt, r) {
                             *entry = entry.max(e1rm);
@@ -1128,18 +4850,7 @@ impl AppService {
                     }
                 }
                 GraphType::WorkoutVolume => {
-                    let s = w.sets.unwrap_or(1).max(1);
-                    let r = w.reps.unwrap_or(0);
-
-                    let weight_for_volume = match exercise_definition.type_ {
-                        ExerciseType::BodyWeight => {
-                            calculate_effective_weight(&exercise_definition, w.weight, w.bodyweight)
-                                .unwrap_or(0.0)
-                        }
-                        _ => w.weight.unwrap_or(0.0),
-                    };
-
-                    let v = s as f64 * r as f64 * weight_for_volume;
+                    let v = workout_volume(&exercise_definition, &w);
                     if v > 0.0 {
                         *entry += v;
                     }
@@ -1152,14 +4863,44 @@ impl AppService {
                         *entry += tr as f64;
                     }
                 }
-                GraphType::WorkoutDuration => {
-                    if let Some(d) = w.duration_minutes.filter(|&dur| dur > 0) {
-                        *entry += d as f64;
+                GraphType::WorkoutDuration => {
+                    if let Some(d) = w.duration_minutes.filter(|&dur| dur > 0) {
+                        *entry += d as f64;
+                    }
+                }
+                GraphType::WorkoutDistance => {
+                    let distance = split_distances.get(&w.id).copied().or(w.distance);
+                    if let Some(d) = distance.filter(|&dist| dist > 0.0) {
+                        *entry += d; 
+                    }
+                }
+                GraphType::CaloriesBurned => {
+                    if let Some(kcal) =
+                        estimate_calories_burned(&exercise_definition, w.duration_minutes, bodyweight_kg)
+                    {
+                        *entry += kcal;
+                    }
+                }
+                GraphType::WorkoutDensity => {
+                    // Only entries with both volume and duration count towards either total
+                    let v = workout_volume(&exercise_definition, &w);
+                    if let Some(d) = w.duration_minutes.filter(|&d| d > 0 && v > 0.0) {
+                        let (volume, minutes) = daily_density.entry(date).or_insert((0.0, 0));
+                        *volume += v;
+                        *minutes += d;
+                        *entry = *volume / *minutes as f64;
                     }
                 }
-                GraphType::WorkoutDistance => {
-                    if let Some(d) = w.distance.filter(|&dist| dist > 0.0) {
-                        *entry += d; 
+                GraphType::RelativeIntensity => {
+                    if let (Some(wt), Some(r)) = (w.weight, w.reps) {
+                        if let Some(e1rm) = calculate_e1rm(wt, r) {
+                            best_e1rm = best_e1rm.max(e1rm);
+                        }
+                        let top_weight = daily_top_weights.entry(date).or_insert(0.0);
+                        *top_weight = top_weight.max(wt);
+                        if best_e1rm > 0.0 {
+                            *entry = *top_weight / best_e1rm * 100.0;
+                        }
                     }
                 }
             }
@@ -1173,10 +4914,7 @@ impl AppService {
                 }
 
                 let final_val = if graph_type == GraphType::WorkoutDistance {
-                    match self.config.units {
-                        Units::Metric => value, 
-                        Units::Imperial => value * KM_TO_MILE,
-                    }
+                    self.display_distance(value)
                 } else {
                     value
                 };
@@ -1184,14 +4922,379 @@ impl AppService {
             })
             .collect();
 
-        Ok(data_points)
+        // Non-positive values are filtered out above, so log10 is always defined
+        Ok(match transform {
+            Some(transform) => transform.apply(data_points),
+            None => data_points,
+        })
+    }
+
+    /// Returns the dates on which a new personal best was set for `metric`, with the record value.
+    ///
+    /// Values form a strictly increasing running maximum; if several records fall on the same
+    /// day only the highest is kept. Distance is converted to the configured units.
+    /// # Errors
+    /// Returns `ServiceError` if the identifier cannot be resolved or the DB query fails.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn get_pb_history(&self, identifier: &str, metric: PbMetric) -> Result<Vec<(NaiveDate, f64)>> {
+        let exercise_definition = self
+            .resolve_exercise_identifier(identifier)?
+            .ok_or_else(|| DbError::ExerciseNotFound(identifier.to_string()))?;
+
+        let mut history = self
+            .list_workouts(&WorkoutFilters {
+                exercise_name: Some(&exercise_definition.name),
+                include_warmups: false,
+                include_failures: false,
+                ..Default::default()
+            })
+            .context(format!("Failed PB history fetch for '{}'", exercise_definition.name))?;
+        history.sort_by_key(|w| (w.timestamp, w.id));
+
+        let mut records: Vec<(NaiveDate, f64)> = Vec::new();
+        let mut best = 0.0;
+        for w in history {
+            let value = match metric {
+                PbMetric::Weight => calculate_effective_weight(&exercise_definition, w.weight, w.bodyweight),
+                PbMetric::Reps => w.reps.map(|r| r as f64),
+                PbMetric::Duration => w.duration_minutes.map(|d| d as f64),
+                PbMetric::Distance => w.distance,
+            };
+            let Some(value) = value.filter(|&v| v > best) else {
+                continue;
+            };
+            best = value;
+            let date = self.local_date(w.timestamp);
+            match records.last_mut() {
+                Some(last) if last.0 == date => last.1 = value,
+                _ => records.push((date, value)),
+            }
+        }
+
+        if metric == PbMetric::Distance {
+            for record in &mut records {
+                record.1 = self.display_distance(record.1);
+            }
+        }
+        Ok(records)
+    }
+
+    /// Share of working sets of an exercise that were failed attempts. Each entry counts as
+    /// its number of sets (at least one); warmups are ignored.
+    /// # Errors
+    /// Returns `ServiceError` if the identifier cannot be resolved or the DB query fails.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn get_failure_rate(&self, identifier: &str) -> Result<FailureRate> {
+        let canonical_name = self
+            .resolve_identifier_to_canonical_name(identifier)?
+            .ok_or_else(|| DbError::ExerciseNotFound(identifier.to_string()))?;
+        let workouts = self
+            .list_workouts(&WorkoutFilters {
+                exercise_name: Some(&canonical_name),
+                include_warmups: false,
+                ..Default::default()
+            })
+            .context(format!("Failed failure rate fetch for '{canonical_name}'"))?;
+
+        let mut rate = FailureRate::default();
+        for w in &workouts {
+            let sets = w.sets.unwrap_or(1).max(1);
+            rate.attempts += sets;
+            if w.is_failure {
+                rate.failures += sets;
+            }
+        }
+        if rate.attempts > 0 {
+            rate.percentage = rate.failures as f64 / rate.attempts as f64 * 100.0;
+        }
+        Ok(rate)
+    }
+
+    /// Most reps performed at (about) `weight` on each day the exercise was trained with it,
+    /// oldest first. Entries count when their effective weight is within `tolerance` of
+    /// `weight`; warmups are ignored.
+    /// # Errors
+    /// Returns `ServiceError` if `tolerance` is negative, the identifier is invalid, or the
+    /// DB query fails.
+    pub fn get_rep_progression(
+        &self,
+        identifier: &str,
+        weight: f64,
+        tolerance: f64,
+    ) -> Result<Vec<(NaiveDate, i64)>> {
+        if !tolerance.is_finite() || tolerance < 0.0 {
+            bail!("Weight tolerance must be zero or a positive number, got {tolerance}.");
+        }
+        let exercise_definition = self
+            .resolve_exercise_identifier(identifier)?
+            .ok_or_else(|| DbError::ExerciseNotFound(identifier.to_string()))?;
+
+        let workouts = self
+            .list_workouts(&WorkoutFilters {
+                exercise_name: Some(&exercise_definition.name),
+                include_warmups: false,
+                include_failures: false,
+                ..Default::default()
+            })
+            .context(format!("Failed rep progression fetch for '{}'", exercise_definition.name))?;
+
+        let mut by_day: BTreeMap<NaiveDate, i64> = BTreeMap::new();
+        for w in workouts {
+            let at_weight = calculate_effective_weight(&exercise_definition, w.weight, w.bodyweight)
+                .is_some_and(|ew| (ew - weight).abs() <= tolerance);
+            let Some(reps) = w.reps.filter(|&r| r > 0 && at_weight) else {
+                continue;
+            };
+            let best = by_day.entry(self.local_date(w.timestamp)).or_insert(0);
+            *best = (*best).max(reps);
+        }
+        Ok(by_day.into_iter().collect())
+    }
+
+    /// Average estimated 1RM of an exercise's entries by the part of the day they were logged
+    /// in (configured timezone), ordered morning to evening. Parts of the day without entries
+    /// are omitted.
+    ///
+    /// Only entries logged with their real time of day count. Entries logged with just a date
+    /// are stored at local noon, so anything at exactly
+    /// 12:00:00 is treated as date-only and excluded. Warmups are ignored.
+    /// # Errors
+    /// Returns `ServiceError` if the identifier is invalid or the DB query fails.
+    #[allow(clippy::cast_precision_loss)]
+    pub fn performance_by_time_of_day(&self, identifier: &str) -> Result<Vec<TimeOfDayPerformance>> {
+        let canonical_name = self
+            .resolve_identifier_to_canonical_name(identifier)?
+            .ok_or_else(|| DbError::ExerciseNotFound(identifier.to_string()))?;
+        let workouts = self
+            .list_workouts(&WorkoutFilters {
+                exercise_name: Some(&canonical_name),
+                include_warmups: false,
+                include_failures: false,
+                ..Default::default()
+            })
+            .context(format!("Failed time-of-day fetch for '{canonical_name}'"))?;
+
+        let tz = self.config.tz();
+        let noon = chrono::NaiveTime::from_hms_opt(12, 0, 0).expect("valid time");
+        let mut buckets: BTreeMap<TimeOfDay, (f64, usize)> = BTreeMap::new();
+        for w in workouts {
+            let local_time = w.timestamp.with_timezone(&tz).time();
+            if local_time == noon {
+                continue; // Date-only entry
+            }
+            let Some(e1rm) = w.weight.zip(w.reps).and_then(|(wt, r)| calculate_e1rm(wt, r)) else {
+                continue;
+            };
+            let bucket = buckets
+                .entry(TimeOfDay::from_hour(local_time.hour()))
+                .or_insert((0.0, 0));
+            bucket.0 += e1rm;
+            bucket.1 += 1;
+        }
+        Ok(buckets
+            .into_iter()
+            .map(|(time_of_day, (total, entries))| TimeOfDayPerformance {
+                time_of_day,
+                average_e1rm: total / entries as f64,
+                entries,
+            })
+            .collect())
+    }
+
+    /// Rep-max table for an exercise: for each rep count from 1 to `REP_MAX_TABLE_SIZE`, the
+    /// heaviest effective weight lifted for at least that many reps. Rep counts with no data
+    /// are omitted.
+    /// # Errors
+    /// - `DbError::ExerciseNotFound` if the identifier does not resolve.
+    /// - `ServiceError` wrapping other `DbError` variants.
+    pub fn get_rep_max_table(&self, identifier: &str) -> Result<BTreeMap<i64, f64>> {
+        let canonical_name = self
+            .resolve_identifier_to_canonical_name(identifier)?
+            .ok_or_else(|| DbError::ExerciseNotFound(identifier.to_string()))?;
+        let by_reps = db::get_max_weight_by_reps(&*self.connection()?, &canonical_name)
+            .with_context(|| format!("Failed to build rep-max table for '{canonical_name}'"))?;
+
+        let mut table = BTreeMap::new();
+        for target in 1..=REP_MAX_TABLE_SIZE {
+            let best = by_reps
+                .iter()
+                .filter(|(reps, _)| *reps >= target)
+                .map(|&(_, weight)| weight)
+                .reduce(f64::max);
+            if let Some(weight) = best {
+                table.insert(target, weight);
+            }
+        }
+        Ok(table)
+    }
+
+    /// Training weights for 50% to 100% of the best estimated 1RM (in 5% steps), as
+    /// `(percent, weight)` pairs. The E1RM is the highest over all non-warmup entries with
+    /// weight and reps; weights are rounded to `weight_increment` if set.
+    /// # Errors
+    /// - `DbError::ExerciseNotFound` if the identifier does not resolve.
+    /// - `DbError::NoWorkoutDataFound` if no entry has both weight and reps.
+    /// - `ServiceError` wrapping other `DbError` variants.
+    pub fn percentage_table(&self, identifier: &str) -> Result<Vec<(u32, f64)>> {
+        let canonical_name = self
+            .resolve_identifier_to_canonical_name(identifier)?
+            .ok_or_else(|| DbError::ExerciseNotFound(identifier.to_string()))?;
+        let workouts = self
+            .list_workouts(&WorkoutFilters {
+                exercise_name: Some(&canonical_name),
+                include_warmups: false,
+                include_failures: false,
+                ..Default::default()
+            })
+            .context(format!("Failed percentage table fetch for '{canonical_name}'"))?;
+        let Some(best_e1rm) = workouts
+            .iter()
+            .filter_map(|w| calculate_e1rm(w.weight?, w.reps?))
+            .reduce(f64::max)
+        else {
+            bail!(DbError::NoWorkoutDataFound(canonical_name));
+        };
+        Ok((50..=100)
+            .step_by(5)
+            .map(|percent| (percent, self.display_weight(best_e1rm * f64::from(percent) / 100.0)))
+            .collect())
+    }
+
+    /// Top set of the session containing `workout_id`: among the non-warmup entries of the
+    /// same exercise on the same (local) day, the one with the highest estimated 1RM.
+    /// Entries marked AMRAP take precedence over the rest. Returns `None` if no entry in the
+    /// session has both weight and reps.
+    /// # Errors
+    /// - `DbError::WorkoutNotFound` if no active workout has this ID.
+    /// - `ServiceError` wrapping other `DbError` variants.
+    pub fn get_top_set(&self, workout_id: i64) -> Result<Option<TopSet>> {
+        let workout = db::get_workout_by_id(&*self.connection()?, workout_id)?
+            .ok_or(DbError::WorkoutNotFound(workout_id))?;
+        let session = self.list_workouts(&WorkoutFilters {
+            exercise_name: Some(&workout.exercise_name),
+            date: Some(self.local_date(workout.timestamp)),
+            ..Default::default()
+        })?;
+        Ok(select_top_set(&session))
+    }
+
+    /// Returns the `n`th best value ever recorded for `metric` (1 = personal best),
+    /// counting every non-warmup entry. Weight is effective weight for BodyWeight exercises;
+    /// distance is converted to the configured units.
+    /// Returns `None` if there are fewer than `n` records.
+    /// # Errors
+    /// Returns `ServiceError` if `n` is 0, the identifier is invalid, or the DB query fails.
+    pub fn get_nth_best(&self, identifier: &str, metric: PbMetric, n: u32) -> Result<Option<f64>> {
+        if n == 0 {
+            bail!("Rank must be at least 1.");
+        }
+        let canonical_name = self
+            .resolve_identifier_to_canonical_name(identifier)?
+            .ok_or_else(|| DbError::ExerciseNotFound(identifier.to_string()))?;
+        let value = db::get_nth_best_for_exercise(&*self.connection()?, &canonical_name, metric, n)
+            .with_context(|| format!("Failed to get ranked {metric:?} for '{canonical_name}'"))?;
+        Ok(value.map(|v| match metric {
+            PbMetric::Distance => self.display_distance(v),
+            _ => v,
+        }))
+    }
+
+    /// Usage of every exercise that has been performed: workout count, total volume and
+    /// last-performed date (in the configured timezone), most frequently logged first.
+    /// Soft-deleted exercises and workouts are ignored.
+    /// # Errors
+    /// Returns `ServiceError` wrapping DB errors.
+    #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+    pub fn get_exercise_leaderboard(&self) -> Result<Vec<ExerciseUsage>> {
+        let rows = db::list_exercise_usage(&*self.connection()?)
+            .context("Failed to build exercise leaderboard")?;
+        Ok(rows
+            .into_iter()
+            .map(|(name, count, total_volume, last)| ExerciseUsage {
+                name,
+                workout_count: count as usize,
+                total_volume,
+                last_performed: self.local_date(last),
+            })
+            .collect())
+    }
+
+    /// Lifetime totals over every logged workout: entry count, volume, reps, time and
+    /// distance (converted to the configured units). Soft-deleted rows are ignored.
+    /// # Errors
+    /// Returns `ServiceError` wrapping DB errors.
+    #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+    pub fn get_lifetime_totals(&self) -> Result<LifetimeTotals> {
+        let (count, volume, reps, duration, distance_km) =
+            db::get_lifetime_totals(&*self.connection()?).context("Failed to compute lifetime totals")?;
+        Ok(LifetimeTotals {
+            workout_count: count as usize,
+            total_volume: volume,
+            total_reps: reps,
+            total_duration_minutes: duration,
+            total_distance: self.display_distance(distance_km),
+        })
+    }
+
+    /// Checks the database for inconsistencies: workouts and aliases referring to missing or
+    /// deleted exercises, impossible (negative) values and duplicate exercise names. With
+    /// `repair`, the safe fixes are applied in a single transaction: dangling aliases are
+    /// soft-deleted. Everything else is only reported, since fixing it means discarding or
+    /// guessing at data. The report always lists what was found before repairing.
+    /// # Errors
+    /// Returns `ServiceError` wrapping DB errors.
+    pub fn check_integrity(&self, repair: bool) -> Result<IntegrityReport> {
+        let mut conn = self.connection()?;
+        let tx = conn.transaction().context("Failed to start integrity check transaction")?;
+        let mut report = IntegrityReport {
+            orphaned_workouts: db::find_orphaned_workouts(&tx)?,
+            dangling_aliases: db::find_dangling_aliases(&tx)?,
+            invalid_values: db::find_invalid_workout_values(&tx)?,
+            duplicate_names: db::find_duplicate_exercise_names(&tx)?,
+            repaired_aliases: 0,
+        };
+        if repair && !report.dangling_aliases.is_empty() {
+            report.repaired_aliases = db::delete_dangling_aliases(&tx)?;
+        }
+        tx.commit().context("Failed to commit integrity repairs")?;
+        Ok(report)
+    }
+
+    /// Personal bests set on `date` (in the configured timezone): for each exercise, every
+    /// metric whose all-time maximum was first reached that day, as
+    /// `(exercise name, metric, value)`. Ordered by exercise name, then metric. Distance is
+    /// converted to the configured units.
+    /// # Errors
+    /// Returns `ServiceError` wrapping DB errors.
+    pub fn records_set_on(&self, date: NaiveDate) -> Result<Vec<(String, PbMetric, f64)>> {
+        let conn = self.connection()?;
+        let exercises = db::list_exercises(&conn, &ExerciseFilters::default())?;
+        let mut records = Vec::new();
+        for exercise in exercises {
+            for metric in PbMetric::ALL {
+                let best = db::get_max_with_timestamp_for_exercise(&conn, &exercise.name, metric)
+                    .with_context(|| format!("Failed to get {metric:?} record for '{}'", exercise.name))?;
+                let Some((value, achieved)) = best else {
+                    continue;
+                };
+                if self.local_date(achieved) != date {
+                    continue;
+                }
+                let value = match metric {
+                    PbMetric::Distance => self.display_distance(value),
+                    _ => value,
+                };
+                records.push((exercise.name.clone(), metric, value));
+            }
+        }
+        Ok(records)
     }
 
     /// Lists all unique muscle groups.
     /// # Errors
-    /// Returns `anyhow::Error` wrapping DB errors.
+    /// Returns `ServiceError` wrapping DB errors.
     pub fn list_all_muscles(&self) -> Result<Vec<String>> {
-        db::list_all_muscles(&self.conn)
+        db::list_all_muscles(&*self.connection()?)
             .context("Failed to list all muscles")
             .map_err(Into::into)
     }
@@ -1209,6 +5312,7 @@ impl AppService {
         server_url_override
             .or_else(|| self.config.sync_server_url.clone())
             .ok_or_else(|| anyhow::anyhow!("Sync server URL not configured and no override provided."))
+            .map_err(Into::into)
     }
 
     pub fn set_sync_server_url(&mut self, url: Option<String>) -> Result<(), ConfigError> {
@@ -1235,23 +5339,72 @@ impl AppService {
             None
         };
 
+        let conn = self.connection()?;
         Ok(ChangesPayload {
             config: config_change,
-            exercises: db::get_exercises_modified_since(&self.conn, since)
+            exercises: db::get_exercises_modified_since(&conn, since)
                 .context("Failed to get modified exercises for sync")?,
-            workouts: db::get_workouts_modified_since(&self.conn, since)
+            workouts: db::get_workouts_modified_since(&conn, since)
                 .context("Failed to get modified workouts for sync")?,
-            aliases: db::get_aliases_modified_since(&self.conn, since)
+            aliases: db::get_aliases_modified_since(&conn, since)
                 .context("Failed to get modified aliases for sync")?,
-            bodyweights: db::get_bodyweights_modified_since(&self.conn, since)
+            bodyweights: db::get_bodyweights_modified_since(&conn, since)
                 .context("Failed to get modified bodyweights for sync")?,
         })
     }
 
-    pub fn apply_server_changes(&mut self, changes: ChangesPayload) -> Result<SyncSummary> {
+    /// Exercises, workouts, aliases and bodyweight entries modified after `since`, newest
+    /// first, for a "recent activity" view. Uses the same queries as `collect_local_changes`.
+    /// # Errors
+    /// - `ServiceError` wrapping `DbError` variants.
+    pub fn recent_changes(&self, since: DateTime<Utc>) -> Result<Vec<RecentChange>> {
+        let conn = self.connection()?;
+        let mut changes: Vec<RecentChange> = db::get_exercises_modified_since(&conn, Some(since))
+            .context("Failed to get modified exercises")?
+            .into_iter()
+            .map(RecentChange::Exercise)
+            .collect();
+        changes.extend(
+            db::get_workouts_modified_since(&conn, Some(since))
+                .context("Failed to get modified workouts")?
+                .into_iter()
+                .map(RecentChange::Workout),
+        );
+        changes.extend(
+            db::get_aliases_modified_since(&conn, Some(since))
+                .context("Failed to get modified aliases")?
+                .into_iter()
+                .map(RecentChange::Alias),
+        );
+        changes.extend(
+            db::get_bodyweights_modified_since(&conn, Some(since))
+                .context("Failed to get modified bodyweights")?
+                .into_iter()
+                .map(RecentChange::Bodyweight),
+        );
+        changes.sort_by_key(|change| std::cmp::Reverse(change.last_edited()));
+        Ok(changes)
+    }
+
+    /// Applies changes received from the sync server in one transaction. With `strict`, any
+    /// record that fails to apply aborts the whole sync; otherwise failing records are skipped
+    /// and listed in `SyncSummary::failures`, and the rest are committed. An invalid server
+    /// theme is never applied: the local one is kept and the theme listed as a failure.
+    /// # Errors
+    /// Returns `ServiceError` if the backup, config merge or commit fails, or (when `strict`)
+    /// any record fails to apply.
+    pub fn apply_server_changes(&mut self, changes: ChangesPayload, strict: bool) -> Result<SyncSummary> {
         let mut summary = SyncSummary::default();
+        if self.config.auto_backup_before_sync {
+            summary.backup = Some(
+                self.backup_database(None)
+                    .context("Backup before applying server changes failed")?,
+            );
+        }
         let local_config_path = self.get_config_path().to_path_buf();
-        let tx = self.conn.transaction().context("Failed to start transaction for applying server changes")?;
+        // Borrow the field directly so the config can still be replaced while the transaction is open.
+        let mut conn = self.conn.get()?;
+        let tx = conn.transaction().context("Failed to start transaction for applying server changes")?;
 
         if let Some(server_config_change) = changes.config {
             let local_config_mod_time: DateTime<Utc> = std::fs::metadata(&local_config_path)
@@ -1259,37 +5412,56 @@ impl AppService {
                 .map(Into::into)
                 .unwrap_or_else(|_| Utc.timestamp_opt(0,0).unwrap());
 
-            if server_config_change.last_edited > local_config_mod_time {
-                let new_config_from_server: Config = toml::from_str(&server_config_change.content)
-                    .context("Failed to parse server config content")?;
-                
-                let old_last_sync = self.config.last_sync_timestamp;
-                self.config = new_config_from_server;
-                self.config.last_sync_timestamp = old_last_sync;
+            let mut server_config: Config = toml::from_str(&server_config_change.content)
+                .context("Failed to parse server config content")?;
+            // `config::load` rejects unknown colors, so an invalid synced theme must not be saved
+            if let Err(e) = server_config.theme.validate() {
+                summary.failures.push(SyncFailure { record: "config theme".to_string(), error: e.to_string() });
+                server_config.theme = self.config.theme.clone();
+            }
+
+            // Without a stored base, fall back to whole-config "newest wins".
+            let base_config = match config::load_sync_base(&local_config_path)
+                .context("Failed to load config sync base")?
+            {
+                Some(base) => base,
+                None if server_config_change.last_edited > local_config_mod_time => self.config.clone(),
+                None => server_config.clone(),
+            };
 
+            let merged_config = merge_config(&self.config, &server_config, &base_config);
+            if merged_config == self.config {
+                println!("Server config has no new changes, skipped applying server config. Server last_edited: {}, Local approx last_edited: {}", server_config_change.last_edited, local_config_mod_time);
+            } else {
+                self.config = merged_config;
                 config::save(&local_config_path, &self.config).context("Failed to save synced config")?;
                 println!("Applied server config changes.");
                 summary.config = true;
-            } else {
-                 println!("Server config not newer or local file missing, skipped applying server config. Server last_edited: {}, Local approx last_edited: {}", server_config_change.last_edited, local_config_mod_time);
             }
+            config::save(&config::sync_base_path(&local_config_path), &server_config)
+                .context("Failed to save config sync base")?;
         }
 
+        // Each upsert is a single statement, so a failed one leaves nothing behind in `tx`.
         for exercise_def in changes.exercises {
-            db::upsert_exercise(&tx, &exercise_def).context(format!("Failed to upsert exercise ID {}", exercise_def.id))?;
-            summary.exercises += 1;
+            let result = db::upsert_exercise(&tx, &exercise_def);
+            let record = format!("exercise ID {}", exercise_def.id);
+            apply_upsert(result, record, strict, &mut summary.exercises, &mut summary.failures)?;
         }
         for workout in changes.workouts {
-            db::upsert_workout(&tx, &workout).context(format!("Failed to upsert workout ID {}", workout.id))?;
-            summary.workouts += 1;
+            let result = db::upsert_workout(&tx, &workout);
+            let record = format!("workout ID {}", workout.id);
+            apply_upsert(result, record, strict, &mut summary.workouts, &mut summary.failures)?;
         }
         for alias_entry in changes.aliases {
-            db::upsert_alias(&tx, &alias_entry).context(format!("Failed to upsert alias '{}'", alias_entry.alias_name))?;
-            summary.aliases += 1;
+            let result = db::upsert_alias(&tx, &alias_entry);
+            let record = format!("alias '{}'", alias_entry.alias_name);
+            apply_upsert(result, record, strict, &mut summary.aliases, &mut summary.failures)?;
         }
         for bw_entry in changes.bodyweights {
-            db::upsert_bodyweight_entry(&tx, &bw_entry).context(format!("Failed to upsert bodyweight entry ID {}", bw_entry.id))?;
-            summary.bodyweights += 1;
+            let result = db::upsert_bodyweight_entry(&tx, &bw_entry);
+            let record = format!("bodyweight entry ID {}", bw_entry.id);
+            apply_upsert(result, record, strict, &mut summary.bodyweights, &mut summary.failures)?;
         }
         
         tx.commit().context("Failed to commit transaction for server changes")?;
@@ -1299,6 +5471,137 @@ impl AppService {
 
 // --- Helper Functions ---
 
+/// Counts a successful sync upsert, or handles a failed one: an error when `strict`,
+/// otherwise a `SyncFailure` entry.
+fn apply_upsert(
+    result: Result<(), DbError>,
+    record: String,
+    strict: bool,
+    applied: &mut usize,
+    failures: &mut Vec<SyncFailure>,
+) -> Result<()> {
+    match result {
+        Ok(()) => {
+            *applied += 1;
+            Ok(())
+        }
+        Err(e) if strict => Err(anyhow::Error::new(e).context(format!("Failed to upsert {record}")).into()),
+        Err(e) => {
+            failures.push(SyncFailure { record, error: e.to_string() });
+            Ok(())
+        }
+    }
+}
+
+/// Centered simple moving average over a `(date, value)` series.
+///
+/// Each value becomes the mean of the `window` points around it (for even windows the extra
+/// point is taken after it); windows are truncated at the ends of the series. A window of 0
+/// or 1 leaves the series unchanged, and a window at least as long as the series replaces
+/// every value with the overall mean.
+#[allow(clippy::cast_precision_loss)]
+pub fn moving_average(series: &[(NaiveDate, f64)], window: u32) -> Vec<(NaiveDate, f64)> {
+    let window = window as usize;
+    if window <= 1 || series.is_empty() {
+        return series.to_vec();
+    }
+    if window >= series.len() {
+        let mean = series.iter().map(|&(_, v)| v).sum::<f64>() / series.len() as f64;
+        return series.iter().map(|&(date, _)| (date, mean)).collect();
+    }
+    let before = (window - 1) / 2;
+    let after = window / 2;
+    series
+        .iter()
+        .enumerate()
+        .map(|(i, &(date, _))| {
+            let slice = &series[i.saturating_sub(before)..(i + after + 1).min(series.len())];
+            let mean = slice.iter().map(|&(_, v)| v).sum::<f64>() / slice.len() as f64;
+            (date, mean)
+        })
+        .collect()
+}
+
+/// Least-squares line through `(x, y)` points, as `(slope, intercept, r_squared)`.
+/// `None` with fewer than two distinct `x` values. R² is 1.0 when every `y` is equal.
+#[allow(clippy::cast_precision_loss)]
+fn fit_line(points: &[(f64, f64)]) -> Option<(f64, f64, f64)> {
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|&(x, _)| x).sum::<f64>() / n;
+    let mean_y = points.iter().map(|&(_, y)| y).sum::<f64>() / n;
+    let sxx: f64 = points.iter().map(|&(x, _)| (x - mean_x).powi(2)).sum();
+    let sxy: f64 = points.iter().map(|&(x, y)| (x - mean_x) * (y - mean_y)).sum();
+    let syy: f64 = points.iter().map(|&(_, y)| (y - mean_y).powi(2)).sum();
+    if sxx == 0.0 {
+        return None;
+    }
+    let slope = sxy / sxx;
+    let intercept = mean_y - slope * mean_x;
+    let r_squared = if syy == 0.0 { 1.0 } else { (sxy * sxy) / (sxx * syy) };
+    Some((slope, intercept, r_squared))
+}
+
+/// Adds `extra`'s sets to `target`: sets are summed, the heavier set's weight, reps and
+/// bodyweight are kept (more reps break ties), duration and distance are summed, the average
+/// heart rate is weighted by duration, the higher max heart rate is kept and distinct notes
+/// are joined.
+fn merge_workout_into(target: &mut Workout, extra: &Workout) {
+    let heavier = |w: &Workout| (w.weight.unwrap_or(f64::NEG_INFINITY), w.reps);
+    let (extra_weight, extra_reps) = heavier(extra);
+    let (target_weight, target_reps) = heavier(target);
+    if extra_weight.total_cmp(&target_weight).then(extra_reps.cmp(&target_reps)).is_gt() {
+        target.weight = extra.weight;
+        target.reps = extra.reps;
+        target.bodyweight = extra.bodyweight;
+    }
+    target.sets = Some(target.sets.unwrap_or(1) + extra.sets.unwrap_or(1));
+    target.avg_heart_rate = match (target.avg_heart_rate, extra.avg_heart_rate) {
+        (Some(a), Some(b)) => match (target.duration_minutes, extra.duration_minutes) {
+            (Some(da), Some(db)) if da + db > 0 => Some((a * da + b * db) / (da + db)),
+            _ => Some((a + b) / 2),
+        },
+        (a, b) => a.or(b),
+    };
+    target.max_heart_rate = target.max_heart_rate.max(extra.max_heart_rate);
+    target.duration_minutes = match (target.duration_minutes, extra.duration_minutes) {
+        (Some(a), Some(b)) => Some(a + b),
+        (a, b) => a.or(b),
+    };
+    target.distance = match (target.distance, extra.distance) {
+        (Some(a), Some(b)) => Some(a + b),
+        (a, b) => a.or(b),
+    };
+    if let Some(note) = extra.notes.as_deref().filter(|n| !n.is_empty()) {
+        target.notes = match target.notes.take() {
+            Some(existing) if existing.split("; ").any(|n| n == note) => Some(existing),
+            Some(existing) if !existing.is_empty() => Some(format!("{existing}; {note}")),
+            _ => Some(note.to_string()),
+        };
+    }
+}
+
+/// Picks the entry with the highest E1RM, considering only AMRAP entries if there are any.
+/// Warmups and failed attempts never count.
+fn select_top_set(entries: &[Workout]) -> Option<TopSet> {
+    let counts = |w: &&Workout| !w.is_warmup && !w.is_failure;
+    let has_amrap = entries.iter().filter(counts).any(|w| w.is_amrap);
+    entries
+        .iter()
+        .filter(counts)
+        .filter(|w| w.is_amrap || !has_amrap)
+        .filter_map(|w| {
+            let (weight, reps) = (w.weight?, w.reps?);
+            calculate_e1rm(weight, reps).map(|e1rm| TopSet {
+                workout_id: w.id,
+                reps,
+                weight,
+                e1rm,
+                is_amrap: w.is_amrap,
+            })
+        })
+        .max_by(|a, b| a.e1rm.total_cmp(&b.e1rm))
+}
+
 #[allow(clippy::cast_precision_loss)]
 fn calculate_e1rm(weight: f64, reps: i64) -> Option<f64> {
     if reps > 0 && weight > 0.0 {
@@ -1308,14 +5611,172 @@ fn calculate_e1rm(weight: f64, reps: i64) -> Option<f64> {
     }
 }
 
-/// Creates a UTC timestamp representing noon on the given date.
+/// Checks heart rates (bpm) given for a workout: both must be positive and the average
+/// can't exceed the max.
+/// # Errors
+/// Returns `ServiceError::Validation` if a value is invalid.
+fn check_heart_rates(avg: Option<i64>, max: Option<i64>) -> Result<()> {
+    if let Some(bpm) = [avg, max].into_iter().flatten().find(|&bpm| bpm <= 0) {
+        bail!("Heart rate must be positive, got {bpm} bpm.");
+    }
+    if let (Some(avg), Some(max)) = (avg, max) {
+        if avg > max {
+            bail!("Average heart rate ({avg} bpm) can't exceed max heart rate ({max} bpm).");
+        }
+    }
+    Ok(())
+}
+
+/// Converts a weight between kilograms and pounds.
+fn convert_weight(weight: f64, from: Units, to: Units) -> f64 {
+    match (from, to) {
+        (Units::Imperial, Units::Metric) => weight * LBS_TO_KG,
+        (Units::Metric, Units::Imperial) => weight / LBS_TO_KG,
+        _ => weight,
+    }
+}
+
+/// Creates a UTC timestamp representing local noon on the given date in `tz`.
 /// # Errors
-/// Returns `anyhow::Error` if date components invalid.
-fn create_timestamp_from_date(date: NaiveDate) -> Result<DateTime<Utc>> {
+/// Returns `ServiceError` if date components invalid.
+fn create_timestamp_from_date(date: NaiveDate, tz: Tz) -> Result<DateTime<Utc>> {
     let naive_dt = date
         .and_hms_opt(12, 0, 0)
         .ok_or_else(|| anyhow::anyhow!("Invalid date components: {date}"))?;
-    Ok(Utc.from_utc_datetime(&naive_dt))
+    tz.from_local_datetime(&naive_dt)
+        .earliest()
+        .map(|local| local.with_timezone(&Utc))
+        .ok_or_else(|| anyhow::anyhow!("Noon does not exist on {date} in {tz}"))
+        .map_err(Into::into)
+}
+
+/// Whole minutes (rounded to nearest) between a start and end time.
+/// # Errors
+/// Returns `ServiceError` if `end` is not after `start`.
+fn duration_minutes_between(start: DateTime<Utc>, end: DateTime<Utc>) -> Result<i64> {
+    if end <= start {
+        bail!("End time ({end}) must be after start time ({start}).");
+    }
+    Ok(((end - start).num_seconds() + 30) / 60)
+}
+
+/// Pace in minutes per km, or `None` unless both duration and distance are positive.
+#[must_use]
+#[allow(clippy::cast_precision_loss)]
+pub fn calculate_pace(duration_minutes: i64, distance_km: f64) -> Option<f64> {
+    (duration_minutes > 0 && distance_km > 0.0).then(|| duration_minutes as f64 / distance_km)
+}
+
+/// Rounds `value` to `decimals` decimal places.
+#[must_use]
+pub fn round_to_decimals(value: f64, decimals: u32) -> f64 {
+    let factor = 10f64.powi(i32::try_from(decimals).unwrap_or(i32::MAX).min(15));
+    (value * factor).round() / factor
+}
+
+/// Fewest plates (heaviest first) adding up to `side_weight`, with unlimited plates of each
+/// weight. Weights are matched to the nearest 0.001. On failure returns the heaviest
+/// achievable load below `side_weight`.
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss, clippy::cast_precision_loss)]
+fn load_plates(side_weight: f64, plates: &[f64]) -> std::result::Result<Vec<f64>, f64> {
+    const SCALE: f64 = 1000.0;
+    let to_units = |weight: f64| {
+        let scaled = (weight * SCALE).round();
+        (0.0..=MAX_PLATE_LOAD_PER_SIDE * SCALE).contains(&scaled).then_some(scaled as usize)
+    };
+    let Some(target) = to_units(side_weight) else {
+        return Err(0.0);
+    };
+    // Plates heavier than the target can't be used, so out-of-range ones are dropped too
+    let units: Vec<usize> = plates
+        .iter()
+        .filter_map(|&p| to_units(p))
+        .filter(|&p| p > 0 && p <= target)
+        .collect();
+    // fewest[w] = (plate count, last plate) for the cheapest way to make `w`
+    let mut fewest: Vec<Option<(usize, usize)>> = vec![None; target + 1];
+    fewest[0] = Some((0, 0));
+    for w in 1..=target {
+        fewest[w] = units
+            .iter()
+            .filter(|&&p| p <= w)
+            .filter_map(|&p| fewest[w - p].map(|(count, _)| (count + 1, p)))
+            .min_by_key(|&(count, p)| (count, std::cmp::Reverse(p)));
+    }
+    if fewest[target].is_none() {
+        let closest = (0..target).rev().find(|&w| fewest[w].is_some()).unwrap_or(0);
+        return Err(closest as f64 / SCALE);
+    }
+    let mut loaded = Vec::new();
+    let mut remaining = target;
+    while let Some((_, plate)) = fewest[remaining].filter(|_| remaining > 0) {
+        loaded.push(plate as f64 / SCALE);
+        remaining -= plate;
+    }
+    loaded.sort_by(|a, b| b.total_cmp(a));
+    Ok(loaded)
+}
+
+/// Fills the `{exercise}`, `{metric}` and `{value}` placeholders of a `pb_command`.
+/// Values are single-quoted for the shell, so exercise names can't inject commands.
+#[must_use]
+pub fn render_pb_command(template: &str, event: &PbEvent) -> String {
+    let quote = |value: &str| format!("'{}'", value.replace('\'', "'\\''"));
+    template
+        .replace("{exercise}", &quote(&event.exercise))
+        .replace("{metric}", &quote(&event.metric))
+        .replace("{value}", &quote(&event.value.to_string()))
+}
+
+/// Edit distance (insertions, deletions, substitutions) between two strings, by character.
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let above = row[j + 1];
+            row[j + 1] = (above + 1).min(row[j] + 1).min(diagonal + usize::from(ca != cb));
+            diagonal = above;
+        }
+    }
+    row[b.len()]
+}
+
+/// Rounds `value` to the nearest multiple of `increment`. Non-positive increments leave
+/// the value unchanged.
+#[must_use]
+pub fn round_to_increment(value: f64, increment: f64) -> f64 {
+    if increment <= 0.0 || !increment.is_finite() {
+        return value;
+    }
+    (value / increment).round() * increment
+}
+
+/// Scores how regularly workouts are spaced, from 0 (erratic) to 100 (perfectly even).
+///
+/// Uses the gaps in days between consecutive distinct workout days:
+/// `score = 100 / (1 + cv)`, where `cv = std_dev(gaps) / mean(gaps)` is the coefficient of
+/// variation. Even spacing gives `cv = 0` and a score of 100; the score falls as spacing
+/// becomes less regular. Returns `None` with fewer than 3 distinct workout days.
+/// `dates` are the local dates of the workouts, in any order and with repeats.
+#[allow(clippy::cast_precision_loss)]
+fn calculate_consistency_score(dates: &[NaiveDate]) -> Option<f64> {
+    let mut days = dates.to_vec();
+    days.sort_unstable();
+    days.dedup();
+    if days.len() < 3 {
+        return None;
+    }
+    let gaps: Vec<f64> = days
+        .windows(2)
+        .map(|w| (w[1] - w[0]).num_days() as f64)
+        .collect();
+    let mean = gaps.iter().sum::<f64>() / gaps.len() as f64;
+    let variance = gaps.iter().map(|g| (g - mean).powi(2)).sum::<f64>() / gaps.len() as f64;
+    let cv = variance.sqrt() / mean;
+    Some(100.0 / (1.0 + cv))
 }
 
 /// Calculates current and longest streaks.
@@ -1356,7 +5817,337 @@ fn calculate_streaks(timestamps: &[DateTime<Utc>], interval: Duration) -> (u32,
     (current, longest)
 }
 
+/// Normalizes a workout tag for storage and lookup.
+fn normalize_tag(tag: &str) -> String {
+    tag.trim().to_lowercase()
+}
+
+/// Trims and lowercases a workout metadata key, rejecting empty keys and keys containing
+/// whitespace or `=`.
+fn normalize_metadata_key(key: &str) -> Result<String> {
+    let key = key.trim().to_lowercase();
+    if key.is_empty() {
+        bail!("Metadata key cannot be empty.");
+    }
+    if key.chars().any(|c| c.is_whitespace() || c == '=') {
+        bail!("Invalid metadata key '{key}': must not contain whitespace or '='.");
+    }
+    Ok(key)
+}
+
+/// Parses a `key=value` argument (as given to a repeatable `--meta` flag) into a metadata
+/// pair. The key is normalized as in `AppService::set_workout_metadata`; the value is trimmed.
+/// # Errors
+/// Returns `ServiceError` if there is no `=` or the key is invalid.
+pub fn parse_metadata_arg(arg: &str) -> Result<(String, String)> {
+    let (key, value) = arg
+        .split_once('=')
+        .ok_or_else(|| anyhow::anyhow!("Invalid metadata '{arg}': expected key=value."))?;
+    Ok((normalize_metadata_key(key)?, value.trim().to_string()))
+}
+
+/// Parses a user-entered number using `decimal_separator` ('.' or ',').
+///
+/// The other of the two characters, spaces and `'` are accepted as thousands separators,
+/// but only between groups of three digits, so with '.' as the decimal separator
+/// "1,250.5" is 1250.5 while "100,5" is rejected rather than read as 1005.
+/// # Errors
+/// Returns `ServiceError::Validation` if the input is empty or not a number.
+pub fn parse_number(input: &str, decimal_separator: char) -> Result<f64> {
+    let trimmed = input.trim();
+    if trimmed.is_empty() {
+        bail!("Number cannot be empty.");
+    }
+    let invalid = || {
+        ServiceError::raised(anyhow::anyhow!(
+            "Invalid number '{trimmed}'. Use '{decimal_separator}' as the decimal separator."
+        ))
+    };
+    let (sign, unsigned) = match trimmed.strip_prefix('-') {
+        Some(rest) => ("-", rest),
+        None => ("", trimmed.strip_prefix('+').unwrap_or(trimmed)),
+    };
+    let (integer, fraction) = match unsigned.split_once(decimal_separator) {
+        Some((integer, fraction)) => (integer, Some(fraction)),
+        None => (unsigned, None),
+    };
+    let thousands = |c: char| c != decimal_separator && matches!(c, '.' | ',' | ' ' | '\'');
+    let groups: Vec<&str> = integer.split(thousands).collect();
+    let well_grouped = groups.len() == 1
+        || (!groups[0].is_empty() && groups[0].len() <= 3 && groups[1..].iter().all(|g| g.len() == 3));
+    let all_digits = |part: &str| part.chars().all(|c| c.is_ascii_digit());
+    if !well_grouped
+        || !groups.iter().all(|g| all_digits(g))
+        || fraction.is_some_and(|f| !all_digits(f))
+        || (integer.is_empty() && fraction.is_none_or(str::is_empty))
+    {
+        return Err(invalid());
+    }
+    format!("{sign}{}.{}", groups.concat(), fraction.unwrap_or("0"))
+        .parse::<f64>()
+        .map_err(|_| invalid())
+}
+
+/// Parses a user-entered date relative to `today`.
+///
+/// Accepts (case-insensitive) `today`, `yesterday`, `N days ago`, `N weeks ago`, weekday names
+/// such as `mon` or `friday` (the most recent such day, today included), and `YYYY-MM-DD`.
+/// # Errors
+/// Returns `ServiceError::Validation` if the input matches none of these forms, or the
+/// relative offset is out of range.
+pub fn parse_flexible_date(input: &str, today: NaiveDate) -> Result<NaiveDate> {
+    let normalized = input.trim().to_lowercase();
+    match normalized.as_str() {
+        "" => bail!("Date cannot be empty."),
+        "today" => return Ok(today),
+        "yesterday" => return Ok(today - Duration::days(1)),
+        _ => {}
+    }
+    if let Ok(weekday) = normalized.parse::<Weekday>() {
+        let days_back = (7 + today.weekday().num_days_from_monday()
+            - weekday.num_days_from_monday())
+            % 7;
+        return Ok(today - Duration::days(i64::from(days_back)));
+    }
+    let words: Vec<&str> = normalized.split_whitespace().collect();
+    if let [count, unit, "ago"] = words.as_slice() {
+        let Ok(count) = count.parse::<u32>() else {
+            bail!("Invalid date '{input}': '{count}' is not a whole number.");
+        };
+        let days = match *unit {
+            "day" | "days" => i64::from(count),
+            "week" | "weeks" => i64::from(count) * 7,
+            _ => bail!("Invalid date '{input}': use days or weeks, e.g. '3 days ago'."),
+        };
+        return today
+            .checked_sub_signed(Duration::days(days))
+            .ok_or_else(|| ServiceError::raised(anyhow::anyhow!("Date '{input}' is out of range.")));
+    }
+    NaiveDate::parse_from_str(&normalized, "%Y-%m-%d").map_err(|_| {
+        ServiceError::raised(anyhow::anyhow!(
+            "Invalid date '{input}'. Use YYYY-MM-DD, today, yesterday, a weekday name or 'N days ago'."
+        ))
+    })
+}
+
+/// Checks the ranges of a bodyweight entry's values.
+fn validate_body_measurement(params: &BodyMeasurementParams) -> Result<()> {
+    if params.weight <= 0.0 {
+        bail!(ConfigError::InvalidBodyweightInput(
+            "Bodyweight must be positive.".to_string()
+        ));
+    }
+    if params.body_fat_pct.is_some_and(|bf| !(bf > 0.0 && bf < 100.0)) {
+        bail!(ConfigError::InvalidBodyweightInput(
+            "Body fat must be between 0 and 100%.".to_string()
+        ));
+    }
+    if params.muscle_mass.is_some_and(|mm| mm <= 0.0) {
+        bail!(ConfigError::InvalidBodyweightInput(
+            "Muscle mass must be positive.".to_string()
+        ));
+    }
+    Ok(())
+}
+
+/// True if a bodyweight CSV line is a header, i.e. its weight column isn't a number.
+fn is_bodyweight_csv_header(fields: &[String]) -> bool {
+    fields
+        .get(1)
+        .is_none_or(|weight| weight.parse::<f64>().is_err())
+}
+
+/// Infers an exercise type from its name using `Config::type_keywords`.
+///
+/// A keyword matches when a word of the name (case-insensitive, split on spaces and
+/// punctuation other than `-`) starts with it, so "run" matches "Running" but not "Brunch".
+/// Returns `None` when nothing matches or keywords for different types match.
+#[must_use]
+pub fn infer_exercise_type(name: &str, config: &Config) -> Option<ExerciseType> {
+    let name = name.to_lowercase();
+    let words: Vec<&str> = name
+        .split(|c: char| !c.is_alphanumeric() && c != '-')
+        .filter(|w| !w.is_empty())
+        .collect();
+    let mut inferred = None;
+    for (keyword, &type_) in &config.type_keywords {
+        let keyword = keyword.trim().to_lowercase();
+        if keyword.is_empty() || !words.iter().any(|w| w.starts_with(&keyword)) {
+            continue;
+        }
+        match inferred {
+            Some(existing) if existing != type_ => return None, // Ambiguous
+            _ => inferred = Some(type_),
+        }
+    }
+    inferred
+}
+
+/// Common exercises added by `AppService::seed_default_exercises`, as `(name, type, muscles)`.
+pub const DEFAULT_EXERCISES: &[(&str, ExerciseType, &str)] = &[
+    ("Bench Press", ExerciseType::Resistance, "chest,triceps,shoulders"),
+    ("Incline Bench Press", ExerciseType::Resistance, "chest,shoulders,triceps"),
+    ("Overhead Press", ExerciseType::Resistance, "shoulders,triceps"),
+    ("Squat", ExerciseType::Resistance, "quads,glutes,hamstrings"),
+    ("Front Squat", ExerciseType::Resistance, "quads,glutes"),
+    ("Deadlift", ExerciseType::Resistance, "hamstrings,glutes,back"),
+    ("Romanian Deadlift", ExerciseType::Resistance, "hamstrings,glutes"),
+    ("Barbell Row", ExerciseType::Resistance, "back,biceps"),
+    ("Lat Pulldown", ExerciseType::Resistance, "back,biceps"),
+    ("Leg Press", ExerciseType::Resistance, "quads,glutes"),
+    ("Lunge", ExerciseType::Resistance, "quads,glutes"),
+    ("Hip Thrust", ExerciseType::Resistance, "glutes,hamstrings"),
+    ("Bicep Curl", ExerciseType::Resistance, "biceps"),
+    ("Tricep Extension", ExerciseType::Resistance, "triceps"),
+    ("Lateral Raise", ExerciseType::Resistance, "shoulders"),
+    ("Calf Raise", ExerciseType::Resistance, "calves"),
+    ("Pull-up", ExerciseType::BodyWeight, "back,biceps"),
+    ("Chin-up", ExerciseType::BodyWeight, "back,biceps"),
+    ("Push-up", ExerciseType::BodyWeight, "chest,triceps,shoulders"),
+    ("Dip", ExerciseType::BodyWeight, "chest,triceps"),
+    ("Running", ExerciseType::Cardio, "legs"),
+    ("Cycling", ExerciseType::Cardio, "legs"),
+    ("Rowing", ExerciseType::Cardio, "back,legs"),
+    ("Walking", ExerciseType::Cardio, "legs"),
+    ("Swimming", ExerciseType::Cardio, "full body"),
+];
+
+/// Column names of `AppService::export_workouts_to_csv`.
+const WORKOUT_CSV_HEADER: [&str; 13] = [
+    "id",
+    "timestamp",
+    "exercise",
+    "sets",
+    "reps",
+    "weight",
+    "duration_minutes",
+    "distance",
+    "bodyweight",
+    "is_warmup",
+    "is_amrap",
+    "is_failure",
+    "notes",
+];
+
+/// Rejects dialects that can't round-trip and date formats chrono can't render.
+fn validate_csv_options(options: &CsvOptions) -> Result<()> {
+    if options.delimiter == options.quote {
+        bail!("CSV delimiter and quote must differ.");
+    }
+    if ['\r', '\n'].contains(&options.delimiter) || ['\r', '\n'].contains(&options.quote) {
+        bail!("CSV delimiter and quote can't be line breaks.");
+    }
+    if !matches!(options.line_ending.as_str(), "\n" | "\r\n") {
+        bail!("CSV line ending must be \"\\n\" or \"\\r\\n\".");
+    }
+    if let Some(format) = options.date_format.as_deref() {
+        let invalid = format.is_empty()
+            || chrono::format::StrftimeItems::new(format)
+                .any(|item| matches!(item, chrono::format::Item::Error));
+        if invalid {
+            bail!("Invalid date format '{format}'.");
+        }
+    }
+    Ok(())
+}
+
+/// Splits one CSV line into trimmed fields. Quoted fields may contain the delimiter, and a
+/// doubled quote inside them stands for a literal quote.
+fn split_csv_line(line: &str, options: &CsvOptions) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        if in_quotes {
+            if c != options.quote {
+                field.push(c);
+            } else if chars.peek() == Some(&options.quote) {
+                field.push(c);
+                chars.next();
+            } else {
+                in_quotes = false;
+            }
+        } else if c == options.quote {
+            in_quotes = true;
+        } else if c == options.delimiter {
+            fields.push(field.trim().to_string());
+            field.clear();
+        } else {
+            field.push(c);
+        }
+    }
+    fields.push(field.trim().to_string());
+    fields
+}
+
+/// Writes one CSV row, quoting fields that contain the delimiter, quote or a line break.
+fn write_csv_row<W: Write, S: AsRef<str>>(
+    writer: &mut W,
+    fields: &[S],
+    options: &CsvOptions,
+) -> Result<()> {
+    let quote = options.quote.to_string();
+    let row = fields
+        .iter()
+        .map(|field| {
+            let field = field.as_ref();
+            if field.contains([options.delimiter, options.quote, '\r', '\n']) {
+                format!("{quote}{}{quote}", field.replace(&quote, &quote.repeat(2)))
+            } else {
+                field.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(&options.delimiter.to_string());
+    write!(writer, "{row}{}", options.line_ending).context("Failed to write CSV row")?;
+    Ok(())
+}
+
+/// Splits a comma-separated `muscles` field into trimmed, lowercase muscle names.
+fn parse_muscles(muscles: Option<&str>) -> Vec<String> {
+    muscles
+        .unwrap_or_default()
+        .split(',')
+        .map(|m| m.trim().to_lowercase())
+        .filter(|m| !m.is_empty())
+        .collect()
+}
+
+/// Estimates calories burned (kcal) as MET x bodyweight (kg) x duration (hours).
+/// Resistance exercises are not estimated.
+#[allow(clippy::cast_precision_loss)]
+fn estimate_calories_burned(
+    ex_def: &ExerciseDefinition,
+    duration_minutes: Option<i64>,
+    bodyweight_kg: Option<f64>,
+) -> Option<f64> {
+    if ex_def.type_ == ExerciseType::Resistance {
+        return None;
+    }
+    let met = ex_def.met_value?;
+    let minutes = duration_minutes.filter(|&d| d > 0)?;
+    let bodyweight = bodyweight_kg.filter(|&bw| bw > 0.0)?;
+    Some(met * bodyweight * (minutes as f64 / 60.0))
+}
+
 /// Calculates the effective weight (additional + bodyweight) for calculations.
+/// Duration of a new entry: the explicit value, or the minutes between start and end time.
+fn entry_duration(params: &AddWorkoutParams) -> Result<Option<i64>> {
+    Ok(match (params.duration, params.start_time, params.end_time) {
+        (Some(explicit), _, _) => Some(explicit),
+        (None, Some(start), Some(end)) => Some(duration_minutes_between(start, end)?),
+        _ => None,
+    })
+}
+
+/// Volume of one entry: sets * reps * effective weight (sets default to 1).
+#[allow(clippy::cast_precision_loss)]
+fn workout_volume(ex_def: &ExerciseDefinition, w: &Workout) -> f64 {
+    let weight = calculate_effective_weight(ex_def, w.weight, w.bodyweight).unwrap_or(0.0);
+    w.sets.unwrap_or(1).max(1) as f64 * w.reps.unwrap_or(0) as f64 * weight
+}
+
 fn calculate_effective_weight(
     ex_def: &ExerciseDefinition,
     additional_weight: Option<f64>,
@@ -1,13 +1,24 @@
 // tests/lib_test.rs
 use anyhow::Result;
-use chrono::{DateTime, Duration, NaiveDate, Utc};
+use chrono::{DateTime, Datelike, Duration, NaiveDate, TimeZone, Utc, Weekday};
+use std::sync::Arc;
 use std::thread;
 use std::time::Duration as StdDuration;
 use task_athlete_lib::{
-    AddWorkoutParams, AppService, Config, ConfigError, DbError, EditWorkoutParams, ExerciseType,
-    GraphType, Units, VolumeFilters, WorkoutFilters,
+    AddWorkoutParams, AppService, CLEAR_ALL_DATA_CONFIRMATION, CsvOptions, DEFAULT_EXERCISES, BodyMeasurementParams, BodyMetric, Config, ConfigError, DbError,
+    EditExerciseParams, EditWorkoutParams, ExerciseDefaults, ExerciseFilters, ExerciseSort, ExerciseType, GraphTransform, GraphType, HrZone, PbMetric, ProgressionScheme, ServiceError, UndoAction, Units, VolumeFilters,
+    ExerciseMatch, MatchKind, MergeResult, MuscleMatch, NewExerciseParams, PurgeCounts, RecentChange, SearchResult, PbEvent, Theme, TimeOfDay, ValidationConfig, VolumePeriod,
+    WorkoutFilters, calculate_pace, moving_average, parse_flexible_date, parse_metadata_arg, render_pb_command, infer_exercise_type, parse_number, round_to_decimals, round_to_increment,
 };
 
+// Unique config directory per test service, so tests don't write to the repo or race each
+// other (files like the sync base are kept next to the config)
+fn test_config_path() -> std::path::PathBuf {
+    std::env::temp_dir()
+        .join(format!("ta_test_{}", uuid::Uuid::new_v4()))
+        .join("config.toml")
+}
+
 // Helper function to create a test service with in-memory database
 fn create_test_service() -> Result<AppService> {
     let conn = rusqlite::Connection::open_in_memory()?;
@@ -22,12 +33,14 @@ fn create_test_service() -> Result<AppService> {
     config.pb_notifications.notify_reps = true;
     config.pb_notifications.notify_duration = true;
     config.pb_notifications.notify_distance = true;
+    config.pb_notifications.notify_volume = true;
+    config.pb_notifications.notify_pace = true;
 
     Ok(AppService {
         config,
-        conn,
+        conn: conn.into(),
         db_path: ":memory:".into(),
-        config_path: "test_config.toml".into(),
+        config_path: test_config_path(),
     })
 }
 
@@ -120,6 +133,7 @@ fn test_exercise_aliases() -> Result<()> {
         implicit_type: None,
         implicit_muscles: None,
         bodyweight_to_use: None,
+        ..Default::default()
     };
     let (workout_id, _) = service.add_workout(add_params)?;
 
@@ -166,16 +180,18 @@ fn test_edit_exercise_with_alias_and_name_change() -> Result<()> {
         implicit_type: None,
         implicit_muscles: None,
         bodyweight_to_use: None,
+        ..Default::default()
     };
     service.add_workout(add_params)?;
 
     // Edit using the alias identifier
     service.edit_exercise(
         "on", // Identify by alias
-        Some("New Name"),
-        None,
-        None,                          // Keep type
-        Some(Some("muscle1,muscle2")), // Change muscles
+        EditExerciseParams {
+            new_name: Some("New Name"),
+            new_muscles: Some(Some("muscle1,muscle2")), // Change muscles
+            ..Default::default()
+        },
     )?;
 
     // --- Verification ---
@@ -257,7 +273,7 @@ fn test_delete_exercise_with_alias() -> Result<()> {
 #[test]
 fn test_add_workout_past_date() -> Result<()> {
     let mut service = create_test_service()?;
-    let res = service.list_exercises(None, None);
+    let res = service.list_exercises(&ExerciseFilters::default());
     println!("here");
     res.unwrap_or(Vec::new())
         .into_iter()
@@ -279,6 +295,7 @@ fn test_add_workout_past_date() -> Result<()> {
         implicit_type: None,
         implicit_muscles: None,
         bodyweight_to_use: None,
+        ..Default::default()
     })?;
     service.add_workout(AddWorkoutParams {
         exercise_identifier: "Rowing",
@@ -292,6 +309,7 @@ fn test_add_workout_past_date() -> Result<()> {
         implicit_type: None,
         implicit_muscles: None,
         bodyweight_to_use: None,
+        ..Default::default()
     })?;
 
     // List for yesterday
@@ -340,6 +358,7 @@ fn test_edit_workout_date() -> Result<()> {
         implicit_type: None,
         implicit_muscles: None,
         bodyweight_to_use: Some(70.0), // Use config bodyweight
+        ..Default::default()
     })?;
 
     // Edit the date
@@ -446,9 +465,15 @@ fn test_pb_detection_and_config() -> Result<()> {
     assert_eq!(info4.reps.previous_value, Some(6), "PB4: Prev reps");
     thread::sleep(StdDuration::from_millis(10));
 
-    // Workout 5: No PB
+    // Workout 5: No weight/reps PB, but a volume PB (5*5*105 = 2625 > 3*6*90 = 1620)
     let (_, pb5) = add_dl_workout!(Some(5), Some(5), Some(105.0))?;
-    assert!(pb5.is_none(), "PB5: Should not detect PB");
+    assert!(pb5.is_some(), "PB5: Should detect volume PB");
+    let info5 = pb5.unwrap();
+    assert!(!info5.weight.achieved, "PB5: Weight PB flag");
+    assert!(!info5.reps.achieved, "PB5: Reps PB flag");
+    assert!(info5.volume.achieved, "PB5: Volume PB flag");
+    assert_eq!(info5.volume.new_value, Some(2625.0), "PB5: New volume");
+    assert_eq!(info5.volume.previous_value, Some(1620.0), "PB5: Prev volume");
     thread::sleep(StdDuration::from_millis(10));
 
     // --- Test Disabling Specific PBs ---
@@ -560,33 +585,63 @@ fn test_create_and_list_exercises() -> Result<()> {
     )?;
 
     // List all
-    let exercises = service.list_exercises(None, None)?;
+    let exercises = service.list_exercises(&ExerciseFilters::default())?;
     assert_eq!(exercises.len(), 3);
 
     // Filter by type
-    let resistance_exercises = service.list_exercises(Some(ExerciseType::Resistance), None)?;
+    let resistance_exercises = service.list_exercises(&ExerciseFilters { type_: Some(ExerciseType::Resistance), ..Default::default() })?;
     assert_eq!(resistance_exercises.len(), 1);
     assert_eq!(resistance_exercises[0].name, "Bench Press");
 
     // Filter by muscle
-    let leg_exercises = service.list_exercises(None, Some(vec!["legs"]))?;
+    let leg_exercises = service.list_exercises(&ExerciseFilters { muscle: Some(vec!["legs"]), ..Default::default() })?;
     assert_eq!(leg_exercises.len(), 1); // Running
     assert_eq!(leg_exercises[0].name, "Running");
 
-    let back_exercises = service.list_exercises(None, Some(vec!["back"]))?;
+    let back_exercises = service.list_exercises(&ExerciseFilters { muscle: Some(vec!["back"]), ..Default::default() })?;
     assert_eq!(back_exercises.len(), 1); // Pull-ups
     assert_eq!(back_exercises[0].name, "Pull-ups");
 
-    let back_biceps_exercises = service.list_exercises(None, Some(vec!["back", "biceps"]))?;
+    let back_biceps_exercises = service.list_exercises(&ExerciseFilters { muscle: Some(vec!["back", "biceps"]), ..Default::default() })?;
     assert_eq!(back_biceps_exercises.len(), 1); // Pull-ups
     assert_eq!(back_biceps_exercises[0].name, "Pull-ups");
 
-    let back_abs_exercises = service.list_exercises(None, Some(vec!["back", "abs"]))?;
+    let back_abs_exercises = service.list_exercises(&ExerciseFilters { muscle: Some(vec!["back", "abs"]), ..Default::default() })?;
     assert_eq!(back_abs_exercises.len(), 0); // Pull-ups
 
     Ok(())
 }
 
+#[test]
+fn test_list_exercises_muscle_match() -> Result<()> {
+    let service = create_test_service()?;
+    service.create_exercise("Bench Press", ExerciseType::Resistance, None, Some("chest,triceps"))?;
+    service.create_exercise("Curl", ExerciseType::Resistance, None, Some("biceps"))?;
+    service.create_exercise("Fly", ExerciseType::Resistance, None, Some("chest"))?;
+    let names = |muscles: Vec<&str>, muscle_match| -> Result<Vec<String>> {
+        Ok(service
+            .list_exercises(&ExerciseFilters { muscle: Some(muscles), muscle_match, ..Default::default() })?
+            .into_iter()
+            .map(|e| e.name)
+            .collect())
+    };
+
+    assert_eq!(names(vec!["chest", "triceps"], MuscleMatch::All)?, vec!["Bench Press"]);
+    assert_eq!(
+        names(vec!["chest", "biceps"], MuscleMatch::Any)?,
+        vec!["Bench Press", "Curl", "Fly"]
+    );
+    assert!(names(vec!["chest", "biceps"], MuscleMatch::All)?.is_empty());
+
+    // Matching is by substring: partial words match, and "ceps" hits biceps and triceps
+    assert_eq!(names(vec!["bic"], MuscleMatch::All)?, vec!["Curl"]);
+    assert_eq!(names(vec!["CEPS"], MuscleMatch::Any)?, vec!["Bench Press", "Curl"]);
+    // Blank entries don't filter anything
+    assert_eq!(names(vec![" ", ""], MuscleMatch::Any)?.len(), 3);
+    assert_eq!(names(vec!["chest", " "], MuscleMatch::Any)?, vec!["Bench Press", "Fly"]);
+    Ok(())
+}
+
 #[test]
 fn test_pb_config_interaction() -> Result<()> {
     let mut service = create_test_service()?; // PB enabled: Some(true) initially
@@ -656,6 +711,7 @@ fn test_list_filter_with_alias() -> Result<()> {
         implicit_type: None,
         implicit_muscles: None,
         bodyweight_to_use: None,
+        ..Default::default()
     })?;
 
     // Filter list using alias
@@ -702,6 +758,7 @@ fn test_add_and_list_workouts_with_distance() -> Result<()> {
         implicit_type: None,
         implicit_muscles: None,
         bodyweight_to_use: None,
+        ..Default::default()
     })?;
     service.add_workout(AddWorkoutParams {
         exercise_identifier: "Running",
@@ -715,6 +772,7 @@ fn test_add_and_list_workouts_with_distance() -> Result<()> {
         implicit_type: None,
         implicit_muscles: None,
         bodyweight_to_use: None,
+        ..Default::default()
     })?;
 
     // List workouts
@@ -759,6 +817,7 @@ fn test_add_workout_imperial_distance() -> Result<()> {
         implicit_type: None,
         implicit_muscles: None,
         bodyweight_to_use: None,
+        ..Default::default()
     })?;
 
     // List workout and check stored distance (should be km)
@@ -792,6 +851,7 @@ fn test_edit_workout_distance() -> Result<()> {
         implicit_type: None,
         implicit_muscles: None,
         bodyweight_to_use: None,
+        ..Default::default()
     })?;
 
     // Edit distance (Metric input)
@@ -855,6 +915,7 @@ fn test_bodyweight_workouts() -> Result<()> {
         implicit_type: Some(ExerciseType::BodyWeight),
         implicit_muscles: None,
         bodyweight_to_use: Some(70.0), // Explicitly pass BW for test clarity
+        ..Default::default()
     })?;
     println!("body");
 
@@ -880,10 +941,12 @@ fn test_edit_exercise() -> Result<()> {
     // Edit the exercise
     service.edit_exercise(
         "Bench Press",
-        Some("Barbell Bench Press"),
-        Some(ExerciseType::Resistance),
-        None,                                  // Keep type
-        Some(Some("chest,triceps,shoulders")), // Update muscles
+        EditExerciseParams {
+            new_name: Some("Barbell Bench Press"),
+            new_type: Some(ExerciseType::Resistance),
+            new_muscles: Some(Some("chest,triceps,shoulders")), // Update muscles
+            ..Default::default()
+        },
     )?;
 
     // Verify changes
@@ -897,7 +960,7 @@ fn test_edit_exercise() -> Result<()> {
     );
 
     // Try editing non-existent exercise
-    let edit_result = service.edit_exercise("NonExistent", Some("WontWork"), None, None, None);
+    let edit_result = service.edit_exercise("NonExistent", EditExerciseParams { new_name: Some("WontWork"), ..Default::default() });
     assert!(edit_result.is_err());
     assert!(matches!(
         edit_result.unwrap_err().downcast_ref::<DbError>(),
@@ -955,6 +1018,7 @@ fn test_workout_filters() -> Result<()> {
         implicit_type: None,
         implicit_muscles: None,
         bodyweight_to_use: None,
+        ..Default::default()
     })?;
     thread::sleep(StdDuration::from_millis(10)); // Ensure different timestamp
     service.add_workout(AddWorkoutParams {
@@ -969,6 +1033,7 @@ fn test_workout_filters() -> Result<()> {
         implicit_type: None,
         implicit_muscles: None,
         bodyweight_to_use: None,
+        ..Default::default()
     })?;
 
     // Filter by type
@@ -1121,7 +1186,7 @@ fn test_exercise_not_found() -> Result<()> {
     assert!(result.is_ok() && result?.is_none()); // Should be Ok(None)
 
     // Try to edit non-existent exercise
-    let result = service.edit_exercise("Non-existent", None, None, None, None);
+    let result = service.edit_exercise("Non-existent", EditExerciseParams::default());
     assert!(result.is_err());
     assert!(matches!(
         result.unwrap_err().downcast_ref::<DbError>(),
@@ -1149,6 +1214,7 @@ fn test_exercise_not_found() -> Result<()> {
         implicit_type: None,
         implicit_muscles: None,
         bodyweight_to_use: None,
+        ..Default::default()
     });
     assert!(result.is_err());
     assert!(result
@@ -1262,6 +1328,7 @@ fn test_workout_volume() -> Result<()> {
         implicit_type: None,
         implicit_muscles: None,
         bodyweight_to_use: None,
+        ..Default::default()
     })?; // Vol=3000
     add_workout(AddWorkoutParams {
         exercise_identifier: "Bench Press",
@@ -1275,6 +1342,7 @@ fn test_workout_volume() -> Result<()> {
         implicit_type: None,
         implicit_muscles: None,
         bodyweight_to_use: None,
+        ..Default::default()
     })?; // Vol=840
     add_workout(AddWorkoutParams {
         exercise_identifier: "Pull-ups",
@@ -1288,6 +1356,7 @@ fn test_workout_volume() -> Result<()> {
         implicit_type: None,
         implicit_muscles: None,
         bodyweight_to_use: Some(70.0),
+        ..Default::default()
     })?; // Vol=1920
     add_workout(AddWorkoutParams {
         exercise_identifier: "Running",
@@ -1301,6 +1370,7 @@ fn test_workout_volume() -> Result<()> {
         implicit_type: None,
         implicit_muscles: None,
         bodyweight_to_use: None,
+        ..Default::default()
     })?; // Vol=0
 
     // Day 2
@@ -1316,6 +1386,7 @@ fn test_workout_volume() -> Result<()> {
         implicit_type: None,
         implicit_muscles: None,
         bodyweight_to_use: None,
+        ..Default::default()
     })?; // Vol=3000
     add_workout(AddWorkoutParams {
         exercise_identifier: "Bench Press",
@@ -1329,6 +1400,7 @@ fn test_workout_volume() -> Result<()> {
         implicit_type: None,
         implicit_muscles: None,
         bodyweight_to_use: None,
+        ..Default::default()
     })?; // Vol=2400
 
     // --- Test Volume Calculation ---
@@ -1445,6 +1517,7 @@ fn test_exercise_stats() -> Result<()> {
             implicit_type: None,
             implicit_muscles: None,
             bodyweight_to_use: None,
+            ..Default::default()
         })?;
         thread::sleep(StdDuration::from_millis(5)); // Ensure unique timestamp
         Ok(())
@@ -1527,6 +1600,7 @@ fn test_exercise_stats() -> Result<()> {
         implicit_type: None,
         implicit_muscles: None,
         bodyweight_to_use: None,
+        ..Default::default()
     })?;
     let one_stats = service.get_exercise_stats("One Workout")?;
 
@@ -1603,6 +1677,7 @@ fn test_bodyweight_workout_needs_log() -> Result<()> {
         implicit_type: None,
         implicit_muscles: None,
         bodyweight_to_use: None, // Simulate main not finding a logged BW
+        ..Default::default()
     });
 
     assert!(result.is_err());
@@ -1628,6 +1703,7 @@ fn test_bodyweight_workout_needs_log() -> Result<()> {
         implicit_type: None,
         implicit_muscles: None,
         bodyweight_to_use: Some(logged_bw), // Pass the fetched BW
+        ..Default::default()
     });
 
     assert!(add_result.is_ok());
@@ -1685,6 +1761,50 @@ fn test_add_list_bodyweight() -> Result<()> {
     Ok(())
 }
 
+#[test]
+fn test_bodyweight_notes() -> Result<()> {
+    let service = create_test_service()?;
+    let now = Utc::now();
+    service.add_body_measurement(&BodyMeasurementParams {
+        timestamp: now - Duration::days(1),
+        weight: 80.4,
+        notes: Some(" morning fasted ".to_string()),
+        ..Default::default()
+    })?;
+    service.add_body_measurement(&BodyMeasurementParams {
+        timestamp: now,
+        weight: 81.2,
+        notes: Some("  ".to_string()),
+        ..Default::default()
+    })?;
+    service.add_bodyweight_entry(now - Duration::days(2), 80.0)?;
+
+    let entries = service.list_body_measurements(10)?;
+    let notes: Vec<_> = entries.iter().map(|e| e.notes.as_deref()).collect();
+    assert_eq!(notes, vec![None, Some("morning fasted"), None]);
+    assert_eq!(
+        service.list_body_measurements(1)?[0].notes,
+        None,
+        "blank notes are stored as NULL"
+    );
+
+    let mut out = Vec::new();
+    service.export_bodyweights_to_csv(&mut out, &CsvOptions::default())?;
+    let csv = String::from_utf8(out)?;
+    assert!(csv.lines().any(|line| line.ends_with(",80.4,,,morning fasted")));
+
+    let imported = service.import_bodyweights_from_csv(
+        "2024-01-01,79.0,,,\"post-workout, hydrated\"\n".as_bytes(),
+        false,
+        &CsvOptions::default(),
+        None,
+    )?;
+    assert_eq!(imported.imported, 1);
+    let oldest = service.list_body_measurements(10)?.pop().expect("imported entry");
+    assert_eq!(oldest.notes.as_deref(), Some("post-workout, hydrated"));
+    Ok(())
+}
+
 #[test]
 fn test_target_bodyweight_config() -> Result<()> {
     let mut service = create_test_service()?;
@@ -1734,6 +1854,7 @@ fn get_all_dates_exercised() -> Result<()> {
             implicit_type: None,
             implicit_muscles: None,
             bodyweight_to_use: None,
+            ..Default::default()
         })?;
         thread::sleep(StdDuration::from_millis(5)); // Ensure unique timestamp if needed
         Ok(())
@@ -1841,7 +1962,7 @@ fn test_graph_data_fetching() -> Result<()> {
 
     // Test E1RM
     let e1rm_data =
-        service.get_data_for_graph("Bench Press", GraphType::Estimated1RM, None, None)?; // Added None, None
+        service.get_data_for_graph("Bench Press", GraphType::Estimated1RM, None, None, None)?; // Added None, None
     println!("here44");
     assert_eq!(
         e1rm_data,
@@ -1855,7 +1976,7 @@ fn test_graph_data_fetching() -> Result<()> {
 
     // Test Max Weight
     let weight_data =
-        service.get_data_for_graph("Bench Press", GraphType::MaxWeight, None, None)?; // Added None, None
+        service.get_data_for_graph("Bench Press", GraphType::MaxWeight, None, None, None)?; // Added None, None
     assert_eq!(
         weight_data,
         vec![
@@ -1866,7 +1987,7 @@ fn test_graph_data_fetching() -> Result<()> {
     );
 
     // Test Max Reps
-    let reps_data = service.get_data_for_graph("Bench Press", GraphType::MaxReps, None, None)?; // Added None, None
+    let reps_data = service.get_data_for_graph("Bench Press", GraphType::MaxReps, None, None, None)?; // Added None, None
     assert_eq!(
         reps_data,
         vec![
@@ -1878,7 +1999,7 @@ fn test_graph_data_fetching() -> Result<()> {
 
     // Test Workout Volume
     let volume_data =
-        service.get_data_for_graph("Bench Press", GraphType::WorkoutVolume, None, None)?; // Added None, None
+        service.get_data_for_graph("Bench Press", GraphType::WorkoutVolume, None, None, None)?; // Added None, None
     assert_eq!(
         volume_data,
         vec![
@@ -1890,7 +2011,7 @@ fn test_graph_data_fetching() -> Result<()> {
 
     // Test Workout Reps (Total reps)
     let workout_reps_data =
-        service.get_data_for_graph("Bench Press", GraphType::WorkoutReps, None, None)?; // Added None, None
+        service.get_data_for_graph("Bench Press", GraphType::WorkoutReps, None, None, None)?; // Added None, None
     assert_eq!(
         workout_reps_data,
         vec![
@@ -1902,7 +2023,7 @@ fn test_graph_data_fetching() -> Result<()> {
 
     // Test Workout Duration (Running - Summed)
     let duration_data =
-        service.get_data_for_graph("Running", GraphType::WorkoutDuration, None, None)?; // Added None, None
+        service.get_data_for_graph("Running", GraphType::WorkoutDuration, None, None, None)?; // Added None, None
     assert_eq!(
         duration_data,
         vec![
@@ -1913,7 +2034,7 @@ fn test_graph_data_fetching() -> Result<()> {
 
     // Test Workout Distance (Running - Metric - Summed)
     let distance_data_metric =
-        service.get_data_for_graph("Running", GraphType::WorkoutDistance, None, None)?; // Added None, None
+        service.get_data_for_graph("Running", GraphType::WorkoutDistance, None, None, None)?; // Added None, None
     assert_eq!(
         distance_data_metric,
         vec![
@@ -1925,7 +2046,7 @@ fn test_graph_data_fetching() -> Result<()> {
     // Test Workout Distance (Running - Imperial - Summed)
     service.config.units = Units::Imperial; // Assuming Units enum is available
     let distance_data_imperial =
-        service.get_data_for_graph("Running", GraphType::WorkoutDistance, None, None)?; // Added None, None
+        service.get_data_for_graph("Running", GraphType::WorkoutDistance, None, None, None)?; // Added None, None
     assert_eq!(distance_data_imperial.len(), 2);
     assert_eq!(distance_data_imperial[0].0, date_2023_10_27); // Compare with NaiveDate
     assert!((distance_data_imperial[0].1 - (7.0 * 0.621_371)).abs() < 0.01); // miles
@@ -1934,7 +2055,7 @@ fn test_graph_data_fetching() -> Result<()> {
 
     // Test for exercise with no data
     service.create_exercise("Untouched", ExerciseType::Resistance, None, None)?;
-    let no_data = service.get_data_for_graph("Untouched", GraphType::MaxWeight, None, None)?; // Added None, None
+    let no_data = service.get_data_for_graph("Untouched", GraphType::MaxWeight, None, None, None)?; // Added None, None
     assert!(no_data.is_empty());
 
     Ok(())
@@ -2243,3 +2364,4253 @@ fn test_get_workout_dates_for_month() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_muscle_volume_breakdown() -> Result<()> {
+    let mut service = create_test_service()?;
+    service.create_exercise(
+        "Bench Press",
+        ExerciseType::Resistance,
+        None,
+        Some("chest, triceps"),
+    )?;
+    service.create_exercise("Pull-ups", ExerciseType::BodyWeight, None, Some("back"))?;
+    service.create_exercise("Curl", ExerciseType::Resistance, None, None)?;
+    let day = NaiveDate::from_ymd_opt(2024, 3, 4).unwrap();
+    let date: DateTime<Utc> =
+        DateTime::from_naive_utc_and_offset(day.and_hms_opt(12, 0, 0).unwrap(), Utc);
+
+    service.add_workout(AddWorkoutParams {
+        exercise_identifier: "Bench Press",
+        date,
+        sets: Some(3),
+        reps: Some(10),
+        weight: Some(100.0),
+        ..Default::default()
+    })?; // 3000
+    service.add_workout(AddWorkoutParams {
+        exercise_identifier: "Pull-ups",
+        date,
+        sets: Some(2),
+        reps: Some(5),
+        weight: Some(10.0),
+        bodyweight_to_use: Some(70.0),
+        ..Default::default()
+    })?; // 2 * 5 * 80 = 800
+    service.add_workout(AddWorkoutParams {
+        exercise_identifier: "Curl",
+        date,
+        sets: Some(1),
+        reps: Some(10),
+        weight: Some(20.0),
+        ..Default::default()
+    })?; // 200
+
+    let breakdown = service.muscle_volume_breakdown(Some(day), Some(day))?;
+    assert_eq!(breakdown.len(), 4);
+    assert_eq!(breakdown.get("chest"), Some(&3000.0));
+    assert_eq!(breakdown.get("triceps"), Some(&3000.0));
+    assert_eq!(breakdown.get("back"), Some(&800.0));
+    assert_eq!(breakdown.get("unspecified"), Some(&200.0));
+
+    let next_day = day + Duration::days(1);
+    assert!(service
+        .muscle_volume_breakdown(Some(next_day), None)?
+        .is_empty());
+
+    Ok(())
+}
+
+#[test]
+fn test_volume_aggregation_periods() -> Result<()> {
+    let mut service = create_test_service()?;
+    service.create_exercise("Squats", ExerciseType::Resistance, None, Some("legs"))?;
+
+    // Wed 2024-01-31, Sun 2024-02-04 (same ISO week), Mon 2024-02-05 (next week)
+    for (y, m, d, weight) in [(2024, 1, 31, 100.0), (2024, 2, 4, 110.0), (2024, 2, 5, 120.0)] {
+        let day = NaiveDate::from_ymd_opt(y, m, d).unwrap();
+        service.add_workout(AddWorkoutParams {
+            exercise_identifier: "Squats",
+            date: DateTime::from_naive_utc_and_offset(day.and_hms_opt(12, 0, 0).unwrap(), Utc),
+            sets: Some(1),
+            reps: Some(10),
+            weight: Some(weight),
+            ..Default::default()
+        })?;
+    }
+
+    let daily = service.calculate_daily_volume(&VolumeFilters::default())?;
+    assert_eq!(daily.len(), 3);
+
+    let weekly = service.calculate_daily_volume(&VolumeFilters {
+        aggregation: VolumePeriod::Weekly,
+        ..Default::default()
+    })?;
+    assert_eq!(
+        weekly,
+        vec![
+            (
+                NaiveDate::from_ymd_opt(2024, 2, 5).unwrap(),
+                "Squats".to_string(),
+                1200.0
+            ),
+            (
+                NaiveDate::from_ymd_opt(2024, 1, 29).unwrap(),
+                "Squats".to_string(),
+                2100.0
+            ),
+        ]
+    );
+
+    let monthly = service.calculate_daily_volume(&VolumeFilters {
+        aggregation: VolumePeriod::Monthly,
+        ..Default::default()
+    })?;
+    assert_eq!(
+        monthly,
+        vec![
+            (
+                NaiveDate::from_ymd_opt(2024, 2, 1).unwrap(),
+                "Squats".to_string(),
+                2300.0
+            ),
+            (
+                NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(),
+                "Squats".to_string(),
+                1000.0
+            ),
+        ]
+    );
+
+    // Date filters apply to workout dates before bucketing
+    let weekly_filtered = service.calculate_daily_volume(&VolumeFilters {
+        start_date: NaiveDate::from_ymd_opt(2024, 2, 1),
+        aggregation: VolumePeriod::Weekly,
+        ..Default::default()
+    })?;
+    assert_eq!(weekly_filtered.len(), 2);
+    assert_eq!(weekly_filtered[1].2, 1100.0);
+
+    Ok(())
+}
+
+#[test]
+fn test_volume_with_totals() -> Result<()> {
+    let mut service = create_test_service()?;
+    service.create_exercise("Bench", ExerciseType::Resistance, None, Some("chest"))?;
+    service.create_exercise("Squat", ExerciseType::Resistance, None, Some("legs"))?;
+    let day = |d| Utc.from_utc_datetime(&NaiveDate::from_ymd_opt(2024, 4, d).unwrap().and_hms_opt(12, 0, 0).unwrap());
+    for (exercise, d, weight) in [("Bench", 1, 100.0), ("Squat", 1, 150.0), ("Bench", 3, 110.0), ("Squat", 10, 160.0)] {
+        service.add_workout(AddWorkoutParams {
+            exercise_identifier: exercise,
+            date: day(d),
+            sets: Some(2),
+            reps: Some(5),
+            weight: Some(weight),
+            ..Default::default()
+        })?;
+    }
+
+    let filters = VolumeFilters {
+        end_date: NaiveDate::from_ymd_opt(2024, 4, 5),
+        ..Default::default()
+    };
+    let totals = service.calculate_volume_with_totals(&filters)?;
+    assert_eq!(totals.rows, service.calculate_daily_volume(&filters)?);
+    assert_eq!(totals.by_exercise.len(), 2);
+    assert_eq!(totals.by_exercise["Bench"], 2100.0);
+    assert_eq!(totals.by_exercise["Squat"], 1500.0);
+    assert_eq!(totals.total, 3600.0);
+
+    let empty = service.calculate_volume_with_totals(&VolumeFilters {
+        start_date: NaiveDate::from_ymd_opt(2030, 1, 1),
+        ..Default::default()
+    })?;
+    assert!(empty.rows.is_empty() && empty.by_exercise.is_empty());
+    assert_eq!(empty.total, 0.0);
+    Ok(())
+}
+
+#[test]
+fn test_volume_week_start() -> Result<()> {
+    let mut service = create_test_service()?;
+    service.create_exercise("Squats", ExerciseType::Resistance, None, Some("legs"))?;
+
+    // Wed 2024-01-31, Sun 2024-02-04, Mon 2024-02-05
+    for (m, d, weight) in [(1, 31, 100.0), (2, 4, 110.0), (2, 5, 120.0)] {
+        let day = NaiveDate::from_ymd_opt(2024, m, d).unwrap();
+        service.add_workout(AddWorkoutParams {
+            exercise_identifier: "Squats",
+            date: DateTime::from_naive_utc_and_offset(day.and_hms_opt(12, 0, 0).unwrap(), Utc),
+            sets: Some(1),
+            reps: Some(10),
+            weight: Some(weight),
+            ..Default::default()
+        })?;
+    }
+
+    assert_eq!(service.config.week_start, Weekday::Mon);
+    service.config.week_start = Weekday::Sun;
+    let weekly = service.calculate_daily_volume(&VolumeFilters {
+        aggregation: VolumePeriod::Weekly,
+        ..Default::default()
+    })?;
+    let buckets: Vec<(NaiveDate, f64)> = weekly.into_iter().map(|(d, _, v)| (d, v)).collect();
+    assert_eq!(
+        buckets,
+        vec![
+            (NaiveDate::from_ymd_opt(2024, 2, 4).unwrap(), 2300.0),
+            (NaiveDate::from_ymd_opt(2024, 1, 28).unwrap(), 1000.0),
+        ]
+    );
+
+    // An explicit filter overrides the configured week start
+    let weekly = service.calculate_daily_volume(&VolumeFilters {
+        aggregation: VolumePeriod::Weekly,
+        week_start: Some(Weekday::Mon),
+        ..Default::default()
+    })?;
+    assert_eq!(weekly[0].0, NaiveDate::from_ymd_opt(2024, 2, 5).unwrap());
+    assert_eq!(weekly[1].0, NaiveDate::from_ymd_opt(2024, 1, 29).unwrap());
+
+    Ok(())
+}
+
+#[test]
+fn test_pooled_service_concurrent_reads() -> Result<()> {
+    let db_path = std::env::temp_dir().join(format!("ta_pool_test_{}.sqlite", uuid::Uuid::new_v4()));
+    let pool = task_athlete_lib::db::open_pool(&db_path, 4)?;
+    task_athlete_lib::db::init(&*pool.get()?)?;
+
+    let mut service = AppService {
+        config: Config {
+            bodyweight: Some(70.0),
+            ..Default::default()
+        },
+        conn: pool.into(),
+        db_path: db_path.clone(),
+        config_path: test_config_path(),
+    };
+
+    // Writes (including the transactional exercise edit) go through pooled connections
+    service.create_exercise("Squat", ExerciseType::Resistance, None, Some("legs"))?;
+    service.edit_exercise("Squat", EditExerciseParams { new_name: Some("Back Squat"), ..Default::default() })?;
+    for reps in 1..=5 {
+        service.add_workout(AddWorkoutParams {
+            exercise_identifier: "Back Squat",
+            date: Utc::now(),
+            sets: Some(1),
+            reps: Some(reps),
+            weight: Some(100.0),
+            ..Default::default()
+        })?;
+    }
+
+    let service = Arc::new(service);
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            let service = Arc::clone(&service);
+            thread::spawn(move || -> Result<usize> {
+                let stats = service.get_exercise_stats("Back Squat")?;
+                assert_eq!(stats.personal_bests.max_reps, Some(5));
+                Ok(service
+                    .list_workouts(&WorkoutFilters {
+                        exercise_name: Some("Back Squat"),
+                        ..Default::default()
+                    })?
+                    .len())
+            })
+        })
+        .collect();
+    for handle in handles {
+        assert_eq!(handle.join().expect("reader thread panicked")?, 5);
+    }
+
+    drop(service);
+    let _ = std::fs::remove_file(&db_path);
+    Ok(())
+}
+
+#[test]
+fn test_workout_tags() -> Result<()> {
+    let mut service = create_test_service()?;
+    service.create_exercise("Squat", ExerciseType::Resistance, None, Some("legs"))?;
+    let add = |service: &mut AppService| {
+        service.add_workout(AddWorkoutParams {
+            exercise_identifier: "Squat",
+            date: Utc::now(),
+            sets: Some(3),
+            reps: Some(5),
+            weight: Some(100.0),
+            ..Default::default()
+        })
+    };
+    let (id1, _) = add(&mut service)?;
+    let (id2, _) = add(&mut service)?;
+
+    assert_eq!(service.add_workout_tags(id1, &[" Deload ", "travel"])?, 2);
+    assert_eq!(service.add_workout_tags(id1, &["DELOAD"])?, 0); // Already tagged
+    service.add_workout_tags(id2, &["competition"])?;
+    assert!(service.add_workout_tags(id1, &["  "]).is_err());
+    assert!(service.add_workout_tags(9999, &["deload"]).is_err());
+
+    assert_eq!(service.get_workout_tags(id1)?, vec!["deload", "travel"]);
+    assert_eq!(service.list_tags()?, vec!["competition", "deload", "travel"]);
+
+    let deload = service.list_workouts(&WorkoutFilters {
+        tag: Some("Deload"),
+        ..Default::default()
+    })?;
+    assert_eq!(deload.len(), 1);
+    assert_eq!(deload[0].id, id1);
+
+    service.remove_workout_tag(id1, "TRAVEL")?;
+    assert!(service.remove_workout_tag(id1, "travel").is_err());
+    assert_eq!(service.get_workout_tags(id1)?, vec!["deload"]);
+
+    // Deleting a workout hides its tags; restoring (or undoing the delete) brings them back
+    service.delete_workouts(&[id2])?;
+    assert!(service.get_workout_tags(id2)?.is_empty());
+    assert_eq!(service.list_tags()?, vec!["deload"]);
+    service.restore_workout(id2)?;
+    assert_eq!(service.get_workout_tags(id2)?, vec!["competition"]);
+    service.delete_workouts(&[id2])?;
+    service.undo_last()?;
+    assert_eq!(service.list_tags()?, vec!["competition", "deload"]);
+
+    Ok(())
+}
+
+#[test]
+fn test_estimate_calories() -> Result<()> {
+    let mut service = create_test_service()?; // Config bodyweight is 70 kg
+    service.create_exercise("Running", ExerciseType::Cardio, None, Some("legs"))?;
+    service.create_exercise(
+        "Bench",
+        ExerciseType::Resistance,
+        Some((None, None, Some(true), None)),
+        Some("chest"),
+    )?;
+
+    let day = Utc::now() - Duration::days(1);
+    let (run_id, _) = service.add_workout(AddWorkoutParams {
+        exercise_identifier: "Running",
+        date: day,
+        duration: Some(30),
+        distance: Some(5.0),
+        ..Default::default()
+    })?;
+
+    // No MET value yet
+    assert_eq!(service.estimate_calories(run_id)?, None);
+
+    assert!(service.set_exercise_met_value("Running", Some(0.0)).is_err());
+    service.set_exercise_met_value("running", Some(10.0))?;
+    let kcal = service.estimate_calories(run_id)?.unwrap();
+    assert!((kcal - 350.0).abs() < 1e-9); // 10 MET * 70 kg * 0.5 h
+
+    // Latest logged bodyweight takes precedence over config
+    service.add_bodyweight_entry(Utc::now(), 80.0)?;
+    let kcal = service.estimate_calories(run_id)?.unwrap();
+    assert!((kcal - 400.0).abs() < 1e-9);
+
+    let (run2_id, _) = service.add_workout(AddWorkoutParams {
+        exercise_identifier: "Running",
+        date: day,
+        duration: Some(15),
+        ..Default::default()
+    })?;
+    let (no_duration_id, _) = service.add_workout(AddWorkoutParams {
+        exercise_identifier: "Running",
+        date: day,
+        distance: Some(2.0),
+        ..Default::default()
+    })?;
+    assert_eq!(service.estimate_calories(no_duration_id)?, None);
+    assert!((service.estimate_calories(run2_id)?.unwrap() - 200.0).abs() < 1e-9);
+
+    service.set_exercise_met_value("Bench", Some(5.0))?;
+    let (bench_id, _) = service.add_workout(AddWorkoutParams {
+        exercise_identifier: "Bench",
+        date: day,
+        sets: Some(3),
+        reps: Some(5),
+        weight: Some(80.0),
+        duration: Some(20),
+        ..Default::default()
+    })?;
+    assert_eq!(service.estimate_calories(bench_id)?, None); // Resistance is not estimated
+    assert!(service.estimate_calories(9999).is_err());
+
+    let graph = service.get_data_for_graph("Running", GraphType::CaloriesBurned, None, None, None)?;
+    assert_eq!(graph.len(), 1);
+    assert_eq!(graph[0].0, day.date_naive());
+    assert!((graph[0].1 - 600.0).abs() < 1e-9);
+
+    Ok(())
+}
+
+#[test]
+fn test_merge_config_field_level() {
+    let base = Config::default();
+
+    let mut local = base.clone();
+    local.units = Units::Imperial; // Changed locally
+    local.theme.header_color = "Blue".to_string(); // Local-only preference
+    local.sync_server_url = Some("http://local:3030".to_string());
+
+    let mut server = base.clone();
+    server.streak_interval_days = 3; // Changed on the server
+    server.sync_server_url = Some("http://elsewhere:3030".to_string());
+    server.last_sync_timestamp = Some(Utc::now());
+    server.hard_delete = !base.hard_delete;
+    server.auto_backup_before_sync = !base.auto_backup_before_sync;
+
+    let merged = task_athlete_lib::merge_config(&local, &server, &base);
+    assert_eq!(merged.units, Units::Imperial);
+    assert_eq!(merged.streak_interval_days, 3);
+    assert_eq!(merged.theme.header_color, "Blue");
+    // Sync settings always stay local
+    assert_eq!(merged.sync_server_url.as_deref(), Some("http://local:3030"));
+    assert_eq!(merged.last_sync_timestamp, None);
+    // So do device safety settings
+    assert_eq!(merged.hard_delete, local.hard_delete);
+    assert_eq!(merged.auto_backup_before_sync, local.auto_backup_before_sync);
+
+    // When both sides changed the same field, the server change wins
+    let mut server_units = base.clone();
+    server_units.units = Units::Imperial;
+    server_units.bodyweight = Some(80.0);
+    let mut local_bw = base.clone();
+    local_bw.bodyweight = Some(75.0);
+    let merged = task_athlete_lib::merge_config(&local_bw, &server_units, &base);
+    assert_eq!(merged.bodyweight, Some(80.0));
+    assert_eq!(merged.units, Units::Imperial);
+
+    // Nothing changed on the server: local config is kept as-is
+    assert_eq!(task_athlete_lib::merge_config(&local, &base, &base), local);
+}
+
+#[test]
+fn test_restore_deleted_items() -> Result<()> {
+    let mut service = create_test_service()?;
+    service.create_exercise("Squat", ExerciseType::Resistance, None, Some("legs"))?;
+    service.create_alias("sq", "Squat")?;
+    let (id1, _) = service.add_workout(AddWorkoutParams {
+        exercise_identifier: "Squat",
+        date: Utc::now() - Duration::days(1),
+        sets: Some(3),
+        reps: Some(5),
+        weight: Some(100.0),
+        ..Default::default()
+    })?;
+    let (id2, _) = service.add_workout(AddWorkoutParams {
+        exercise_identifier: "Squat",
+        date: Utc::now(),
+        sets: Some(3),
+        reps: Some(5),
+        weight: Some(105.0),
+        ..Default::default()
+    })?;
+
+    // Workouts
+    assert!(service.restore_workout(id1).is_err()); // Not deleted
+    service.delete_workouts(&[id1])?;
+    thread::sleep(StdDuration::from_millis(5));
+    service.delete_workouts(&[id2])?;
+    let trash = service.list_deleted_workouts(10)?;
+    assert_eq!(trash.iter().map(|w| w.id).collect::<Vec<_>>(), vec![id2, id1]);
+    assert!(trash.iter().all(|w| w.deleted));
+    assert_eq!(service.list_deleted_workouts(1)?.len(), 1);
+
+    service.restore_workout(id1)?;
+    assert_eq!(service.list_deleted_workouts(10)?.len(), 1);
+    let active = service.list_workouts(&WorkoutFilters::default())?;
+    assert_eq!(active.len(), 1);
+    assert_eq!(active[0].id, id1);
+    assert!(!active[0].deleted);
+    assert!(service.restore_workout(9999).is_err());
+
+    // Exercises (with their aliases)
+    service.delete_exercise(&["Squat".to_string()])?;
+    assert!(service.resolve_exercise_identifier("Squat")?.is_none());
+    assert!(service.list_aliases()?.is_empty());
+
+    assert!(service.restore_exercise("Deadlift").is_err());
+    assert_eq!(service.restore_exercise("squat")?, "Squat");
+    assert!(service.resolve_exercise_identifier("Squat")?.is_some());
+    assert_eq!(service.list_aliases()?.get("sq").map(String::as_str), Some("Squat"));
+    assert!(service.restore_exercise("Squat").is_err()); // Already active
+
+    Ok(())
+}
+
+#[test]
+fn test_pb_history() -> Result<()> {
+    let mut service = create_test_service()?;
+    service.create_exercise("Deadlift", ExerciseType::Resistance, None, Some("back"))?;
+
+    let base = Utc::now() - Duration::days(10);
+    for (day, weight, reps) in [
+        (0, 100.0, 5),
+        (1, 110.0, 3),
+        (2, 105.0, 8), // Not a weight PB, but a reps PB
+        (3, 110.0, 2), // Ties are not new records
+        (4, 115.0, 1),
+        (4, 120.0, 1), // Two records on one day collapse into the best
+    ] {
+        service.add_workout(AddWorkoutParams {
+            exercise_identifier: "Deadlift",
+            date: base + Duration::days(day),
+            sets: Some(1),
+            reps: Some(reps),
+            weight: Some(weight),
+            ..Default::default()
+        })?;
+    }
+
+    let day = |offset: i64| (base + Duration::days(offset)).date_naive();
+    assert_eq!(
+        service.get_pb_history("Deadlift", PbMetric::Weight)?,
+        vec![(day(0), 100.0), (day(1), 110.0), (day(4), 120.0)]
+    );
+    assert_eq!(
+        service.get_pb_history("Deadlift", PbMetric::Reps)?,
+        vec![(day(0), 5.0), (day(2), 8.0)]
+    );
+    assert!(service.get_pb_history("Deadlift", PbMetric::Distance)?.is_empty());
+    assert!(service.get_pb_history("Unknown", PbMetric::Weight).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_warmup_sets_excluded_from_pbs_and_volume() -> Result<()> {
+    let mut service = create_test_service()?;
+    service.create_exercise("Bench", ExerciseType::Resistance, None, Some("chest"))?;
+    let today = Utc::now();
+
+    let (working_id, _) = service.add_workout(AddWorkoutParams {
+        exercise_identifier: "Bench",
+        date: today,
+        sets: Some(3),
+        reps: Some(5),
+        weight: Some(80.0),
+        ..Default::default()
+    })?;
+    // A heavier "warmup" (e.g. mislabelled) must not register as a PB
+    let (warmup_id, pb) = service.add_workout(AddWorkoutParams {
+        exercise_identifier: "Bench",
+        date: today,
+        sets: Some(1),
+        reps: Some(10),
+        weight: Some(90.0),
+        is_warmup: true,
+        ..Default::default()
+    })?;
+    assert!(pb.is_none());
+
+    let all = service.list_workouts(&WorkoutFilters::default())?;
+    assert_eq!(all.len(), 2);
+    assert!(all.iter().any(|w| w.id == warmup_id && w.is_warmup));
+    let working_only = service.list_workouts(&WorkoutFilters {
+        include_warmups: false,
+        ..Default::default()
+    })?;
+    assert_eq!(working_only.len(), 1);
+    assert_eq!(working_only[0].id, working_id);
+
+    let volume = service.calculate_daily_volume(&VolumeFilters::default())?;
+    assert_eq!(volume.len(), 1);
+    assert_eq!(volume[0].2, 1200.0);
+    let graph = service.get_data_for_graph("Bench", GraphType::MaxWeight, None, None, None)?;
+    assert_eq!(graph[0].1, 80.0);
+    let stats = service.get_exercise_stats("Bench")?;
+    assert_eq!(stats.personal_bests.max_weight, Some(80.0));
+    assert_eq!(stats.personal_bests.max_reps, Some(5));
+
+    // Un-flagging the warmup brings it back into the stats
+    service.edit_workout(EditWorkoutParams {
+        id: warmup_id,
+        new_is_warmup: Some(false),
+        ..Default::default()
+    })?;
+    let volume = service.calculate_daily_volume(&VolumeFilters::default())?;
+    assert_eq!(volume[0].2, 2100.0);
+    assert_eq!(service.get_exercise_stats("Bench")?.personal_bests.max_weight, Some(90.0));
+
+    Ok(())
+}
+
+#[test]
+fn test_workout_splits() -> Result<()> {
+    let mut service = create_test_service()?;
+    service.create_exercise("Running", ExerciseType::Cardio, None, Some("legs"))?;
+    service.create_exercise("Squat", ExerciseType::Resistance, None, Some("legs"))?;
+    let day = Utc::now() - Duration::days(1);
+
+    let (intervals_id, _) = service.add_workout(AddWorkoutParams {
+        exercise_identifier: "Running",
+        date: day,
+        duration: Some(20),
+        distance: Some(3.0), // Superseded by splits in the distance graph
+        ..Default::default()
+    })?;
+    let (plain_id, _) = service.add_workout(AddWorkoutParams {
+        exercise_identifier: "Running",
+        date: day,
+        duration: Some(30),
+        distance: Some(5.0),
+        ..Default::default()
+    })?;
+    let (squat_id, _) = service.add_workout(AddWorkoutParams {
+        exercise_identifier: "Squat",
+        date: day,
+        sets: Some(3),
+        reps: Some(5),
+        weight: Some(100.0),
+        ..Default::default()
+    })?;
+
+    assert!(service.add_splits(squat_id, &[(1.0, 300)]).is_err()); // Not cardio
+    assert!(service.add_splits(intervals_id, &[(0.0, 60)]).is_err());
+    assert!(service.add_splits(9999, &[(1.0, 300)]).is_err());
+
+    assert_eq!(service.add_splits(intervals_id, &[(1.0, 300), (1.0, 270)])?, 2);
+    service.add_splits(intervals_id, &[(0.5, 140)])?;
+    let splits = service.get_splits(intervals_id)?;
+    assert_eq!(splits.iter().map(|s| s.split_index).collect::<Vec<_>>(), vec![1, 2, 3]);
+    assert_eq!(splits[1].pace_seconds_per_km(), Some(270.0));
+    assert!(service.get_splits(plain_id)?.is_empty());
+
+    let fastest = service.fastest_split("Running")?.unwrap();
+    assert_eq!((fastest.workout_id, fastest.split_index), (intervals_id, 2));
+    assert!(service.fastest_split("Squat")?.is_none());
+
+    let graph = service.get_data_for_graph("Running", GraphType::WorkoutDistance, None, None, None)?;
+    assert_eq!(graph.len(), 1);
+    assert!((graph[0].1 - 7.5).abs() < 1e-9); // 2.5 km of splits + 5 km plain entry
+
+    Ok(())
+}
+
+#[test]
+fn test_detect_deloads() -> Result<()> {
+    let mut service = create_test_service()?;
+    service.create_exercise("Squat", ExerciseType::Resistance, None, Some("legs"))?;
+    service.create_exercise("Bench", ExerciseType::Resistance, None, Some("chest"))?;
+
+    // Mondays starting 2024-01-01; Squat volume per week (None = no workouts that week)
+    let squat_weeks = [
+        Some(1000.0),
+        Some(1000.0),
+        Some(1000.0),
+        Some(1000.0),
+        Some(500.0), // Deload: 0.5 of trailing average
+        Some(1000.0),
+        None, // Skipped week
+        Some(1000.0),
+    ];
+    let monday = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+    for (i, weight) in squat_weeks.iter().enumerate() {
+        let Some(weight) = weight else { continue };
+        let day = monday + Duration::days(7 * i as i64 + 2);
+        service.add_workout(AddWorkoutParams {
+            exercise_identifier: "Squat",
+            date: DateTime::from_naive_utc_and_offset(day.and_hms_opt(12, 0, 0).unwrap(), Utc),
+            sets: Some(1),
+            reps: Some(10),
+            weight: Some(weight / 10.0),
+            ..Default::default()
+        })?;
+    }
+    // Bench only in the deload week, lifting overall volume back to normal
+    service.add_workout(AddWorkoutParams {
+        exercise_identifier: "Bench",
+        date: DateTime::from_naive_utc_and_offset(
+            (monday + Duration::days(28)).and_hms_opt(12, 0, 0).unwrap(),
+            Utc,
+        ),
+        sets: Some(1),
+        reps: Some(10),
+        weight: Some(50.0),
+        ..Default::default()
+    })?;
+
+    let week = |i: i64| monday + Duration::days(7 * i);
+    let squat_deloads = service.detect_deloads(Some("Squat"), 0.6)?;
+    assert_eq!(squat_deloads.len(), 2);
+    assert_eq!(squat_deloads[0].0, week(4));
+    assert!((squat_deloads[0].1 - 0.5).abs() < 1e-9);
+    assert_eq!(squat_deloads[1], (week(6), 0.0));
+
+    let overall = service.detect_deloads(None, 0.6)?;
+    assert_eq!(overall, vec![(week(6), 0.0)]);
+
+    assert!(service.detect_deloads(None, 0.0).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_body_composition_measurements() -> Result<()> {
+    let service = create_test_service()?;
+    let now = Utc::now();
+
+    // Plain entries keep working and have no composition data
+    service.add_bodyweight_entry(now - Duration::days(10), 82.0)?;
+    service.add_body_measurement(&BodyMeasurementParams {
+        timestamp: now - Duration::days(5),
+        weight: 81.0,
+        body_fat_pct: Some(20.0),
+        muscle_mass: Some(36.0),
+        notes: None,
+    })?;
+    service.add_body_measurement(&BodyMeasurementParams {
+        timestamp: now - Duration::days(1),
+        weight: 80.0,
+        body_fat_pct: Some(18.5),
+        muscle_mass: None,
+        notes: None,
+    })?;
+    assert!(service
+        .add_body_measurement(&BodyMeasurementParams {
+            timestamp: now,
+            weight: 80.0,
+            body_fat_pct: Some(120.0),
+            muscle_mass: None,
+            notes: None,
+        })
+        .is_err());
+
+    let entries = service.list_body_measurements(10)?;
+    assert_eq!(entries.len(), 3);
+    assert_eq!(entries[0].body_fat_pct, Some(18.5));
+    assert_eq!(entries[1].muscle_mass, Some(36.0));
+    assert_eq!(entries[2].body_fat_pct, None);
+    assert_eq!(service.list_bodyweights(10)?.len(), 3);
+
+    let fat = service.get_body_metric_series(BodyMetric::BodyFatPct, None, None)?;
+    assert_eq!(
+        fat,
+        vec![
+            ((now - Duration::days(5)).date_naive(), 20.0),
+            ((now - Duration::days(1)).date_naive(), 18.5),
+        ]
+    );
+    assert_eq!(service.get_body_metric_series(BodyMetric::Weight, None, None)?.len(), 3);
+
+    assert_eq!(service.get_body_metric_trend(BodyMetric::BodyFatPct, 30)?, Some(-1.5));
+    assert_eq!(service.get_body_metric_trend(BodyMetric::Weight, 7)?, Some(-1.0));
+    assert_eq!(service.get_body_metric_trend(BodyMetric::MuscleMass, 30)?, None);
+
+    Ok(())
+}
+
+#[test]
+fn test_get_nth_best() -> Result<()> {
+    let mut service = create_test_service()?;
+    service.create_exercise("Pull-up", ExerciseType::BodyWeight, None, Some("back"))?;
+    for (extra, reps) in [(10.0, 5), (0.0, 12), (20.0, 3), (5.0, 8)] {
+        service.add_workout(AddWorkoutParams {
+            exercise_identifier: "Pull-up",
+            date: Utc::now(),
+            sets: Some(1),
+            reps: Some(reps),
+            weight: Some(extra),
+            bodyweight_to_use: Some(70.0),
+            ..Default::default()
+        })?;
+    }
+
+    // Effective weight = bodyweight + added weight
+    assert_eq!(service.get_nth_best("Pull-up", PbMetric::Weight, 1)?, Some(90.0));
+    assert_eq!(service.get_nth_best("Pull-up", PbMetric::Weight, 2)?, Some(80.0));
+    assert_eq!(service.get_nth_best("Pull-up", PbMetric::Reps, 3)?, Some(5.0));
+    assert_eq!(service.get_nth_best("Pull-up", PbMetric::Reps, 5)?, None);
+    assert_eq!(service.get_nth_best("Pull-up", PbMetric::Distance, 1)?, None);
+    assert!(service.get_nth_best("Pull-up", PbMetric::Reps, 0).is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_timezone_local_dates() -> Result<()> {
+    let mut service = create_test_service()?;
+    service.create_exercise("Squat", ExerciseType::Resistance, None, Some("legs"))?;
+    // 14:00 UTC on the 5th is already the 6th in Sydney (UTC+10/+11)
+    let ts = Utc.with_ymd_and_hms(2024, 3, 5, 14, 0, 0).unwrap();
+    service.add_workout(AddWorkoutParams {
+        exercise_identifier: "Squat",
+        date: ts,
+        sets: Some(3),
+        reps: Some(5),
+        weight: Some(100.0),
+        ..Default::default()
+    })?;
+
+    let utc_day = NaiveDate::from_ymd_opt(2024, 3, 5).unwrap();
+    let sydney_day = NaiveDate::from_ymd_opt(2024, 3, 6).unwrap();
+    let on = |date| WorkoutFilters { date: Some(date), ..Default::default() };
+
+    // Default is UTC
+    assert_eq!(service.list_workouts(&on(utc_day))?.len(), 1);
+    assert_eq!(service.get_workout_dates_for_month(2024, 3)?, vec!["2024-03-05"]);
+
+    service.config.timezone = Some("Australia/Sydney".to_string());
+    assert_eq!(service.list_workouts(&on(utc_day))?.len(), 0);
+    assert_eq!(service.list_workouts(&on(sydney_day))?.len(), 1);
+    assert_eq!(service.get_workout_dates_for_month(2024, 3)?, vec!["2024-03-06"]);
+
+    assert!(matches!(
+        service.set_timezone(Some("Mars/Olympus_Mons")),
+        Err(ConfigError::InvalidTimezone(_))
+    ));
+    assert_eq!(service.config.timezone.as_deref(), Some("Australia/Sydney"));
+
+    // SQL date filters use the offset in effect at each entry, not today's: 23:30 local in
+    // both winter (UTC-5) and summer (UTC-4) stays on the same New York day
+    service.set_timezone(Some("America/New_York"))?;
+    let winter = Utc.with_ymd_and_hms(2024, 1, 16, 4, 30, 0).unwrap();
+    let summer = Utc.with_ymd_and_hms(2024, 7, 16, 3, 30, 0).unwrap();
+    for ts in [winter, summer] {
+        service.add_workout(AddWorkoutParams {
+            exercise_identifier: "Squat",
+            date: ts,
+            sets: Some(1),
+            reps: Some(5),
+            weight: Some(100.0),
+            ..Default::default()
+        })?;
+        service.add_bodyweight_entry(ts, 80.0)?;
+    }
+    for (year_month, day) in [((2024, 1), 15), ((2024, 7), 15)] {
+        let date = NaiveDate::from_ymd_opt(year_month.0, year_month.1, day).unwrap();
+        assert_eq!(service.list_workouts(&on(date))?.len(), 1, "{date}");
+        assert_eq!(service.get_workout_dates_for_month(year_month.0, year_month.1)?, vec![date.to_string()]);
+        let series = service.get_body_metric_series(BodyMetric::Weight, Some(date), Some(date))?;
+        assert_eq!(series, vec![(date, 80.0)]);
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_exercise_description() -> Result<()> {
+    let mut service = create_test_service()?;
+    service.add_exercise(NewExerciseParams {
+        muscles: Some("back,legs"),
+        description: Some("Brace, bar over midfoot"),
+        ..NewExerciseParams::new("Deadlift", ExerciseType::Resistance)
+    })?;
+    service.create_exercise("Row", ExerciseType::Resistance, None, Some("back"))?;
+
+    let def = service.get_exercise_by_identifier_service("Deadlift")?.unwrap();
+    assert_eq!(def.description.as_deref(), Some("Brace, bar over midfoot"));
+    let row = service.get_exercise_by_identifier_service("Row")?.unwrap();
+    assert_eq!(row.description, None);
+
+    service.edit_exercise("Row", EditExerciseParams { new_description: Some(Some("Chest to pad")), ..Default::default() })?;
+    let row = service.get_exercise_by_identifier_service("Row")?.unwrap();
+    assert_eq!(row.description.as_deref(), Some("Chest to pad"));
+
+    // Editing other fields keeps it; an empty string clears it
+    service.edit_exercise("Row", EditExerciseParams { new_muscles: Some(Some("back,biceps")), ..Default::default() })?;
+    let row = service.get_exercise_by_identifier_service("Row")?.unwrap();
+    assert_eq!(row.description.as_deref(), Some("Chest to pad"));
+    service.edit_exercise("Row", EditExerciseParams { new_description: Some(Some("")), ..Default::default() })?;
+    let row = service.get_exercise_by_identifier_service("Row")?.unwrap();
+    assert_eq!(row.description, None);
+
+    Ok(())
+}
+
+#[test]
+fn test_volume_pb() -> Result<()> {
+    let mut service = create_test_service()?;
+    service.create_exercise("Squat", ExerciseType::Resistance, None, Some("legs"))?;
+    service.create_exercise("Dip", ExerciseType::BodyWeight, None, Some("chest"))?;
+
+    let mut add = |name, sets, reps, weight| {
+        service.add_workout(AddWorkoutParams {
+            exercise_identifier: name,
+            date: Utc::now(),
+            sets: Some(sets),
+            reps: Some(reps),
+            weight: Some(weight),
+            bodyweight_to_use: Some(70.0),
+            ..Default::default()
+        })
+    };
+
+    add("Squat", 1, 5, 140.0)?; // 700
+    // Lighter and fewer reps than the best, but more total tonnage
+    let (_, pb) = add("Squat", 5, 5, 100.0)?; // 2500
+    let info = pb.expect("volume PB");
+    assert!(info.volume.achieved && !info.weight.achieved && !info.reps.achieved);
+    assert_eq!(info.volume.previous_value, Some(700.0));
+    assert_eq!(info.volume.new_value, Some(2500.0));
+
+    // Bodyweight exercises use effective weight: 3 * 10 * (70 + 10) = 2400
+    add("Dip", 3, 10, 10.0)?;
+    let (_, pb) = add("Dip", 4, 8, 10.0)?; // 4 * 8 * 80 = 2560
+    let info = pb.expect("bodyweight volume PB");
+    assert!(info.volume.achieved);
+    assert_eq!(info.volume.previous_value, Some(2400.0));
+
+    service.config.pb_notifications.notify_volume = false;
+    let (_, pb) = service.add_workout(AddWorkoutParams {
+        exercise_identifier: "Squat",
+        date: Utc::now(),
+        sets: Some(10),
+        reps: Some(5),
+        weight: Some(100.0),
+        ..Default::default()
+    })?;
+    assert!(pb.is_none(), "Volume PB should not notify when disabled");
+
+    Ok(())
+}
+
+#[test]
+fn test_merge_exercises() -> Result<()> {
+    let mut service = create_test_service()?;
+    service.create_exercise("Bench Press", ExerciseType::Resistance, None, Some("chest"))?;
+    service.create_exercise("Barbell Bench Press", ExerciseType::Resistance, None, Some("chest"))?;
+    service.create_exercise("Dips", ExerciseType::BodyWeight, None, Some("chest"))?;
+    service.create_alias("bp", "Bench Press")?;
+    for (name, weight) in [("Bench Press", 80.0), ("Bench Press", 85.0), ("Barbell Bench Press", 90.0)] {
+        service.add_workout(AddWorkoutParams {
+            exercise_identifier: name,
+            date: Utc::now(),
+            sets: Some(3),
+            reps: Some(5),
+            weight: Some(weight),
+            ..Default::default()
+        })?;
+    }
+
+    assert!(service.merge_exercises("bp", "Bench Press").is_err(), "Self-merge must fail");
+    assert!(service.merge_exercises("Nope", "Bench Press").is_err());
+
+    let merged = service.merge_exercises("Bench Press", "Barbell Bench Press")?;
+    assert_eq!(merged, MergeResult { reassigned: 2, type_mismatch: None });
+
+    let filters = WorkoutFilters {
+        exercise_name: Some("Barbell Bench Press"),
+        ..Default::default()
+    };
+    assert_eq!(service.list_workouts(&filters)?.len(), 3);
+    assert!(service.get_exercise_by_identifier_service("Bench Press")?.is_none());
+    // Alias moved with the workouts
+    let via_alias = service.get_exercise_by_identifier_service("bp")?.unwrap();
+    assert_eq!(via_alias.name, "Barbell Bench Press");
+
+    // A type mismatch is reported, not refused
+    assert_eq!(
+        service.merge_exercises("Dips", "Barbell Bench Press")?,
+        MergeResult {
+            reassigned: 0,
+            type_mismatch: Some((ExerciseType::BodyWeight, ExerciseType::Resistance)),
+        }
+    );
+
+    Ok(())
+}
+
+#[test]
+fn test_list_workouts_by_muscle() -> Result<()> {
+    let mut service = create_test_service()?;
+    service.create_exercise("Bench Press", ExerciseType::Resistance, None, Some("chest,triceps"))?;
+    service.create_exercise("Fly", ExerciseType::Resistance, None, Some("chest"))?;
+    service.create_exercise("Squat", ExerciseType::Resistance, None, Some("legs"))?;
+    service.create_exercise("Pec Deck", ExerciseType::Resistance, None, Some("chest"))?;
+
+    let today = Utc::now();
+    let last_month = today - Duration::days(40);
+    let mut ids = Vec::new();
+    for (name, date) in [
+        ("Bench Press", today),
+        ("Fly", today),
+        ("Squat", today),
+        ("Bench Press", last_month),
+        ("Pec Deck", today),
+    ] {
+        let (id, _) = service.add_workout(AddWorkoutParams {
+            exercise_identifier: name,
+            date,
+            sets: Some(3),
+            reps: Some(10),
+            weight: Some(50.0),
+            ..Default::default()
+        })?;
+        ids.push(id);
+    }
+    service.delete_workouts(&[ids[1]])?; // Deleted Fly workout
+    service.delete_exercise(&["Pec Deck".to_string()])?; // Deleted exercise
+
+    let all_chest = service.list_workouts_by_muscle("chest", None)?;
+    assert_eq!(all_chest.len(), 2);
+    assert!(all_chest.iter().all(|w| w.exercise_name == "Bench Press"));
+
+    let range = (today.date_naive() - Duration::days(7), today.date_naive());
+    let recent = service.list_workouts_by_muscle("Chest", Some(range))?;
+    assert_eq!(recent.len(), 1);
+    assert_eq!(recent[0].id, ids[0]);
+
+    assert_eq!(service.list_workouts_by_muscle("legs", Some(range))?.len(), 1);
+    assert!(service.list_workouts_by_muscle(" ", None).is_err());
+    assert!(service
+        .list_workouts_by_muscle("chest", Some((range.1, range.0)))
+        .is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_rep_max_table() -> Result<()> {
+    let mut service = create_test_service()?;
+    service.create_exercise("Squat", ExerciseType::Resistance, None, Some("legs"))?;
+    service.create_exercise("Chin-up", ExerciseType::BodyWeight, None, Some("back"))?;
+    assert!(service.get_rep_max_table("Squat")?.is_empty());
+
+    for (reps, weight) in [(1, 180.0), (3, 160.0), (5, 150.0), (5, 145.0), (8, 120.0), (12, 100.0)] {
+        service.add_workout(AddWorkoutParams {
+            exercise_identifier: "Squat",
+            date: Utc::now(),
+            sets: Some(1),
+            reps: Some(reps),
+            weight: Some(weight),
+            ..Default::default()
+        })?;
+    }
+    let table = service.get_rep_max_table("Squat")?;
+    assert_eq!(table.len(), 10);
+    assert_eq!(table[&1], 180.0);
+    assert_eq!(table[&2], 160.0); // No 2-rep set; the 3-rep set counts
+    assert_eq!(table[&5], 150.0);
+    assert_eq!(table[&6], 120.0);
+    assert_eq!(table[&10], 100.0);
+
+    service.add_workout(AddWorkoutParams {
+        exercise_identifier: "Chin-up",
+        date: Utc::now(),
+        sets: Some(1),
+        reps: Some(3),
+        weight: Some(20.0),
+        bodyweight_to_use: Some(70.0),
+        ..Default::default()
+    })?;
+    let table = service.get_rep_max_table("Chin-up")?;
+    assert_eq!(table.into_iter().collect::<Vec<_>>(), vec![(1, 90.0), (2, 90.0), (3, 90.0)]);
+
+    assert!(service.get_rep_max_table("Unknown").is_err());
+    Ok(())
+}
+
+#[test]
+fn test_graph_date_range_matches_full_history() -> Result<()> {
+    let mut service = create_test_service()?;
+    service.set_timezone(Some("America/New_York"))?;
+    service.create_exercise("Bench", ExerciseType::Resistance, None, Some("chest"))?;
+    // Daily sets around the March DST change, logged late in the local evening
+    for day in 1..=20 {
+        service.add_workout(AddWorkoutParams {
+            exercise_identifier: "Bench",
+            date: Utc.with_ymd_and_hms(2024, 3, day, 3, 30, 0).unwrap(),
+            sets: Some(3),
+            reps: Some(5),
+            weight: Some(60.0 + f64::from(day)),
+            ..Default::default()
+        })?;
+    }
+    let date = |d| NaiveDate::from_ymd_opt(2024, 3, d).unwrap();
+    for graph_type in [GraphType::Estimated1RM, GraphType::WorkoutVolume, GraphType::RelativeIntensity] {
+        let full = service.get_data_for_graph("Bench", graph_type, None, None, None)?;
+        let ranged = service.get_data_for_graph("Bench", graph_type, Some(date(5)), Some(date(12)), None)?;
+        // RelativeIntensity still measures against the best E1RM from before the range
+        let expected: Vec<_> = full
+            .into_iter()
+            .filter(|(d, _)| (date(5)..=date(12)).contains(d))
+            .collect();
+        assert_eq!(ranged, expected, "{graph_type:?}");
+        // Local dates are the previous evening, so the range spans 8 entries
+        assert_eq!(ranged.len(), 8);
+    }
+    Ok(())
+}
+
+#[test]
+fn test_graph_transforms() -> Result<()> {
+    let mut service = create_test_service()?;
+    service.create_exercise("Bench", ExerciseType::Resistance, None, Some("chest"))?;
+    let day = |d| NaiveDate::from_ymd_opt(2024, 3, d).unwrap();
+    for (d, weight) in [(1, 10.0), (2, 100.0), (3, 50.0)] {
+        service.add_workout(AddWorkoutParams {
+            exercise_identifier: "Bench",
+            date: Utc.from_utc_datetime(&day(d).and_hms_opt(12, 0, 0).unwrap()),
+            sets: Some(1),
+            reps: Some(1),
+            weight: Some(weight),
+            ..Default::default()
+        })?;
+    }
+
+    let log = service.get_data_for_graph("Bench", GraphType::MaxWeight, None, None, Some(GraphTransform::Log10))?;
+    let values: Vec<f64> = log.iter().map(|&(_, v)| v).collect();
+    assert_eq!(values.len(), 3);
+    assert!((values[0] - 1.0).abs() < 1e-12 && (values[1] - 2.0).abs() < 1e-12);
+
+    let percent = service.get_data_for_graph("Bench", GraphType::MaxWeight, None, None, Some(GraphTransform::PercentOfFirst))?;
+    assert_eq!(percent, vec![(day(1), 100.0), (day(2), 1000.0), (day(3), 500.0)]);
+    // The first point of the filtered range is the baseline
+    let ranged = service.get_data_for_graph(
+        "Bench",
+        GraphType::MaxWeight,
+        Some(day(2)),
+        None,
+        Some(GraphTransform::PercentOfFirst),
+    )?;
+    assert_eq!(ranged, vec![(day(2), 100.0), (day(3), 50.0)]);
+    assert!(GraphTransform::PercentOfFirst.apply(Vec::new()).is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_e1rm_rounding() -> Result<()> {
+    let mut service = create_test_service()?;
+    service.create_exercise("Bench", ExerciseType::Resistance, None, Some("chest"))?;
+    service.add_workout(AddWorkoutParams {
+        exercise_identifier: "Bench",
+        date: Utc.with_ymd_and_hms(2024, 3, 1, 12, 0, 0).unwrap(),
+        sets: Some(1),
+        reps: Some(5),
+        weight: Some(100.0),
+        ..Default::default()
+    })?;
+
+    let points = service.get_graph_points("Bench", GraphType::Estimated1RM, None, None)?;
+    assert_eq!(points.len(), 1);
+    let raw = points[0].value;
+    assert_eq!(raw, service.get_data_for_graph("Bench", GraphType::Estimated1RM, None, None, None)?[0].1);
+    assert_eq!(points[0].rounded, round_to_decimals(raw, 1));
+    assert_eq!(service.format_e1rm(116.666), "116.7");
+
+    service.set_e1rm_decimals(0)?;
+    assert_eq!(service.format_e1rm(116.666), "117");
+    service.set_e1rm_decimals(2)?;
+    service.set_weight_increment(Some(2.5))?;
+    assert_eq!(service.round_e1rm(116.666), 117.5);
+    assert_eq!(service.format_e1rm(116.666), "117.50");
+
+    // Other graph types keep their exact values
+    let volume = service.get_graph_points("Bench", GraphType::WorkoutVolume, None, None)?;
+    assert_eq!(volume[0].rounded, volume[0].value);
+    Ok(())
+}
+
+#[test]
+fn test_get_multi_graph_data() -> Result<()> {
+    let mut service = create_test_service()?;
+    service.create_exercise("Squat", ExerciseType::Resistance, None, Some("legs"))?;
+    service.create_exercise("Deadlift", ExerciseType::Resistance, None, Some("back"))?;
+    service.create_alias("dl", "Deadlift")?;
+    for (name, weight) in [("Squat", 100.0), ("Deadlift", 140.0)] {
+        service.add_workout(AddWorkoutParams {
+            exercise_identifier: name,
+            date: Utc::now(),
+            sets: Some(1),
+            reps: Some(1),
+            weight: Some(weight),
+            ..Default::default()
+        })?;
+    }
+
+    let data = service.get_multi_graph_data(
+        &["Squat", "dl", "Missing", "Deadlift"],
+        GraphType::MaxWeight,
+        None,
+        None,
+    )?;
+    assert_eq!(data.series.len(), 2);
+    let today = Utc::now().date_naive();
+    assert_eq!(data.series["Squat"], vec![(today, 100.0)]);
+    assert_eq!(data.series["Deadlift"], vec![(today, 140.0)]);
+    assert_eq!(data.skipped, vec!["Missing"]);
+
+    let data = service.get_multi_graph_data(&["Missing", "Other"], GraphType::MaxWeight, None, None)?;
+    assert!(data.series.is_empty());
+    assert_eq!(data.skipped, vec!["Missing", "Other"]);
+    Ok(())
+}
+
+#[test]
+fn test_weight_increment_rounding() -> Result<()> {
+    let mut service = create_test_service()?;
+    service.create_exercise("Bench", ExerciseType::Resistance, None, Some("chest"))?;
+
+    assert_eq!(round_to_increment(102.34, 2.5), 102.5);
+    assert_eq!(round_to_increment(101.2, 2.5), 100.0);
+    assert_eq!(round_to_increment(101.2, 0.0), 101.2);
+
+    let log = |service: &mut AppService, weight| {
+        service
+            .add_workout(AddWorkoutParams {
+                exercise_identifier: "Bench",
+                date: Utc::now(),
+                sets: Some(1),
+                reps: Some(5),
+                weight: Some(weight),
+                ..Default::default()
+            })
+            .map(|(id, _)| id)
+    };
+    let stored = |service: &AppService, id| -> Result<Option<f64>> {
+        let workouts = service.list_workouts(&WorkoutFilters::default())?;
+        Ok(workouts.into_iter().find(|w| w.id == id).and_then(|w| w.weight))
+    };
+
+    // Default: exact values everywhere
+    assert_eq!(service.display_weight(102.34), 102.34);
+    let id = log(&mut service, 102.34)?;
+    assert_eq!(stored(&service, id)?, Some(102.34));
+
+    // Increment set: display rounds, storage stays exact until enabled
+    service.config.weight_increment = Some(2.5);
+    assert_eq!(service.display_weight(102.34), 102.5);
+    let id = log(&mut service, 102.34)?;
+    assert_eq!(stored(&service, id)?, Some(102.34));
+
+    service.config.round_stored_weights = true;
+    let id = log(&mut service, 102.34)?;
+    assert_eq!(stored(&service, id)?, Some(102.5));
+
+    assert!(matches!(
+        service.set_weight_increment(Some(-1.0)),
+        Err(ConfigError::InvalidWeightIncrement(_))
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_get_stale_exercises() -> Result<()> {
+    let mut service = create_test_service()?;
+    for name in ["Squat", "Lunge", "Curl", "Old Press"] {
+        service.create_exercise(name, ExerciseType::Resistance, None, Some("misc"))?;
+    }
+    let now = Utc::now();
+    for (name, days_ago) in [("Squat", 2), ("Lunge", 45), ("Old Press", 60)] {
+        service.add_workout(AddWorkoutParams {
+            exercise_identifier: name,
+            date: now - Duration::days(days_ago),
+            sets: Some(1),
+            reps: Some(5),
+            weight: Some(50.0),
+            ..Default::default()
+        })?;
+    }
+    service.delete_exercise(&["Old Press".to_string()])?;
+
+    let stale = service.get_stale_exercises(30)?;
+    let names: Vec<_> = stale.iter().map(|ex| ex.name.as_str()).collect();
+    assert_eq!(names, vec!["Curl", "Lunge"]);
+    assert_eq!(stale[0].last_date, None);
+    assert_eq!(stale[1].last_date, Some((now - Duration::days(45)).date_naive()));
+
+    assert_eq!(service.get_stale_exercises(90)?.len(), 1);
+    Ok(())
+}
+
+#[test]
+fn test_delete_workouts_by_filter() -> Result<()> {
+    let mut service = create_test_service()?;
+    service.create_exercise("Row", ExerciseType::Resistance, None, Some("back"))?;
+    service.create_exercise("Curl", ExerciseType::Resistance, None, Some("arms"))?;
+    let today = Utc::now();
+    let imported_day = today - Duration::days(3);
+    for (name, date) in [("Row", imported_day), ("Curl", imported_day), ("Row", today)] {
+        service.add_workout(AddWorkoutParams {
+            exercise_identifier: name,
+            date,
+            sets: Some(3),
+            reps: Some(10),
+            weight: Some(20.0),
+            ..Default::default()
+        })?;
+    }
+
+    assert!(service.delete_workouts_by_filter(&WorkoutFilters::default()).is_err());
+    let only_limit = WorkoutFilters { limit: Some(1), ..Default::default() };
+    assert!(service.delete_workouts_by_filter(&only_limit).is_err());
+    assert_eq!(service.list_workouts(&WorkoutFilters::default())?.len(), 3);
+
+    let deleted = service.delete_workouts_by_filter(&WorkoutFilters {
+        date: Some(imported_day.date_naive()),
+        ..Default::default()
+    })?;
+    assert_eq!(deleted.len(), 2);
+    let remaining = service.list_workouts(&WorkoutFilters::default())?;
+    assert_eq!(remaining.len(), 1);
+    assert_eq!(remaining[0].timestamp.date_naive(), today.date_naive());
+    assert_eq!(service.list_deleted_workouts(10)?.len(), 2);
+
+    let none = service.delete_workouts_by_filter(&WorkoutFilters {
+        exercise_name: Some("Curl"),
+        ..Default::default()
+    })?;
+    assert!(none.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_pace_pb() -> Result<()> {
+    let mut service = create_test_service()?;
+    service.create_exercise("Running", ExerciseType::Cardio, None, Some("legs"))?;
+
+    assert_eq!(calculate_pace(30, 5.0), Some(6.0));
+    assert_eq!(calculate_pace(30, 0.0), None);
+    assert_eq!(calculate_pace(0, 5.0), None);
+
+    let mut run = |duration, distance| {
+        service.add_workout(AddWorkoutParams {
+            exercise_identifier: "Running",
+            date: Utc::now(),
+            duration: Some(duration),
+            distance: Some(distance),
+            ..Default::default()
+        })
+    };
+    run(30, 5.0)?; // 6:00 min/km baseline
+    let (_, pb) = run(20, 2.0)?; // Slower, shorter: no PB
+    assert!(pb.is_none());
+    let (_, pb) = run(22, 4.0)?; // 5.5 min/km
+    let info = pb.expect("pace PB");
+    assert!(info.pace.achieved && !info.distance.achieved && !info.duration.achieved);
+    assert_eq!(info.pace.previous_value, Some(6.0));
+    assert_eq!(info.pace.new_value, Some(5.5));
+    let (_, pb) = run(10, 0.0)?; // Zero distance never counts as a pace
+    assert!(pb.is_none_or(|info| !info.pace.achieved));
+
+    let stats = service.get_exercise_stats("Running")?;
+    assert_eq!(stats.personal_bests.best_pace_min_per_km, Some(5.5));
+
+    service.config.units = Units::Imperial;
+    assert!((service.display_pace(5.5) - 5.5 * 1.60934).abs() < 1e-9);
+    Ok(())
+}
+
+#[test]
+fn test_distance_round_trip_is_stable() -> Result<()> {
+    let mut service = create_test_service()?;
+    service.create_exercise("Running", ExerciseType::Cardio, None, Some("legs"))?;
+    let inputs: Vec<f64> = (1..=200)
+        .map(|i| f64::from(i) * 0.25)
+        .chain([0.1, 1.609, 3.107, 13.1, 26.2, 42.195])
+        .collect();
+
+    for units in [Units::Metric, Units::Imperial] {
+        service.config.units = units;
+        for &input in &inputs {
+            let (id, _) = service.add_workout(AddWorkoutParams {
+                exercise_identifier: "Running",
+                date: Utc::now(),
+                duration: Some(30),
+                distance: Some(input),
+                ..Default::default()
+            })?;
+            let stored_km = service
+                .list_workouts(&WorkoutFilters { limit: Some(1), ..Default::default() })?
+                .into_iter()
+                .find(|w| w.id == id)
+                .and_then(|w| w.distance)
+                .unwrap();
+            assert_eq!(
+                service.display_distance(stored_km),
+                input,
+                "{input} {} did not round-trip",
+                units.distance_abbr()
+            );
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn test_undo_last() -> Result<()> {
+    let mut service = create_test_service()?;
+    service.create_exercise("Squat", ExerciseType::Resistance, None, Some("legs"))?;
+    assert!(service.undo_last()?.is_none());
+
+    let mut add = |reps| {
+        service
+            .add_workout(AddWorkoutParams {
+                exercise_identifier: "Squat",
+                date: Utc::now(),
+                sets: Some(1),
+                reps: Some(reps),
+                weight: Some(100.0),
+                ..Default::default()
+            })
+            .map(|(id, _)| id)
+    };
+    let first = add(5)?;
+    let second = add(6)?;
+    let count = |service: &AppService| -> Result<usize> {
+        Ok(service.list_workouts(&WorkoutFilters::default())?.len())
+    };
+
+    service.delete_workouts(&[first])?;
+    assert_eq!(count(&service)?, 1);
+
+    // Undo the delete, then the second add
+    let undone = service.undo_last()?.unwrap();
+    assert_eq!(undone.action, UndoAction::DeleteWorkouts);
+    assert_eq!(undone.workout_ids, vec![first]);
+    assert_eq!(count(&service)?, 2);
+
+    let undone = service.undo_last()?.unwrap();
+    assert_eq!(undone.action, UndoAction::AddWorkout);
+    assert_eq!(undone.workout_ids, vec![second]);
+    let remaining = service.list_workouts(&WorkoutFilters::default())?;
+    assert_eq!(remaining.len(), 1);
+    assert_eq!(remaining[0].id, first);
+
+    // Depth is capped
+    for _ in 0..25 {
+        service.add_workout(AddWorkoutParams {
+            exercise_identifier: "Squat",
+            date: Utc::now(),
+            sets: Some(1),
+            reps: Some(1),
+            weight: Some(60.0),
+            ..Default::default()
+        })?;
+    }
+    let mut undone = 0;
+    while service.undo_last()?.is_some() {
+        undone += 1;
+    }
+    assert_eq!(undone, task_athlete_lib::UNDO_LOG_DEPTH);
+    assert_eq!(count(&service)?, 6);
+    Ok(())
+}
+
+#[test]
+fn test_duration_from_start_end_times() -> Result<()> {
+    let mut service = create_test_service()?;
+    service.create_exercise("Running", ExerciseType::Cardio, None, Some("legs"))?;
+    let start = Utc::now() - Duration::hours(2);
+    let mut run = |duration, end| {
+        service.add_workout(AddWorkoutParams {
+            exercise_identifier: "Running",
+            date: start,
+            duration,
+            distance: Some(5.0),
+            start_time: Some(start),
+            end_time: Some(end),
+            ..Default::default()
+        })
+    };
+
+    let (computed, _) = run(None, start + Duration::minutes(42) + Duration::seconds(20))?;
+    let (explicit, _) = run(Some(50), start + Duration::minutes(42))?; // Explicit wins
+    assert!(run(None, start).is_err(), "End must be after start");
+    assert!(run(None, start - Duration::minutes(5)).is_err());
+
+    let workouts = service.list_workouts(&WorkoutFilters::default())?;
+    let duration_of = |id| workouts.iter().find(|w| w.id == id).unwrap().duration_minutes;
+    assert_eq!(workouts.len(), 2);
+    assert_eq!(duration_of(computed), Some(42));
+    assert_eq!(duration_of(explicit), Some(50));
+    Ok(())
+}
+
+#[test]
+fn test_consistency_score() -> Result<()> {
+    let mut service = create_test_service()?;
+    service.create_exercise("Regular", ExerciseType::Resistance, None, Some("legs"))?;
+    service.create_exercise("Erratic", ExerciseType::Resistance, None, Some("legs"))?;
+    let now = Utc::now();
+    let log = |service: &mut AppService, name, days_ago: i64| {
+        service.add_workout(AddWorkoutParams {
+            exercise_identifier: name,
+            date: now - Duration::days(days_ago),
+            sets: Some(1),
+            reps: Some(5),
+            weight: Some(60.0),
+            ..Default::default()
+        })
+    };
+
+    // Two workouts (plus a same-day duplicate) aren't enough
+    for days_ago in [8, 4, 4] {
+        log(&mut service, "Regular", days_ago)?;
+    }
+    for days_ago in [20, 19, 2, 1] {
+        log(&mut service, "Erratic", days_ago)?;
+    }
+    assert_eq!(service.get_exercise_stats("Regular")?.consistency_score, None);
+
+    log(&mut service, "Regular", 0)?; // Gaps 4, 4: perfectly even
+    let regular = service.get_exercise_stats("Regular")?.consistency_score.unwrap();
+    assert!((regular - 100.0).abs() < 1e-9);
+
+    // Gaps 1, 17, 1: mean 6.33, std dev 7.54, cv 1.19
+    let erratic = service.get_exercise_stats("Erratic")?.consistency_score.unwrap();
+    assert!(erratic > 0.0 && erratic < 50.0, "erratic score was {erratic}");
+
+    // Days are local: sessions on the 1st, 5th and 9th in New York (two late in the evening)
+    // fall on the 2nd, 5th and 10th in UTC, but are still evenly spaced
+    service.set_timezone(Some("America/New_York"))?;
+    service.create_exercise("Evening", ExerciseType::Resistance, None, Some("legs"))?;
+    for (day, hour) in [(2, 3), (5, 16), (10, 3)] {
+        service.add_workout(AddWorkoutParams {
+            exercise_identifier: "Evening",
+            date: Utc.with_ymd_and_hms(2024, 6, day, hour, 30, 0).unwrap(),
+            reps: Some(5),
+            weight: Some(60.0),
+            ..Default::default()
+        })?;
+    }
+    let evening = service.get_exercise_stats("Evening")?.consistency_score.unwrap();
+    assert!((evening - 100.0).abs() < 1e-9, "evening score was {evening}");
+    Ok(())
+}
+
+#[test]
+fn test_exercise_categories() -> Result<()> {
+    let mut service = create_test_service()?;
+    for (name, muscles, category) in [
+        ("Bench", "chest", "Push"),
+        ("OHP", "shoulders", "push"),
+        ("Row", "back", "Pull"),
+    ] {
+        service.add_exercise(NewExerciseParams {
+            muscles: Some(muscles),
+            category: Some(category),
+            ..NewExerciseParams::new(name, ExerciseType::Resistance)
+        })?;
+    }
+    service.create_exercise("Plank Row", ExerciseType::Resistance, None, Some("core"))?;
+
+    let push = service.list_exercises(&ExerciseFilters { category: Some("PUSH"), ..Default::default() })?;
+    assert_eq!(push.iter().map(|e| e.name.as_str()).collect::<Vec<_>>(), vec!["Bench", "OHP"]);
+
+    service.edit_exercise("Plank Row", EditExerciseParams { new_category: Some(Some("Core")), ..Default::default() })?;
+    assert_eq!(service.list_exercises(&ExerciseFilters { category: Some("core"), ..Default::default() })?.len(), 1);
+    service.edit_exercise("Plank Row", EditExerciseParams { new_category: Some(Some(" ")), ..Default::default() })?;
+    assert!(service.list_exercises(&ExerciseFilters { category: Some("core"), ..Default::default() })?.is_empty());
+    let plank_row = service.get_exercise_by_identifier_service("Plank Row")?.unwrap();
+    assert_eq!(plank_row.category, None);
+
+    for (name, weight) in [("Bench", 100.0), ("OHP", 50.0), ("Row", 80.0), ("Plank Row", 10.0)] {
+        service.add_workout(AddWorkoutParams {
+            exercise_identifier: name,
+            date: Utc::now(),
+            sets: Some(1),
+            reps: Some(10),
+            weight: Some(weight),
+            ..Default::default()
+        })?;
+    }
+    let volume = service.volume_by_category(None, None)?;
+    assert_eq!(volume.len(), 3);
+    assert_eq!(volume["Push"], 1500.0);
+    assert_eq!(volume["Pull"], 800.0);
+    assert_eq!(volume[task_athlete_lib::UNCATEGORIZED], 100.0);
+    Ok(())
+}
+
+#[test]
+fn test_stream_workouts_json_lines() -> Result<()> {
+    let mut service = create_test_service()?;
+    service.create_exercise("Squat", ExerciseType::Resistance, None, Some("legs"))?;
+    let now = Utc::now();
+    for i in 0..5 {
+        service.add_workout(AddWorkoutParams {
+            exercise_identifier: "Squat",
+            date: now - Duration::days(i),
+            sets: Some(1),
+            reps: Some(5),
+            weight: Some(100.0 + i as f64),
+            ..Default::default()
+        })?;
+    }
+
+    let mut out = Vec::new();
+    assert_eq!(service.stream_workouts(&WorkoutFilters::default(), &mut out)?, 5);
+    let text = String::from_utf8(out)?;
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(lines.len(), 5);
+    let first: serde_json::Value = serde_json::from_str(lines[0])?;
+    assert_eq!(first["exercise_name"], "Squat");
+    assert_eq!(first["weight"], 100.0); // Newest first
+
+    // Offset and limit page through the history
+    let mut out = Vec::new();
+    let filters = WorkoutFilters { offset: Some(1), limit: Some(2), ..Default::default() };
+    assert_eq!(service.stream_workouts(&filters, &mut out)?, 2);
+    let weights: Vec<f64> = String::from_utf8(out)?
+        .lines()
+        .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap()["weight"].as_f64().unwrap())
+        .collect();
+    assert_eq!(weights, vec![101.0, 102.0]);
+    Ok(())
+}
+
+#[test]
+fn test_suggest_next_workout() -> Result<()> {
+    let mut service = create_test_service()?;
+    for (name, muscles) in [
+        ("Squat", "legs"),
+        ("Lunge", "legs"),
+        ("Bench", "chest"),
+        ("Fly", "chest"),
+        ("Curl", "arms"),
+        ("Row", "back"),
+    ] {
+        service.create_exercise(name, ExerciseType::Resistance, None, Some(muscles))?;
+    }
+    let now = Utc::now();
+    for (name, days_ago, weight) in [
+        ("Squat", 1, 100.0),
+        ("Lunge", 10, 40.0),
+        ("Bench", 5, 50.0),
+        ("Row", 20, 60.0),
+    ] {
+        service.add_workout(AddWorkoutParams {
+            exercise_identifier: name,
+            date: now - Duration::days(days_ago),
+            sets: Some(1),
+            reps: Some(5),
+            weight: Some(weight),
+            ..Default::default()
+        })?;
+    }
+
+    let suggestions = service.suggest_next_workout()?;
+    let names: Vec<_> = suggestions.iter().map(|s| s.name.as_str()).collect();
+    // Untrained muscles first, one per muscle group, then the remaining stale exercise.
+    assert_eq!(names, vec!["Curl", "Row", "Fly", "Lunge", "Bench"]);
+    assert_eq!(suggestions[0].last_performed, None);
+    assert!(suggestions[0].reason.starts_with("Never performed"));
+    assert_eq!(
+        suggestions[1].last_performed,
+        Some((now - Duration::days(20)).date_naive())
+    );
+    assert!(suggestions[1].reason.contains("back not trained"));
+    assert_eq!(service.suggest_next_workout()?, suggestions);
+
+    let today = now.date_naive().weekday();
+    service.set_scheduled_days(vec![today.succ()])?;
+    assert!(service.suggest_next_workout()?.is_empty());
+    service.set_scheduled_days(vec![today])?;
+    assert_eq!(service.suggest_next_workout()?.len(), 5);
+    Ok(())
+}
+
+#[test]
+fn test_pb_tolerance() -> Result<()> {
+    let mut service = create_test_service()?;
+    service.create_exercise("Bench", ExerciseType::Resistance, None, Some("chest"))?;
+    service.set_pb_notify_volume(false)?;
+    assert!(service.set_pb_tolerance(-0.5, 0.0).is_err());
+    assert!(service.set_pb_tolerance(0.0, f64::NAN).is_err());
+
+    let add = |service: &mut AppService, weight| {
+        service
+            .add_workout(AddWorkoutParams {
+                exercise_identifier: "Bench",
+                date: Utc::now(),
+                sets: Some(1),
+                reps: Some(5),
+                weight: Some(weight),
+                ..Default::default()
+            })
+            .map(|(_, pb)| pb)
+    };
+
+    add(&mut service, 100.0)?;
+    // Default tolerance of 0 keeps the old behaviour
+    assert!(add(&mut service, 100.01)?.is_some_and(|pb| pb.weight.achieved));
+
+    service.set_pb_tolerance(0.5, 0.0)?;
+    assert!(add(&mut service, 100.3)?.is_none(), "+0.29 is within the tolerance");
+    assert!(add(&mut service, 100.9)?.is_some_and(|pb| pb.weight.achieved));
+
+    service.set_pb_tolerance(0.0, 1.0)?;
+    assert!(add(&mut service, 101.5)?.is_none(), "+0.6 is under 1% of 100.9");
+    assert!(add(&mut service, 102.6)?.is_some_and(|pb| pb.weight.achieved));
+    Ok(())
+}
+
+#[test]
+fn test_pb_notify_ties() -> Result<()> {
+    let mut service = create_test_service()?;
+    service.create_exercise("Bench", ExerciseType::Resistance, None, Some("chest"))?;
+    service.set_pb_notify_volume(false)?;
+    service.set_pb_notify_reps(false)?;
+    assert!(!service.config.pb_notifications.notify_ties);
+
+    let add = |service: &mut AppService, weight| {
+        service
+            .add_workout(AddWorkoutParams {
+                exercise_identifier: "Bench",
+                date: Utc::now(),
+                sets: Some(1),
+                reps: Some(5),
+                weight: Some(weight),
+                ..Default::default()
+            })
+            .map(|(_, pb)| pb)
+    };
+
+    add(&mut service, 100.0)?;
+    assert!(add(&mut service, 100.0)?.is_none(), "Ties are not reported by default");
+
+    service.set_pb_notify_ties(true)?;
+    let tie = add(&mut service, 100.0)?.expect("tie reported");
+    assert!(tie.weight.tied && !tie.weight.achieved && !tie.any_pb());
+    assert_eq!(tie.weight.headline(), Some("Matched PB"));
+    assert_eq!(tie.reps.headline(), None); // Reps notifications are off
+    assert!(service.pb_events("Bench", &tie).is_empty());
+    assert!(add(&mut service, 99.0)?.is_none());
+
+    service.set_pb_tolerance(0.5, 0.0)?;
+    assert!(add(&mut service, 99.6)?.is_some_and(|pb| pb.weight.tied));
+    let pb = add(&mut service, 101.0)?.expect("new PB");
+    assert!(pb.weight.achieved && !pb.weight.tied);
+    assert_eq!(pb.weight.headline(), Some("New PB"));
+    Ok(())
+}
+
+#[test]
+fn test_amrap_top_set() -> Result<()> {
+    let mut service = create_test_service()?;
+    service.create_exercise("Squat", ExerciseType::Resistance, None, Some("legs"))?;
+    let today = Utc::now();
+    let yesterday = today - Duration::days(1);
+
+    let add = |service: &mut AppService, date, reps, weight, is_amrap| {
+        service
+            .add_workout(AddWorkoutParams {
+                exercise_identifier: "Squat",
+                date,
+                sets: Some(1),
+                reps: Some(reps),
+                weight: Some(weight),
+                is_amrap,
+                ..Default::default()
+            })
+            .map(|(id, _)| id)
+    };
+
+    add(&mut service, yesterday, 5, 100.0, false)?;
+    let heavy_triple = add(&mut service, yesterday, 3, 110.0, false)?;
+    let amrap = add(&mut service, yesterday, 8, 85.0, true)?;
+    let solo = add(&mut service, today, 5, 90.0, false)?;
+
+    // The AMRAP set wins even though the triple has a higher E1RM
+    let top = service.get_top_set(heavy_triple)?.expect("top set");
+    assert_eq!(top.workout_id, amrap);
+    assert_eq!((top.reps, top.weight), (8, 85.0));
+    assert!(top.is_amrap);
+    assert!((top.e1rm - 85.0 * (1.0 + 8.0 / 30.0)).abs() < 1e-9);
+
+    let graph = service.get_data_for_graph("Squat", GraphType::Estimated1RM, None, None, None)?;
+    assert_eq!(graph.len(), 2);
+    assert!((graph[0].1 - top.e1rm).abs() < 1e-9);
+    assert!((graph[1].1 - 90.0 * (1.0 + 5.0 / 30.0)).abs() < 1e-9);
+
+    let top_today = service.get_top_set(solo)?.expect("top set");
+    assert_eq!(top_today.workout_id, solo);
+    assert!(!top_today.is_amrap);
+
+    // Without the AMRAP flag the highest E1RM is used
+    service.edit_workout(EditWorkoutParams {
+        id: amrap,
+        new_is_amrap: Some(false),
+        ..Default::default()
+    })?;
+    assert_eq!(service.get_top_set(amrap)?.map(|t| t.workout_id), Some(heavy_triple));
+
+    assert!(service.get_top_set(9999).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_workout_metadata() -> Result<()> {
+    let mut service = create_test_service()?;
+    service.create_exercise("Squat", ExerciseType::Resistance, None, Some("legs"))?;
+    let (id, _) = service.add_workout(AddWorkoutParams {
+        exercise_identifier: "Squat",
+        date: Utc::now(),
+        sets: Some(3),
+        reps: Some(5),
+        weight: Some(100.0),
+        ..Default::default()
+    })?;
+
+    assert!(service.get_workout_metadata(id)?.is_empty());
+    service.set_workout_metadata(id, " Tempo ", "3-1-1")?;
+    service.set_workout_metadata(id, "band.tension", "light")?;
+    service.set_workout_metadata(id, "band.tension", "heavy")?; // Replaces
+    let meta = service.get_workout_metadata(id)?;
+    assert_eq!(meta.len(), 2);
+    assert_eq!(meta["tempo"], "3-1-1");
+    assert_eq!(meta["band.tension"], "heavy");
+
+    assert!(service.set_workout_metadata(id, "  ", "x").is_err());
+    assert!(service.set_workout_metadata(id, "bad key", "x").is_err());
+    assert!(service.set_workout_metadata(9999, "tempo", "x").is_err());
+
+    let (key, value) = parse_metadata_arg("Incline = 12%")?;
+    assert_eq!((key.as_str(), value.as_str()), ("incline", "12%"));
+    assert!(parse_metadata_arg("incline").is_err());
+    assert!(parse_metadata_arg("=5").is_err());
+
+    // Deleting a workout removes its metadata
+    service.delete_workouts(&[id])?;
+    assert!(service.get_workout_metadata(id)?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_list_aliases_for() -> Result<()> {
+    let service = create_test_service()?;
+    service.create_exercise("Bench Press", ExerciseType::Resistance, None, Some("chest"))?;
+    service.create_exercise("Squat", ExerciseType::Resistance, None, Some("legs"))?;
+    service.create_alias("bp", "Bench Press")?;
+    service.create_alias("bench", "Bench Press")?;
+    service.create_alias("sq", "Squat")?;
+    service.create_alias("flat", "bp")?;
+
+    assert_eq!(service.list_aliases_for("bench press")?, vec!["bench", "bp", "flat"]);
+    assert_eq!(service.list_aliases_for("sq")?, vec!["sq"]);
+
+    service.delete_alias("bench")?;
+    assert_eq!(service.list_aliases_for("Bench Press")?, vec!["bp", "flat"]);
+
+    service.create_exercise("Deadlift", ExerciseType::Resistance, None, Some("back"))?;
+    assert!(service.list_aliases_for("Deadlift")?.is_empty());
+    assert!(service.list_aliases_for("Nonexistent").is_err());
+    Ok(())
+}
+
+#[test]
+fn test_smoothed_graph_data() -> Result<()> {
+    let mut service = create_test_service()?;
+    service.create_exercise("Squat", ExerciseType::Resistance, None, Some("legs"))?;
+    let start = Utc::now() - Duration::days(10);
+    for (day, reps) in [(0, 4), (1, 8), (2, 6), (3, 10), (4, 2)] {
+        service.add_workout(AddWorkoutParams {
+            exercise_identifier: "Squat",
+            date: start + Duration::days(day),
+            sets: Some(1),
+            reps: Some(reps),
+            weight: Some(100.0),
+            ..Default::default()
+        })?;
+    }
+
+    let raw = service.get_data_for_graph("Squat", GraphType::MaxReps, None, None, None)?;
+    let values = |series: &[(NaiveDate, f64)]| series.iter().map(|&(_, v)| v).collect::<Vec<_>>();
+    assert_eq!(values(&raw), vec![4.0, 8.0, 6.0, 10.0, 2.0]);
+
+    let smoothed = service.get_smoothed_graph_data("Squat", GraphType::MaxReps, None, None, 3)?;
+    let dates = |series: &[(NaiveDate, f64)]| series.iter().map(|&(d, _)| d).collect::<Vec<_>>();
+    assert_eq!(dates(&smoothed), dates(&raw));
+    assert_eq!(values(&smoothed), vec![6.0, 6.0, 8.0, 6.0, 6.0]);
+
+    // Unsmoothed for windows of 0 or 1; overall mean once the window covers the series
+    assert_eq!(moving_average(&raw, 1), raw);
+    assert_eq!(moving_average(&raw, 0), raw);
+    assert_eq!(values(&moving_average(&raw, 50)), vec![6.0; 5]);
+    assert!(moving_average(&[], 3).is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_service_error_variants() -> Result<()> {
+    let mut service = create_test_service()?;
+    service.create_exercise("Squat", ExerciseType::Resistance, None, Some("legs"))?;
+    let (id, _) = service.add_workout(AddWorkoutParams {
+        exercise_identifier: "Squat",
+        date: Utc::now(),
+        reps: Some(5),
+        weight: Some(100.0),
+        ..Default::default()
+    })?;
+
+    let err = service.get_exercise_stats("Nonexistent").unwrap_err();
+    assert!(matches!(err, ServiceError::NotFound(_)));
+    assert!(matches!(err.downcast_ref::<DbError>(), Some(DbError::ExerciseNotFound(_))));
+
+    let err = service.get_top_set(9999).unwrap_err();
+    assert!(matches!(err, ServiceError::NotFound(_)));
+
+    let err = service
+        .create_exercise("squat", ExerciseType::Resistance, None, None)
+        .unwrap_err();
+    assert!(matches!(err, ServiceError::Conflict(_)));
+    assert!(err.to_string().contains("must be unique"));
+
+    // Exercise edits and restores keep the `DbError` under their message
+    service.create_exercise("Bench", ExerciseType::Resistance, None, Some("chest"))?;
+    let err = service
+        .edit_exercise("Bench", EditExerciseParams { new_name: Some("Squat"), ..Default::default() })
+        .unwrap_err();
+    assert!(matches!(err, ServiceError::Conflict(_)));
+    assert!(err.to_string().contains("already taken"), "{err}");
+    let err = service.restore_exercise("Nope").unwrap_err();
+    assert!(matches!(err, ServiceError::NotFound(_)));
+    assert!(err.to_string().contains("No deleted exercise 'Nope'"), "{err}");
+    assert!(matches!(service.delete_exercise(&["Nope".to_string()]), Err(ServiceError::NotFound(_))));
+
+    let err = service.set_workout_metadata(id, " ", "x").unwrap_err();
+    assert!(matches!(err, ServiceError::Validation(_)));
+
+    let err = service.add_bodyweight_entry(Utc::now(), -1.0).unwrap_err();
+    assert!(matches!(err, ServiceError::Validation(_)));
+    assert!(err.downcast_ref::<ConfigError>().is_some());
+
+    // Errors still convert into `anyhow::Error` for callers that don't care about the kind
+    let as_anyhow: anyhow::Error = service.list_aliases_for("Nonexistent").unwrap_err().into();
+    assert!(as_anyhow.downcast_ref::<ServiceError>().is_some());
+    Ok(())
+}
+
+#[test]
+fn test_best_session_volume() -> Result<()> {
+    let mut service = create_test_service()?;
+    for name in ["Squat", "Bench", "Curl"] {
+        service.create_exercise(name, ExerciseType::Resistance, None, Some("misc"))?;
+    }
+    assert!(service.best_session_volume()?.is_none());
+
+    let today = Utc::now();
+    let last_week = today - Duration::days(7);
+    let add = |service: &mut AppService, name, date, sets, reps, weight| {
+        service
+            .add_workout(AddWorkoutParams {
+                exercise_identifier: name,
+                date,
+                sets: Some(sets),
+                reps: Some(reps),
+                weight: Some(weight),
+                ..Default::default()
+            })
+            .map(|(_, pb)| pb)
+    };
+
+    add(&mut service, "Squat", last_week, 3, 5, 100.0)?; // 1500
+    add(&mut service, "Bench", last_week, 3, 5, 60.0)?; // 900 -> day total 2400
+    assert!(add(&mut service, "Squat", today, 3, 5, 100.0)?.is_none()); // 1500 < 2400
+
+    let pb = add(&mut service, "Bench", today, 5, 5, 70.0)?.expect("session record"); // 3250
+    assert!(pb.session_volume.achieved);
+    assert_eq!(pb.session_volume.previous_value, Some(2400.0));
+    assert_eq!(pb.session_volume.new_value, Some(3250.0));
+
+    // Already the record day: further entries don't flag it again
+    let pb = add(&mut service, "Curl", today, 1, 10, 10.0)?;
+    assert!(pb.is_none_or(|pb| !pb.session_volume.achieved));
+
+    let best = service.best_session_volume()?.expect("best session");
+    assert_eq!(best.date, today.date_naive());
+    assert_eq!(best.total_volume, 3350.0);
+    assert_eq!(
+        best.by_exercise,
+        vec![
+            ("Bench".to_string(), 1750.0),
+            ("Squat".to_string(), 1500.0),
+            ("Curl".to_string(), 100.0)
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_parse_flexible_date() -> Result<()> {
+    let today = NaiveDate::from_ymd_opt(2024, 3, 13).unwrap(); // A Wednesday
+    let day = |d| NaiveDate::from_ymd_opt(2024, 3, d).unwrap();
+
+    assert_eq!(parse_flexible_date("today", today)?, today);
+    assert_eq!(parse_flexible_date(" Yesterday ", today)?, day(12));
+    assert_eq!(parse_flexible_date("3 days ago", today)?, day(10));
+    assert_eq!(parse_flexible_date("1 day ago", today)?, day(12));
+    assert_eq!(parse_flexible_date("2 weeks ago", today)?, NaiveDate::from_ymd_opt(2024, 2, 28).unwrap());
+    assert_eq!(parse_flexible_date("2024-01-05", today)?, NaiveDate::from_ymd_opt(2024, 1, 5).unwrap());
+
+    // Weekdays resolve to the most recent occurrence, today included
+    assert_eq!(parse_flexible_date("mon", today)?, day(11));
+    assert_eq!(parse_flexible_date("Wednesday", today)?, today);
+    assert_eq!(parse_flexible_date("thu", today)?, day(7));
+
+    for bad in ["", "someday", "01/05/2024", "2024-13-01", "three days ago", "3 months ago", "-1 days ago"] {
+        let err = parse_flexible_date(bad, today).unwrap_err();
+        assert!(matches!(err, ServiceError::Validation(_)), "{bad}: {err}");
+    }
+    assert!(parse_flexible_date("05/01/2024", today)
+        .unwrap_err()
+        .to_string()
+        .contains("YYYY-MM-DD"));
+    Ok(())
+}
+
+#[test]
+fn test_pb_previous_date() -> Result<()> {
+    let mut service = create_test_service()?;
+    service.create_exercise("Bench", ExerciseType::Resistance, None, Some("chest"))?;
+    let now = Utc::now();
+    let add = |service: &mut AppService, days_ago, reps, weight| {
+        service
+            .add_workout(AddWorkoutParams {
+                exercise_identifier: "Bench",
+                date: now - Duration::days(days_ago),
+                sets: Some(1),
+                reps: Some(reps),
+                weight: Some(weight),
+                ..Default::default()
+            })
+            .map(|(_, pb)| pb)
+    };
+
+    add(&mut service, 90, 5, 100.0)?;
+    add(&mut service, 30, 8, 100.0)?; // Ties the weight PB, sets the reps PB
+    let pb = add(&mut service, 0, 3, 105.0)?.expect("weight PB");
+    assert!(pb.weight.achieved);
+    assert_eq!(pb.weight.previous_value, Some(100.0));
+    // The tie doesn't move the date: the best was first achieved 90 days ago
+    assert_eq!(pb.weight.previous_date, Some((now - Duration::days(90)).date_naive()));
+    assert_eq!(pb.reps.previous_date, Some((now - Duration::days(30)).date_naive()));
+    assert_eq!(pb.volume.previous_date, Some((now - Duration::days(30)).date_naive()));
+    assert_eq!(pb.pace.previous_date, None);
+    // Day totals 500, 800 and now 315: the session record is still 30 days ago
+    assert_eq!(pb.session_volume.previous_date, Some((now - Duration::days(30)).date_naive()));
+    assert!(!pb.session_volume.achieved);
+    Ok(())
+}
+
+#[test]
+fn test_import_bodyweights_from_csv() -> Result<()> {
+    let service = create_test_service()?;
+    let csv = "timestamp,weight,body_fat_pct,muscle_mass
+2024-01-01T07:00:00Z,80.5,18.2,35.0
+2024-01-02 07:30,80.1
+2024-01-03,79.8,,34.9
+
+2024-01-04,heavy
+not-a-date,79.0
+2024-01-05,-3
+2024-01-01T07:00:00Z,81.0
+";
+    let summary = service.import_bodyweights_from_csv(csv.as_bytes(), true, &CsvOptions::default(), None)?;
+    assert_eq!(summary.imported, 3);
+    assert_eq!(summary.skipped_duplicates, 0);
+    let error_lines: Vec<_> = summary.errors.iter().map(|e| e.line).collect();
+    // Bad weight, bad timestamp, negative weight, and a clashing timestamp
+    assert_eq!(error_lines, vec![6, 7, 8, 9]);
+    assert!(summary.errors[0].message.contains("heavy"));
+
+    let entries = service.list_body_measurements(10)?;
+    assert_eq!(entries.len(), 3);
+    let jan_1 = entries.iter().find(|e| e.weight == 80.5).expect("first row");
+    assert_eq!(jan_1.body_fat_pct, Some(18.2));
+    assert_eq!(jan_1.muscle_mass, Some(35.0));
+    let jan_3 = entries.iter().find(|e| e.weight == 79.8).expect("third row");
+    assert_eq!((jan_3.body_fat_pct, jan_3.muscle_mass), (None, Some(34.9)));
+
+    // Re-importing the same rows skips exact duplicates instead of failing them
+    let again = service.import_bodyweights_from_csv(
+        "2024-01-01T07:00:00Z,80.5\n2024-01-02 07:30,80.1\n2024-01-06,79.5\n".as_bytes(),
+        true,
+        &CsvOptions::default(),
+        None,
+    )?;
+    assert_eq!((again.imported, again.skipped_duplicates), (1, 2));
+    assert!(again.errors.is_empty());
+
+    let strict = service.import_bodyweights_from_csv(
+        "2024-01-06,79.5\n".as_bytes(),
+        false,
+        &CsvOptions::default(),
+        None,
+    )?;
+    assert_eq!(strict.imported, 0);
+    assert_eq!(strict.errors.len(), 1);
+    Ok(())
+}
+
+#[test]
+fn test_import_bodyweights_source_units() -> Result<()> {
+    let mut service = create_test_service()?;
+    assert_eq!(service.config.weight_units(), Units::Metric);
+    let lbs_csv = "timestamp,weight,body_fat_pct,muscle_mass\n2024-01-01,200,20.0,100\n";
+    let summary =
+        service.import_bodyweights_from_csv(lbs_csv.as_bytes(), false, &CsvOptions::default(), Some(Units::Imperial))?;
+    assert_eq!(summary.imported, 1);
+    let entry = &service.list_body_measurements(1)?[0];
+    assert!((entry.weight - 90.7184).abs() < 1e-9);
+    assert!((entry.muscle_mass.unwrap() - 45.3592).abs() < 1e-9);
+    assert_eq!(entry.body_fat_pct, Some(20.0)); // Percentages are not converted
+
+    // Matching units (explicit or assumed) are stored as given
+    service.import_bodyweights_from_csv("2024-01-02,80\n".as_bytes(), false, &CsvOptions::default(), Some(Units::Metric))?;
+    service.set_units(Units::Imperial)?;
+    service.import_bodyweights_from_csv("2024-01-03,180\n".as_bytes(), false, &CsvOptions::default(), None)?;
+    service.import_bodyweights_from_csv("2024-01-04,100\n".as_bytes(), false, &CsvOptions::default(), Some(Units::Metric))?;
+    let weights: Vec<f64> = service.list_body_measurements(3)?.iter().map(|e| e.weight).collect();
+    assert_eq!(weights[1..], [180.0, 80.0]);
+    assert!((weights[0] - 100.0 / 0.453_592).abs() < 1e-9);
+    Ok(())
+}
+
+#[test]
+fn test_csv_options() -> Result<()> {
+    let mut service = create_test_service()?;
+    let european = CsvOptions {
+        delimiter: ';',
+        line_ending: "\r\n".to_string(),
+        date_format: Some("%d.%m.%Y %H:%M".to_string()),
+        ..CsvOptions::default()
+    };
+    let summary = service.import_bodyweights_from_csv(
+        "datum;gewicht\r\n01.02.2024 07:30;80,5\r\n02.02.2024 07:30;80.1;\"18.5\"\r\n".as_bytes(),
+        false,
+        &european,
+        None,
+    )?;
+    // "80,5" is a single field with a semicolon delimiter, and not a valid number
+    assert_eq!(summary.imported, 1);
+    assert_eq!(summary.errors.len(), 1);
+    assert_eq!(summary.errors[0].line, 2);
+
+    let mut out = Vec::new();
+    assert_eq!(service.export_bodyweights_to_csv(&mut out, &european)?, 1);
+    let text = String::from_utf8(out.clone())?;
+    assert_eq!(
+        text,
+        "timestamp;weight;body_fat_pct;muscle_mass;notes\r\n02.02.2024 07:30;80.1;18.5;;\r\n"
+    );
+    // The export reads back with the same dialect
+    let again = service.import_bodyweights_from_csv(out.as_slice(), true, &european, None)?;
+    assert_eq!((again.imported, again.skipped_duplicates), (0, 1));
+
+    service.create_exercise("Bench", ExerciseType::Resistance, None, Some("chest"))?;
+    service.add_workout(AddWorkoutParams {
+        exercise_identifier: "Bench",
+        date: Utc.with_ymd_and_hms(2024, 2, 2, 12, 0, 0).unwrap(),
+        sets: Some(3),
+        reps: Some(5),
+        weight: Some(100.0),
+        notes: Some("felt \"easy\"; add 2.5".to_string()),
+        ..Default::default()
+    })?;
+    let mut out = Vec::new();
+    let written = service.export_workouts_to_csv(&WorkoutFilters::default(), &mut out, &european)?;
+    assert_eq!(written, 1);
+    let text = String::from_utf8(out)?;
+    let lines: Vec<_> = text.split("\r\n").collect();
+    assert!(lines[0].starts_with("id;timestamp;exercise;sets;reps;weight"));
+    assert!(lines[1].contains(";02.02.2024 "));
+    assert!(lines[1].ends_with(";\"felt \"\"easy\"\"; add 2.5\""));
+
+    let bad = CsvOptions { delimiter: '"', ..CsvOptions::default() };
+    assert!(service.export_bodyweights_to_csv(Vec::new(), &bad).is_err());
+    let bad = CsvOptions { date_format: Some("%Q".to_string()), ..CsvOptions::default() };
+    assert!(service.export_bodyweights_to_csv(Vec::new(), &bad).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_get_exercise_by_id() -> Result<()> {
+    let mut service = create_test_service()?;
+    let id = service.create_exercise("Bench", ExerciseType::Resistance, None, Some("chest"))?;
+
+    let exercise = service.get_exercise_by_id(id)?.expect("exercise by id");
+    assert_eq!(exercise.name, "Bench");
+    assert!(service.get_exercise_by_id(id + 100)?.is_none());
+
+    service.delete_exercise(&["Bench".to_string()])?;
+    assert!(service.get_exercise_by_id(id)?.is_none());
+    Ok(())
+}
+
+#[test]
+fn test_default_implicit_type() -> Result<()> {
+    let mut service = create_test_service()?;
+    let params = |name| AddWorkoutParams {
+        exercise_identifier: name,
+        date: Utc::now(),
+        sets: Some(3),
+        reps: Some(10),
+        ..Default::default()
+    };
+    // No default configured: unknown exercises still need a type
+    assert!(matches!(
+        service.add_workout(params("Sled Drag")),
+        Err(ServiceError::Validation(_))
+    ));
+
+    service.set_default_implicit_type(Some(ExerciseType::Resistance))?;
+    service.add_workout(params("Sled Drag"))?;
+    let sled = service.get_exercise_by_identifier_service("Sled Drag")?.expect("implicit exercise");
+    assert_eq!(sled.type_, ExerciseType::Resistance);
+    assert_eq!(sled.muscles, None);
+
+    // An explicit type still wins over the default
+    service.add_workout(AddWorkoutParams {
+        implicit_type: Some(ExerciseType::Cardio),
+        implicit_muscles: Some("legs".to_string()),
+        sets: None,
+        reps: None,
+        duration: Some(30),
+        ..params("Rowing")
+    })?;
+    let rowing = service.get_exercise_by_identifier_service("Rowing")?.expect("explicit exercise");
+    assert_eq!(rowing.type_, ExerciseType::Cardio);
+
+    service.set_default_implicit_type(None)?;
+    assert!(service.add_workout(params("Landmine Twist")).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_infer_exercise_type() -> Result<()> {
+    let mut service = create_test_service()?;
+    let config = Config::default();
+    assert_eq!(infer_exercise_type("Morning Run", &config), Some(ExerciseType::Cardio));
+    assert_eq!(infer_exercise_type("Weighted Pull-ups", &config), Some(ExerciseType::BodyWeight));
+    assert_eq!(infer_exercise_type("Dumbbell Curl", &config), Some(ExerciseType::Resistance));
+    // Keywords only match the start of a word, and conflicting keywords infer nothing
+    assert_eq!(infer_exercise_type("Brunch", &config), None);
+    assert_eq!(infer_exercise_type("Farmer's Walk", &config), None);
+
+    let mut custom = Config::default();
+    custom.type_keywords.insert("sled".to_string(), ExerciseType::Cardio);
+    assert_eq!(infer_exercise_type("Sled Push", &custom), Some(ExerciseType::Cardio));
+
+    let log = |service: &mut AppService, name| {
+        service.add_workout(AddWorkoutParams {
+            exercise_identifier: name,
+            date: Utc::now(),
+            duration: Some(20),
+            distance: Some(3.0),
+            ..Default::default()
+        })
+    };
+    log(&mut service, "Running")?;
+    let running = service.get_exercise_by_identifier_service("Running")?.expect("inferred");
+    assert_eq!(running.type_, ExerciseType::Cardio);
+    // An explicit type overrides the inferred one
+    service.add_workout(AddWorkoutParams {
+        exercise_identifier: "Bike Sprint",
+        date: Utc::now(),
+        sets: Some(5),
+        reps: Some(10),
+        implicit_type: Some(ExerciseType::Resistance),
+        implicit_muscles: Some("legs".to_string()),
+        ..Default::default()
+    })?;
+    let sprint = service.get_exercise_by_identifier_service("Bike Sprint")?.expect("explicit");
+    assert_eq!(sprint.type_, ExerciseType::Resistance);
+    // Names without a keyword still need a type
+    assert!(log(&mut service, "Sled Drag").is_err());
+    Ok(())
+}
+
+#[test]
+fn test_seed_default_exercises() -> Result<()> {
+    let service = create_test_service()?;
+    service.create_exercise("squat", ExerciseType::Resistance, None, Some("legs"))?;
+    service.create_exercise("Bench", ExerciseType::Resistance, None, Some("chest"))?;
+    service.create_alias("Deadlift", "Bench")?;
+
+    // Names taken by an exercise (any case) or an alias are skipped
+    let created = service.seed_default_exercises()?;
+    assert_eq!(created, DEFAULT_EXERCISES.len() - 2);
+    let squat = service.get_exercise_by_identifier_service("Squat")?.expect("squat");
+    assert_eq!(squat.muscles.as_deref(), Some("legs"));
+    let press = service
+        .get_exercise_by_identifier_service("Bench Press")?
+        .expect("seeded exercise");
+    assert_eq!(press.type_, ExerciseType::Resistance);
+    let running = service.get_exercise_by_identifier_service("Running")?.expect("seeded cardio");
+    assert!(running.log_duration && running.log_distance);
+
+    // Seeding again is a no-op
+    assert_eq!(service.seed_default_exercises()?, 0);
+    assert!(Config::default().seed_on_first_run);
+    Ok(())
+}
+
+#[test]
+fn test_overall_activity() -> Result<()> {
+    let mut service = create_test_service()?;
+    service.create_exercise("Bench", ExerciseType::Resistance, None, Some("chest"))?;
+    service.create_exercise("Squat", ExerciseType::Resistance, None, Some("legs"))?;
+    assert_eq!(service.days_since_last_workout()?, None);
+    assert!(!service.is_currently_active(30)?);
+
+    let log = |service: &mut AppService, name, days_ago| {
+        service.add_workout(AddWorkoutParams {
+            exercise_identifier: name,
+            date: Utc::now() - Duration::days(days_ago),
+            sets: Some(3),
+            reps: Some(5),
+            weight: Some(100.0),
+            ..Default::default()
+        })
+    };
+    log(&mut service, "Bench", 20)?;
+    let (squat_id, _) = log(&mut service, "Squat", 9)?;
+    // The latest workout across all exercises counts
+    assert_eq!(service.days_since_last_workout()?, Some(9));
+    assert!(service.is_currently_active(9)?);
+    assert!(!service.is_currently_active(8)?);
+
+    service.delete_workouts(&[squat_id])?;
+    assert_eq!(service.days_since_last_workout()?, Some(20));
+    Ok(())
+}
+
+#[test]
+fn test_weekly_counts() -> Result<()> {
+    let mut service = create_test_service()?;
+    service.set_week_start(Weekday::Sun)?;
+    service.create_exercise("Bench", ExerciseType::Resistance, None, Some("chest"))?;
+    let today = Utc::now().date_naive();
+    let this_week = today - Duration::days(i64::from(today.weekday().num_days_from_sunday()));
+    let log = |service: &mut AppService, date: NaiveDate| {
+        service.add_workout(AddWorkoutParams {
+            exercise_identifier: "Bench",
+            date: Utc.from_utc_datetime(&date.and_hms_opt(12, 0, 0).unwrap()),
+            sets: Some(3),
+            reps: Some(5),
+            weight: Some(100.0),
+            ..Default::default()
+        })
+    };
+    log(&mut service, this_week)?;
+    log(&mut service, this_week)?;
+    log(&mut service, this_week - Duration::days(1))?; // Saturday of last week
+    log(&mut service, this_week - Duration::weeks(3))?; // Outside the window
+
+    let counts = service.weekly_counts(2)?;
+    assert_eq!(
+        counts,
+        vec![
+            (this_week - Duration::weeks(2), 0),
+            (this_week - Duration::weeks(1), 1),
+            (this_week, 2),
+        ]
+    );
+    assert_eq!(service.weekly_counts(0)?, vec![(this_week, 2)]);
+    Ok(())
+}
+
+#[test]
+fn test_exercise_variations() -> Result<()> {
+    let mut service = create_test_service()?;
+    service.create_exercise("Bench Press", ExerciseType::Resistance, None, Some("chest"))?;
+    service.create_exercise("Paused Bench", ExerciseType::Resistance, None, Some("chest"))?;
+    service.create_exercise("Spoto Press", ExerciseType::Resistance, None, Some("chest"))?;
+    let log = |service: &mut AppService, name, days_ago, weight, reps| {
+        service.add_workout(AddWorkoutParams {
+            exercise_identifier: name,
+            date: Utc::now() - Duration::days(days_ago),
+            sets: Some(3),
+            reps: Some(reps),
+            weight: Some(weight),
+            ..Default::default()
+        })
+    };
+    log(&mut service, "Bench Press", 10, 100.0, 5)?;
+    log(&mut service, "Paused Bench", 5, 90.0, 8)?;
+    log(&mut service, "Spoto Press", 2, 105.0, 3)?;
+
+    service.set_exercise_parent("Paused Bench", Some("Bench Press"))?;
+    service.set_exercise_parent("Spoto Press", Some("Paused Bench"))?;
+    assert_eq!(service.get_exercise_variations("bench press")?, vec!["Paused Bench", "Spoto Press"]);
+    // Cycles are rejected, including an exercise being its own parent
+    assert!(service.set_exercise_parent("Bench Press", Some("Spoto Press")).is_err());
+    assert!(service.set_exercise_parent("Bench Press", Some("Bench Press")).is_err());
+
+    let own = service.get_exercise_stats("Bench Press")?;
+    assert_eq!(own.total_workouts, 1);
+    let rolled_up = service.get_stats_including_variations("Bench Press")?;
+    assert_eq!(rolled_up.canonical_name, "Bench Press");
+    assert_eq!(rolled_up.total_workouts, 3);
+    assert_eq!(rolled_up.personal_bests.max_weight, Some(105.0));
+    assert_eq!(rolled_up.personal_bests.max_reps, Some(8));
+
+    // Renaming the parent keeps its variations attached
+    service.edit_exercise("Paused Bench", EditExerciseParams { new_name: Some("Pause Bench"), ..Default::default() })?;
+    let spoto = service.get_exercise_by_identifier_service("Spoto Press")?.expect("variation");
+    assert_eq!(spoto.parent_exercise.as_deref(), Some("Pause Bench"));
+    assert_eq!(service.get_exercise_variations("Bench Press")?, vec!["Pause Bench", "Spoto Press"]);
+
+    // Deleting a parent leaves its variations standalone
+    service.delete_exercise(&["Pause Bench".to_string()])?;
+    let spoto = service.get_exercise_by_identifier_service("Spoto Press")?.expect("variation");
+    assert_eq!(spoto.parent_exercise, None);
+    assert!(service.get_exercise_variations("Bench Press")?.is_empty());
+
+    service.set_exercise_parent("Spoto Press", Some("Bench Press"))?;
+    service.set_exercise_parent("Spoto Press", None)?;
+    assert!(service.get_exercise_variations("Bench Press")?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_parse_number() -> Result<()> {
+    assert_eq!(parse_number("100.5", '.')?, 100.5);
+    assert_eq!(parse_number(" 1,250.5 ", '.')?, 1250.5);
+    assert_eq!(parse_number("100,5", ',')?, 100.5);
+    assert_eq!(parse_number("1.250,5", ',')?, 1250.5);
+    assert_eq!(parse_number("1 250", ',')?, 1250.0);
+    assert_eq!(parse_number("-2,5", ',')?, -2.5);
+    assert_eq!(parse_number(".5", '.')?, 0.5);
+    assert_eq!(parse_number("80", '.')?, 80.0);
+
+    // A comma in a '.' locale is only a thousands separator between groups of three
+    let err = parse_number("100,5", '.').unwrap_err();
+    assert!(matches!(err, ServiceError::Validation(_)));
+    assert!(err.to_string().contains("'.' as the decimal separator"));
+    for bad in ["", "abc", "1,2345", ",100", "1.5.5", "12kg", "-", "."] {
+        assert!(parse_number(bad, '.').is_err(), "{bad:?} should be rejected");
+    }
+
+    let mut service = create_test_service()?;
+    assert_eq!(service.parse_number_input("100.5")?, 100.5);
+    service.set_decimal_separator(',')?;
+    assert_eq!(service.parse_number_input("100,5")?, 100.5);
+    assert!(matches!(
+        service.set_decimal_separator(';'),
+        Err(ConfigError::InvalidDecimalSeparator(';'))
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_duplicate_workout() -> Result<()> {
+    let mut service = create_test_service()?;
+    service.create_exercise("Bench", ExerciseType::Resistance, None, Some("chest"))?;
+    service.create_exercise("Squat", ExerciseType::Resistance, None, Some("legs"))?;
+    let day1 = NaiveDate::from_ymd_opt(2024, 3, 4).unwrap();
+    let day2 = NaiveDate::from_ymd_opt(2024, 3, 6).unwrap();
+    let log = |service: &mut AppService, name, hour, weight| {
+        service.add_workout(AddWorkoutParams {
+            exercise_identifier: name,
+            date: Utc.from_utc_datetime(&day1.and_hms_opt(hour, 30, 0).unwrap()),
+            sets: Some(3),
+            reps: Some(5),
+            weight: Some(weight),
+            notes: Some(format!("{name} day")),
+            ..Default::default()
+        })
+    };
+    let (bench_id, _) = log(&mut service, "Bench", 9, 80.0)?;
+    let (squat_id, _) = log(&mut service, "Squat", 10, 120.0)?;
+
+    // The copy is identical apart from date and ID, and keeps the time of day
+    let (copy_id, pb_info) = service.duplicate_workout(bench_id, day2)?;
+    assert_ne!(copy_id, bench_id);
+    assert!(pb_info.is_none());
+    let copies = service.list_workouts(&WorkoutFilters { date: Some(day2), ..Default::default() })?;
+    assert_eq!(copies.len(), 1);
+    let copy = &copies[0];
+    assert_eq!(copy.id, copy_id);
+    assert_eq!(copy.timestamp, Utc.from_utc_datetime(&day2.and_hms_opt(9, 30, 0).unwrap()));
+    assert_eq!((copy.sets, copy.reps, copy.weight), (Some(3), Some(5), Some(80.0)));
+    assert_eq!(copy.notes.as_deref(), Some("Bench day"));
+
+    // Copies count towards their new day's session volume
+    let (_, pb_info) = service.duplicate_workout(squat_id, day2)?;
+    assert!(pb_info.is_none()); // Ties day 1
+    let (_, pb_info) = service.duplicate_workout(bench_id, day2)?;
+    assert!(pb_info.unwrap().session_volume.achieved);
+
+    let copied_ids = service.duplicate_day(day1, day2)?;
+    assert_eq!(copied_ids.len(), 2);
+    let day2_entries = service.list_workouts(&WorkoutFilters { date: Some(day2), ..Default::default() })?;
+    assert_eq!(day2_entries.len(), 5);
+
+    // The whole day is undone as one step
+    let undone = service.undo_last()?.unwrap();
+    assert_eq!(undone.workout_ids, copied_ids);
+    assert_eq!(service.list_workouts(&WorkoutFilters { date: Some(day2), ..Default::default() })?.len(), 3);
+
+    assert!(service.duplicate_day(day2 + Duration::days(1), day2)?.is_empty());
+    assert!(matches!(
+        service.duplicate_workout(9999, day2),
+        Err(ServiceError::NotFound(_))
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_predict_metric() -> Result<()> {
+    let mut service = create_test_service()?;
+    service.create_exercise("Squat", ExerciseType::Resistance, None, Some("legs"))?;
+    let start = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+    let log = |service: &mut AppService, week: i64, weight| {
+        service.add_workout(AddWorkoutParams {
+            exercise_identifier: "Squat",
+            date: Utc.from_utc_datetime(&(start + Duration::weeks(week)).and_hms_opt(12, 0, 0).unwrap()),
+            sets: Some(3),
+            reps: Some(5),
+            weight: Some(weight),
+            ..Default::default()
+        })
+    };
+    log(&mut service, 0, 100.0)?;
+    log(&mut service, 1, 105.0)?;
+    // Not enough history yet
+    assert_eq!(service.predict_metric("Squat", GraphType::MaxWeight, 120.0)?, None);
+
+    log(&mut service, 2, 110.0)?;
+    let prediction = service.predict_metric("Squat", GraphType::MaxWeight, 120.0)?.unwrap();
+    assert_eq!(prediction.target_date, start + Duration::weeks(4));
+    assert!((prediction.weekly_rate - 5.0).abs() < 1e-9);
+    assert!((prediction.r_squared - 1.0).abs() < 1e-9);
+
+    // A noisier history fits the line less well
+    log(&mut service, 3, 107.5)?;
+    let prediction = service.predict_metric("Squat", GraphType::Estimated1RM, 140.0)?.unwrap();
+    assert!(prediction.weekly_rate > 0.0);
+    assert!(prediction.r_squared < 1.0);
+
+    // A declining trend has no projected date
+    log(&mut service, 4, 90.0)?;
+    log(&mut service, 5, 85.0)?;
+    assert_eq!(service.predict_metric("Squat", GraphType::MaxWeight, 120.0)?, None);
+
+    assert!(matches!(
+        service.predict_metric("Squat", GraphType::WorkoutVolume, 1000.0),
+        Err(ServiceError::Validation(_))
+    ));
+    assert!(matches!(
+        service.predict_metric("Nope", GraphType::MaxWeight, 100.0),
+        Err(ServiceError::NotFound(_))
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_weight_per_side() -> Result<()> {
+    let mut service = create_test_service()?;
+    service.create_exercise("Leg Press", ExerciseType::Resistance, None, Some("legs"))?;
+    service.create_exercise("Squat", ExerciseType::Resistance, None, Some("legs"))?;
+    service.set_exercise_weight_per_side("Leg Press", true, Some(20.0))?;
+    let def = service.get_exercise_by_identifier_service("Leg Press")?.unwrap();
+    assert!(def.weight_is_per_side);
+    assert_eq!(def.carriage_weight, Some(20.0));
+    assert_eq!(def.entered_weight(def.total_weight(50.0)), 50.0);
+
+    let log = |service: &mut AppService, name, weight| {
+        service.add_workout(AddWorkoutParams {
+            exercise_identifier: name,
+            date: Utc::now(),
+            sets: Some(3),
+            reps: Some(10),
+            weight: Some(weight),
+            ..Default::default()
+        })
+    };
+    // 50 per side on a 20 carriage is 120 total, compared like any other weight
+    let (id, _) = log(&mut service, "Leg Press", 50.0)?;
+    let (_, pb_info) = log(&mut service, "Leg Press", 55.0)?;
+    let pb = pb_info.unwrap();
+    assert_eq!(pb.weight.new_value, Some(130.0));
+    assert_eq!(pb.weight.previous_value, Some(120.0));
+    let (squat_id, _) = log(&mut service, "Squat", 50.0)?;
+
+    let weight_of = |service: &AppService, id| -> Result<Option<f64>> {
+        let workouts = service.list_workouts(&WorkoutFilters::default())?;
+        Ok(workouts.into_iter().find(|w| w.id == id).unwrap().weight)
+    };
+    assert_eq!(weight_of(&service, id)?, Some(120.0));
+    assert_eq!(weight_of(&service, squat_id)?, Some(50.0));
+
+    service.edit_workout(EditWorkoutParams {
+        id,
+        new_weight: Some(40.0),
+        ..Default::default()
+    })?;
+    assert_eq!(weight_of(&service, id)?, Some(100.0));
+
+    // Turning it off clears the carriage weight and leaves logged totals alone
+    service.set_exercise_weight_per_side("Leg Press", false, Some(20.0))?;
+    let def = service.get_exercise_by_identifier_service("Leg Press")?.unwrap();
+    assert_eq!((def.weight_is_per_side, def.carriage_weight), (false, None));
+    assert_eq!(weight_of(&service, id)?, Some(100.0));
+
+    assert!(matches!(
+        service.set_exercise_weight_per_side("Leg Press", true, Some(-5.0)),
+        Err(ServiceError::Validation(_))
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_export_schedule_ical() -> Result<()> {
+    let mut service = create_test_service()?;
+    service.set_scheduled_days(vec![Weekday::Mon, Weekday::Thu])?;
+    let mut output = Vec::new();
+    assert_eq!(service.export_schedule_ical(3, &mut output)?, 6);
+    let ical = String::from_utf8(output)?;
+    assert!(ical.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+    assert!(ical.ends_with("END:VCALENDAR\r\n"));
+    assert_eq!(ical.matches("BEGIN:VEVENT").count(), 6);
+
+    let today = Utc::now().date_naive();
+    let next_monday = today + Duration::days(i64::from((7 - today.weekday().num_days_from_monday()) % 7));
+    assert!(ical.contains(&format!("DTSTART;VALUE=DATE:{}\r\n", next_monday.format("%Y%m%d"))));
+    assert!(ical.contains(&format!("DTEND;VALUE=DATE:{}\r\n", (next_monday + Duration::days(1)).format("%Y%m%d"))));
+
+    // Without a schedule every day is a training day
+    service.set_scheduled_days(Vec::new())?;
+    assert_eq!(service.export_schedule_ical(2, std::io::sink())?, 14);
+    assert_eq!(service.export_schedule_ical(0, std::io::sink())?, 0);
+    Ok(())
+}
+
+#[test]
+fn test_get_exercise_stats_batch() -> Result<()> {
+    let mut service = create_test_service()?;
+    service.create_exercise("Bench", ExerciseType::Resistance, None, Some("chest"))?;
+    service.create_exercise("Pull-ups", ExerciseType::BodyWeight, None, Some("back"))?;
+    service.create_exercise("Run", ExerciseType::Cardio, None, Some("legs"))?;
+    service.create_exercise("Curl", ExerciseType::Resistance, None, Some("arms"))?;
+    service.create_alias("bp", "Bench")?;
+    for (days_ago, weight) in [(10, 80.0), (3, 85.0)] {
+        service.add_workout(AddWorkoutParams {
+            exercise_identifier: "Bench",
+            date: Utc::now() - Duration::days(days_ago),
+            sets: Some(3),
+            reps: Some(5),
+            weight: Some(weight),
+            ..Default::default()
+        })?;
+    }
+    service.add_workout(AddWorkoutParams {
+        exercise_identifier: "Pull-ups",
+        date: Utc::now() - Duration::days(2),
+        sets: Some(3),
+        reps: Some(8),
+        weight: Some(10.0),
+        bodyweight_to_use: Some(70.0),
+        ..Default::default()
+    })?;
+    service.add_workout(AddWorkoutParams {
+        exercise_identifier: "Run",
+        date: Utc::now() - Duration::days(1),
+        duration: Some(30),
+        distance: Some(6.0),
+        ..Default::default()
+    })?;
+
+    let batch = service.get_exercise_stats_batch(&["Bench", "bp", "Pull-ups", "Run", "Curl", "Nope"])?;
+    // Aliases are deduplicated; exercises without data and unknown names are skipped
+    assert_eq!(batch.skipped, vec!["Nope", "Curl"]);
+    let stats = batch.stats;
+    assert_eq!(stats.len(), 3);
+    for name in ["Bench", "Pull-ups", "Run"] {
+        assert_eq!(stats[name], service.get_exercise_stats(name)?);
+    }
+    assert_eq!(stats["Pull-ups"].personal_bests.max_weight, Some(80.0));
+    assert_eq!(stats["Run"].personal_bests.best_pace_min_per_km, Some(5.0));
+    assert_eq!(service.get_exercise_stats_batch(&[])?, Default::default());
+    Ok(())
+}
+
+#[test]
+fn test_suggest_progression() -> Result<()> {
+    let mut service = create_test_service()?;
+    service.create_exercise("Squat", ExerciseType::Resistance, None, Some("legs"))?;
+    service.create_exercise("Run", ExerciseType::Cardio, None, Some("legs"))?;
+    assert_eq!(service.suggest_progression("Squat")?, None);
+    assert_eq!(service.suggest_progression("Run")?, None);
+
+    let day = NaiveDate::from_ymd_opt(2024, 5, 6).unwrap();
+    let log = |service: &mut AppService, reps, weight, is_warmup, is_failure| {
+        service.add_workout(AddWorkoutParams {
+            exercise_identifier: "Squat",
+            date: Utc.from_utc_datetime(&day.and_hms_opt(12, 0, 0).unwrap()),
+            sets: Some(3),
+            reps: Some(reps),
+            weight: Some(weight),
+            is_warmup,
+            is_failure,
+            ..Default::default()
+        })
+    };
+    log(&mut service, 5, 60.0, true, false)?;
+    let (top_id, _) = log(&mut service, 8, 100.0, false, false)?;
+    log(&mut service, 10, 90.0, false, false)?;
+
+    // Linear by default: same sets and reps, 2.5 more on the top set
+    let suggestion = service.suggest_progression("Squat")?.unwrap();
+    assert_eq!((suggestion.sets, suggestion.reps, suggestion.weight), (3, 8, 102.5));
+    assert_eq!(suggestion.based_on, day);
+
+    service.set_progression_scheme(ProgressionScheme::DoubleProgression {
+        min_reps: 6,
+        max_reps: 10,
+        increment: 5.0,
+    })?;
+    let suggestion = service.suggest_progression("Squat")?.unwrap();
+    assert_eq!((suggestion.reps, suggestion.weight), (9, 100.0));
+    service.edit_workout(EditWorkoutParams {
+        id: top_id,
+        new_reps: Some(10),
+        ..Default::default()
+    })?;
+    let suggestion = service.suggest_progression("Squat")?.unwrap();
+    assert_eq!((suggestion.reps, suggestion.weight), (6, 105.0));
+
+    // A failed top set is repeated
+    service.edit_workout(EditWorkoutParams {
+        id: top_id,
+        new_is_failure: Some(true),
+        ..Default::default()
+    })?;
+    let suggestion = service.suggest_progression("Squat")?.unwrap();
+    assert_eq!((suggestion.reps, suggestion.weight), (10, 100.0));
+
+    assert!(matches!(
+        service.set_progression_scheme(ProgressionScheme::DoubleProgression {
+            min_reps: 8,
+            max_reps: 8,
+            increment: 2.5,
+        }),
+        Err(ConfigError::InvalidProgressionScheme(_))
+    ));
+    assert!(matches!(
+        service.set_progression_scheme(ProgressionScheme::Linear { increment: 0.0 }),
+        Err(ConfigError::InvalidProgressionScheme(_))
+    ));
+    assert!(matches!(service.suggest_progression("Nope"), Err(ServiceError::NotFound(_))));
+    Ok(())
+}
+
+#[test]
+fn test_global_search() -> Result<()> {
+    let mut service = create_test_service()?;
+    service.create_exercise("Bench Press", ExerciseType::Resistance, None, Some("chest"))?;
+    service.create_exercise("Incline Bench", ExerciseType::Resistance, None, Some("chest"))?;
+    service.create_exercise("Squat", ExerciseType::Resistance, None, Some("legs"))?;
+    service.create_alias("benchy", "Squat")?;
+    let log = |service: &mut AppService, days_ago, notes: &str| {
+        service.add_workout(AddWorkoutParams {
+            exercise_identifier: "Squat",
+            date: Utc::now() - Duration::days(days_ago),
+            sets: Some(3),
+            reps: Some(5),
+            weight: Some(100.0),
+            notes: Some(notes.to_string()),
+            ..Default::default()
+        })
+    };
+    let (older_id, _) = log(&mut service, 5, "Felt strong, try BENCH next")?;
+    let (newer_id, _) = log(&mut service, 1, "bench shirt helped")?;
+    log(&mut service, 2, "knees ok")?;
+
+    let results = service.global_search("bench", 10)?;
+    assert_eq!(results.len(), 5);
+    assert!(matches!(&results[0], SearchResult::Exercise { canonical_name, kind: MatchKind::Prefix } if canonical_name == "Bench Press"));
+    assert!(matches!(&results[1], SearchResult::Alias { alias, canonical_name, .. } if alias == "benchy" && canonical_name == "Squat"));
+    assert!(matches!(&results[2], SearchResult::Exercise { canonical_name, kind: MatchKind::Contains } if canonical_name == "Incline Bench"));
+    assert!(matches!(&results[3], SearchResult::WorkoutNote { workout_id, exercise_name, .. } if *workout_id == newer_id && exercise_name == "Squat"));
+    assert!(matches!(&results[4], SearchResult::WorkoutNote { workout_id, .. } if *workout_id == older_id));
+
+    assert_eq!(service.global_search("bench", 2)?.len(), 2);
+    assert!(service.global_search("  ", 10)?.is_empty());
+    assert!(service.global_search("zzz", 10)?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_purge_deleted() -> Result<()> {
+    let mut service = create_test_service()?;
+    service.create_exercise("Bench", ExerciseType::Resistance, None, Some("chest"))?;
+    service.create_exercise("Old Press", ExerciseType::Resistance, None, Some("shoulders"))?;
+    service.create_alias("op", "Old Press")?;
+    service.create_alias("bp", "Bench")?;
+    let log = |service: &mut AppService| {
+        service.add_workout(AddWorkoutParams {
+            exercise_identifier: "Bench",
+            date: Utc::now(),
+            sets: Some(3),
+            reps: Some(5),
+            weight: Some(80.0),
+            ..Default::default()
+        })
+    };
+    let (kept_id, _) = log(&mut service)?;
+    let (deleted_id, _) = log(&mut service)?;
+    service.add_workout_tags(deleted_id, &["heavy"])?;
+    let bw_id = service.add_bodyweight_entry(Utc::now(), 80.0)?;
+    service.delete_workouts(&[deleted_id])?;
+    service.delete_exercise(&["Old Press".to_string()])?;
+    service.delete_alias("bp")?;
+    service.delete_bodyweight(bw_id)?;
+
+    // Nothing is old enough yet
+    assert_eq!(service.purge_deleted(Duration::days(30))?, PurgeCounts::default());
+    let counts = service.purge_deleted(Duration::zero())?;
+    assert_eq!(
+        counts,
+        PurgeCounts { exercises: 1, workouts: 1, aliases: 2, bodyweights: 1 }
+    );
+    assert!(service.restore_workout(deleted_id).is_err());
+    assert!(service.restore_exercise("Old Press").is_err());
+    assert_eq!(service.list_workouts(&WorkoutFilters::default())?.len(), 1);
+    assert!(service.purge_deleted(Duration::days(-1)).is_err());
+
+    // Hard-delete mode skips the soft flag
+    service.set_hard_delete(true)?;
+    service.undo_last()?; // Drop the undo entry of the earlier delete
+    service.delete_workouts(&[kept_id])?;
+    assert!(service.restore_workout(kept_id).is_err());
+    assert_eq!(service.undo_last()?.map(|e| e.action), Some(UndoAction::AddWorkout));
+    service.delete_exercise(&["Bench".to_string()])?;
+    assert_eq!(service.purge_deleted(Duration::zero())?, PurgeCounts::default());
+    Ok(())
+}
+
+#[test]
+fn test_muscle_recovery_status() -> Result<()> {
+    let mut service = create_test_service()?;
+    service.create_exercise("Bench", ExerciseType::Resistance, None, Some("chest, triceps"))?;
+    service.create_exercise("Squat", ExerciseType::Resistance, None, Some("Quads"))?;
+    service.create_exercise("Dips", ExerciseType::Resistance, None, Some("triceps"))?;
+    let log = |service: &mut AppService, name, hours_ago| {
+        service.add_workout(AddWorkoutParams {
+            exercise_identifier: name,
+            date: Utc::now() - Duration::hours(hours_ago),
+            sets: Some(3),
+            reps: Some(5),
+            weight: Some(60.0),
+            ..Default::default()
+        })
+    };
+    log(&mut service, "Bench", 36)?;
+    log(&mut service, "Dips", 12)?;
+    log(&mut service, "Squat", 60)?;
+
+    let status = service.muscle_recovery_status()?;
+    let muscles: Vec<&str> = status.iter().map(|m| m.muscle.as_str()).collect();
+    assert_eq!(muscles, ["triceps", "chest", "quads"]);
+    // The most recent exercise hitting a muscle counts
+    assert!((status[0].recovered_pct - 25.0).abs() < 0.1);
+    assert!((status[1].recovered_pct - 75.0).abs() < 0.1);
+    assert!(!status[1].fully_recovered);
+    assert!(status[2].fully_recovered);
+    assert_eq!(status[2].recovery_hours, 48);
+
+    service.set_muscle_recovery_hours("Quads", Some(72))?;
+    service.set_recovery_hours(24)?;
+    let status = service.muscle_recovery_status()?;
+    let quads = status.iter().find(|m| m.muscle == "quads").unwrap();
+    assert_eq!(quads.recovery_hours, 72);
+    assert!(!quads.fully_recovered);
+    assert!(status.iter().find(|m| m.muscle == "chest").unwrap().fully_recovered);
+
+    // Muscles never trained are fully recovered
+    service.create_exercise("Calf Raise", ExerciseType::Resistance, None, Some("calves"))?;
+    let calves = service.muscle_recovery_status()?.into_iter().find(|m| m.muscle == "calves").unwrap();
+    assert_eq!((calves.last_trained, calves.recovered_pct, calves.fully_recovered), (None, 100.0, true));
+
+    assert!(matches!(service.set_recovery_hours(0), Err(ConfigError::InvalidRecoveryHours(0))));
+    Ok(())
+}
+
+#[test]
+fn test_build_exercise_report() -> Result<()> {
+    let mut service = create_test_service()?;
+    service.create_exercise("Bench", ExerciseType::Resistance, None, Some("chest"))?;
+    service.create_exercise("Dips", ExerciseType::BodyWeight, None, Some("chest"))?;
+    service.create_exercise("Curl", ExerciseType::Resistance, None, Some("arms"))?;
+    service.create_alias("bp", "Bench")?;
+    let day = |d| Utc.from_utc_datetime(&NaiveDate::from_ymd_opt(2024, 3, d).unwrap().and_hms_opt(12, 0, 0).unwrap());
+    for (d, sets, reps, weight, is_warmup) in [(1, 3, 5, 100.0, false), (2, 5, 10, 60.0, false), (3, 1, 1, 120.0, true)] {
+        service.add_workout(AddWorkoutParams {
+            exercise_identifier: "Bench",
+            date: day(d),
+            sets: Some(sets),
+            reps: Some(reps),
+            weight: Some(weight),
+            is_warmup,
+            ..Default::default()
+        })?;
+    }
+    service.add_workout(AddWorkoutParams {
+        exercise_identifier: "Dips",
+        date: day(2),
+        sets: Some(2),
+        reps: Some(10),
+        weight: Some(10.0),
+        bodyweight_to_use: Some(70.0),
+        ..Default::default()
+    })?;
+
+    let report = service.build_exercise_report(Some(&["bp", "Dips", "Bench", "Curl", "Nope"]))?;
+    assert_eq!(report.skipped, vec!["Nope"]);
+    let report = report.rows;
+    let names: Vec<&str> = report.iter().map(|r| r.exercise_name.as_str()).collect();
+    assert_eq!(names, ["Bench", "Dips", "Curl"]);
+    let bench = &report[0];
+    assert_eq!(bench.max_weight, Some(100.0)); // Warmup ignored
+    assert!((bench.e1rm.unwrap() - 100.0 * (1.0 + 5.0 / 30.0)).abs() < 1e-9);
+    assert_eq!(bench.best_volume, Some(3000.0));
+    assert_eq!(bench.last_performed, Some(day(3))); // Warmups still count as performed
+    assert_eq!(report[1].max_weight, Some(80.0));
+    assert_eq!(report[1].best_volume, Some(1600.0));
+    assert_eq!(report[2].exercise_type, ExerciseType::Resistance);
+    assert_eq!((report[2].max_weight, report[2].last_performed), (None, None));
+
+    let all = service.build_exercise_report(None)?.rows;
+    assert_eq!(all.len(), 3);
+    assert_eq!(all.iter().find(|r| r.exercise_name == "Bench"), Some(bench));
+
+    let mut out = Vec::new();
+    assert_eq!(service.export_exercise_report_to_csv(&report, &mut out, &CsvOptions::default())?, 3);
+    let csv = String::from_utf8(out)?;
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines[0], "exercise,type,max_weight,e1rm,best_volume,last_performed");
+    assert_eq!(lines[3], "Curl,resistance,,,,");
+    Ok(())
+}
+
+#[test]
+fn test_recent_changes() -> Result<()> {
+    let mut service = create_test_service()?;
+    service.create_exercise("Old", ExerciseType::Resistance, None, Some("chest"))?;
+    thread::sleep(StdDuration::from_millis(20));
+    let since = Utc::now();
+    assert!(service.recent_changes(since)?.is_empty());
+
+    thread::sleep(StdDuration::from_millis(20));
+    service.create_exercise("Bench", ExerciseType::Resistance, None, Some("chest"))?;
+    let (workout_id, _) = service.add_workout(AddWorkoutParams {
+        exercise_identifier: "Bench",
+        date: Utc::now() - Duration::days(3),
+        sets: Some(3),
+        reps: Some(5),
+        weight: Some(80.0),
+        ..Default::default()
+    })?;
+    service.create_alias("bp", "Bench")?;
+    service.add_bodyweight_entry(Utc::now(), 80.0)?;
+    thread::sleep(StdDuration::from_millis(20));
+    service.delete_alias("bp")?;
+
+    let changes = service.recent_changes(since)?;
+    assert_eq!(changes.len(), 4);
+    assert!(changes.windows(2).all(|w| w[0].last_edited() >= w[1].last_edited()));
+    assert!(matches!(&changes[0], RecentChange::Alias(a) if a.alias_name == "bp"));
+    assert!(changes[0].is_deleted());
+    assert!(changes.iter().any(|c| matches!(c, RecentChange::Workout(w) if w.id == workout_id)));
+    assert!(changes.iter().any(|c| matches!(c, RecentChange::Exercise(e) if e.name == "Bench")));
+    assert!(changes.iter().any(|c| matches!(c, RecentChange::Bodyweight(b) if b.weight == 80.0)));
+    assert!(!changes.iter().any(|c| matches!(c, RecentChange::Exercise(e) if e.name == "Old")));
+    Ok(())
+}
+
+#[test]
+fn test_same_day_entries_keep_insertion_order() -> Result<()> {
+    let mut service = create_test_service()?;
+    service.create_exercise("Squat", ExerciseType::Resistance, None, Some("legs"))?;
+    let day = NaiveDate::from_ymd_opt(2024, 5, 6).unwrap();
+    let noon = Utc.from_utc_datetime(&day.and_hms_opt(12, 0, 0).unwrap());
+    let mut ids = Vec::new();
+    for weight in [100.0, 120.0, 110.0] {
+        let (id, _) = service.add_workout(AddWorkoutParams {
+            exercise_identifier: "Squat",
+            date: noon,
+            sets: Some(1),
+            reps: Some(3),
+            weight: Some(weight),
+            ..Default::default()
+        })?;
+        ids.push(id);
+    }
+    // Editing the first entry must not make it the latest
+    thread::sleep(StdDuration::from_millis(20));
+    service.edit_workout(EditWorkoutParams {
+        id: ids[0],
+        new_notes: Some("felt heavy".to_string()),
+        ..Default::default()
+    })?;
+
+    let listed = |filters: &WorkoutFilters| -> Result<Vec<i64>> {
+        Ok(service.list_workouts(filters)?.iter().map(|w| w.id).collect())
+    };
+    assert_eq!(listed(&WorkoutFilters { date: Some(day), ..Default::default() })?, ids);
+    let mut newest_first = ids.clone();
+    newest_first.reverse();
+    assert_eq!(listed(&WorkoutFilters::default())?, newest_first);
+    assert_eq!(listed(&WorkoutFilters { limit: Some(1), ..Default::default() })?, [ids[2]]);
+    Ok(())
+}
+
+#[test]
+fn test_clear_all_data() -> Result<()> {
+    let mut service = create_test_service()?;
+    service.set_units(Units::Imperial)?;
+    service.create_exercise("Bench", ExerciseType::Resistance, None, Some("chest"))?;
+    service.create_exercise("Run", ExerciseType::Cardio, None, Some("legs"))?;
+    service.create_alias("bp", "Bench")?;
+    let mut ids = Vec::new();
+    for days_ago in [1, 2] {
+        let (id, _) = service.add_workout(AddWorkoutParams {
+            exercise_identifier: "Bench",
+            date: Utc::now() - Duration::days(days_ago),
+            sets: Some(3),
+            reps: Some(5),
+            weight: Some(80.0),
+            ..Default::default()
+        })?;
+        ids.push(id);
+    }
+    service.add_workout_tags(ids[0], &["heavy"])?;
+    service.delete_workouts(&[ids[1]])?; // Soft-deleted rows go too
+    service.add_bodyweight_entry(Utc::now(), 180.0)?;
+
+    for wrong in ["", "delete all my data", "DELETE ALL MY DATA "] {
+        assert!(matches!(service.clear_all_data(wrong), Err(ServiceError::Validation(_))));
+    }
+    assert_eq!(service.list_workouts(&WorkoutFilters::default())?.len(), 1);
+
+    let counts = service.clear_all_data(CLEAR_ALL_DATA_CONFIRMATION)?;
+    assert_eq!(
+        counts,
+        PurgeCounts {
+            exercises: 2,
+            workouts: 2,
+            aliases: 1,
+            bodyweights: 1,
+        }
+    );
+    assert!(service.list_workouts(&WorkoutFilters::default())?.is_empty());
+    assert!(service.list_exercises(&ExerciseFilters::default())?.is_empty());
+    assert!(service.list_aliases()?.is_empty());
+    assert!(service.list_body_measurements(10)?.is_empty());
+    assert!(service.list_tags()?.is_empty());
+    assert_eq!(service.config.weight_units(), Units::Imperial); // Config is kept
+
+    // The database is still usable
+    service.create_exercise("Bench", ExerciseType::Resistance, None, Some("chest"))?;
+    assert_eq!(service.clear_all_data(CLEAR_ALL_DATA_CONFIRMATION)?.exercises, 1);
+    Ok(())
+}
+
+#[test]
+fn test_hr_zone() -> Result<()> {
+    let mut service = create_test_service()?;
+    service.create_exercise("Run", ExerciseType::Cardio, None, Some("legs"))?;
+    let run = |service: &mut AppService, avg: Option<i64>, max: Option<i64>| {
+        service.add_workout(AddWorkoutParams {
+            exercise_identifier: "Run",
+            date: Utc::now(),
+            duration: Some(30),
+            avg_heart_rate: avg,
+            max_heart_rate: max,
+            ..Default::default()
+        })
+    };
+    let (easy_id, _) = run(&mut service, Some(130), Some(150))?;
+    let (no_hr_id, _) = run(&mut service, None, None)?;
+
+    let easy = service.list_workouts(&WorkoutFilters::default())?.into_iter().find(|w| w.id == easy_id).unwrap();
+    assert_eq!((easy.avg_heart_rate, easy.max_heart_rate), (Some(130), Some(150)));
+    assert_eq!(service.hr_zone(easy_id)?, None); // No max heart rate or age configured
+
+    service.set_age(Some(30))?; // Estimated max: 190, 130 bpm is 68%
+    assert_eq!(service.effective_max_hr(), Some(190));
+    assert_eq!(service.hr_zone(easy_id)?, Some(HrZone::Zone2));
+    service.set_max_hr(Some(200))?; // Takes precedence over age: 65%
+    assert_eq!(service.hr_zone(easy_id)?, Some(HrZone::Zone2));
+    assert_eq!(service.hr_zone(no_hr_id)?, None);
+
+    service.edit_workout(EditWorkoutParams { id: easy_id, new_avg_heart_rate: Some(Some(185)), new_max_heart_rate: Some(Some(195)), ..Default::default() })?;
+    assert_eq!(service.hr_zone(easy_id)?, Some(HrZone::Zone5));
+    assert!(matches!(service.hr_zone(9999), Err(ServiceError::NotFound(_))));
+
+    // Average above max (also against the stored max) and non-positive values are rejected
+    assert!(matches!(run(&mut service, Some(160), Some(150)), Err(ServiceError::Validation(_))));
+    assert!(matches!(run(&mut service, Some(0), None), Err(ServiceError::Validation(_))));
+    assert!(matches!(
+        service.edit_workout(EditWorkoutParams { id: easy_id, new_avg_heart_rate: Some(Some(196)), ..Default::default() }),
+        Err(ServiceError::Validation(_))
+    ));
+
+    // Clearing the max lifts the bound on the average; clearing the average removes the zone
+    service.edit_workout(EditWorkoutParams { id: easy_id, new_max_heart_rate: Some(None), ..Default::default() })?;
+    service.edit_workout(EditWorkoutParams { id: easy_id, new_avg_heart_rate: Some(Some(196)), ..Default::default() })?;
+    assert_eq!(service.hr_zone(easy_id)?, Some(HrZone::Zone5));
+    service.edit_workout(EditWorkoutParams { id: easy_id, new_avg_heart_rate: Some(None), ..Default::default() })?;
+    let easy = service.list_workouts(&WorkoutFilters::default())?.into_iter().find(|w| w.id == easy_id).unwrap();
+    assert_eq!((easy.avg_heart_rate, easy.max_heart_rate), (None, None));
+    assert_eq!(service.hr_zone(easy_id)?, None);
+    assert!(matches!(service.set_max_hr(Some(50)), Err(ConfigError::InvalidMaxHeartRate(50))));
+    assert!(matches!(service.set_age(Some(0)), Err(ConfigError::InvalidAge(0))));
+
+    assert_eq!(HrZone::from_heart_rate(99, 200), None);
+    assert_eq!(HrZone::from_heart_rate(100, 200), Some(HrZone::Zone1));
+    assert_eq!(HrZone::from_heart_rate(160, 200), Some(HrZone::Zone4));
+    Ok(())
+}
+
+#[test]
+fn test_records_set_on() -> Result<()> {
+    let mut service = create_test_service()?;
+    service.create_exercise("Bench", ExerciseType::Resistance, None, Some("chest"))?;
+    service.create_exercise("Squat", ExerciseType::Resistance, None, Some("legs"))?;
+    service.create_exercise("Run", ExerciseType::Cardio, None, Some("legs"))?;
+    let now = Utc::now();
+    let today = now.date_naive();
+    let add = |service: &mut AppService, exercise, days_ago, reps, weight, distance| {
+        service.add_workout(AddWorkoutParams {
+            exercise_identifier: exercise,
+            date: now - Duration::days(days_ago),
+            sets: Some(1),
+            reps,
+            weight,
+            distance,
+            ..Default::default()
+        })
+    };
+
+    add(&mut service, "Bench", 7, Some(5), Some(100.0), None)?;
+    add(&mut service, "Bench", 0, Some(3), Some(105.0), None)?; // Weight PB, not reps
+    add(&mut service, "Squat", 7, Some(5), Some(140.0), None)?;
+    add(&mut service, "Squat", 0, Some(5), Some(140.0), None)?; // Only ties
+    add(&mut service, "Run", 0, None, None, Some(5.0))?;
+
+    let records = service.records_set_on(today)?;
+    assert_eq!(
+        records,
+        vec![
+            ("Bench".to_string(), PbMetric::Weight, 105.0),
+            ("Run".to_string(), PbMetric::Distance, 5.0),
+        ]
+    );
+
+    let last_week = service.records_set_on(today - Duration::days(7))?;
+    assert_eq!(
+        last_week,
+        vec![
+            ("Bench".to_string(), PbMetric::Reps, 5.0),
+            ("Squat".to_string(), PbMetric::Weight, 140.0),
+            ("Squat".to_string(), PbMetric::Reps, 5.0),
+        ]
+    );
+    assert!(service.records_set_on(today - Duration::days(1))?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_workout_validation_limits() -> Result<()> {
+    let mut service = create_test_service()?;
+    service.create_exercise("Bench", ExerciseType::Resistance, None, Some("chest"))?;
+    let entry = |reps, weight| AddWorkoutParams {
+        exercise_identifier: "Bench",
+        date: Utc::now(),
+        sets: Some(3),
+        reps: Some(reps),
+        weight: Some(weight),
+        ..Default::default()
+    };
+
+    // No limits by default
+    let (id, _) = service.add_workout(entry(1000, 5000.0))?;
+    service.delete_workouts(&[id])?;
+
+    assert!(matches!(
+        service.set_validation_config(ValidationConfig {
+            max_reps: Some(0),
+            ..Default::default()
+        }),
+        Err(ConfigError::InvalidValidationLimit(_))
+    ));
+    service.set_validation_config(ValidationConfig {
+        max_reps: Some(100),
+        max_weight: Some(500.0),
+        warn_on_pb_jump: true,
+        ..Default::default()
+    })?;
+
+    let err = service.add_workout(entry(1000, 5000.0)).unwrap_err();
+    assert!(matches!(err, ServiceError::Validation(_)));
+    let message = err.to_string();
+    assert!(message.contains("reps 1000 (max 100)"), "{message}");
+    assert!(message.contains("weight 5000 (max 500)"), "{message}");
+
+    let (id, _) = service.add_workout(entry(5, 100.0))?;
+    let err = service
+        .edit_workout(EditWorkoutParams {
+            id,
+            new_reps: Some(500),
+            ..Default::default()
+        })
+        .unwrap_err();
+    assert!(matches!(err, ServiceError::Validation(_)));
+    let stored = service.list_workouts(&WorkoutFilters::default())?;
+    assert_eq!(stored.iter().find(|w| w.id == id).and_then(|w| w.reps), Some(5));
+
+    // Large jumps over the PB only warn; the entry is still logged
+    let warnings = service.pb_jump_warnings(&entry(6, 160.0))?;
+    assert_eq!(warnings.len(), 1);
+    assert!(warnings[0].starts_with("Weight 160"), "{}", warnings[0]);
+    assert!(service.pb_jump_warnings(&entry(7, 140.0))?.is_empty());
+    let (_, pb) = service.add_workout(entry(6, 160.0))?;
+    assert_eq!(pb.map(|pb| pb.warnings), Some(warnings));
+    let (_, pb) = service.add_workout(entry(7, 140.0))?;
+    assert!(pb.is_none_or(|pb| pb.warnings.is_empty()));
+    Ok(())
+}
+
+#[test]
+fn test_exercise_leaderboard() -> Result<()> {
+    let mut service = create_test_service()?;
+    service.create_exercise("Bench", ExerciseType::Resistance, None, Some("chest"))?;
+    service.create_exercise("Squat", ExerciseType::Resistance, None, Some("legs"))?;
+    service.create_exercise("Run", ExerciseType::Cardio, None, Some("legs"))?;
+    service.create_exercise("Curl", ExerciseType::Resistance, None, Some("arms"))?;
+    let now = Utc::now();
+    let add = |service: &mut AppService, exercise, days_ago, is_warmup| {
+        service
+            .add_workout(AddWorkoutParams {
+                exercise_identifier: exercise,
+                date: now - Duration::days(days_ago),
+                sets: Some(2),
+                reps: Some(5),
+                weight: Some(100.0),
+                is_warmup,
+                ..Default::default()
+            })
+            .map(|(id, _)| id)
+    };
+
+    add(&mut service, "Squat", 3, true)?;
+    add(&mut service, "Squat", 3, false)?;
+    add(&mut service, "Squat", 1, false)?;
+    add(&mut service, "Bench", 5, false)?;
+    let deleted = add(&mut service, "Bench", 0, false)?;
+    service.delete_workouts(&[deleted])?;
+    service.add_workout(AddWorkoutParams {
+        exercise_identifier: "Run",
+        date: now - Duration::days(2),
+        distance: Some(5.0),
+        ..Default::default()
+    })?;
+
+    let board = service.get_exercise_leaderboard()?;
+    let summary: Vec<_> = board
+        .iter()
+        .map(|u| (u.name.as_str(), u.workout_count, u.total_volume))
+        .collect();
+    // Curl was never performed; the deleted Bench entry and the Squat warmup's volume are ignored
+    assert_eq!(
+        summary,
+        vec![("Squat", 3, 2000.0), ("Bench", 1, 1000.0), ("Run", 1, 0.0)]
+    );
+    assert_eq!(board[0].last_performed, (now - Duration::days(1)).date_naive());
+    assert_eq!(board[1].last_performed, (now - Duration::days(5)).date_naive());
+    Ok(())
+}
+
+#[test]
+fn test_backup_database() -> Result<()> {
+    let dir = std::env::temp_dir().join(format!("ta_backup_test_{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&dir)?;
+    let mut service = create_test_service()?;
+    service.db_path = dir.join("workouts.sqlite");
+    service.create_exercise("Bench", ExerciseType::Resistance, None, Some("chest"))?;
+    let count_exercises = |path: &std::path::Path| -> Result<i64> {
+        let conn = rusqlite::Connection::open(path)?;
+        Ok(conn.query_row("SELECT COUNT(*) FROM exercises", [], |row| row.get(0))?)
+    };
+
+    // A directory gets a timestamped file inside it
+    let in_dir = service.backup_database(Some(dir.clone()))?;
+    assert_eq!(in_dir.parent(), Some(dir.as_path()));
+    assert!(in_dir.file_name().unwrap().to_str().unwrap().starts_with("workouts-"));
+    assert_eq!(count_exercises(&in_dir)?, 1);
+
+    let explicit = dir.join("manual.sqlite");
+    assert_eq!(service.backup_database(Some(explicit.clone()))?, explicit);
+    assert!(matches!(
+        service.backup_database(Some(explicit)),
+        Err(ServiceError::Conflict(_))
+    ));
+
+    // Sync only backs up when enabled, into the default directory next to the database
+    let summary = service.apply_server_changes(Default::default(), true)?;
+    assert!(summary.backup.is_none());
+    service.config.auto_backup_before_sync = true;
+    let summary = service.apply_server_changes(Default::default(), true)?;
+    let backup = summary.backup.expect("backup before sync");
+    assert_eq!(backup.parent(), Some(dir.join("backups").as_path()));
+    assert_eq!(count_exercises(&backup)?, 1);
+
+    let _ = std::fs::remove_dir_all(&dir);
+    Ok(())
+}
+
+#[test]
+fn test_rep_progression() -> Result<()> {
+    let mut service = create_test_service()?;
+    service.create_exercise("Bench", ExerciseType::Resistance, None, Some("chest"))?;
+    let now = Utc::now();
+    let add = |service: &mut AppService, days_ago, reps, weight, is_warmup| {
+        service.add_workout(AddWorkoutParams {
+            exercise_identifier: "Bench",
+            date: now - Duration::days(days_ago),
+            sets: Some(1),
+            reps: Some(reps),
+            weight: Some(weight),
+            is_warmup,
+            ..Default::default()
+        })
+    };
+
+    add(&mut service, 14, 5, 100.0, false)?;
+    add(&mut service, 14, 4, 100.0, false)?;
+    add(&mut service, 7, 6, 101.0, false)?; // Within tolerance
+    add(&mut service, 7, 12, 60.0, false)?; // Different weight
+    add(&mut service, 3, 10, 100.0, true)?; // Warmup
+    add(&mut service, 0, 7, 100.0, false)?;
+
+    let date = |days_ago| (now - Duration::days(days_ago)).date_naive();
+    assert_eq!(
+        service.get_rep_progression("Bench", 100.0, 1.0)?,
+        vec![(date(14), 5), (date(7), 6), (date(0), 7)]
+    );
+    assert_eq!(
+        service.get_rep_progression("Bench", 100.0, 0.0)?,
+        vec![(date(14), 5), (date(0), 7)]
+    );
+    assert!(service.get_rep_progression("Bench", 140.0, 2.5)?.is_empty());
+    assert!(matches!(
+        service.get_rep_progression("Bench", 100.0, -1.0),
+        Err(ServiceError::Validation(_))
+    ));
+    Ok(())
+}
+
+#[test]
+fn test_theme_presets_and_validation() -> Result<()> {
+    for name in Theme::PRESETS {
+        Theme::preset(name)?.validate()?;
+    }
+    assert_eq!(Theme::preset("Dark")?.header_color, "Cyan");
+    assert!(matches!(
+        Theme::preset("neon"),
+        Err(ConfigError::UnknownThemePreset(_))
+    ));
+
+    let mut service = create_test_service()?;
+    service.apply_theme_preset("high-contrast")?;
+    assert_eq!(service.config.theme.header_color, "Yellow");
+    assert!(service.apply_theme_preset("neon").is_err());
+    assert_eq!(service.config.theme.header_color, "Yellow");
+
+    let typo = Theme {
+        header_color: "Gren".to_string(),
+    };
+    let err = typo.validate().unwrap_err();
+    assert!(err.to_string().contains("header_color = 'Gren'"), "{err}");
+
+    // A bad color is reported when the config is loaded instead of silently ignored
+    let path = std::env::temp_dir().join(format!("ta_theme_test_{}.toml", uuid::Uuid::new_v4()));
+    std::fs::write(&path, "[theme]\nheader_color = \"Gren\"\n")?;
+    let loaded = task_athlete_lib::load_config_util(&path);
+    assert!(matches!(loaded, Err(ConfigError::InvalidColor(_))));
+    std::fs::write(&path, "[theme]\nheader_color = \"darkred\"\n")?;
+    assert_eq!(task_athlete_lib::load_config_util(&path)?.theme.header_color, "darkred");
+    let _ = std::fs::remove_file(&path);
+    Ok(())
+}
+
+#[test]
+fn test_lifetime_totals() -> Result<()> {
+    let mut service = create_test_service()?;
+    assert_eq!(service.get_lifetime_totals()?, Default::default());
+    service.create_exercise("Squat", ExerciseType::Resistance, None, Some("legs"))?;
+    service.create_exercise("Run", ExerciseType::Cardio, None, Some("legs"))?;
+    let squat = |sets, reps, is_warmup| AddWorkoutParams {
+        exercise_identifier: "Squat",
+        date: Utc::now(),
+        sets: Some(sets),
+        reps: Some(reps),
+        weight: Some(100.0),
+        is_warmup,
+        ..Default::default()
+    };
+    service.add_workout(squat(3, 5, false))?;
+    service.add_workout(squat(1, 10, true))?; // Warmup reps count, its volume doesn't
+    let (deleted, _) = service.add_workout(squat(5, 5, false))?;
+    service.delete_workouts(&[deleted])?;
+    service.add_workout(AddWorkoutParams {
+        exercise_identifier: "Run",
+        date: Utc::now(),
+        duration: Some(30),
+        distance: Some(8.0),
+        ..Default::default()
+    })?;
+
+    let totals = service.get_lifetime_totals()?;
+    assert_eq!(totals.workout_count, 3);
+    assert_eq!(totals.total_volume, 1500.0);
+    assert_eq!(totals.total_reps, 25);
+    assert_eq!(totals.total_duration_minutes, 30);
+    assert_eq!(totals.total_distance, 8.0);
+
+    service.config.units = Units::Imperial;
+    assert!((service.get_lifetime_totals()?.total_distance - 8.0 * task_athlete_lib::KM_TO_MILE).abs() < 0.001);
+    Ok(())
+}
+
+#[test]
+fn test_exercise_pb_metrics() -> Result<()> {
+    let mut service = create_test_service()?;
+    service.create_exercise("Squat", ExerciseType::Resistance, None, Some("legs"))?;
+    service.set_pb_notify_volume(false)?;
+    let add = |service: &mut AppService, reps, weight| {
+        service
+            .add_workout(AddWorkoutParams {
+                exercise_identifier: "Squat",
+                date: Utc::now(),
+                sets: Some(1),
+                reps: Some(reps),
+                weight: Some(weight),
+                ..Default::default()
+            })
+            .map(|(_, pb)| pb)
+    };
+
+    // Duplicates are dropped and the order normalized
+    service.set_exercise_pb_metrics("squat", Some(&[PbMetric::Weight, PbMetric::Weight]))?;
+    let def = service.resolve_exercise_identifier("Squat")?.expect("exercise");
+    assert_eq!(def.pb_metrics, Some(vec![PbMetric::Weight]));
+
+    add(&mut service, 5, 100.0)?;
+    assert!(add(&mut service, 8, 100.0)?.is_none(), "reps don't count for Squat");
+    assert!(add(&mut service, 3, 110.0)?.is_some_and(|pb| pb.weight.achieved));
+
+    // The override also enables metrics turned off globally
+    service.set_pb_notify_weight(false)?;
+    service.set_pb_notify_reps(false)?;
+    service.set_exercise_pb_metrics("Squat", Some(&[PbMetric::Reps, PbMetric::Weight]))?;
+    let pb = add(&mut service, 10, 115.0)?.expect("PB");
+    assert!(pb.weight.achieved && pb.reps.achieved);
+
+    // Clearing the override goes back to the (now disabled) global flags
+    service.set_exercise_pb_metrics("Squat", None)?;
+    assert_eq!(service.resolve_exercise_identifier("Squat")?.unwrap().pb_metrics, None);
+    assert!(add(&mut service, 12, 120.0)?.is_none());
+    assert!(service.set_exercise_pb_metrics("Nope", None).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_performance_by_time_of_day() -> Result<()> {
+    let mut service = create_test_service()?;
+    service.create_exercise("Bench", ExerciseType::Resistance, None, Some("chest"))?;
+    let at = |day, hour, min| Utc.with_ymd_and_hms(2024, 3, day, hour, min, 0).unwrap();
+    let add = |service: &mut AppService, date, weight| {
+        service.add_workout(AddWorkoutParams {
+            exercise_identifier: "Bench",
+            date,
+            sets: Some(1),
+            reps: Some(1),
+            weight: Some(weight),
+            ..Default::default()
+        })
+    };
+
+    add(&mut service, at(1, 7, 30), 100.0)?;
+    add(&mut service, at(2, 9, 15), 110.0)?;
+    add(&mut service, at(3, 18, 45), 90.0)?;
+    add(&mut service, at(4, 1, 5), 80.0)?; // Late night counts as evening
+    add(&mut service, at(5, 12, 0), 200.0)?; // Date-only entry, excluded
+
+    let perf = service.performance_by_time_of_day("Bench")?;
+    let summary: Vec<_> = perf.iter().map(|p| (p.time_of_day, p.entries)).collect();
+    assert_eq!(summary, vec![(TimeOfDay::Morning, 2), (TimeOfDay::Evening, 2)]);
+    let e1rm = |weight: f64| weight * (1.0 + 1.0 / 30.0);
+    assert!((perf[0].average_e1rm - e1rm(105.0)).abs() < 1e-9);
+    assert!((perf[1].average_e1rm - e1rm(85.0)).abs() < 1e-9);
+
+    // Hours are local to the configured timezone (UTC+11); the noon UTC entry now has a time
+    service.set_timezone(Some("Australia/Sydney"))?;
+    let perf = service.performance_by_time_of_day("Bench")?;
+    let summary: Vec<_> = perf.iter().map(|p| (p.time_of_day, p.entries)).collect();
+    assert_eq!(
+        summary,
+        vec![(TimeOfDay::Morning, 1), (TimeOfDay::Afternoon, 1), (TimeOfDay::Evening, 3)]
+    );
+    Ok(())
+}
+
+#[test]
+fn test_calculate_plates() -> Result<()> {
+    let mut service = create_test_service()?;
+    // Standard metric set by default
+    assert_eq!(service.calculate_plates(100.0, 20.0)?, vec![25.0, 15.0]);
+    assert_eq!(service.calculate_plates(62.5, 20.0)?, vec![20.0, 1.25]);
+    assert!(service.calculate_plates(20.0, 20.0)?.is_empty());
+    assert!(matches!(
+        service.calculate_plates(10.0, 20.0),
+        Err(ServiceError::Validation(_))
+    ));
+    let err = service.calculate_plates(101.0, 20.0).unwrap_err();
+    assert!(err.to_string().contains("Closest lighter load is 100 kg"), "{err}");
+    // Loads past MAX_PLATE_LOAD_PER_SIDE are rejected up front
+    assert_eq!(service.calculate_plates(2020.0, 20.0)?, vec![25.0; 40]);
+    for target in [2022.0, 1e7, 1e18] {
+        assert!(matches!(
+            service.calculate_plates(target, 20.0),
+            Err(ServiceError::Validation(_))
+        ));
+    }
+
+    // A greedy pick of 15 would get stuck; two 10s work
+    service.set_available_plates(vec![15.0, 10.0])?;
+    assert_eq!(service.calculate_plates(60.0, 20.0)?, vec![10.0, 10.0]);
+    assert!(matches!(
+        service.set_available_plates(vec![10.0, 0.0]),
+        Err(ConfigError::InvalidPlateWeight(_))
+    ));
+
+    service.set_available_plates(Vec::new())?;
+    service.config.units = Units::Imperial;
+    assert_eq!(service.calculate_plates(225.0, 45.0)?, vec![45.0, 45.0]);
+    Ok(())
+}
+
+#[test]
+fn test_percentage_table() -> Result<()> {
+    let mut service = create_test_service()?;
+    service.create_exercise("Squat", ExerciseType::Resistance, None, Some("legs"))?;
+    let err = service.percentage_table("Squat").unwrap_err();
+    assert!(matches!(err.downcast_ref::<DbError>(), Some(DbError::NoWorkoutDataFound(_))));
+    assert!(matches!(err, ServiceError::NotFound(_)));
+
+    for (reps, weight, is_warmup) in [(1, 150.0, false), (3, 140.0, false), (10, 200.0, true)] {
+        service.add_workout(AddWorkoutParams {
+            exercise_identifier: "Squat",
+            date: Utc::now(),
+            sets: Some(1),
+            reps: Some(reps),
+            weight: Some(weight),
+            is_warmup,
+            ..Default::default()
+        })?;
+    }
+    // Best E1RM is 150 * (1 + 1/30) = 155; the heavier warmup doesn't count
+    let table = service.percentage_table("Squat")?;
+    assert_eq!(table.len(), 11);
+    assert_eq!(table[0].0, 50);
+    assert!((table[0].1 - 77.5).abs() < 1e-9);
+    assert_eq!(table[10].0, 100);
+    assert!((table[10].1 - 155.0).abs() < 1e-9);
+
+    service.set_weight_increment(Some(2.5))?;
+    let table = service.percentage_table("Squat")?;
+    assert_eq!(table[1], (55, 85.0)); // 85.25 rounded
+    assert_eq!(table[8], (90, 140.0)); // 139.5 rounded
+    Ok(())
+}
+
+#[test]
+fn test_pb_command_events() -> Result<()> {
+    let mut service = create_test_service()?;
+    service.create_exercise("Bench", ExerciseType::Resistance, None, Some("chest"))?;
+    service.set_pb_notify_volume(false)?;
+    let add = |service: &mut AppService, reps, weight| {
+        service
+            .add_workout(AddWorkoutParams {
+                exercise_identifier: "Bench",
+                date: Utc::now(),
+                sets: Some(1),
+                reps: Some(reps),
+                weight: Some(weight),
+                ..Default::default()
+            })
+            .map(|(_, pb)| pb)
+    };
+    add(&mut service, 5, 100.0)?;
+    let pb = add(&mut service, 5, 105.0)?.expect("weight PB");
+
+    let events = service.pb_events("Bench", &pb);
+    assert_eq!(
+        events,
+        vec![PbEvent {
+            exercise: "Bench".to_string(),
+            metric: "weight".to_string(),
+            value: 105.0,
+        }]
+    );
+
+    let template = "notify-send \"New PB\" {exercise}:{metric}={value}";
+    assert_eq!(
+        render_pb_command(template, &events[0]),
+        "notify-send \"New PB\" 'Bench':'weight'='105'"
+    );
+    let sneaky = PbEvent {
+        exercise: "x'; rm -rf ~; echo '".to_string(),
+        ..events[0].clone()
+    };
+    assert_eq!(
+        render_pb_command("echo {exercise}", &sneaky),
+        "echo 'x'\\''; rm -rf ~; echo '\\'''"
+    );
+
+    // No commands without a template; otherwise one rendered command per event
+    assert!(service.pb_commands(&events).is_empty());
+    service.set_pb_command(Some("echo {metric}".to_string()))?;
+    assert_eq!(service.pb_commands(&events), vec!["echo 'weight'".to_string()]);
+    service.set_pb_command(Some("  ".to_string()))?;
+    assert!(service.pb_commands(&events).is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_workout_history() -> Result<()> {
+    let mut service = create_test_service()?;
+    service.create_exercise("Bench", ExerciseType::Resistance, None, Some("chest"))?;
+    let (id, _) = service.add_workout(AddWorkoutParams {
+        exercise_identifier: "Bench",
+        date: Utc::now(),
+        sets: Some(3),
+        reps: Some(5),
+        weight: Some(100.0),
+        ..Default::default()
+    })?;
+    assert!(service.get_workout_history(id)?.is_empty());
+
+    service.edit_workout(EditWorkoutParams {
+        id,
+        new_reps: Some(6),
+        ..Default::default()
+    })?;
+    // Setting a field to its current value is a no-op and isn't recorded
+    service.edit_workout(EditWorkoutParams {
+        id,
+        new_reps: Some(6),
+        ..Default::default()
+    })?;
+    service.edit_workout(EditWorkoutParams {
+        id,
+        new_weight: Some(102.5),
+        new_notes: Some("felt easy".to_string()),
+        ..Default::default()
+    })?;
+
+    let history = service.get_workout_history(id)?;
+    let versions: Vec<_> = history
+        .iter()
+        .map(|(_, w)| (w.reps, w.weight, w.notes.clone()))
+        .collect();
+    assert_eq!(
+        versions,
+        vec![(Some(6), Some(100.0), None), (Some(5), Some(100.0), None)]
+    );
+    assert!(history[0].0 >= history[1].0);
+    assert!(history.iter().all(|(_, w)| w.id == id && w.exercise_name == "Bench"));
+
+    assert!(matches!(service.get_workout_history(9999), Err(ServiceError::NotFound(_))));
+    Ok(())
+}
+
+#[test]
+fn test_suggest_similar_exercises() -> Result<()> {
+    let service = create_test_service()?;
+    service.create_exercise("Bench Press", ExerciseType::Resistance, None, Some("chest"))?;
+    service.create_exercise("Bent Over Row", ExerciseType::Resistance, None, Some("back"))?;
+    service.create_exercise("Deadlift", ExerciseType::Resistance, None, Some("back"))?;
+    service.create_alias("rdl", "Deadlift")?;
+
+    // The exact resolver is unchanged
+    assert!(service.resolve_exercise_identifier("benchpres")?.is_none());
+    assert_eq!(service.suggest_similar_exercises("benchpres", 3)?, vec!["Bench Press"]);
+    assert_eq!(service.suggest_similar_exercises("DEADLIFTT", 3)?, vec!["Deadlift"]);
+    // Aliases match too, reported by canonical name
+    assert_eq!(service.suggest_similar_exercises("rld", 3)?, vec!["Deadlift"]);
+    assert!(service.suggest_similar_exercises("squat", 3)?.is_empty());
+    assert!(service.suggest_similar_exercises("benchpres", 0)?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_search_exercises() -> Result<()> {
+    let service = create_test_service()?;
+    service.create_exercise("Overhead Pull", ExerciseType::Resistance, None, Some("back"))?;
+    service.create_exercise("Pull-up", ExerciseType::BodyWeight, None, Some("back"))?;
+    service.create_exercise("Lat Pulldown", ExerciseType::Resistance, None, Some("back"))?;
+    service.create_exercise("Squat", ExerciseType::Resistance, None, Some("legs"))?;
+    service.create_alias("chin", "Pull-up")?;
+    service.create_alias("pulldown", "Lat Pulldown")?;
+
+    let names = |matches: Vec<ExerciseMatch>| -> Vec<String> {
+        matches.into_iter().map(|m| m.canonical_name).collect()
+    };
+    let results = service.search_exercises("pul", 10)?;
+    assert_eq!(
+        results,
+        vec![
+            ExerciseMatch {
+                canonical_name: "Pull-up".to_string(),
+                matched_alias: None,
+                kind: MatchKind::Prefix,
+            },
+            ExerciseMatch {
+                canonical_name: "Lat Pulldown".to_string(),
+                matched_alias: Some("pulldown".to_string()),
+                kind: MatchKind::Prefix,
+            },
+            ExerciseMatch {
+                canonical_name: "Overhead Pull".to_string(),
+                matched_alias: None,
+                kind: MatchKind::Contains,
+            },
+        ]
+    );
+    assert_eq!(names(service.search_exercises("pul", 2)?), vec!["Pull-up", "Lat Pulldown"]);
+
+    // A typo still finds the exercise, ranked after exact substring matches
+    let fuzzy = service.search_exercises("sqaut", 10)?;
+    assert_eq!(names(fuzzy.clone()), vec!["Squat"]);
+    assert_eq!(fuzzy[0].kind, MatchKind::Fuzzy);
+    assert_eq!(names(service.search_exercises("CHI", 10)?), vec!["Pull-up"]);
+    assert!(service.search_exercises("xyz", 10)?.is_empty());
+    Ok(())
+}
+
+#[test]
+fn test_check_integrity() -> Result<()> {
+    let mut service = create_test_service()?;
+    assert!(service.check_integrity(false)?.is_clean());
+    service.create_exercise("Squat", ExerciseType::Resistance, None, Some("legs"))?;
+    service.create_exercise("Lunge", ExerciseType::Resistance, None, Some("legs"))?;
+    service.create_exercise("Dip", ExerciseType::BodyWeight, None, Some("chest"))?;
+    service.create_alias("sq", "Squat")?;
+    let (squat_id, _) = service.add_workout(AddWorkoutParams {
+        exercise_identifier: "Squat",
+        date: Utc::now(),
+        sets: Some(3),
+        reps: Some(5),
+        weight: Some(100.0),
+        ..Default::default()
+    })?;
+    let (lunge_id, _) = service.add_workout(AddWorkoutParams {
+        exercise_identifier: "Lunge",
+        date: Utc::now(),
+        sets: Some(3),
+        reps: Some(8),
+        ..Default::default()
+    })?;
+    service.add_workout(AddWorkoutParams {
+        exercise_identifier: "Dip",
+        date: Utc::now(),
+        reps: Some(5),
+        weight: Some(-20.0), // Assisted: fine for body-weight exercises
+        bodyweight_to_use: Some(80.0),
+        ..Default::default()
+    })?;
+    assert!(service.check_integrity(false)?.is_clean());
+
+    // Deleting an exercise keeps its workouts; damage the rest directly.
+    service.delete_exercise(&["Lunge".to_string()])?;
+    {
+        let conn = service.conn.get()?;
+        conn.execute("UPDATE workouts SET weight = -5, reps = -1 WHERE id = ?1", [squat_id])?;
+        conn.execute(
+            "INSERT INTO aliases (alias_name, exercise_name, last_edited) VALUES ('ghost', 'Nope', '2024-01-01T00:00:00+00:00')",
+            [],
+        )?;
+        conn.execute(
+            "INSERT INTO exercises (name, type, last_edited) VALUES ('squat ', 'resistance', '2024-01-01T00:00:00+00:00')",
+            [],
+        )?;
+    }
+
+    let report = service.check_integrity(false)?;
+    assert!(!report.is_clean());
+    assert_eq!(report.orphaned_workouts, vec![(lunge_id, "Lunge".to_string())]);
+    assert_eq!(report.dangling_aliases, vec![("ghost".to_string(), "Nope".to_string())]);
+    assert_eq!(
+        report.invalid_values,
+        vec![(squat_id, "reps".to_string()), (squat_id, "weight".to_string())]
+    );
+    assert_eq!(report.duplicate_names, vec![vec!["Squat".to_string(), "squat ".to_string()]]);
+    assert_eq!(report.repaired_aliases, 0);
+
+    let repaired = service.check_integrity(true)?;
+    assert_eq!(repaired.repaired_aliases, 1);
+    assert_eq!(repaired.dangling_aliases.len(), 1); // Reported as found before the repair
+    let after = service.check_integrity(false)?;
+    assert!(after.dangling_aliases.is_empty());
+    assert_eq!(after.orphaned_workouts.len(), 1); // Only reported, never repaired
+    assert!(service.list_aliases()?.contains_key("sq"));
+    Ok(())
+}
+
+#[test]
+fn test_exercise_defaults() -> Result<()> {
+    let mut service = create_test_service()?;
+    let three_by_twelve = ExerciseDefaults { sets: Some(3), reps: Some(12), weight: Some(15.0) };
+    service.add_exercise(NewExerciseParams {
+        muscles: Some("shoulders"),
+        defaults: Some(three_by_twelve),
+        ..NewExerciseParams::new("Lateral Raise", ExerciseType::Resistance)
+    })?;
+    service.create_exercise("Squat", ExerciseType::Resistance, None, Some("legs"))?;
+    service.create_alias("lr", "Lateral Raise")?;
+
+    assert_eq!(service.get_exercise_defaults("lr")?, three_by_twelve);
+    assert_eq!(service.get_exercise_defaults("Squat")?, ExerciseDefaults::default());
+    assert!(matches!(service.get_exercise_defaults("Nope"), Err(ServiceError::NotFound(_))));
+
+    // Editing other fields keeps the defaults; new defaults replace all three
+    service.edit_exercise("Lateral Raise", EditExerciseParams { new_muscles: Some(Some("shoulders,traps")), ..Default::default() })?;
+    assert_eq!(service.get_exercise_defaults("Lateral Raise")?, three_by_twelve);
+    let squat_defaults = ExerciseDefaults { sets: Some(5), reps: Some(5), weight: None };
+    service.edit_exercise("Squat", EditExerciseParams { new_defaults: Some(squat_defaults), ..Default::default() })?;
+    assert_eq!(service.get_exercise_defaults("Squat")?, squat_defaults);
+    service.edit_exercise("Squat", EditExerciseParams { new_defaults: Some(ExerciseDefaults::default()), ..Default::default() })?;
+    assert_eq!(service.get_exercise_defaults("Squat")?, ExerciseDefaults::default());
+
+    let invalid = ExerciseDefaults { sets: Some(0), ..Default::default() };
+    assert!(matches!(
+        service.edit_exercise("Squat", EditExerciseParams { new_defaults: Some(invalid), ..Default::default() }),
+        Err(ServiceError::Validation(_))
+    ));
+    assert!(service
+        .add_exercise(NewExerciseParams { defaults: Some(invalid), ..NewExerciseParams::new("Curl", ExerciseType::Resistance) })
+        .is_err());
+    assert!(service.resolve_exercise_identifier("Curl")?.is_none());
+    Ok(())
+}
+
+#[test]
+fn test_relative_intensity_graph() -> Result<()> {
+    let mut service = create_test_service()?;
+    service.create_exercise("Squat", ExerciseType::Resistance, None, Some("legs"))?;
+    let day = |d| NaiveDate::from_ymd_opt(2024, 3, d).unwrap();
+    let log = |service: &mut AppService, d, reps, weight| {
+        service.add_workout(AddWorkoutParams {
+            exercise_identifier: "Squat",
+            date: Utc.from_utc_datetime(&day(d).and_hms_opt(12, 0, 0).unwrap()),
+            sets: Some(1),
+            reps: Some(reps),
+            weight: Some(weight),
+            ..Default::default()
+        })
+    };
+    log(&mut service, 1, 5, 120.0)?; // E1RM 140
+    log(&mut service, 2, 10, 90.0)?; // E1RM 120, best stays 140
+    log(&mut service, 3, 1, 154.0)?; // New best E1RM on the same day
+    log(&mut service, 3, 3, 100.0)?;
+
+    let graph = service.get_data_for_graph("Squat", GraphType::RelativeIntensity, None, None, None)?;
+    let pct = |g: &[(NaiveDate, f64)], i: usize| (g[i].0, (g[i].1 * 100.0).round() / 100.0);
+    assert_eq!(graph.len(), 3);
+    assert_eq!(pct(&graph, 0), (day(1), 85.71)); // 120 / 140
+    assert_eq!(pct(&graph, 1), (day(2), 64.29)); // 90 / 140
+    assert_eq!(pct(&graph, 2), (day(3), 96.77)); // 154 / 159.13
+
+    // History before the range still counts towards the best E1RM
+    let filtered = service.get_data_for_graph("Squat", GraphType::RelativeIntensity, Some(day(2)), None, None)?;
+    assert_eq!(pct(&filtered, 0), (day(2), 64.29));
+    Ok(())
+}
+
+#[test]
+fn test_failed_attempts() -> Result<()> {
+    let mut service = create_test_service()?;
+    service.create_exercise("Bench", ExerciseType::Resistance, None, Some("chest"))?;
+    assert_eq!(service.get_failure_rate("Bench")?, Default::default());
+    let bench = |sets, reps, weight, is_failure| AddWorkoutParams {
+        exercise_identifier: "Bench",
+        date: Utc::now(),
+        sets: Some(sets),
+        reps: Some(reps),
+        weight: Some(weight),
+        is_failure,
+        ..Default::default()
+    };
+    service.add_workout(bench(3, 5, 100.0, false))?;
+    let (failed_id, pb) = service.add_workout(bench(1, 1, 120.0, true))?;
+    assert!(pb.is_none()); // A missed attempt is never a PB
+    service.add_workout(AddWorkoutParams { is_warmup: true, ..bench(1, 0, 130.0, true) })?;
+
+    // Kept for display, skipped for PBs and volume
+    let all = service.list_workouts(&WorkoutFilters::default())?;
+    assert!(all.iter().any(|w| w.id == failed_id && w.is_failure));
+    let counted = service.list_workouts(&WorkoutFilters { include_failures: false, ..Default::default() })?;
+    assert!(counted.iter().all(|w| !w.is_failure));
+    assert_eq!(service.get_lifetime_totals()?.total_volume, 1500.0);
+    let max_weight = service.get_data_for_graph("Bench", GraphType::MaxWeight, None, None, None)?;
+    assert_eq!(max_weight[0].1, 100.0);
+    let (_, pb) = service.add_workout(bench(1, 1, 110.0, false))?;
+    assert!(pb.is_some_and(|pb| pb.weight.achieved && pb.weight.previous_value == Some(100.0)));
+
+    let rate = service.get_failure_rate("Bench")?;
+    assert_eq!((rate.attempts, rate.failures), (5, 1)); // Warmup ignored
+    assert_eq!(rate.percentage, 20.0);
+
+    // Clearing the flag makes the entry count again
+    service.edit_workout(EditWorkoutParams { id: failed_id, new_is_failure: Some(false), ..Default::default() })?;
+    assert_eq!(service.get_failure_rate("Bench")?.failures, 0);
+    let max_weight = service.get_data_for_graph("Bench", GraphType::MaxWeight, None, None, None)?;
+    assert_eq!(max_weight[0].1, 120.0);
+    Ok(())
+}
+
+#[test]
+fn test_merge_same_day_sets() -> Result<()> {
+    let mut service = create_test_service()?;
+    service.create_exercise("Curl", ExerciseType::Resistance, None, Some("biceps"))?;
+    let date = Utc.with_ymd_and_hms(2024, 5, 1, 18, 0, 0).unwrap();
+    let curl = |reps, weight, notes: Option<&str>| AddWorkoutParams {
+        exercise_identifier: "Curl",
+        date,
+        sets: Some(1),
+        reps: Some(reps),
+        weight: Some(weight),
+        notes: notes.map(str::to_string),
+        ..Default::default()
+    };
+    let day = date.date_naive();
+    let entries_on = |service: &mut AppService| {
+        service.list_workouts(&WorkoutFilters { date: Some(day), ..Default::default() })
+    };
+
+    // Off by default: one row per call
+    service.add_workout(curl(12, 15.0, None))?;
+    service.add_workout(curl(10, 17.5, Some("slow eccentric")))?;
+    service.add_workout(AddWorkoutParams { is_warmup: true, ..curl(15, 5.0, None) })?;
+    assert_eq!(entries_on(&mut service)?.len(), 3);
+
+    // Opt-in merge appends to the day's entry of the same kind
+    service.set_merge_same_day_sets(true)?;
+    let (id, _) = service.add_workout(curl(8, 20.0, Some("grip")))?;
+    let (same_id, _) = service.add_workout(curl(12, 15.0, Some("grip")))?;
+    assert_eq!(id, same_id);
+    let entries = entries_on(&mut service)?;
+    assert_eq!(entries.len(), 3);
+    let merged = entries.iter().find(|w| w.id == id).unwrap();
+    assert_eq!((merged.sets, merged.reps, merged.weight), (Some(3), Some(8), Some(20.0)));
+    assert_eq!(merged.notes.as_deref(), Some("slow eccentric; grip"));
+    assert_eq!(service.get_workout_history(id)?.len(), 2);
+
+    // Undo takes back one merged set at a time, then the add that created the entry
+    assert_eq!(service.undo_last()?.map(|e| (e.action, e.workout_ids)), Some((UndoAction::MergeWorkout, vec![id])));
+    let merged = service.list_workouts(&WorkoutFilters::default())?.into_iter().find(|w| w.id == id).unwrap();
+    assert_eq!((merged.sets, merged.reps, merged.weight), (Some(2), Some(8), Some(20.0)));
+    assert_eq!(service.get_workout_history(id)?.len(), 1);
+    assert_eq!(service.undo_last()?.map(|e| e.action), Some(UndoAction::MergeWorkout));
+    let merged = service.list_workouts(&WorkoutFilters::default())?.into_iter().find(|w| w.id == id).unwrap();
+    assert_eq!((merged.sets, merged.reps, merged.weight), (Some(1), Some(10), Some(17.5)));
+    assert_eq!(merged.notes.as_deref(), Some("slow eccentric"));
+    let (redo_id, _) = service.add_workout(curl(8, 20.0, Some("grip")))?;
+    service.add_workout(curl(12, 15.0, Some("grip")))?;
+    assert_eq!(redo_id, id);
+
+    // Retroactive consolidation keeps warmups apart
+    service.set_merge_same_day_sets(false)?;
+    service.add_workout(curl(12, 15.0, None))?;
+    assert_eq!(service.consolidate_day(day, "Curl")?, 2);
+    let entries = entries_on(&mut service)?;
+    assert_eq!(entries.len(), 2);
+    let working = entries.iter().find(|w| !w.is_warmup).unwrap();
+    assert_eq!((working.sets, working.reps, working.weight), (Some(5), Some(8), Some(20.0)));
+    assert_eq!(service.consolidate_day(day, "Curl")?, 0);
+    assert!(service.consolidate_day(day, "Nope").is_err());
+    Ok(())
+}
+
+#[test]
+fn test_favorite_exercises() -> Result<()> {
+    let service = create_test_service()?;
+    for name in ["Bench", "Curl", "Squat"] {
+        service.create_exercise(name, ExerciseType::Resistance, None, None)?;
+    }
+    service.create_alias("sq", "Squat")?;
+    let names = |favorites_only| -> Result<Vec<String>> {
+        Ok(service
+            .list_exercises(&ExerciseFilters { favorites_only, ..Default::default() })?
+            .into_iter()
+            .map(|e| e.name)
+            .collect())
+    };
+    assert_eq!(names(false)?, ["Bench", "Curl", "Squat"]);
+    assert!(names(true)?.is_empty());
+
+    let before = service.get_exercise_by_identifier_service("Squat")?.unwrap().last_edited;
+    assert!(service.toggle_favorite("sq")?);
+    let squat = service.get_exercise_by_identifier_service("Squat")?.unwrap();
+    assert!(squat.is_favorite);
+    assert!(squat.last_edited > before); // Picked up by sync
+    assert_eq!(names(false)?, ["Squat", "Bench", "Curl"]);
+    assert_eq!(names(true)?, ["Squat"]);
+
+    assert!(!service.toggle_favorite("Squat")?);
+    assert_eq!(names(false)?, ["Bench", "Curl", "Squat"]);
+    assert!(matches!(service.toggle_favorite("Nope"), Err(ServiceError::NotFound(_))));
+    Ok(())
+}
+
+#[test]
+fn test_exercise_custom_order() -> Result<()> {
+    let service = create_test_service()?;
+    for name in ["Bench", "Curl", "Deadlift", "Squat"] {
+        service.create_exercise(name, ExerciseType::Resistance, None, None)?;
+    }
+    service.create_alias("sq", "Squat")?;
+    service.toggle_favorite("Curl")?;
+    let names = |sort| -> Result<Vec<String>> {
+        Ok(service
+            .list_exercises(&ExerciseFilters { sort, ..Default::default() })?
+            .into_iter()
+            .map(|e| e.name)
+            .collect())
+    };
+    // Without positions the custom order falls back to name
+    assert_eq!(names(ExerciseSort::Custom)?, ["Bench", "Curl", "Deadlift", "Squat"]);
+
+    let before = service.get_exercise_by_identifier_service("Squat")?.unwrap().last_edited;
+    service.set_exercise_order("sq", Some(0))?;
+    service.set_exercise_order("Deadlift", Some(1))?;
+    let squat = service.get_exercise_by_identifier_service("Squat")?.unwrap();
+    assert_eq!(squat.sort_order, Some(0));
+    assert!(squat.last_edited > before); // Picked up by sync
+    assert_eq!(names(ExerciseSort::Custom)?, ["Squat", "Deadlift", "Bench", "Curl"]);
+    assert_eq!(names(ExerciseSort::Name)?, ["Curl", "Bench", "Deadlift", "Squat"]);
+    assert!(service.set_exercise_order("Bench", Some(-1)).is_err());
+    assert!(matches!(service.set_exercise_order("Nope", Some(2)), Err(ServiceError::NotFound(_))));
+
+    service.reorder_exercises(&["Curl", "Bench"])?;
+    assert_eq!(names(ExerciseSort::Custom)?, ["Curl", "Bench", "Deadlift", "Squat"]);
+    assert!(matches!(service.reorder_exercises(&["Squat", "sq"]), Err(ServiceError::Validation(_))));
+    assert!(matches!(service.reorder_exercises(&["Squat", "Nope"]), Err(ServiceError::NotFound(_))));
+    assert_eq!(names(ExerciseSort::Custom)?, ["Curl", "Bench", "Deadlift", "Squat"]);
+
+    service.set_exercise_order("Curl", None)?;
+    assert_eq!(names(ExerciseSort::Custom)?, ["Bench", "Curl", "Deadlift", "Squat"]);
+    Ok(())
+}
+
+#[test]
+fn test_best_effort_sync() -> Result<()> {
+    let mut service = create_test_service()?;
+    service.create_exercise("Squat", ExerciseType::Resistance, None, Some("legs"))?;
+    let squat = service.get_exercise_by_identifier_service("Squat")?.unwrap();
+    let mut clash = squat.clone(); // Same name under another ID: violates the unique name
+    clash.id = 99;
+    clash.name = "SQUAT".to_string();
+    let mut row = squat.clone();
+    row.id = 100;
+    row.name = "Row".to_string();
+    let changes = task_athlete_lib::sync_client::ChangesPayload {
+        exercises: vec![clash, row],
+        ..Default::default()
+    };
+
+    // Strict: nothing is applied
+    let err = service.apply_server_changes(changes.clone(), true).unwrap_err();
+    assert!(format!("{err:#}").contains("Failed to upsert exercise ID 99"));
+    assert!(service.resolve_exercise_identifier("Row")?.is_none());
+
+    // Best effort: the bad record is reported, the rest committed
+    let summary = service.apply_server_changes(changes, false)?;
+    assert_eq!(summary.exercises, 1);
+    assert_eq!(summary.failures.len(), 1);
+    assert_eq!(summary.failures[0].record, "exercise ID 99");
+    assert!(service.resolve_exercise_identifier("Row")?.is_some());
+    assert_eq!(service.list_exercises(&ExerciseFilters::default())?.len(), 2);
+
+    // A synced theme with an unknown color keeps the local theme, so the config still loads
+    let mut server_config = service.config.clone();
+    server_config.theme.header_color = "Chartreuse".to_string();
+    server_config.streak_interval_days = 3;
+    let changes = task_athlete_lib::sync_client::ChangesPayload {
+        config: Some(task_athlete_lib::sync_client::ConfigChange {
+            content: toml::to_string(&server_config)?,
+            last_edited: Utc::now() + Duration::days(1),
+        }),
+        ..Default::default()
+    };
+    let summary = service.apply_server_changes(changes, true)?;
+    assert!(summary.config);
+    assert_eq!(summary.failures.len(), 1);
+    assert_eq!(summary.failures[0].record, "config theme");
+    assert_eq!(service.config.theme.header_color, "Green");
+    assert_eq!(service.config.streak_interval_days, 3);
+    let saved: task_athlete_lib::Config = toml::from_str(&std::fs::read_to_string(service.get_config_path())?)?;
+    assert!(saved.theme.validate().is_ok());
+    Ok(())
+}
+
+#[test]
+fn test_compare_periods() -> Result<()> {
+    let mut service = create_test_service()?;
+    service.create_exercise("Squat", ExerciseType::Resistance, None, Some("legs"))?;
+    service.create_exercise("Bench", ExerciseType::Resistance, None, Some("chest"))?;
+    service.create_exercise("Row", ExerciseType::Resistance, None, Some("back"))?;
+    let log = |service: &mut AppService, name, (y, m, d), weight| {
+        service.add_workout(AddWorkoutParams {
+            exercise_identifier: name,
+            date: Utc.with_ymd_and_hms(y, m, d, 12, 0, 0).unwrap(),
+            sets: Some(1),
+            reps: Some(10),
+            weight: Some(weight),
+            ..Default::default()
+        })
+    };
+    log(&mut service, "Squat", (2024, 1, 5), 100.0)?;
+    log(&mut service, "Bench", (2024, 1, 20), 60.0)?;
+    log(&mut service, "Squat", (2024, 2, 3), 100.0)?;
+    log(&mut service, "Squat", (2024, 2, 10), 50.0)?;
+    log(&mut service, "Row", (2024, 2, 29), 70.0)?;
+
+    let jan = (NaiveDate::from_ymd_opt(2024, 1, 1).unwrap(), NaiveDate::from_ymd_opt(2024, 1, 31).unwrap());
+    let feb = (NaiveDate::from_ymd_opt(2024, 2, 1).unwrap(), NaiveDate::from_ymd_opt(2024, 2, 29).unwrap());
+    let cmp = service.compare_periods(jan, feb)?;
+    assert_eq!((cmp.volume_a, cmp.volume_b), (1600.0, 2200.0));
+    assert_eq!(cmp.volume_change_pct, Some(37.5));
+    assert_eq!((cmp.workouts_a, cmp.workouts_b), (2, 3));
+    assert_eq!(cmp.workouts_change_pct, Some(50.0));
+
+    let names: Vec<&str> = cmp.exercises.iter().map(|e| e.name.as_str()).collect();
+    assert_eq!(names, ["Bench", "Row", "Squat"]);
+    let bench = &cmp.exercises[0];
+    assert_eq!((bench.volume_a, bench.volume_b, bench.workouts_b), (600.0, 0.0, 0));
+    assert_eq!(bench.volume_change_pct, Some(-100.0));
+    let row = &cmp.exercises[1];
+    assert_eq!((row.volume_a, row.volume_b, row.volume_change_pct), (0.0, 700.0, None));
+    assert_eq!(cmp.exercises[2].volume_change_pct, Some(50.0));
+
+    assert!(matches!(service.compare_periods((jan.1, jan.0), feb), Err(ServiceError::Validation(_))));
+    Ok(())
+}
+
+#[test]
+fn test_per_metric_units() -> Result<()> {
+    // A config written before the split keeps using `units` for both
+    let path = std::env::temp_dir().join(format!("ta_units_test_{}.toml", uuid::Uuid::new_v4()));
+    std::fs::write(&path, "units = \"imperial\"\n")?;
+    let legacy = task_athlete_lib::load_config_util(&path)?;
+    assert_eq!((legacy.weight_units(), legacy.distance_units()), (Units::Imperial, Units::Imperial));
+    let _ = std::fs::remove_file(&path);
+
+    // Lifting in kg, running in miles
+    let mut service = create_test_service()?;
+    service.set_units(Units::Imperial)?;
+    service.set_weight_units(Units::Metric)?;
+    assert_eq!(service.config.weight_units(), Units::Metric);
+    assert_eq!(service.config.distance_units(), Units::Imperial);
+    assert!((service.display_distance(1.609_344) - 1.0).abs() < 0.001);
+    assert_eq!(service.calculate_plates(60.0, 20.0)?, vec![20.0]); // Metric plate set
+
+    service.create_exercise("Run", ExerciseType::Cardio, None, Some("legs"))?;
+    service.add_workout(AddWorkoutParams {
+        exercise_identifier: "Run",
+        date: Utc::now(),
+        duration: Some(30),
+        distance: Some(3.0), // Miles
+        ..Default::default()
+    })?;
+    let graph = service.get_data_for_graph("Run", GraphType::WorkoutDistance, None, None, None)?;
+    assert!((graph[0].1 - 3.0).abs() < 0.001);
+
+    // The convenience setter puts both back in one system
+    service.set_units(Units::Metric)?;
+    assert_eq!((service.config.weight_units(), service.config.distance_units()), (Units::Metric, Units::Metric));
+    let graph = service.get_data_for_graph("Run", GraphType::WorkoutDistance, None, None, None)?;
+    assert!((graph[0].1 - 4.828).abs() < 0.001);
+    Ok(())
+}
+
+#[test]
+fn test_replace_in_notes() -> Result<()> {
+    let mut service = create_test_service()?;
+    service.create_exercise("Row", ExerciseType::Resistance, None, Some("back"))?;
+    service.create_exercise("Press", ExerciseType::Resistance, None, Some("shoulders"))?;
+    let log = |service: &mut AppService, name, notes: &str| {
+        service
+            .add_workout(AddWorkoutParams {
+                exercise_identifier: name,
+                date: Utc::now(),
+                reps: Some(10),
+                notes: Some(notes.to_string()),
+                ..Default::default()
+            })
+            .map(|(id, _)| id)
+    };
+    let row = log(&mut service, "Row", "DB row, DB on bench")?;
+    let press = log(&mut service, "Press", "seated DB")?;
+    let untouched = log(&mut service, "Press", "barbell")?;
+    let deleted = log(&mut service, "Row", "DB")?;
+    service.delete_workouts(&[deleted])?;
+    let notes_of = |service: &AppService, id| -> Result<Option<String>> {
+        Ok(service
+            .list_workouts(&WorkoutFilters::default())?
+            .into_iter()
+            .find(|w| w.id == id)
+            .and_then(|w| w.notes))
+    };
+
+    // Dry run reports without writing
+    let mut ids = service.replace_in_notes("DB", "dumbbell", None, true)?;
+    ids.sort_unstable();
+    assert_eq!(ids, [row, press]); // The deleted entry is left alone
+    assert_eq!(notes_of(&service, row)?.as_deref(), Some("DB row, DB on bench"));
+
+    // Filters narrow the selection
+    let filters = WorkoutFilters { exercise_name: Some("Press"), ..Default::default() };
+    assert_eq!(service.replace_in_notes("DB", "dumbbell", Some(filters), false)?, [press]);
+    assert_eq!(notes_of(&service, press)?.as_deref(), Some("seated dumbbell"));
+    assert_eq!(notes_of(&service, row)?.as_deref(), Some("DB row, DB on bench"));
+
+    assert_eq!(service.replace_in_notes("DB", "dumbbell", None, false)?, [row]);
+    assert_eq!(notes_of(&service, row)?.as_deref(), Some("dumbbell row, dumbbell on bench"));
+    assert_eq!(notes_of(&service, untouched)?.as_deref(), Some("barbell"));
+    assert_eq!(service.get_workout_history(row)?.len(), 1);
+    assert!(service.replace_in_notes("", "x", None, false).is_err());
+    Ok(())
+}
+
+#[test]
+fn test_workout_density() -> Result<()> {
+    let mut service = create_test_service()?;
+    service.create_exercise("Swing", ExerciseType::Resistance, Some((Some(true), Some(true), Some(true), None)), None)?;
+    let date = Utc.with_ymd_and_hms(2024, 6, 1, 12, 0, 0).unwrap();
+    let swing = |sets, duration| AddWorkoutParams {
+        exercise_identifier: "Swing",
+        date,
+        sets: Some(sets),
+        reps: Some(10),
+        weight: Some(24.0),
+        duration,
+        ..Default::default()
+    };
+    let (timed, _) = service.add_workout(swing(5, Some(10)))?; // 1200 volume in 10 min
+    let (untimed, _) = service.add_workout(swing(3, None))?;
+    service.add_workout(swing(10, Some(30)))?; // 2400 volume in 30 min
+
+    assert_eq!(service.get_density(timed)?, Some(120.0));
+    assert_eq!(service.get_density(untimed)?, None);
+    assert!(matches!(service.get_density(999), Err(ServiceError::NotFound(_))));
+
+    // Daily totals over entries with both values: 3600 / 40 min
+    let graph = service.get_data_for_graph("Swing", GraphType::WorkoutDensity, None, None, None)?;
+    assert_eq!(graph, vec![(date.date_naive(), 90.0)]);
+    Ok(())
+}